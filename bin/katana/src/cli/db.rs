@@ -1,13 +1,21 @@
-use std::path::{self};
+mod check;
+mod export;
+mod prune;
 
-use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{self, PathBuf};
+
+use anyhow::{bail, ensure, Context, Result};
 use clap::{Args, Subcommand};
 use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::Table;
-use katana_db::abstraction::Database;
+use katana_db::abstraction::{Database, DbCursor, DbTx};
 use katana_db::mdbx::{DbEnv, DbEnvKind};
-use katana_db::tables::NUM_TABLES;
+use katana_db::tables::{self, NUM_TABLES};
+use katana_db::utils::is_database_empty;
+use katana_db::version::{get_db_version, CURRENT_DB_VERSION};
 
 /// Create a human-readable byte unit string (eg. 16.00 KiB)
 macro_rules! byte_unit {
@@ -36,6 +44,30 @@ pub struct DbArgs {
 enum Commands {
     #[command(about = "Retrieves database statistics")]
     Stats,
+
+    #[command(about = "Removes the history of old blocks (ie traces and state changes)")]
+    Prune {
+        #[arg(long)]
+        #[arg(value_name = "N")]
+        #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+        #[arg(help = "Number of most recent blocks whose history is kept")]
+        keep_blocks: u64,
+    },
+
+    #[command(about = "Exports the database to a portable file")]
+    Export {
+        #[arg(help = "Path of the file to export the database to")]
+        output: PathBuf,
+    },
+
+    #[command(about = "Imports a file created by `katana db export` into a new database")]
+    Import {
+        #[arg(help = "Path of the file to import the database from")]
+        input: PathBuf,
+    },
+
+    #[command(about = "Verifies the integrity of the database")]
+    Check,
 }
 
 impl DbArgs {
@@ -121,6 +153,78 @@ impl DbArgs {
 
                 println!("{table}");
             }
+
+            Commands::Prune { keep_blocks } => {
+                let db = open_db_rw(&self.path)?;
+
+                let output = db.update(|tx| -> Result<_> {
+                    let Some((latest, _)) = tx.cursor::<tables::Headers>()?.last()? else {
+                        return Ok(None);
+                    };
+
+                    let horizon = (latest + 1).saturating_sub(keep_blocks);
+                    prune::prune(tx, horizon).map(|output| Some((horizon, output)))
+                })??;
+
+                match output {
+                    Some((horizon, output)) => {
+                        println!("Pruned the history of blocks below {horizon}.");
+                        println!("Traces: {}", output.traces);
+                        println!("Storage changes: {}", output.storage_changes);
+                        println!("Nonce changes: {}", output.nonce_changes);
+                        println!("Class changes: {}", output.class_changes);
+                    }
+                    None => println!("The database is empty, nothing to prune."),
+                }
+            }
+
+            Commands::Export { output } => {
+                let version = get_db_version(expand_path(&self.path)?)?;
+                let db = open_db_ro(&self.path)?;
+
+                let file = File::create(&output)
+                    .with_context(|| format!("Creating export file at {}", output.display()))?;
+                db.view(|tx| export::export(tx, version, BufWriter::new(file)))??;
+
+                println!("Exported database to {}.", output.display());
+            }
+
+            Commands::Import { input } => {
+                let path = expand_path(&self.path)?;
+                ensure!(
+                    is_database_empty(&path),
+                    "Database at {} is not empty, imports must be made into a new database",
+                    path.display()
+                );
+
+                let file = File::open(&input)
+                    .with_context(|| format!("Opening export file at {}", input.display()))?;
+                let db = katana_db::init_db(&path)?;
+                db.update(|tx| export::import(tx, CURRENT_DB_VERSION, BufReader::new(file)))??;
+
+                println!("Imported {} into database at {}.", input.display(), path.display());
+            }
+
+            Commands::Check => {
+                let db = open_db_ro(&self.path)?;
+                let report = db.view(check::check)??;
+
+                let mut table = table();
+                table.set_header(vec!["Table", "Entries"]);
+                for (name, entries) in report.entries {
+                    table.add_row(vec![name.to_string(), entries.to_string()]);
+                }
+                println!("{table}");
+
+                if !report.issues.is_empty() {
+                    for issue in &report.issues {
+                        println!("{issue}");
+                    }
+                    bail!("Found {} inconsistencies in the database", report.issues.len());
+                }
+
+                println!("No inconsistencies found.");
+            }
         }
 
         Ok(())
@@ -131,13 +235,27 @@ impl DbArgs {
 ///
 /// The path is expanded and resolved to an absolute path before opening the database for clearer
 /// error messages.
-fn open_db_ro(path: &str) -> Result<DbEnv> {
-    let path = path::absolute(shellexpand::full(path)?.into_owned())?;
+pub(crate) fn open_db_ro(path: &str) -> Result<DbEnv> {
+    let path = expand_path(path)?;
     DbEnv::open(&path, DbEnvKind::RO).with_context(|| {
         format!("Opening database file in read-only mode at path {}", path.display())
     })
 }
 
+/// Open the existing database at `path` in read-write mode.
+///
+/// The database must not be in use by a running node.
+fn open_db_rw(path: &str) -> Result<DbEnv> {
+    let path = expand_path(path)?;
+    ensure!(!is_database_empty(&path), "No database found at path {}", path.display());
+    katana_db::init_db(&path)
+}
+
+/// Expands and resolves `path` to an absolute path.
+fn expand_path(path: &str) -> Result<PathBuf> {
+    Ok(path::absolute(shellexpand::full(path)?.into_owned())?)
+}
+
 /// Create a table with the default UTF-8 full border and rounded corners.
 fn table() -> Table {
     let mut table = Table::new();
@@ -0,0 +1,194 @@
+use anyhow::Result;
+use katana_db::abstraction::{DbCursor, DbTx};
+use katana_db::tables::{self, Table, TableVisitor, Tables};
+
+/// The result of checking the database.
+#[derive(Debug, Default)]
+pub(super) struct CheckReport {
+    /// The number of entries in each table.
+    pub(super) entries: Vec<(Tables, usize)>,
+    /// The inconsistencies found in the database.
+    pub(super) issues: Vec<String>,
+}
+
+/// Decodes every entry of every table and verifies that the block and transaction indices are
+/// consistent with each other.
+pub(super) fn check<Tx: DbTx>(tx: &Tx) -> Result<CheckReport> {
+    let mut report = CheckReport::default();
+
+    for table in Tables::ALL {
+        let entries = table.visit(&mut DecodeTable { tx, issues: &mut report.issues })?;
+        report.entries.push((table, entries));
+    }
+
+    check_tx_indices(tx, &mut report.issues)?;
+    check_block_indices(tx, &mut report.issues)?;
+    check_block_body_indices(tx, &mut report.issues)?;
+
+    Ok(report)
+}
+
+/// Checks that `TxNumbers` and `TxHashes` map to each other.
+fn check_tx_indices<Tx: DbTx>(tx: &Tx, issues: &mut Vec<String>) -> Result<()> {
+    for entry in tx.cursor::<tables::TxNumbers>()?.walk(None)? {
+        let (hash, number) = entry?;
+        match tx.get::<tables::TxHashes>(number)? {
+            Some(actual) if actual == hash => {}
+            Some(actual) => issues.push(format!(
+                "TxNumbers maps {hash:#x} to tx {number}, but TxHashes maps it to {actual:#x}"
+            )),
+            None => {
+                issues.push(format!("TxNumbers maps {hash:#x} to tx {number}, missing in TxHashes"))
+            }
+        }
+    }
+
+    let numbers = tx.entries::<tables::TxNumbers>()?;
+    let hashes = tx.entries::<tables::TxHashes>()?;
+    if numbers != hashes {
+        issues.push(format!("TxNumbers has {numbers} entries, but TxHashes has {hashes}"));
+    }
+
+    Ok(())
+}
+
+/// Checks that `BlockNumbers` and `BlockHashes` map to each other.
+fn check_block_indices<Tx: DbTx>(tx: &Tx, issues: &mut Vec<String>) -> Result<()> {
+    for entry in tx.cursor::<tables::BlockNumbers>()?.walk(None)? {
+        let (hash, number) = entry?;
+        match tx.get::<tables::BlockHashes>(number)? {
+            Some(actual) if actual == hash => {}
+            Some(actual) => issues.push(format!(
+                "BlockNumbers maps {hash:#x} to block {number}, but BlockHashes maps it to \
+                 {actual:#x}"
+            )),
+            None => issues.push(format!(
+                "BlockNumbers maps {hash:#x} to block {number}, missing in BlockHashes"
+            )),
+        }
+    }
+
+    let numbers = tx.entries::<tables::BlockNumbers>()?;
+    let hashes = tx.entries::<tables::BlockHashes>()?;
+    if numbers != hashes {
+        issues.push(format!("BlockNumbers has {numbers} entries, but BlockHashes has {hashes}"));
+    }
+
+    Ok(())
+}
+
+/// Checks that every block has body indices, that the transactions of consecutive blocks are
+/// contiguous, and that every transaction referenced by a block exists.
+fn check_block_body_indices<Tx: DbTx>(tx: &Tx, issues: &mut Vec<String>) -> Result<()> {
+    let mut next_tx_offset = 0;
+
+    for entry in tx.cursor::<tables::BlockHashes>()?.walk(None)? {
+        let (block, _) = entry?;
+
+        let Some(indices) = tx.get::<tables::BlockBodyIndices>(block)? else {
+            issues.push(format!("Block {block} is missing in BlockBodyIndices"));
+            continue;
+        };
+
+        if indices.tx_offset != next_tx_offset {
+            issues.push(format!(
+                "Block {block} starts at tx {}, expected tx {next_tx_offset}",
+                indices.tx_offset
+            ));
+        }
+
+        next_tx_offset = indices.tx_offset + indices.tx_count;
+
+        for number in indices.tx_offset..next_tx_offset {
+            if tx.get::<tables::Transactions>(number)?.is_none() {
+                issues.push(format!("Tx {number} of block {block} is missing in Transactions"));
+            }
+
+            match tx.get::<tables::TxBlocks>(number)? {
+                Some(actual) if actual == block => {}
+                Some(actual) => issues.push(format!(
+                    "Tx {number} is in block {block}, but TxBlocks maps it to block {actual}"
+                )),
+                None => issues.push(format!("Tx {number} of block {block} is missing in TxBlocks")),
+            }
+        }
+    }
+
+    let total = tx.entries::<tables::Transactions>()? as u64;
+    if total != next_tx_offset {
+        issues.push(format!(
+            "Transactions has {total} entries, but the blocks contain {next_tx_offset} txs"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Decodes all the entries of a table, recording the ones that can't be decoded.
+struct DecodeTable<'a, Tx> {
+    tx: &'a Tx,
+    issues: &'a mut Vec<String>,
+}
+
+impl<Tx: DbTx> TableVisitor for DecodeTable<'_, Tx> {
+    type Output = Result<usize>;
+
+    fn visit<T: Table>(&mut self) -> Self::Output {
+        let mut entries = 0;
+
+        for entry in self.tx.cursor::<T>()?.walk(None)? {
+            if let Err(error) = entry {
+                self.issues.push(format!("Failed to decode an entry of {}: {error}", T::NAME));
+            }
+            entries += 1;
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_db::abstraction::{Database, DbTxMut};
+    use katana_db::models::block::StoredBlockBodyIndices;
+    use katana_db::{init_ephemeral_db, tables};
+    use katana_primitives::felt;
+    use katana_primitives::transaction::{InvokeTx, Tx};
+
+    use super::check;
+
+    #[test]
+    fn detect_inconsistent_indices() {
+        let db = init_ephemeral_db().unwrap();
+
+        db.update(|tx| {
+            for (block, hash) in [(0, felt!("0xa")), (1, felt!("0xb"))] {
+                tx.put::<tables::BlockHashes>(block, hash).unwrap();
+                tx.put::<tables::BlockNumbers>(hash, block).unwrap();
+
+                let indices = StoredBlockBodyIndices { tx_offset: block, tx_count: 1 };
+                tx.put::<tables::BlockBodyIndices>(block, indices).unwrap();
+
+                let tx_hash = hash + felt!("0x100");
+                tx.put::<tables::TxNumbers>(tx_hash, block).unwrap();
+                tx.put::<tables::TxHashes>(block, tx_hash).unwrap();
+                tx.put::<tables::TxBlocks>(block, block).unwrap();
+                tx.put::<tables::Transactions>(block, Tx::Invoke(InvokeTx::V1(Default::default())))
+                    .unwrap();
+            }
+        })
+        .unwrap();
+
+        let report = db.view(check).unwrap().unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+
+        db.update(|tx| {
+            tx.delete::<tables::TxHashes>(1, None).unwrap();
+            tx.put::<tables::TxBlocks>(0, 1).unwrap();
+        })
+        .unwrap();
+
+        let report = db.view(check).unwrap().unwrap();
+        assert_eq!(report.issues.len(), 3, "{:?}", report.issues);
+    }
+}
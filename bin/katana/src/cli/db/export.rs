@@ -0,0 +1,179 @@
+//! A portable format for moving a database between machines.
+//!
+//! The file starts with [`MAGIC`], the version of the database and the number of tables. Each table
+//! is then written as its name and number of entries, followed by the entries themselves. Keys and
+//! values are stored in their database encoding, each prefixed by its length.
+
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+use anyhow::{ensure, Result};
+use katana_db::abstraction::{DbCursor, DbTx, DbTxMut};
+use katana_db::codecs::{Compress, Decode, Decompress, Encode};
+use katana_db::tables::{Table, TableVisitor, Tables};
+
+/// The bytes at the start of every export file.
+const MAGIC: &[u8; 8] = b"KATANADB";
+
+/// Writes all the tables of the database to `writer`.
+pub(super) fn export<Tx: DbTx, W: Write>(tx: &Tx, version: u32, mut writer: W) -> Result<()> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&version.to_be_bytes())?;
+    writer.write_all(&(Tables::ALL.len() as u32).to_be_bytes())?;
+
+    for table in Tables::ALL {
+        table.visit(&mut ExportTable { tx, writer: &mut writer })?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the tables written by [`export`] into the database.
+///
+/// Fails if the file was exported from a database with a version other than `version`.
+pub(super) fn import<Tx: DbTxMut, R: Read>(tx: &Tx, version: u32, mut reader: R) -> Result<()> {
+    let mut magic = [0u8; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    ensure!(&magic == MAGIC, "Not a database export file");
+
+    let found = read_u32(&mut reader)?;
+    ensure!(found == version, "Database version mismatch. Expected {version}, found {found}.");
+
+    for _ in 0..read_u32(&mut reader)? {
+        let name = String::from_utf8(read_bytes(&mut reader)?)?;
+        let table = Tables::from_str(&name).map_err(anyhow::Error::msg)?;
+        table.visit(&mut ImportTable { tx, reader: &mut reader })?;
+    }
+
+    Ok(())
+}
+
+struct ExportTable<'a, Tx, W> {
+    tx: &'a Tx,
+    writer: &'a mut W,
+}
+
+impl<Tx: DbTx, W: Write> TableVisitor for ExportTable<'_, Tx, W> {
+    type Output = Result<()>;
+
+    fn visit<T: Table>(&mut self) -> Self::Output {
+        let entries = self.tx.entries::<T>()? as u64;
+
+        write_bytes(self.writer, T::NAME.as_bytes())?;
+        self.writer.write_all(&entries.to_be_bytes())?;
+
+        let mut written = 0;
+        for entry in self.tx.cursor::<T>()?.walk(None)? {
+            let (key, value) = entry?;
+            write_bytes(self.writer, key.encode().as_ref())?;
+            write_bytes(self.writer, value.compress().as_ref())?;
+            written += 1;
+        }
+
+        ensure!(written == entries, "Table {} changed while being exported", T::NAME);
+        Ok(())
+    }
+}
+
+struct ImportTable<'a, Tx, R> {
+    tx: &'a Tx,
+    reader: &'a mut R,
+}
+
+impl<Tx: DbTxMut, R: Read> TableVisitor for ImportTable<'_, Tx, R> {
+    type Output = Result<()>;
+
+    fn visit<T: Table>(&mut self) -> Self::Output {
+        let mut entries = [0u8; 8];
+        self.reader.read_exact(&mut entries)?;
+
+        for _ in 0..u64::from_be_bytes(entries) {
+            let key = T::Key::decode(read_bytes(self.reader)?)?;
+            let value = T::Value::decompress(read_bytes(self.reader)?)?;
+            self.tx.put::<T>(key, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<()> {
+    writer.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut bytes = vec![0u8; read_u32(reader)? as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_db::abstraction::{Database, DbCursor, DbTx, DbTxMut};
+    use katana_db::models::storage::StorageEntry;
+    use katana_db::{init_ephemeral_db, tables};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::{address, felt};
+
+    use super::{export, import};
+
+    #[test]
+    fn export_and_import_roundtrip() {
+        let source = init_ephemeral_db().unwrap();
+        let address = address!("0x1337");
+
+        source
+            .update(|tx| {
+                tx.put::<tables::BlockHashes>(0, felt!("0xa")).unwrap();
+                tx.put::<tables::BlockHashes>(1, felt!("0xb")).unwrap();
+                tx.put::<tables::BlockNumbers>(felt!("0xa"), 0).unwrap();
+                tx.put::<tables::BlockNumbers>(felt!("0xb"), 1).unwrap();
+
+                let entry = StorageEntry { key: felt!("0x1"), value: felt!("0x2") };
+                tx.put::<tables::ContractStorage>(address, entry).unwrap();
+                let entry = StorageEntry { key: felt!("0x3"), value: felt!("0x4") };
+                tx.put::<tables::ContractStorage>(address, entry).unwrap();
+            })
+            .unwrap();
+
+        let mut file = Vec::new();
+        source.view(|tx| export(tx, 1, &mut file)).unwrap().unwrap();
+
+        // the version of the destination database must match
+        let target = init_ephemeral_db().unwrap();
+        assert!(target.update(|tx| import(tx, 2, file.as_slice())).unwrap().is_err());
+
+        let target = init_ephemeral_db().unwrap();
+        target.update(|tx| import(tx, 1, file.as_slice())).unwrap().unwrap();
+
+        target
+            .view(|tx| {
+                assert_eq!(tx.get::<tables::BlockHashes>(1).unwrap(), Some(felt!("0xb")));
+                assert_eq!(tx.get::<tables::BlockNumbers>(felt!("0xa")).unwrap(), Some(0));
+
+                let storage = tx
+                    .cursor::<tables::ContractStorage>()
+                    .unwrap()
+                    .walk(None)
+                    .unwrap()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap();
+
+                let expected = vec![
+                    (address, StorageEntry { key: felt!("0x1"), value: felt!("0x2") }),
+                    (address, StorageEntry { key: felt!("0x3"), value: felt!("0x4") }),
+                ];
+                assert_eq!(storage, expected);
+            })
+            .unwrap();
+    }
+}
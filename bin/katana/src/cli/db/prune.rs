@@ -0,0 +1,206 @@
+use anyhow::Result;
+use katana_db::abstraction::{DbCursor, DbCursorMut, DbDupSortCursor, DbTxMut};
+use katana_db::models::list::BlockList;
+use katana_db::tables;
+use katana_primitives::block::BlockNumber;
+use katana_provider::providers::db::PRUNE_CHECKPOINT;
+
+/// The number of entries removed from each history table by [`prune`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct PruneOutput {
+    pub(super) traces: usize,
+    pub(super) storage_changes: usize,
+    pub(super) nonce_changes: usize,
+    pub(super) class_changes: usize,
+}
+
+/// Prunes the historical data of every block below `horizon`.
+///
+/// For each value, the most recent change made before `horizon` is kept so that the historical
+/// state of the blocks at or after `horizon` can still be queried. The horizon is stored so that
+/// the state of the pruned blocks is no longer served.
+pub(super) fn prune<Tx: DbTxMut>(tx: &Tx, horizon: BlockNumber) -> Result<PruneOutput> {
+    let mut output = PruneOutput::default();
+
+    if horizon == 0 {
+        return Ok(output);
+    }
+
+    // a previous prune may have removed more blocks
+    let previous = tx.get::<tables::StageCheckpoints>(PRUNE_CHECKPOINT.to_string())?;
+    if previous.map_or(true, |previous| previous < horizon) {
+        tx.put::<tables::StageCheckpoints>(PRUNE_CHECKPOINT.to_string(), horizon)?;
+    }
+
+    // traces
+
+    if let Some(indices) = tx.get::<tables::BlockBodyIndices>(horizon)? {
+        let mut cursor = tx.cursor_mut::<tables::TxTraces>()?;
+        while let Some((tx_number, _)) = cursor.first()? {
+            if tx_number >= indices.tx_offset {
+                break;
+            }
+
+            cursor.delete_current()?;
+            output.traces += 1;
+        }
+    }
+
+    // storage
+
+    let mut changes = Vec::new();
+    for entry in tx.cursor::<tables::StorageChangeSet>()?.walk(None)? {
+        let (key, list) = entry?;
+        let obsolete = obsolete_blocks(&list, horizon);
+        if !obsolete.is_empty() {
+            changes.push((key, list, obsolete));
+        }
+    }
+
+    let mut cursor = tx.cursor_dup_mut::<tables::StorageChangeHistory>()?;
+    for (key, mut list, obsolete) in changes {
+        for block in obsolete {
+            if let Some(entry) = cursor.seek_by_key_subkey(block, key.clone())? {
+                if entry.key == key {
+                    cursor.delete_current()?;
+                    output.storage_changes += 1;
+                }
+            }
+
+            list.remove(block);
+        }
+
+        tx.put::<tables::StorageChangeSet>(key, list)?;
+    }
+
+    // contract info
+
+    let mut changes = Vec::new();
+    for entry in tx.cursor::<tables::ContractInfoChangeSet>()?.walk(None)? {
+        let (address, change_set) = entry?;
+        let obsolete_nonces = obsolete_blocks(&change_set.nonce_change_list, horizon);
+        let obsolete_classes = obsolete_blocks(&change_set.class_change_list, horizon);
+        if !obsolete_nonces.is_empty() || !obsolete_classes.is_empty() {
+            changes.push((address, change_set, obsolete_nonces, obsolete_classes));
+        }
+    }
+
+    let mut nonce_cursor = tx.cursor_dup_mut::<tables::NonceChangeHistory>()?;
+    let mut class_cursor = tx.cursor_dup_mut::<tables::ClassChangeHistory>()?;
+
+    for (address, mut change_set, obsolete_nonces, obsolete_classes) in changes {
+        for block in obsolete_nonces {
+            if let Some(entry) = nonce_cursor.seek_by_key_subkey(block, address)? {
+                if entry.contract_address == address {
+                    nonce_cursor.delete_current()?;
+                    output.nonce_changes += 1;
+                }
+            }
+
+            change_set.nonce_change_list.remove(block);
+        }
+
+        for block in obsolete_classes {
+            if let Some(entry) = class_cursor.seek_by_key_subkey(block, address)? {
+                if entry.contract_address == address {
+                    class_cursor.delete_current()?;
+                    output.class_changes += 1;
+                }
+            }
+
+            change_set.class_change_list.remove(block);
+        }
+
+        tx.put::<tables::ContractInfoChangeSet>(address, change_set)?;
+    }
+
+    Ok(output)
+}
+
+/// Returns the blocks in `list` that are below `horizon`, except for the most recent one.
+fn obsolete_blocks(list: &BlockList, horizon: BlockNumber) -> Vec<BlockNumber> {
+    let below = list.rank(horizon - 1);
+    (0..below.saturating_sub(1)).filter_map(|n| list.select(n)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_db::abstraction::{Database, DbDupSortCursor, DbTx, DbTxMut};
+    use katana_db::models::block::StoredBlockBodyIndices;
+    use katana_db::models::contract::{ContractInfoChangeList, ContractNonceChange};
+    use katana_db::models::list::BlockList;
+    use katana_db::models::storage::{ContractStorageEntry, ContractStorageKey};
+    use katana_db::{init_ephemeral_db, tables};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::trace::TxExecInfo;
+    use katana_primitives::{address, felt, Felt};
+    use katana_provider::providers::db::PRUNE_CHECKPOINT;
+
+    use super::{prune, PruneOutput};
+
+    #[test]
+    fn prune_keeps_most_recent_change_before_horizon() {
+        let db = init_ephemeral_db().unwrap();
+
+        let address = address!("0x1337");
+        let key = ContractStorageKey { contract_address: address, key: felt!("0x1") };
+
+        db.update(|tx| {
+            for (block, tx_offset) in [(1, 0), (2, 1), (3, 2), (4, 3)] {
+                let indices = StoredBlockBodyIndices { tx_offset, tx_count: 1 };
+                tx.put::<tables::BlockBodyIndices>(block, indices).unwrap();
+                tx.put::<tables::TxTraces>(tx_offset, TxExecInfo::default()).unwrap();
+            }
+
+            for block in [1, 2, 4] {
+                let value = Felt::from(0x10 + block);
+                let entry = ContractStorageEntry { key: key.clone(), value };
+                tx.put::<tables::StorageChangeHistory>(block, entry).unwrap();
+
+                let nonce = Felt::from(block);
+                let nonce = ContractNonceChange { contract_address: address, nonce };
+                tx.put::<tables::NonceChangeHistory>(block, nonce).unwrap();
+            }
+
+            let list = BlockList::from([1, 2, 4]);
+            let change_set = ContractInfoChangeList {
+                class_change_list: BlockList::default(),
+                nonce_change_list: BlockList::from([1, 2, 4]),
+            };
+
+            tx.put::<tables::StorageChangeSet>(key.clone(), list).unwrap();
+            tx.put::<tables::ContractInfoChangeSet>(address, change_set).unwrap();
+        })
+        .unwrap();
+
+        let output = db.update(|tx| prune(tx, 3)).unwrap().unwrap();
+        let expected =
+            PruneOutput { traces: 2, storage_changes: 1, nonce_changes: 1, class_changes: 0 };
+        assert_eq!(output, expected);
+
+        db.view(|tx| {
+            assert_eq!(tx.entries::<tables::TxTraces>().unwrap(), 2);
+            assert!(tx.get::<tables::TxTraces>(1).unwrap().is_none());
+            assert!(tx.get::<tables::TxTraces>(2).unwrap().is_some());
+
+            // the change at block 2 is still needed to read the state at block 3
+            let list = tx.get::<tables::StorageChangeSet>(key.clone()).unwrap().unwrap();
+            assert_eq!(list, BlockList::from([2, 4]));
+
+            let change_set = tx.get::<tables::ContractInfoChangeSet>(address).unwrap().unwrap();
+            assert_eq!(change_set.nonce_change_list, BlockList::from([2, 4]));
+
+            let mut cursor = tx.cursor_dup::<tables::StorageChangeHistory>().unwrap();
+            assert!(cursor.seek_by_key_subkey(1, key.clone()).unwrap().is_none());
+
+            let entry = cursor.seek_by_key_subkey(2, key.clone()).unwrap().unwrap();
+            assert_eq!(entry.value, felt!("0x12"));
+
+            assert_eq!(tx.entries::<tables::NonceChangeHistory>().unwrap(), 2);
+
+            let horizon = tx.get::<tables::StageCheckpoints>(PRUNE_CHECKPOINT.to_string());
+            assert_eq!(horizon.unwrap(), Some(3));
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::{self, Path};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use comfy_table::modifiers::UTF8_ROUND_CORNERS;
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::Table;
+use katana_db::utils::is_database_empty;
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
+use katana_primitives::chain::ChainId;
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::Felt;
+use katana_provider::providers::fork::cache::ForkCache;
+use katana_provider::providers::fork::ForkedProvider;
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider};
+use url::Url;
+
+#[derive(Args)]
+pub struct ForkCacheArgs {
+    #[arg(short, long)]
+    #[arg(global = true)]
+    #[arg(help = "Path to the fork cache directory")]
+    #[arg(default_value = "~/.katana/fork-cache")]
+    path: String,
+
+    #[command(subcommand)]
+    commands: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    #[command(about = "Lists the cached forks and the number of entries they contain")]
+    List,
+
+    #[command(about = "Fetches contracts and classes from a network into the cache")]
+    Warm {
+        #[arg(long)]
+        #[arg(value_name = "URL")]
+        #[arg(help = "The Starknet RPC provider of the forked network")]
+        rpc_url: Url,
+
+        #[arg(long)]
+        #[arg(value_name = "NUM")]
+        #[arg(help = "The forked block number, defaults to the latest block")]
+        block: Option<BlockNumber>,
+
+        #[arg(long = "contract")]
+        #[arg(value_name = "ADDRESS")]
+        #[arg(value_delimiter = ',')]
+        #[arg(help = "Addresses of the contracts whose nonce, class hash and class are fetched")]
+        contracts: Vec<Felt>,
+
+        #[arg(long = "class")]
+        #[arg(value_name = "HASH")]
+        #[arg(value_delimiter = ',')]
+        #[arg(help = "Hashes of the classes to fetch")]
+        classes: Vec<ClassHash>,
+    },
+}
+
+impl ForkCacheArgs {
+    pub(crate) fn execute(self) -> Result<()> {
+        let dir = path::absolute(shellexpand::full(&self.path)?.into_owned())?;
+
+        match self.commands {
+            Commands::List => {
+                let mut table = Table::new();
+                table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
+                table.set_header(vec!["Chain", "Block", "Contracts", "Storage", "Classes"]);
+
+                for (chain_id, block) in cached_forks(&dir)? {
+                    let stats = ForkCache::open(&dir, chain_id, block)?.stats()?;
+                    table.add_row(vec![
+                        chain_id.to_string(),
+                        block.to_string(),
+                        stats.contracts.to_string(),
+                        stats.storage.to_string(),
+                        stats.classes.to_string(),
+                    ]);
+                }
+
+                println!("{table}");
+            }
+
+            Commands::Warm { rpc_url, block, contracts, classes } => {
+                let client = Arc::new(JsonRpcClient::new(HttpTransport::new(rpc_url)));
+
+                let (chain_id, block) = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .context("failed to build tokio runtime")?
+                    .block_on(async {
+                        let chain_id = client.chain_id().await?;
+                        let block = match block {
+                            Some(block) => block,
+                            None => client.block_number().await?,
+                        };
+                        anyhow::Ok((ChainId::from(chain_id), block))
+                    })?;
+
+                let cache = ForkCache::open(&dir, chain_id, block)?;
+                let provider =
+                    ForkedProvider::new_with_cache(client, BlockHashOrNumber::Num(block), cache)?;
+                let state = provider.latest()?;
+
+                let mut classes = classes;
+                for address in contracts.into_iter().map(ContractAddress::from) {
+                    state.nonce(address)?;
+                    if let Some(hash) = state.class_hash_of_contract(address)? {
+                        classes.push(hash);
+                    }
+                }
+
+                classes.sort();
+                classes.dedup();
+                for hash in &classes {
+                    state.class(*hash)?;
+                }
+
+                let path = ForkCache::path(&dir, chain_id, block);
+                println!(
+                    "Cached {} classes of {chain_id} at block {block} in {}.",
+                    classes.len(),
+                    path.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the chain id and block number of every fork cached in `dir`.
+fn cached_forks(dir: &Path) -> Result<Vec<(ChainId, BlockNumber)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut forks = Vec::new();
+
+    for chain in fs::read_dir(dir)? {
+        let chain = chain?;
+        let Some(chain_id) = chain.file_name().to_str().and_then(|s| Felt::from_hex(s).ok()) else {
+            continue;
+        };
+
+        for block in fs::read_dir(chain.path())? {
+            let block = block?;
+            let Some(number) = block.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+
+            if !is_database_empty(block.path()) {
+                forks.push((chain_id, number));
+            }
+        }
+    }
+
+    forks.sort();
+    Ok(forks.into_iter().map(|(id, block)| (ChainId::from(id), block)).collect())
+}
@@ -0,0 +1,207 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use dialoguer::{Input, Select};
+use katana_core::constants::{DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_STRK_L1_GAS_PRICE};
+use katana_primitives::chain::ChainId;
+use katana_primitives::chain_spec::file::{ChainSpecFile, GasPricesFile};
+use katana_primitives::chain_spec::{ChainSpec, SettlementLayer};
+use katana_primitives::Felt;
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SettlementChain {
+    Ethereum,
+    Starknet,
+}
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    #[arg(long, value_name = "ID")]
+    #[arg(value_parser = ChainId::parse)]
+    #[arg(help = "The chain id, as a Cairo short string or a hex string.")]
+    id: Option<ChainId>,
+
+    #[arg(long = "settlement.chain", value_name = "CHAIN")]
+    #[arg(help = "The layer the chain settles on.")]
+    settlement_chain: Option<SettlementChain>,
+
+    #[arg(long = "settlement.rpc-url", value_name = "URL")]
+    #[arg(requires = "settlement_chain")]
+    #[arg(help = "The RPC url of the settlement layer.")]
+    settlement_rpc_url: Option<Url>,
+
+    #[arg(long = "settlement.core-contract", value_name = "ADDRESS")]
+    #[arg(requires = "settlement_chain")]
+    #[arg(help = "The address of the core contract of the chain on the settlement layer.")]
+    settlement_core_contract: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    #[arg(help = "The genesis file of the chain. If not set, the default genesis is used.")]
+    genesis: Option<PathBuf>,
+
+    #[arg(long = "eth-gas-price", value_name = "PRICE")]
+    #[arg(help = "The L1 ETH gas price. (denominated in wei)")]
+    #[arg(default_value_t = DEFAULT_ETH_L1_GAS_PRICE as u64)]
+    l1_eth_gas_price: u64,
+
+    #[arg(long = "strk-gas-price", value_name = "PRICE")]
+    #[arg(help = "The L1 STRK gas price. (denominated in fri)")]
+    #[arg(default_value_t = DEFAULT_STRK_L1_GAS_PRICE as u64)]
+    l1_strk_gas_price: u64,
+
+    #[arg(short, long, value_name = "PATH")]
+    #[arg(default_value = "chain.toml")]
+    #[arg(help = "The path to write the chain spec file to.")]
+    output: PathBuf,
+
+    #[arg(long)]
+    #[arg(help = "Fail instead of prompting for the values that are not provided.")]
+    non_interactive: bool,
+}
+
+impl InitArgs {
+    pub(crate) fn execute(self) -> Result<()> {
+        let id = match self.id {
+            Some(id) => id,
+            None if self.non_interactive => anyhow::bail!("missing chain id, see `--id`"),
+            None => prompt_chain_id()?,
+        };
+
+        let gas_prices = GasPricesFile { eth: self.l1_eth_gas_price, strk: self.l1_strk_gas_price };
+
+        let mut file = ChainSpecFile::new(id, gas_prices);
+        file.settlement = self.settlement()?;
+
+        // the paths of the chain spec are relative to it, so the genesis path is made absolute to
+        // not depend on where the file is written to
+        if let Some(genesis) = &self.genesis {
+            let path = std::path::absolute(genesis)
+                .with_context(|| format!("invalid genesis path {}", genesis.display()))?;
+            file.genesis = Some(path);
+        }
+
+        file.write(&self.output)?;
+        // loading it back checks that the chain it describes is valid
+        ChainSpec::load(&self.output)?;
+
+        println!("Chain spec written to {}", self.output.display());
+        Ok(())
+    }
+
+    fn settlement(&self) -> Result<Option<SettlementLayer>> {
+        let chain = match self.settlement_chain {
+            Some(chain) => chain,
+            None if self.non_interactive => return Ok(None),
+            None => match prompt_settlement_chain()? {
+                Some(chain) => chain,
+                None => return Ok(None),
+            },
+        };
+
+        let rpc_url = match &self.settlement_rpc_url {
+            Some(url) => url.clone(),
+            None if self.non_interactive => {
+                anyhow::bail!("missing settlement layer RPC url, see `--settlement.rpc-url`")
+            }
+            None => Input::<Url>::new().with_prompt("Settlement layer RPC url").interact_text()?,
+        };
+
+        let core_contract = match &self.settlement_core_contract {
+            Some(address) => address.clone(),
+            None if self.non_interactive => {
+                anyhow::bail!("missing core contract address, see `--settlement.core-contract`")
+            }
+            None => Input::<String>::new().with_prompt("Core contract address").interact_text()?,
+        };
+
+        let settlement = match chain {
+            SettlementChain::Ethereum => {
+                let core_contract = alloy_primitives::Address::from_str(&core_contract)
+                    .context("invalid Ethereum core contract address")?;
+                SettlementLayer::Ethereum { rpc_url, core_contract }
+            }
+            SettlementChain::Starknet => {
+                let core_contract = Felt::from_hex(&core_contract)
+                    .context("invalid Starknet core contract address")?;
+                SettlementLayer::Starknet { rpc_url, core_contract: core_contract.into() }
+            }
+        };
+
+        Ok(Some(settlement))
+    }
+}
+
+fn prompt_chain_id() -> Result<ChainId> {
+    let id = Input::<String>::new()
+        .with_prompt("Chain id")
+        .validate_with(|input: &String| ChainId::parse(input).map(|_| ()))
+        .interact_text()?;
+    Ok(ChainId::parse(&id)?)
+}
+
+fn prompt_settlement_chain() -> Result<Option<SettlementChain>> {
+    let items = ["None", "Ethereum", "Starknet"];
+    let selection =
+        Select::new().with_prompt("Settlement layer").items(&items).default(0).interact()?;
+
+    Ok(match selection {
+        1 => Some(SettlementChain::Ethereum),
+        2 => Some(SettlementChain::Starknet),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use katana_primitives::contract::ContractAddress;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: InitArgs,
+    }
+
+    #[test]
+    fn init_chain_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("chain.toml");
+
+        Cli::parse_from([
+            "init",
+            "--id",
+            "APPCHAIN",
+            "--settlement.chain",
+            "starknet",
+            "--settlement.rpc-url",
+            "http://localhost:5050",
+            "--settlement.core-contract",
+            "0x1234",
+            "--output",
+            output.to_str().unwrap(),
+            "--non-interactive",
+        ])
+        .args
+        .execute()
+        .unwrap();
+
+        let chain = ChainSpec::load(&output).unwrap();
+        assert_eq!(chain.id, ChainId::parse("APPCHAIN").unwrap());
+        assert_eq!(chain.genesis.gas_prices.eth, DEFAULT_ETH_L1_GAS_PRICE);
+        assert_eq!(
+            chain.settlement,
+            Some(SettlementLayer::Starknet {
+                rpc_url: "http://localhost:5050".parse().unwrap(),
+                core_contract: ContractAddress::from(Felt::from(0x1234)),
+            })
+        );
+
+        let result = Cli::parse_from(["init", "--non-interactive"]).args.execute();
+        assert!(result.is_err());
+    }
+}
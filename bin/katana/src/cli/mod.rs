@@ -1,5 +1,8 @@
 mod db;
+mod fork_cache;
+mod init;
 mod node;
+mod replay;
 
 use anyhow::Result;
 use clap::{Args, CommandFactory, Parser, Subcommand};
@@ -22,6 +25,9 @@ impl Cli {
             return match cmd {
                 Commands::Completions(args) => args.execute(),
                 Commands::Db(args) => args.execute(),
+                Commands::ForkCache(args) => args.execute(),
+                Commands::Init(args) => args.execute(),
+                Commands::Replay(args) => args.execute(),
             };
         }
 
@@ -36,6 +42,15 @@ enum Commands {
 
     #[command(about = "Database utilities")]
     Db(db::DbArgs),
+
+    #[command(about = "Fork cache utilities")]
+    ForkCache(fork_cache::ForkCacheArgs),
+
+    #[command(about = "Initialize a new chain spec file")]
+    Init(init::InitArgs),
+
+    #[command(about = "Re-execute stored blocks and report mismatches with their stored results")]
+    Replay(replay::ReplayArgs),
 }
 
 #[derive(Debug, Args)]
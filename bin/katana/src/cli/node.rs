@@ -11,10 +11,14 @@
 //!   for more info.
 
 use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufWriter;
 use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use anyhow::{Context, Result};
 use clap::{Args, Parser};
 use console::Style;
@@ -23,20 +27,29 @@ use katana_core::constants::{
     DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_SEQUENCER_ADDRESS, DEFAULT_STRK_L1_GAS_PRICE,
 };
 use katana_core::service::messaging::MessagingConfig;
+use katana_executor::BlockLimits;
 use katana_node::config::db::DbConfig;
 use katana_node::config::dev::DevConfig;
 use katana_node::config::execution::{
     ExecutionConfig, DEFAULT_INVOCATION_MAX_STEPS, DEFAULT_VALIDATION_MAX_STEPS,
 };
 use katana_node::config::fork::ForkingConfig;
+use katana_node::config::gas_oracle::GasOracleConfig;
 use katana_node::config::metrics::MetricsConfig;
+use katana_node::config::pool::PoolConfig;
 use katana_node::config::rpc::{
-    ApiKind, RpcConfig, DEFAULT_RPC_ADDR, DEFAULT_RPC_MAX_CONNECTIONS, DEFAULT_RPC_PORT,
+    ApiKind, RpcConfig, DEFAULT_RPC_ADDR, DEFAULT_RPC_MAX_CONNECTIONS,
+    DEFAULT_RPC_MAX_REQUEST_BODY_SIZE, DEFAULT_RPC_PORT,
 };
+use katana_node::config::sync::{SyncConfig, DEFAULT_SYNC_INTERVAL};
 use katana_node::config::{Config, SequencingConfig};
+use katana_pool::ordering::OrderingKind;
+use katana_pool::pool::{
+    PoolLimits, DEFAULT_MAX_QUEUED_PER_ACCOUNT, DEFAULT_MAX_TXS, DEFAULT_REPLACEMENT_TIP_BUMP,
+};
 use katana_primitives::block::BlockHashOrNumber;
 use katana_primitives::chain::ChainId;
-use katana_primitives::chain_spec::{self, ChainSpec};
+use katana_primitives::chain_spec::{self, ChainSpec, SettlementLayer};
 use katana_primitives::class::ClassHash;
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::genesis::allocation::{DevAllocationsGenerator, GenesisAccountAlloc};
@@ -45,12 +58,15 @@ use katana_primitives::genesis::constant::{
     DEFAULT_PREFUNDED_ACCOUNT_BALANCE, DEFAULT_UDC_ADDRESS,
 };
 use katana_primitives::genesis::Genesis;
+use katana_primitives::Felt;
 use tracing::{info, Subscriber};
 use tracing_log::LogTracer;
 use tracing_subscriber::{fmt, EnvFilter};
 use url::Url;
 
-use crate::utils::{parse_block_hash_or_number, parse_genesis, parse_seed};
+use crate::utils::{
+    parse_block_hash_or_number, parse_chain_spec, parse_genesis, parse_ordering, parse_seed,
+};
 
 #[derive(Parser, Debug)]
 pub struct NodeArgs {
@@ -68,6 +84,15 @@ pub struct NodeArgs {
     #[arg(help = "Block time in milliseconds for interval mining.")]
     pub block_time: Option<u64>,
 
+    #[arg(long)]
+    #[arg(help = "Compute the EIP-4844 blobs of the state diff of every produced block.")]
+    #[arg(long_help = "Compute the EIP-4844 blobs of the state diff of every produced block, \
+                       along with their KZG commitments, as they would be posted to the \
+                       settlement chain. The blobs are served by the \
+                       `katana_getBlockStateDiffBlob` RPC method. Computing them slows down \
+                       block production.")]
+    pub state_diff_blobs: bool,
+
     #[arg(long)]
     #[arg(value_name = "PATH")]
     #[arg(help = "Directory path of the database to initialize from.")]
@@ -87,6 +112,64 @@ pub struct NodeArgs {
     #[arg(value_parser = parse_block_hash_or_number)]
     pub fork_block: Option<BlockHashOrNumber>,
 
+    #[arg(long = "fork.cache-dir", value_name = "PATH")]
+    #[arg(requires = "fork_rpc_url")]
+    #[arg(help = "Directory of the persistent cache of the data fetched from the forked network.")]
+    #[arg(long_help = "Directory of the persistent cache of the data fetched from the forked \
+                       network. The cached data is reused by every run forking the same network \
+                       at the same block. The cache can be inspected and pre-warmed with the \
+                       `katana fork-cache` command.")]
+    pub fork_cache_dir: Option<PathBuf>,
+
+    #[arg(long = "fork.follow", value_name = "SECONDS")]
+    #[arg(requires = "fork_rpc_url", conflicts_with = "fork_block")]
+    #[arg(help = "Periodically rebase onto the head of the forked network.")]
+    #[arg(long_help = "Fork the network at its latest block, and check for a newer head every \
+                       SECONDS seconds. When the head has moved, the chain is rebased onto it: \
+                       the locally produced transactions are executed again on top of the new \
+                       head, and those whose outcome changed are reported as conflicts.")]
+    pub fork_follow: Option<u64>,
+
+    #[arg(long = "sync.rpc-url", value_name = "URL")]
+    #[arg(conflicts_with_all(["fork_rpc_url", "block_time", "no_mining", "genesis"]))]
+    #[arg(help = "The Starknet RPC provider to sync the chain from.")]
+    #[arg(long_help = "The Starknet RPC provider to sync the chain from. The node runs as a \
+                       read-only replica of the network, following its blocks instead of \
+                       producing its own.")]
+    pub sync_rpc_url: Option<Url>,
+
+    #[arg(long = "sync.interval", value_name = "SECONDS")]
+    #[arg(requires = "sync_rpc_url")]
+    #[arg(help = "The interval at which the synced network is polled for new blocks.")]
+    pub sync_interval: Option<u64>,
+
+    #[arg(long = "dump-state", value_name = "PATH")]
+    #[arg(help = "Dump the state of the chain to a file when the node is shut down.")]
+    #[arg(long_help = "Dump the state of the chain to a file when the node is shut down. The \
+                       state is written in the genesis file format, and can be loaded back with \
+                       `--load-state` or used as the genesis file of another node.")]
+    pub dump_state: Option<PathBuf>,
+
+    #[arg(long = "load-state", value_name = "PATH")]
+    #[arg(value_parser = parse_genesis)]
+    #[arg(conflicts_with_all(["genesis", "fork_rpc_url", "sync_rpc_url", "seed", "total_accounts"]))]
+    #[arg(help = "Start the chain from a state dumped with `--dump-state` or `dev_dumpState`.")]
+    pub load_state: Option<Genesis>,
+
+    #[arg(long = "chain", value_name = "PATH")]
+    #[arg(value_parser = parse_chain_spec)]
+    #[arg(conflicts_with_all([
+        "genesis", "chain_id", "load_state", "fork_rpc_url", "sync_rpc_url", "l1_eth_gas_price",
+        "l1_strk_gas_price", "total_accounts", "seed"
+    ]))]
+    #[arg(help = "Path to the chain spec file of the chain to run.")]
+    #[arg(long_help = "Path to the chain spec file of the chain to run, as generated by `katana \
+                       init`. The chain id, genesis, fee tokens, gas prices and settlement \
+                       layer of the chain are all taken from it, and no dev account is \
+                       generated. The messaging configuration, if any, must use the settlement \
+                       layer of the chain.")]
+    pub chain: Option<ChainSpec>,
+
     #[arg(long)]
     pub dev: bool,
 
@@ -117,6 +200,18 @@ pub struct NodeArgs {
     #[command(next_help_heading = "Starknet options")]
     pub starknet: StarknetOptions,
 
+    #[command(flatten)]
+    #[command(next_help_heading = "Transaction pool options")]
+    pub txpool: TxPoolOptions,
+
+    #[command(flatten)]
+    #[command(next_help_heading = "Block limits options")]
+    pub block_limits: BlockLimitsOptions,
+
+    #[command(flatten)]
+    #[command(next_help_heading = "Gas price oracle options")]
+    pub gas_oracle: GasOracleOptions,
+
     #[cfg(feature = "slot")]
     #[command(flatten)]
     #[command(next_help_heading = "Slot options")]
@@ -140,10 +235,24 @@ pub struct ServerOptions {
     #[arg(help = "Maximum number of concurrent connections allowed.")]
     pub max_connections: u32,
 
+    #[arg(long)]
+    #[arg(value_name = "BYTES")]
+    #[arg(default_value_t = DEFAULT_RPC_MAX_REQUEST_BODY_SIZE)]
+    #[arg(help = "Maximum size of the body of a request.")]
+    pub max_request_body_size: u32,
+
     #[arg(long)]
     #[arg(value_delimiter = ',')]
     #[arg(help = "Enables the CORS layer and sets the allowed origins, separated by commas.")]
     pub allowed_origins: Option<Vec<String>>,
+
+    #[arg(long = "rpc.api", value_name = "API")]
+    #[arg(value_delimiter = ',')]
+    #[arg(help = "Additional RPC namespaces to enable, separated by commas.")]
+    #[arg(long_help = "Additional RPC namespaces to enable, separated by commas. The `admin` \
+                       namespace, which reloads the transaction policy, is unauthenticated and \
+                       must be enabled explicitly.")]
+    pub apis: Vec<ApiKind>,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -167,6 +276,11 @@ pub struct StarknetOptions {
     #[arg(help = "Disable validation when executing transactions.")]
     pub disable_validate: bool,
 
+    #[arg(long, value_name = "WORKERS")]
+    #[arg(help = "Execute the transactions of a block optimistically in parallel, on the given \
+                  number of threads.")]
+    pub parallel_execution: Option<NonZeroUsize>,
+
     #[command(flatten)]
     #[command(next_help_heading = "Environment options")]
     pub environment: EnvironmentOptions,
@@ -210,6 +324,98 @@ pub struct EnvironmentOptions {
     pub l1_strk_gas_price: u128,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct TxPoolOptions {
+    #[arg(long = "txpool.ordering", value_name = "ORDERING")]
+    #[arg(default_value = "fifo")]
+    #[arg(value_parser = parse_ordering)]
+    #[arg(
+        help = "The order in which pending transactions are included in a block: `fifo` or `tip`."
+    )]
+    #[arg(long_help = "The order in which pending transactions are included in a block. With \
+                       `fifo`, transactions are included in the order they were received. With \
+                       `tip`, transactions with higher tips are included first. In both cases, \
+                       the transactions of a single account are included in nonce order.")]
+    pub ordering: OrderingKind,
+
+    #[arg(long = "txpool.max-txs", value_name = "NUM")]
+    #[arg(default_value_t = DEFAULT_MAX_TXS)]
+    #[arg(help = "The maximum number of transactions in the pool.")]
+    pub max_txs: usize,
+
+    #[arg(long = "txpool.max-queued-per-account", value_name = "NUM")]
+    #[arg(default_value_t = DEFAULT_MAX_QUEUED_PER_ACCOUNT)]
+    #[arg(
+        help = "The maximum number of transactions of a single account waiting for a lower nonce."
+    )]
+    pub max_queued_per_account: usize,
+
+    #[arg(long = "txpool.ttl", value_name = "SECONDS")]
+    #[arg(help = "Drop transactions that have been in the pool for longer than SECONDS seconds.")]
+    pub ttl: Option<u64>,
+
+    #[arg(long = "txpool.price-bump", value_name = "PERCENT")]
+    #[arg(default_value_t = DEFAULT_REPLACEMENT_TIP_BUMP)]
+    #[arg(help = "The minimum tip increase, in percent, to replace a transaction with the same \
+                  sender and nonce.")]
+    pub price_bump: u64,
+
+    #[arg(long = "txpool.policy", value_name = "PATH")]
+    #[arg(help = "Only accept the transactions permitted by the policy file at PATH.")]
+    #[arg(long_help = "Only accept the transactions permitted by the policy file at PATH. The \
+                       policy restricts the accounts that can send transactions, the contracts \
+                       and entry points they can call, and the accounts that can declare \
+                       classes. The policy file can be reloaded without restarting the node \
+                       through the `admin` RPC namespace, enabled with `--rpc.api admin`.")]
+    pub policy: Option<PathBuf>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct BlockLimitsOptions {
+    #[arg(long = "block.max-steps", value_name = "NUM")]
+    #[arg(help = "The maximum number of Cairo steps in a block.")]
+    pub max_steps: Option<u64>,
+
+    #[arg(long = "block.max-builtins", value_name = "NUM")]
+    #[arg(help = "The maximum number of builtin instances in a block, across all builtins.")]
+    pub max_builtins: Option<u64>,
+
+    #[arg(long = "block.max-l1-gas", value_name = "GAS")]
+    #[arg(help = "The maximum amount of L1 gas used by the transactions of a block.")]
+    pub max_l1_gas: Option<u64>,
+
+    #[arg(long = "block.max-l1-data-gas", value_name = "GAS")]
+    #[arg(help = "The maximum amount of L1 data (blob) gas used by the transactions of a block.")]
+    pub max_l1_data_gas: Option<u64>,
+
+    #[arg(long = "block.max-state-diff-size", value_name = "NUM")]
+    #[arg(help = "The maximum number of state diff entries of a block.")]
+    pub max_state_diff_size: Option<u64>,
+
+    #[arg(long = "block.max-txs", value_name = "NUM")]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    #[arg(help = "The maximum number of transactions in a block.")]
+    pub max_txs: Option<u64>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct GasOracleOptions {
+    #[arg(long = "gpo.l1-sample-interval", value_name = "SECONDS")]
+    #[arg(requires = "messaging")]
+    #[arg(help = "Sample the L1 gas prices from the messaging settlement chain every SECONDS \
+                  seconds, instead of using fixed prices.")]
+    pub l1_sample_interval: Option<u64>,
+
+    #[arg(long = "gpo.l2-target-gas", value_name = "GAS")]
+    #[arg(help = "Adjust the gas prices of every block based on how much L1 gas the previous \
+                  block used compared to GAS, EIP-1559 style.")]
+    #[arg(long_help = "Adjust the gas prices of every block based on how much L1 gas the \
+                       previous block used compared to GAS, EIP-1559 style. The prices go up by \
+                       at most 1/8th per block when blocks use more than GAS, and go back down \
+                       to the L1 prices when they use less.")]
+    pub l2_target_gas: Option<u128>,
+}
+
 #[cfg(feature = "slot")]
 #[derive(Debug, Args, Clone)]
 pub struct SlotOptions {
@@ -235,7 +441,8 @@ impl NodeArgs {
         let config = self.config()?;
         let node = katana_node::build(config).await.context("failed to build node")?;
 
-        if !self.silent {
+        // the dev accounts aren't part of a synced chain
+        if !self.silent && self.sync_rpc_url.is_none() {
             print_intro(&self, &node.backend.chain_spec);
         }
 
@@ -254,6 +461,13 @@ impl NodeArgs {
 
         info!("Shutting down.");
 
+        if let Some(path) = &self.dump_state {
+            let state = handle.node.backend.dump_state().context("failed to dump state")?;
+            let file = File::create(path).context("failed to create state dump file")?;
+            serde_json::to_writer_pretty(BufWriter::new(file), &state)?;
+            info!(target: LOG_TARGET, path = %path.display(), "Dumped state.");
+        }
+
         Ok(())
     }
 
@@ -284,44 +498,97 @@ impl NodeArgs {
         let chain = self.chain_spec()?;
         let metrics = self.metrics_config();
         let forking = self.forking_config()?;
+        let sync = self.sync_config();
         let execution = self.execution_config();
         let sequencing = self.sequencer_config();
-        let messaging = self.messaging.clone();
-
-        Ok(Config { metrics, db, dev, rpc, chain, execution, sequencing, messaging, forking })
+        let messaging = self.messaging_config(&chain)?;
+        let pool = self.pool_config();
+        let gas_oracle = self.gas_oracle_config();
+
+        Ok(Config {
+            metrics,
+            db,
+            dev,
+            rpc,
+            chain,
+            execution,
+            sequencing,
+            messaging,
+            forking,
+            sync,
+            pool,
+            gas_oracle,
+        })
     }
 
     fn sequencer_config(&self) -> SequencingConfig {
-        SequencingConfig { block_time: self.block_time, no_mining: self.no_mining }
+        let limits = &self.block_limits;
+        let block_limits = BlockLimits {
+            n_steps: limits.max_steps.unwrap_or(u64::MAX),
+            builtin_count: limits.max_builtins.unwrap_or(u64::MAX),
+            l1_gas: limits.max_l1_gas.unwrap_or(u64::MAX),
+            l1_data_gas: limits.max_l1_data_gas.unwrap_or(u64::MAX),
+            state_diff_size: limits.max_state_diff_size.unwrap_or(u64::MAX),
+            tx_count: limits.max_txs.unwrap_or(u64::MAX),
+        };
+
+        SequencingConfig {
+            block_time: self.block_time,
+            no_mining: self.no_mining,
+            block_limits,
+            state_diff_blobs: self.state_diff_blobs,
+        }
     }
 
     fn rpc_config(&self) -> RpcConfig {
-        let mut apis = HashSet::from([ApiKind::Starknet, ApiKind::Torii, ApiKind::Saya]);
+        let mut apis = HashSet::from([
+            ApiKind::Starknet,
+            ApiKind::Torii,
+            ApiKind::Saya,
+            ApiKind::TxPool,
+            ApiKind::Katana,
+        ]);
         // only enable `katana` API in dev mode
         if self.dev {
             apis.insert(ApiKind::Dev);
         }
+        apis.extend(self.server.apis.iter().copied());
 
         RpcConfig {
             apis,
             port: self.server.port,
             addr: self.server.host,
             max_connections: self.server.max_connections,
+            max_request_body_size: self.server.max_request_body_size,
             allowed_origins: self.server.allowed_origins.clone(),
         }
     }
 
     fn chain_spec(&self) -> Result<ChainSpec> {
+        // the chain spec file defines the whole chain, including its accounts and sequencer
+        if let Some(chain) = self.chain.clone() {
+            return Ok(chain);
+        }
+
         let mut chain_spec = chain_spec::DEV_UNALLOCATED.clone();
 
         if let Some(id) = self.starknet.environment.chain_id {
             chain_spec.id = id;
         }
 
+        // the dumped state already contains the accounts, so none is generated
+        if let Some(genesis) = self.load_state.clone() {
+            chain_spec.genesis = genesis;
+            return Ok(chain_spec);
+        }
+
         if let Some(genesis) = self.starknet.genesis.clone() {
             chain_spec.genesis = genesis;
         }
 
+        chain_spec.genesis.gas_prices.eth = self.starknet.environment.l1_eth_gas_price;
+        chain_spec.genesis.gas_prices.strk = self.starknet.environment.l1_strk_gas_price;
+
         // generate dev accounts
         let accounts = DevAllocationsGenerator::new(self.starknet.total_accounts)
             .with_seed(parse_seed(&self.starknet.seed))
@@ -330,8 +597,6 @@ impl NodeArgs {
 
         chain_spec.genesis.extend_allocations(accounts.into_iter().map(|(k, v)| (k, v.into())));
         chain_spec.genesis.sequencer_address = *DEFAULT_SEQUENCER_ADDRESS;
-        chain_spec.genesis.gas_prices.eth = self.starknet.environment.l1_eth_gas_price;
-        chain_spec.genesis.gas_prices.strk = self.starknet.environment.l1_strk_gas_price;
 
         #[cfg(feature = "slot")]
         if self.slot.controller {
@@ -341,6 +606,36 @@ impl NodeArgs {
         Ok(chain_spec)
     }
 
+    /// Returns the messaging configuration, which must use the settlement layer of the chain, if
+    /// it has one.
+    fn messaging_config(&self, chain: &ChainSpec) -> Result<Option<MessagingConfig>> {
+        let (Some(messaging), Some(settlement)) = (&self.messaging, &chain.settlement) else {
+            return Ok(self.messaging.clone());
+        };
+
+        let rpc_url = Url::parse(&messaging.rpc_url).ok();
+        let contract = &messaging.contract_address;
+        let is_settlement = rpc_url.as_ref() == Some(settlement.rpc_url())
+            && match settlement {
+                SettlementLayer::Ethereum { core_contract, .. } => {
+                    messaging.chain == "ethereum"
+                        && contract.parse::<Address>().is_ok_and(|c| c == *core_contract)
+                }
+                SettlementLayer::Starknet { core_contract, .. } => {
+                    messaging.chain == "starknet"
+                        && Felt::from_hex(contract).is_ok_and(|c| c == core_contract.0)
+                }
+            };
+
+        anyhow::ensure!(
+            is_settlement,
+            "The messaging configuration doesn't use the settlement layer of the chain: \
+             {settlement:?}"
+        );
+
+        Ok(Some(messaging.clone()))
+    }
+
     fn dev_config(&self) -> DevConfig {
         DevConfig {
             fee: !self.starknet.disable_fee,
@@ -352,18 +647,48 @@ impl NodeArgs {
         ExecutionConfig {
             invocation_max_steps: self.starknet.environment.invoke_max_steps,
             validation_max_steps: self.starknet.environment.validate_max_steps,
+            parallel_workers: self.starknet.parallel_execution,
             ..Default::default()
         }
     }
 
     fn forking_config(&self) -> Result<Option<ForkingConfig>> {
         if let Some(url) = self.fork_rpc_url.clone() {
-            Ok(Some(ForkingConfig { url, block: self.fork_block }))
+            let cache_dir = self.fork_cache_dir.clone();
+            let follow = self.fork_follow.map(Duration::from_secs);
+            Ok(Some(ForkingConfig { url, block: self.fork_block, cache_dir, follow }))
         } else {
             Ok(None)
         }
     }
 
+    fn sync_config(&self) -> Option<SyncConfig> {
+        self.sync_rpc_url.clone().map(|url| SyncConfig {
+            url,
+            interval: self.sync_interval.map_or(DEFAULT_SYNC_INTERVAL, Duration::from_secs),
+        })
+    }
+
+    fn pool_config(&self) -> PoolConfig {
+        PoolConfig {
+            ordering: self.txpool.ordering,
+            limits: PoolLimits {
+                max_txs: self.txpool.max_txs,
+                max_queued_per_account: self.txpool.max_queued_per_account,
+                tx_ttl: self.txpool.ttl.map(Duration::from_secs),
+                replacement_tip_bump: self.txpool.price_bump,
+            },
+            policy_file: self.txpool.policy.clone(),
+        }
+    }
+
+    fn gas_oracle_config(&self) -> GasOracleConfig {
+        GasOracleConfig {
+            l1_sampling: self.gas_oracle.l1_sample_interval.map(Duration::from_secs),
+            l2_target_gas: self.gas_oracle.l2_target_gas,
+        }
+    }
+
     fn db_config(&self) -> DbConfig {
         DbConfig { dir: self.db_dir.clone() }
     }
@@ -487,6 +812,8 @@ PREFUNDED ACCOUNTS
 
 #[cfg(test)]
 mod test {
+    use katana_primitives::chain_spec::file::{ChainSpecFile, GasPricesFile};
+
     use super::*;
 
     #[test]
@@ -497,8 +824,10 @@ mod test {
         assert!(config.dev.fee);
         assert!(config.dev.account_validation);
         assert!(config.forking.is_none());
+        assert!(config.sync.is_none());
         assert_eq!(config.execution.invocation_max_steps, DEFAULT_INVOCATION_MAX_STEPS);
         assert_eq!(config.execution.validation_max_steps, DEFAULT_VALIDATION_MAX_STEPS);
+        assert_eq!(config.execution.parallel_workers, None);
         assert_eq!(config.db.dir, None);
         assert_eq!(config.chain.id, ChainId::parse("KATANA").unwrap());
         assert_eq!(config.chain.genesis.gas_prices.eth, DEFAULT_ETH_L1_GAS_PRICE);
@@ -518,6 +847,8 @@ mod test {
             "200",
             "--validate-max-steps",
             "100",
+            "--parallel-execution",
+            "4",
             "--db-dir",
             "/path/to/db",
             "--eth-gas-price",
@@ -531,9 +862,254 @@ mod test {
         assert!(!config.dev.account_validation);
         assert_eq!(config.execution.invocation_max_steps, 200);
         assert_eq!(config.execution.validation_max_steps, 100);
+        assert_eq!(config.execution.parallel_workers, NonZeroUsize::new(4));
         assert_eq!(config.db.dir, Some(PathBuf::from("/path/to/db")));
         assert_eq!(config.chain.id, ChainId::GOERLI);
         assert_eq!(config.chain.genesis.gas_prices.eth, 10);
         assert_eq!(config.chain.genesis.gas_prices.strk, 20);
     }
+
+    #[test]
+    fn test_sync_config() {
+        let args = NodeArgs::parse_from([
+            "katana",
+            "--sync.rpc-url",
+            "http://localhost:5050",
+            "--sync.interval",
+            "10",
+        ]);
+        let config = args.config().unwrap();
+
+        let sync = config.sync.unwrap();
+        assert_eq!(sync.url.as_str(), "http://localhost:5050/");
+        assert_eq!(sync.interval, Duration::from_secs(10));
+
+        let result = NodeArgs::try_parse_from([
+            "katana",
+            "--sync.rpc-url",
+            "http://localhost:5050",
+            "--fork.rpc-url",
+            "http://localhost:5051",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_fork_follow_config() {
+        let args = NodeArgs::parse_from([
+            "katana",
+            "--fork.rpc-url",
+            "http://localhost:5050",
+            "--fork.follow",
+            "30",
+        ]);
+        let config = args.config().unwrap();
+
+        let forking = config.forking.unwrap();
+        assert!(forking.block.is_none());
+        assert_eq!(forking.follow, Some(Duration::from_secs(30)));
+
+        let result = NodeArgs::try_parse_from([
+            "katana",
+            "--fork.rpc-url",
+            "http://localhost:5050",
+            "--fork.block",
+            "100",
+            "--fork.follow",
+            "30",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_txpool_config() {
+        let args = NodeArgs::parse_from(["katana"]);
+        let config = args.config().unwrap();
+        assert_eq!(config.pool.ordering, OrderingKind::FiFo);
+        assert_eq!(config.pool.limits, PoolLimits::default());
+        assert!(config.rpc.apis.contains(&ApiKind::TxPool));
+        assert!(config.rpc.apis.contains(&ApiKind::Katana));
+
+        let args = NodeArgs::parse_from([
+            "katana",
+            "--txpool.ordering",
+            "tip",
+            "--txpool.max-txs",
+            "100",
+            "--txpool.max-queued-per-account",
+            "5",
+            "--txpool.ttl",
+            "60",
+            "--txpool.price-bump",
+            "20",
+        ]);
+        let config = args.config().unwrap();
+
+        assert_eq!(config.pool.ordering, OrderingKind::Tip);
+        assert_eq!(config.pool.limits.max_txs, 100);
+        assert_eq!(config.pool.limits.max_queued_per_account, 5);
+        assert_eq!(config.pool.limits.tx_ttl, Some(Duration::from_secs(60)));
+        assert_eq!(config.pool.limits.replacement_tip_bump, 20);
+
+        let result = NodeArgs::try_parse_from(["katana", "--txpool.ordering", "random"]);
+        assert!(result.is_err());
+
+        assert!(!config.rpc.apis.contains(&ApiKind::Admin));
+        let args = NodeArgs::parse_from(["katana", "--txpool.policy", "./policy.toml"]);
+        let config = args.config().unwrap();
+        assert_eq!(config.pool.policy_file, Some(PathBuf::from("./policy.toml")));
+        // the unauthenticated `admin` API must be enabled explicitly
+        assert!(!config.rpc.apis.contains(&ApiKind::Admin));
+
+        let args = NodeArgs::parse_from([
+            "katana",
+            "--txpool.policy",
+            "./policy.toml",
+            "--rpc.api",
+            "admin",
+        ]);
+        let config = args.config().unwrap();
+        assert!(config.rpc.apis.contains(&ApiKind::Admin));
+    }
+
+    #[test]
+    fn test_block_limits_config() {
+        let args = NodeArgs::parse_from(["katana"]);
+        let config = args.config().unwrap();
+        assert_eq!(config.sequencing.block_limits, BlockLimits::max());
+
+        let args = NodeArgs::parse_from([
+            "katana",
+            "--block-time",
+            "1000",
+            "--block.max-steps",
+            "1000000",
+            "--block.max-txs",
+            "10",
+        ]);
+        let config = args.config().unwrap();
+
+        assert_eq!(config.sequencing.block_limits.n_steps, 1_000_000);
+        assert_eq!(config.sequencing.block_limits.tx_count, 10);
+        assert_eq!(config.sequencing.block_limits.l1_gas, u64::MAX);
+        assert!(!config.sequencing.state_diff_blobs);
+
+        // a block must be able to hold at least one transaction
+        assert!(NodeArgs::try_parse_from(["katana", "--block.max-txs", "0"]).is_err());
+
+        let args = NodeArgs::parse_from(["katana", "--state-diff-blobs"]);
+        let config = args.config().unwrap();
+        assert!(config.sequencing.state_diff_blobs);
+    }
+
+    #[test]
+    fn test_gas_oracle_config() {
+        let args = NodeArgs::parse_from(["katana"]);
+        let config = args.config().unwrap();
+        assert_eq!(config.gas_oracle.l1_sampling, None);
+        assert_eq!(config.gas_oracle.l2_target_gas, None);
+
+        let args = NodeArgs::parse_from(["katana", "--gpo.l2-target-gas", "1000000"]);
+        let config = args.config().unwrap();
+        assert_eq!(config.gas_oracle.l2_target_gas, Some(1_000_000));
+
+        // sampling the L1 prices requires a settlement chain
+        let result = NodeArgs::try_parse_from(["katana", "--gpo.l1-sample-interval", "10"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_load_state_config() {
+        let args =
+            NodeArgs::parse_from(["katana", "--load-state", "./tests/test-data/genesis.json"]);
+        let config = args.config().unwrap();
+
+        // no dev accounts are generated on top of the loaded state
+        assert_eq!(config.chain.genesis.accounts().count(), 1);
+
+        let result = NodeArgs::try_parse_from([
+            "katana",
+            "--load-state",
+            "./tests/test-data/genesis.json",
+            "--genesis",
+            "./tests/test-data/genesis.json",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_chain_spec_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.toml");
+
+        let mut file = ChainSpecFile::new(
+            ChainId::parse("APPCHAIN").unwrap(),
+            GasPricesFile { eth: 10, strk: 20 },
+        );
+        file.genesis = Some(std::fs::canonicalize("./tests/test-data/genesis.json").unwrap());
+        file.write(&path).unwrap();
+
+        let path = path.to_str().unwrap();
+        let args = NodeArgs::parse_from(["katana", "--chain", path]);
+        let config = args.config().unwrap();
+
+        assert_eq!(config.chain.id, ChainId::parse("APPCHAIN").unwrap());
+        assert_eq!(config.chain.genesis.gas_prices.eth, 10);
+        assert_eq!(config.chain.genesis.gas_prices.strk, 20);
+        // the chain is used as is, without any dev account
+        assert_eq!(config.chain.genesis.accounts().count(), 1);
+        assert_eq!(config.chain.genesis.sequencer_address, Felt::from(0x100u64).into());
+
+        let result =
+            NodeArgs::try_parse_from(["katana", "--chain", path, "--chain-id", "SN_GOERLI"]);
+        assert!(result.is_err());
+        let result = NodeArgs::try_parse_from(["katana", "--chain", path, "--accounts", "2"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_chain_spec_settlement() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain_path = dir.path().join("chain.toml");
+
+        let mut file = ChainSpecFile::new(
+            ChainId::parse("APPCHAIN").unwrap(),
+            GasPricesFile { eth: 10, strk: 20 },
+        );
+        file.settlement = Some(SettlementLayer::Starknet {
+            rpc_url: Url::parse("http://localhost:5050").unwrap(),
+            core_contract: Felt::from(0x1234u64).into(),
+        });
+        file.write(&chain_path).unwrap();
+
+        let messaging = |rpc_url: &str| {
+            let path = dir.path().join("messaging.json");
+            let config = serde_json::json!({
+                "chain": "starknet",
+                "rpc_url": rpc_url,
+                "contract_address": "0x1234",
+                "sender_address": "0x1",
+                "private_key": "0x1",
+                "interval": 2,
+                "from_block": 0
+            });
+            std::fs::write(&path, config.to_string()).unwrap();
+
+            let chain = chain_path.to_str().unwrap();
+            let args = NodeArgs::parse_from([
+                "katana",
+                "--chain",
+                chain,
+                "--messaging",
+                path.to_str().unwrap(),
+            ]);
+            args.config()
+        };
+
+        let config = messaging("http://localhost:5050").unwrap();
+        assert_eq!(config.messaging.unwrap().contract_address, "0x1234");
+
+        // the messaging must be done with the settlement layer of the chain
+        assert!(messaging("http://localhost:6060").is_err());
+    }
 }
@@ -0,0 +1,489 @@
+use std::fmt;
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::Args;
+use katana_executor::implementation::blockifier::BlockifierFactory;
+use katana_executor::{ExecutionFlags, ExecutionResult, ExecutorFactory};
+use katana_node::config::execution::{
+    DEFAULT_INVOCATION_MAX_STEPS, DEFAULT_VALIDATION_MAX_STEPS, MAX_RECURSION_DEPTH,
+};
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber, ExecutableBlock, PartialHeader};
+use katana_primitives::chain::ChainId;
+use katana_primitives::chain_spec::{self, ChainSpec};
+use katana_primitives::env::{CfgEnv, FeeTokenAddressses};
+use katana_primitives::fee::TxFeeInfo;
+use katana_primitives::state::StateUpdates;
+use katana_primitives::transaction::{
+    DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, Tx, TxHash,
+};
+use katana_provider::error::ProviderError;
+use katana_provider::providers::db::{DbProvider, PRUNE_CHECKPOINT};
+use katana_provider::traits::block::{BlockNumberProvider, HeaderProvider};
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::stage::StageCheckpointProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
+use katana_provider::traits::transaction::{
+    ReceiptProvider, TransactionProvider, TransactionTraceProvider,
+};
+
+use super::db::open_db_ro;
+use crate::utils::parse_chain_spec;
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    #[arg(long, value_name = "PATH")]
+    #[arg(default_value = "~/.katana/db")]
+    #[arg(help = "Path to the database directory of the chain to replay.")]
+    db: String,
+
+    #[arg(long, value_name = "BLOCK")]
+    #[arg(default_value_t = 1)]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    #[arg(help = "The first block to replay.")]
+    from: BlockNumber,
+
+    #[arg(long, value_name = "BLOCK")]
+    #[arg(help = "The last block to replay. Defaults to the latest block.")]
+    to: Option<BlockNumber>,
+
+    #[arg(long, value_name = "PATH")]
+    #[arg(value_parser = parse_chain_spec)]
+    #[arg(help = "The chain spec file of the chain, if it was started with `--chain`.")]
+    chain: Option<ChainSpec>,
+
+    #[arg(long)]
+    #[arg(conflicts_with = "chain", required_unless_present = "chain")]
+    #[arg(value_parser = ChainId::parse)]
+    #[arg(help = "The chain ID the chain was started with, if it wasn't started with `--chain`.")]
+    chain_id: Option<ChainId>,
+
+    #[arg(long)]
+    #[arg(help = "Disable charging fee, if the chain was started with `--disable-fee`.")]
+    disable_fee: bool,
+
+    #[arg(long)]
+    #[arg(help = "Disable validation, if the chain was started with `--disable-validate`.")]
+    disable_validate: bool,
+
+    #[arg(long)]
+    #[arg(default_value_t = DEFAULT_VALIDATION_MAX_STEPS)]
+    #[arg(help = "The maximum number of steps available for the account validation logic.")]
+    validate_max_steps: u32,
+
+    #[arg(long)]
+    #[arg(default_value_t = DEFAULT_INVOCATION_MAX_STEPS)]
+    #[arg(help = "The maximum number of steps available for the account execution logic.")]
+    invoke_max_steps: u32,
+}
+
+impl ReplayArgs {
+    pub(crate) fn execute(self) -> Result<()> {
+        let provider = DbProvider::new(open_db_ro(&self.db)?);
+
+        let latest = provider.latest_number()?;
+        let to = self.to.unwrap_or(latest);
+        ensure!(to <= latest, "Block {to} is after the latest block {latest}");
+        ensure!(self.from <= to, "The first block {} is after the last block {to}", self.from);
+
+        // a block is replayed on top of the state of its parent
+        if let Some(horizon) = provider.checkpoint(PRUNE_CHECKPOINT)? {
+            ensure!(
+                self.from > horizon,
+                "The history below block {horizon} has been pruned, only the blocks after it can \
+                 be replayed"
+            );
+        }
+
+        let factory = self.executor_factory()?;
+
+        let mut mismatched_blocks = 0;
+        for block in self.from..=to {
+            match replay_block(&provider, &factory, block)? {
+                Replay::Executed(mismatches) if mismatches.is_empty() => {
+                    println!("Block {block}: ok");
+                }
+                Replay::Executed(mismatches) => {
+                    mismatched_blocks += 1;
+                    println!("Block {block}: {} mismatches", mismatches.len());
+                    for mismatch in mismatches {
+                        println!("  {mismatch}");
+                    }
+                }
+                Replay::NotReplayable => {
+                    println!("Block {block}: not replayable, its state was set directly");
+                }
+            }
+        }
+
+        if mismatched_blocks > 0 {
+            bail!("{mismatched_blocks} blocks don't replay identically");
+        }
+
+        println!("Replayed blocks {} to {to} identically.", self.from);
+        Ok(())
+    }
+
+    fn executor_factory(&self) -> Result<BlockifierFactory> {
+        // the chain spec isn't stored in the database, so it must be provided. a chain started
+        // without `--chain` uses the fee tokens of the dev chain spec.
+        let chain = match (&self.chain, self.chain_id) {
+            (Some(chain), _) => chain.clone(),
+            (None, Some(id)) => ChainSpec { id, ..chain_spec::DEV_UNALLOCATED.clone() },
+            (None, None) => bail!("Either the chain spec or the chain ID must be provided"),
+        };
+
+        let cfg_env = CfgEnv {
+            chain_id: chain.id,
+            invoke_tx_max_n_steps: self.invoke_max_steps,
+            validate_max_n_steps: self.validate_max_steps,
+            max_recursion_depth: MAX_RECURSION_DEPTH,
+            fee_token_addresses: FeeTokenAddressses {
+                eth: chain.fee_contracts.eth,
+                strk: chain.fee_contracts.strk,
+            },
+        };
+
+        let flags = ExecutionFlags::new()
+            .with_fee(!self.disable_fee)
+            .with_account_validation(!self.disable_validate);
+
+        Ok(BlockifierFactory::new(cfg_env, flags))
+    }
+}
+
+/// The outcome of the replay of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Replay {
+    /// The block was re-executed, with the differences between its stored and replayed outputs.
+    Executed(Vec<Mismatch>),
+    /// The block has no transactions but changes the state, which was set directly by the dev
+    /// API or by a fork rebase, so there is nothing to re-execute.
+    NotReplayable,
+}
+
+/// A difference between the stored outputs of a block and the ones of its replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Mismatch {
+    /// The replay executed a different number of transactions than the block has.
+    TxCount { stored: usize, replayed: usize },
+    /// The transaction was included in the block, but fails when replayed.
+    Failed { tx: TxHash, error: String },
+    /// The transaction is charged a different fee.
+    Fee { tx: TxHash, stored: TxFeeInfo, replayed: TxFeeInfo },
+    /// The receipt of the transaction differs, other than by its fee.
+    Receipt { tx: TxHash },
+    /// The execution trace of the transaction differs.
+    Trace { tx: TxHash },
+    /// The state diff of the block differs.
+    StateDiff,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::TxCount { stored, replayed } => {
+                write!(f, "executed {replayed} transactions, the block has {stored}")
+            }
+            Mismatch::Failed { tx, error } => write!(f, "tx {tx:#x} failed: {error}"),
+            Mismatch::Fee { tx, stored, replayed } => write!(
+                f,
+                "tx {tx:#x} fee: stored {} (gas {}), replayed {} (gas {})",
+                stored.overall_fee,
+                stored.gas_consumed,
+                replayed.overall_fee,
+                replayed.gas_consumed
+            ),
+            Mismatch::Receipt { tx } => write!(f, "tx {tx:#x} receipt differs"),
+            Mismatch::Trace { tx } => write!(f, "tx {tx:#x} trace differs"),
+            Mismatch::StateDiff => write!(f, "state diff differs"),
+        }
+    }
+}
+
+/// Re-executes `block` on top of the stored state of its parent, and compares the outputs with
+/// the stored ones.
+pub(crate) fn replay_block<EF: ExecutorFactory>(
+    provider: &DbProvider,
+    factory: &EF,
+    block: BlockNumber,
+) -> Result<Replay> {
+    let block_id = BlockHashOrNumber::Num(block);
+
+    let header = provider.header(block_id)?.with_context(|| format!("Missing block {block}"))?;
+    let state = provider
+        .historical((block - 1).into())?
+        .with_context(|| format!("Missing state of block {}", block - 1))?;
+
+    let txs = provider.transactions_by_block(block_id)?.unwrap_or_default();
+    let receipts = provider.receipts_by_block(block_id)?.unwrap_or_default();
+    let traces = provider.transaction_executions_by_block(block_id)?.unwrap_or_default();
+    let state_diff = provider.state_update(block_id)?.unwrap_or_default();
+
+    if txs.is_empty() && state_diff != StateUpdates::default() {
+        return Ok(Replay::NotReplayable);
+    }
+
+    // classes are never removed, so the latest state has the classes declared in all the blocks
+    let classes = provider.latest()?;
+    let body = txs
+        .into_iter()
+        .map(|tx| {
+            let transaction = match tx.transaction {
+                Tx::Invoke(tx) => ExecutableTx::Invoke(tx),
+                Tx::L1Handler(tx) => ExecutableTx::L1Handler(tx),
+                Tx::DeployAccount(tx) => ExecutableTx::DeployAccount(tx),
+                Tx::Declare(tx) => {
+                    let hash = tx.class_hash();
+                    let compiled_class =
+                        classes.class(hash)?.ok_or(ProviderError::MissingCompiledClass(hash))?;
+                    let sierra_class = classes.sierra_class(hash)?;
+                    ExecutableTx::Declare(DeclareTxWithClass {
+                        sierra_class,
+                        compiled_class,
+                        transaction: tx,
+                    })
+                }
+            };
+            Ok(ExecutableTxWithHash { hash: tx.hash, transaction })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let block = ExecutableBlock {
+        body,
+        header: PartialHeader {
+            parent_hash: header.parent_hash,
+            number: header.number,
+            timestamp: header.timestamp,
+            sequencer_address: header.sequencer_address,
+            l1_gas_prices: header.l1_gas_prices,
+            l1_data_gas_prices: header.l1_data_gas_prices,
+            l1_da_mode: header.l1_da_mode,
+            protocol_version: header.protocol_version,
+        },
+    };
+
+    let mut executor = factory.with_state(state);
+    executor.execute_block(block)?;
+    let output = executor.take_execution_output()?;
+
+    let mut mismatches = Vec::new();
+
+    let (stored, replayed) = (receipts.len(), output.transactions.len());
+    if stored != replayed {
+        mismatches.push(Mismatch::TxCount { stored, replayed });
+    }
+
+    let stored = receipts.into_iter().zip(traces);
+    for ((tx, result), (receipt, trace)) in output.transactions.into_iter().zip(stored) {
+        let tx = tx.hash;
+        match result {
+            ExecutionResult::Failed { error } => {
+                mismatches.push(Mismatch::Failed { tx, error: error.to_string() });
+            }
+            ExecutionResult::Success { receipt: replayed, trace: replayed_trace } => {
+                if receipt.fee() != replayed.fee() {
+                    let (stored, replayed) = (receipt.fee().clone(), replayed.fee().clone());
+                    mismatches.push(Mismatch::Fee { tx, stored, replayed });
+                } else if receipt != replayed {
+                    mismatches.push(Mismatch::Receipt { tx });
+                }
+
+                if trace != replayed_trace {
+                    mismatches.push(Mismatch::Trace { tx });
+                }
+            }
+        }
+    }
+
+    if state_diff != output.states.state_updates {
+        mismatches.push(Mismatch::StateDiff);
+    }
+
+    Ok(Replay::Executed(mismatches))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use katana_core::backend::gas_oracle::{GasOracle, L1GasPrices};
+    use katana_core::backend::storage::Blockchain;
+    use katana_core::backend::Backend;
+    use katana_core::service::metrics::BlockProducerMetrics;
+    use katana_db::abstraction::{Database, DbTx, DbTxMut};
+    use katana_db::mdbx::DbEnv;
+    use katana_db::models::contract::ContractNonceChange;
+    use katana_db::tables;
+    use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+    use katana_primitives::receipt::Receipt;
+    use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+    use katana_primitives::transaction::{ExecutableTx, InvokeTx, InvokeTxV1};
+    use katana_primitives::{address, felt, Felt};
+    use katana_provider::traits::env::BlockEnvProvider;
+    use starknet::macros::selector;
+
+    use super::*;
+
+    /// Mines `count` blocks, each with a transfer from a dev account, in a new database. If
+    /// `updates` is set, it's then committed in a block without transactions, as the dev API does.
+    fn mine_blocks(
+        factory: BlockifierFactory,
+        count: u64,
+        updates: Option<StateUpdatesWithDeclaredClasses>,
+    ) -> DbEnv {
+        let chain = chain_spec::DEV.clone();
+        let db = katana_db::init_ephemeral_db().unwrap();
+        let blockchain = Blockchain::new_with_db(db.clone(), &chain).unwrap();
+
+        let provider = blockchain.provider();
+        let header = provider.header(BlockHashOrNumber::Num(0)).unwrap().unwrap();
+        let prices = L1GasPrices { gas: header.l1_gas_prices, data_gas: header.l1_data_gas_prices };
+
+        let backend = Backend {
+            blockchain,
+            chain_spec: chain.clone(),
+            executor_factory: Arc::new(factory),
+            gas_oracle: GasOracle::fixed(prices),
+            block_context_generator: Default::default(),
+            state_diff_blobs: false,
+            metrics: BlockProducerMetrics::default(),
+        };
+
+        let (sender, _) = chain.genesis.accounts().next().unwrap();
+        for nonce in 0..count {
+            let provider = backend.blockchain.provider();
+            let latest = provider.latest_number().unwrap();
+            let mut block_env = provider.block_env_at(latest.into()).unwrap().unwrap();
+            backend.update_block_env(&mut block_env);
+
+            let tx = InvokeTx::V1(InvokeTxV1 {
+                chain_id: chain.id,
+                sender_address: *sender,
+                nonce: nonce.into(),
+                calldata: vec![
+                    Felt::ONE,
+                    DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+                    selector!("transfer"),
+                    Felt::THREE,
+                    felt!("0x1337"),
+                    Felt::ONE,
+                    Felt::ZERO,
+                ],
+                signature: vec![],
+                max_fee: 0,
+            });
+
+            let state = provider.latest().unwrap();
+            let mut executor = backend.executor_factory.with_state_and_block_env(state, block_env);
+            let tx = ExecutableTxWithHash::new(ExecutableTx::Invoke(tx));
+            executor.execute_transactions(vec![tx]).unwrap();
+
+            let output = executor.take_execution_output().unwrap();
+            let block_env = executor.block_env();
+            drop(executor);
+            backend.do_mine_block(&block_env, output).unwrap();
+        }
+
+        if let Some(updates) = updates {
+            let provider = backend.blockchain.provider();
+            let latest = provider.latest_number().unwrap();
+            let mut block_env = provider.block_env_at(latest.into()).unwrap().unwrap();
+            backend.update_block_env(&mut block_env);
+
+            let state = provider.latest().unwrap();
+            let mut executor =
+                backend.executor_factory.with_state_and_block_env(state, block_env.clone());
+            executor.apply_state_updates(updates).unwrap();
+
+            let output = executor.take_execution_output().unwrap();
+            drop(executor);
+            backend.do_mine_block(&block_env, output).unwrap();
+        }
+
+        db
+    }
+
+    #[test]
+    fn replay_blocks() {
+        let args = ReplayArgs {
+            db: String::new(),
+            from: 1,
+            to: None,
+            chain: None,
+            chain_id: Some(chain_spec::DEV.id),
+            disable_fee: true,
+            disable_validate: true,
+            validate_max_steps: DEFAULT_VALIDATION_MAX_STEPS,
+            invoke_max_steps: DEFAULT_INVOCATION_MAX_STEPS,
+        };
+
+        let factory = args.executor_factory().unwrap();
+        let db = mine_blocks(args.executor_factory().unwrap(), 3, None);
+        let provider = DbProvider::new(db.clone());
+        assert_eq!(provider.latest_number().unwrap(), 3);
+
+        for block in 1..=3 {
+            assert_eq!(replay_block(&provider, &factory, block).unwrap(), Replay::Executed(vec![]));
+        }
+
+        // tamper with the receipt of the transaction of block 2
+        let tx = provider.transaction_hashes_in_range(1..2).unwrap()[0];
+        db.update(|db_tx| {
+            let mut receipt = db_tx.get::<tables::Receipts>(1).unwrap().unwrap();
+            let Receipt::Invoke(ref mut invoke) = receipt else {
+                panic!("expected invoke receipt")
+            };
+            invoke.events.clear();
+            db_tx.put::<tables::Receipts>(1, receipt).unwrap();
+        })
+        .unwrap();
+
+        // tamper with the state diff of block 3
+        db.update(|db_tx| {
+            let change =
+                ContractNonceChange { contract_address: address!("0xdead"), nonce: Felt::ONE };
+            db_tx.put::<tables::NonceChangeHistory>(3, change).unwrap();
+        })
+        .unwrap();
+
+        assert_eq!(replay_block(&provider, &factory, 1).unwrap(), Replay::Executed(vec![]));
+        assert_eq!(
+            replay_block(&provider, &factory, 2).unwrap(),
+            Replay::Executed(vec![Mismatch::Receipt { tx }])
+        );
+        assert_eq!(
+            replay_block(&provider, &factory, 3).unwrap(),
+            Replay::Executed(vec![Mismatch::StateDiff])
+        );
+    }
+
+    #[test]
+    fn replay_dev_block() {
+        let args = ReplayArgs {
+            db: String::new(),
+            from: 1,
+            to: None,
+            chain: None,
+            chain_id: Some(chain_spec::DEV.id),
+            disable_fee: true,
+            disable_validate: true,
+            validate_max_steps: DEFAULT_VALIDATION_MAX_STEPS,
+            invoke_max_steps: DEFAULT_INVOCATION_MAX_STEPS,
+        };
+
+        // block 2 sets a storage slot directly, as `dev_setStorageAt` does
+        let mut updates = StateUpdatesWithDeclaredClasses::default();
+        let storage = BTreeMap::from([(felt!("0x1"), felt!("0x1337"))]);
+        updates.state_updates.storage_updates.insert(DEFAULT_ETH_FEE_TOKEN_ADDRESS, storage);
+
+        let factory = args.executor_factory().unwrap();
+        let db = mine_blocks(args.executor_factory().unwrap(), 1, Some(updates));
+        let provider = DbProvider::new(db);
+        assert_eq!(provider.latest_number().unwrap(), 2);
+
+        assert_eq!(replay_block(&provider, &factory, 1).unwrap(), Replay::Executed(vec![]));
+        assert_eq!(replay_block(&provider, &factory, 2).unwrap(), Replay::NotReplayable);
+    }
+}
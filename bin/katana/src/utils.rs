@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
 use anyhow::{Context, Result};
+use katana_pool::ordering::OrderingKind;
 use katana_primitives::block::{BlockHash, BlockHashOrNumber, BlockNumber};
+use katana_primitives::chain_spec::ChainSpec;
 use katana_primitives::genesis::json::GenesisJson;
 use katana_primitives::genesis::Genesis;
 
@@ -24,6 +26,13 @@ pub fn parse_genesis(value: &str) -> Result<Genesis> {
     Ok(genesis)
 }
 
+/// Used as clap value parser for [ChainSpec].
+pub fn parse_chain_spec(value: &str) -> Result<ChainSpec> {
+    let path = PathBuf::from(shellexpand::full(value)?.into_owned());
+    let chain = ChainSpec::load(path)?;
+    Ok(chain)
+}
+
 /// If the value starts with `0x`, it is parsed as a [`BlockHash`], otherwise as a [`BlockNumber`].
 pub fn parse_block_hash_or_number(value: &str) -> Result<BlockHashOrNumber> {
     if value.starts_with("0x") {
@@ -34,6 +43,15 @@ pub fn parse_block_hash_or_number(value: &str) -> Result<BlockHashOrNumber> {
     }
 }
 
+/// Used as clap value parser for [OrderingKind].
+pub fn parse_ordering(value: &str) -> Result<OrderingKind> {
+    match value.to_lowercase().as_str() {
+        "fifo" => Ok(OrderingKind::FiFo),
+        "tip" => Ok(OrderingKind::Tip),
+        _ => anyhow::bail!("unknown ordering `{value}`, expected `fifo` or `tip`"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,163 @@
+//! Compares the sequential and the parallel block executors.
+//!
+//! Dojo worlds route most of the writes through the world contract, but to disjoint storage
+//! slots. The `Disjoint` workload reproduces this access pattern with transfers of the fee token
+//! between distinct accounts: all the transactions call the same contract, but none of them
+//! touch the storage written by the others. In the `Contended` workload, all the transfers have
+//! the same recipient, so that every transaction conflicts with the previous one, which measures
+//! the overhead of the parallel executor in the worst case.
+
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use alloy_primitives::U256;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use katana_executor::implementation::blockifier::BlockifierFactory;
+use katana_executor::{ExecutionFlags, ExecutorFactory};
+use katana_primitives::block::{
+    BlockHash, ExecutableBlock, FinalityStatus, GasPrices, PartialHeader,
+};
+use katana_primitives::chain_spec::{self, ChainSpec};
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::da::L1DataAvailabilityMode;
+use katana_primitives::env::{CfgEnv, FeeTokenAddressses};
+use katana_primitives::genesis::allocation::DevAllocationsGenerator;
+use katana_primitives::genesis::constant::{
+    DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_PREFUNDED_ACCOUNT_BALANCE,
+};
+use katana_primitives::transaction::{ExecutableTxWithHash, InvokeTx, InvokeTxV1};
+use katana_primitives::Felt;
+use katana_provider::providers::db::DbProvider;
+use katana_provider::traits::block::BlockWriter;
+use katana_provider::traits::state::StateFactoryProvider;
+use starknet::macros::selector;
+
+/// The number of transactions in the executed blocks.
+const BLOCK_SIZE: usize = 256;
+/// The number of worker threads of the parallel executors.
+const WORKERS: [usize; 3] = [2, 4, 8];
+
+fn execute_block(c: &mut Criterion) {
+    let (chain, accounts) = chain();
+    let provider = provider(&chain);
+
+    let (senders, recipients) = accounts.split_at(BLOCK_SIZE);
+    let disjoint = block(&chain, senders.iter().zip(recipients).map(|(s, r)| transfer(*s, *r)));
+    let contended = block(&chain, senders.iter().map(|s| transfer(*s, recipients[0])));
+
+    let mut group = c.benchmark_group("Execute.Block");
+    group.warm_up_time(Duration::from_millis(200));
+    group.sample_size(10);
+
+    for (workload, block) in [("Disjoint", disjoint), ("Contended", contended)] {
+        let sequential = factory(&chain);
+        group.bench_with_input(BenchmarkId::new(workload, "Sequential"), &block, |b, block| {
+            b.iter_batched(
+                || (sequential.with_state(provider.latest().unwrap()), block.clone()),
+                |(mut executor, block)| {
+                    executor.execute_block(block).unwrap();
+                    executor
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        for workers in WORKERS {
+            let workers = NonZeroUsize::new(workers).unwrap();
+            let parallel = factory(&chain).with_parallel_execution(workers);
+            let id = BenchmarkId::new(workload, format!("Parallel.{workers}"));
+            group.bench_with_input(id, &block, |b, block| {
+                b.iter_batched(
+                    || (parallel.with_state(provider.latest().unwrap()), block.clone()),
+                    |(mut executor, block)| {
+                        executor.execute_block(block).unwrap();
+                        executor
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+
+    group.finish();
+}
+
+/// Returns a dev chain with funded accounts, and the addresses of the accounts.
+fn chain() -> (ChainSpec, Vec<ContractAddress>) {
+    let mut chain = chain_spec::DEV_UNALLOCATED.clone();
+
+    let accounts = DevAllocationsGenerator::new(2 * BLOCK_SIZE as u16)
+        .with_balance(U256::from(DEFAULT_PREFUNDED_ACCOUNT_BALANCE))
+        .generate();
+
+    let mut addresses: Vec<_> = accounts.keys().copied().collect();
+    addresses.sort();
+
+    chain.genesis.extend_allocations(accounts.into_iter().map(|(k, v)| (k, v.into())));
+    (chain, addresses)
+}
+
+fn provider(chain: &ChainSpec) -> DbProvider {
+    let provider = DbProvider::new_ephemeral();
+    let block =
+        chain.block().seal_with_hash_and_status(BlockHash::ZERO, FinalityStatus::AcceptedOnL2);
+    provider
+        .insert_block_with_states_and_receipts(block, chain.state_updates(), Vec::new(), Vec::new())
+        .expect("failed to initialize provider");
+    provider
+}
+
+fn factory(chain: &ChainSpec) -> BlockifierFactory {
+    let cfg = CfgEnv {
+        chain_id: chain.id,
+        max_recursion_depth: 100,
+        validate_max_n_steps: 4_000_000,
+        invoke_tx_max_n_steps: 4_000_000,
+        fee_token_addresses: FeeTokenAddressses {
+            eth: chain.fee_contracts.eth,
+            strk: chain.fee_contracts.strk,
+        },
+    };
+
+    // the transactions aren't signed
+    let flags = ExecutionFlags::new().with_account_validation(false);
+    BlockifierFactory::new(cfg, flags)
+}
+
+fn block(chain: &ChainSpec, txs: impl Iterator<Item = ExecutableTxWithHash>) -> ExecutableBlock {
+    let prices = GasPrices { eth: 1, strk: 1 };
+    let header = PartialHeader {
+        number: 1,
+        timestamp: 1,
+        parent_hash: BlockHash::ZERO,
+        sequencer_address: chain.genesis.sequencer_address,
+        l1_gas_prices: prices.clone(),
+        l1_data_gas_prices: prices,
+        l1_da_mode: L1DataAvailabilityMode::Calldata,
+        protocol_version: chain.version.clone(),
+    };
+
+    ExecutableBlock { header, body: txs.collect() }
+}
+
+fn transfer(sender: ContractAddress, recipient: ContractAddress) -> ExecutableTxWithHash {
+    let invoke = InvokeTx::V1(InvokeTxV1 {
+        sender_address: sender,
+        calldata: vec![
+            Felt::ONE,
+            DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+            selector!("transfer"),
+            Felt::THREE,
+            recipient.into(),
+            Felt::from(100u8),
+            Felt::ZERO,
+        ],
+        max_fee: 1_000_000_000_000,
+        ..Default::default()
+    });
+
+    ExecutableTxWithHash::new(invoke.into())
+}
+
+criterion_group!(benches, execute_block);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+use alloy_eips::eip4844::env_settings::EnvKzgSettings;
+use alloy_eips::eip4844::kzg_to_versioned_hash;
+use c_kzg::{Blob, KzgCommitment, KzgProof};
+use katana_primitives::da::{blob, KzgBytes, StateDiffBlob};
+use katana_primitives::state::StateUpdates;
+
+/// Computes the EIP-4844 blobs of the state diff of a block - ie. the exact payload that would be
+/// posted to the settlement chain - along with their KZG commitments and proofs.
+///
+/// The commitments are computed using the trusted setup of the Ethereum KZG ceremony.
+pub fn state_diff_blobs(state_updates: StateUpdates) -> Result<Vec<StateDiffBlob>, c_kzg::Error> {
+    let settings = EnvKzgSettings::Default.get();
+
+    blob::from_state_updates(state_updates)
+        .into_iter()
+        .map(|data| {
+            let data = blob::to_bytes(&data);
+            let blob = Blob::from_bytes(&data)?;
+
+            let commitment = KzgCommitment::blob_to_kzg_commitment(&blob, settings)?.to_bytes();
+            let proof = KzgProof::compute_blob_kzg_proof(&blob, &commitment, settings)?.to_bytes();
+            let commitment = commitment.into_inner();
+
+            Ok(StateDiffBlob {
+                data: data.into(),
+                versioned_hash: kzg_to_versioned_hash(&commitment),
+                commitment: KzgBytes::from(commitment),
+                proof: KzgBytes::from(proof.into_inner()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use alloy_eips::eip4844::env_settings::EnvKzgSettings;
+    use c_kzg::{Blob, Bytes48, KzgProof};
+    use katana_primitives::da::blob::BYTES_PER_BLOB;
+    use katana_primitives::state::StateUpdates;
+    use katana_primitives::{address, felt};
+
+    use super::state_diff_blobs;
+
+    #[test]
+    fn blobs_commitments_are_valid() {
+        let state_updates = StateUpdates {
+            nonce_updates: BTreeMap::from([(address!("0x1"), felt!("0x2"))]),
+            storage_updates: BTreeMap::from([(
+                address!("0x1"),
+                BTreeMap::from([(felt!("0x3"), felt!("0x4"))]),
+            )]),
+            declared_classes: BTreeMap::from([(felt!("0x5"), felt!("0x6"))]),
+            ..Default::default()
+        };
+
+        let blobs = state_diff_blobs(state_updates).unwrap();
+        assert_eq!(blobs.len(), 1);
+
+        let blob = &blobs[0];
+        assert_eq!(blob.data.len(), BYTES_PER_BLOB);
+        // the version byte of the KZG versioned hashes
+        assert_eq!(blob.versioned_hash[0], 0x01);
+
+        let valid = KzgProof::verify_blob_kzg_proof(
+            &Blob::from_bytes(&blob.data).unwrap(),
+            &Bytes48::from_bytes(blob.commitment.as_slice()).unwrap(),
+            &Bytes48::from_bytes(blob.proof.as_slice()).unwrap(),
+            EnvKzgSettings::Default.get(),
+        )
+        .unwrap();
+
+        assert!(valid);
+    }
+}
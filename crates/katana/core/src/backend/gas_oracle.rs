@@ -0,0 +1,271 @@
+//! The gas prices of the blocks produced by the node.
+//!
+//! The L1 gas prices either are fixed, or follow the settlement chain: a [`GasPriceSampler`]
+//! periodically samples its base fee and blob fee. On top of the L1 prices, an optional L2 fee
+//! market adjusts the prices after every block, EIP-1559 style, depending on how much gas the
+//! block used compared to a target.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_network::Ethereum;
+use alloy_provider::{Provider, ReqwestProvider};
+use alloy_rpc_types_eth::BlockNumberOrTag;
+use anyhow::{anyhow, Result};
+use katana_primitives::block::GasPrices;
+use katana_primitives::env::BlockEnv;
+use parking_lot::{Mutex, RwLock};
+use starknet::core::types::{BlockId, BlockTag, MaybePendingBlockWithTxHashes, ResourcePrice};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider as StarknetProvider};
+use tokio::time::MissedTickBehavior;
+use tracing::{error, trace};
+use url::Url;
+
+use crate::service::messaging::{MessagingConfig, CONFIG_CHAIN_ETHEREUM, CONFIG_CHAIN_STARKNET};
+
+pub(crate) const LOG_TARGET: &str = "katana::core::gas_oracle";
+
+/// The precision of the L2 fee market price multiplier.
+const MULTIPLIER_DENOMINATOR: u128 = 1_000_000;
+/// Bounds the change of the L2 price multiplier from one block to the next one to 1/8th, like in
+/// EIP-1559.
+const MULTIPLIER_CHANGE_DENOMINATOR: u128 = 8;
+
+/// The L1 gas and data gas prices.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct L1GasPrices {
+    pub gas: GasPrices,
+    pub data_gas: GasPrices,
+}
+
+/// Provides the gas prices of the next block.
+#[derive(Debug)]
+pub struct GasOracle {
+    l1_prices: Arc<RwLock<L1GasPrices>>,
+    fee_market: Option<Mutex<FeeMarket>>,
+}
+
+impl GasOracle {
+    /// Creates an oracle with fixed L1 prices.
+    pub fn fixed(prices: L1GasPrices) -> Self {
+        Self { l1_prices: Arc::new(RwLock::new(prices)), fee_market: None }
+    }
+
+    /// Creates an oracle whose L1 prices are sampled by `sampler`.
+    pub fn sampled(sampler: &GasPriceSampler) -> Self {
+        Self { l1_prices: sampler.prices.clone(), fee_market: None }
+    }
+
+    /// Enables the L2 fee market, targeting `target_gas` L1 gas used per block.
+    pub fn with_fee_market(mut self, target_gas: u128) -> Self {
+        self.fee_market = Some(Mutex::new(FeeMarket::new(target_gas)));
+        self
+    }
+
+    /// Returns the gas prices of the next block.
+    pub fn current_prices(&self) -> L1GasPrices {
+        let L1GasPrices { gas, data_gas } = self.l1_prices.read().clone();
+
+        match &self.fee_market {
+            Some(market) => {
+                let market = market.lock();
+                L1GasPrices { gas: market.apply(gas), data_gas: market.apply(data_gas) }
+            }
+            None => L1GasPrices { gas, data_gas },
+        }
+    }
+
+    /// Sets the gas prices of `block_env` to the ones of the next block.
+    pub fn update_block_env(&self, block_env: &mut BlockEnv) {
+        let prices = self.current_prices();
+        block_env.l1_gas_prices = prices.gas;
+        block_env.l1_data_gas_prices = prices.data_gas;
+    }
+
+    /// Adjusts the L2 prices after a block that used `gas_used` L1 gas has been mined.
+    pub fn on_block_mined(&self, gas_used: u128) {
+        if let Some(market) = &self.fee_market {
+            market.lock().update(gas_used);
+        }
+    }
+}
+
+/// An EIP-1559 style fee market, where the L2 prices are the L1 prices scaled by a multiplier
+/// that goes up when blocks use more gas than the target, and down when they use less.
+///
+/// The multiplier never goes below 1, so that the L2 prices always cover the L1 costs.
+#[derive(Debug)]
+struct FeeMarket {
+    target_gas: u128,
+    /// The multiplier, scaled by [`MULTIPLIER_DENOMINATOR`].
+    multiplier: u128,
+}
+
+impl FeeMarket {
+    fn new(target_gas: u128) -> Self {
+        Self { target_gas: target_gas.max(1), multiplier: MULTIPLIER_DENOMINATOR }
+    }
+
+    fn apply(&self, prices: GasPrices) -> GasPrices {
+        let scale = |price: u128| price.saturating_mul(self.multiplier) / MULTIPLIER_DENOMINATOR;
+        GasPrices { eth: scale(prices.eth), strk: scale(prices.strk) }
+    }
+
+    fn update(&mut self, gas_used: u128) {
+        let denominator = self.target_gas * MULTIPLIER_CHANGE_DENOMINATOR;
+
+        if gas_used > self.target_gas {
+            let delta = self.multiplier.saturating_mul(gas_used - self.target_gas) / denominator;
+            // the multiplier changes by at most 1/8th per block
+            let delta = delta.clamp(1, self.multiplier / MULTIPLIER_CHANGE_DENOMINATOR);
+            self.multiplier = self.multiplier.saturating_add(delta);
+        } else {
+            let delta = self.multiplier.saturating_mul(self.target_gas - gas_used) / denominator;
+            self.multiplier = self.multiplier.saturating_sub(delta).max(MULTIPLIER_DENOMINATOR);
+        }
+
+        trace!(target: LOG_TARGET, %gas_used, multiplier = %self.multiplier, "Updated L2 gas price.");
+    }
+}
+
+/// The settlement chain the L1 gas prices are sampled from.
+#[derive(Debug)]
+pub enum SettlementClient {
+    Ethereum(ReqwestProvider<Ethereum>),
+    Starknet(JsonRpcClient<HttpTransport>),
+}
+
+impl SettlementClient {
+    pub fn ethereum(url: Url) -> Self {
+        Self::Ethereum(ReqwestProvider::<Ethereum>::new_http(url))
+    }
+
+    pub fn starknet(url: Url) -> Self {
+        Self::Starknet(JsonRpcClient::new(HttpTransport::new(url)))
+    }
+
+    /// Creates a client of the settlement chain of the messaging `config`.
+    pub fn from_messaging(config: &MessagingConfig) -> Result<Self> {
+        let url = Url::parse(&config.rpc_url)?;
+        match config.chain.as_str() {
+            CONFIG_CHAIN_ETHEREUM => Ok(Self::ethereum(url)),
+            CONFIG_CHAIN_STARKNET => Ok(Self::starknet(url)),
+            chain => Err(anyhow!("unsupported settlement chain {chain}")),
+        }
+    }
+}
+
+/// Periodically samples the gas prices of the settlement chain.
+#[derive(Debug)]
+pub struct GasPriceSampler {
+    client: SettlementClient,
+    interval: Duration,
+    prices: Arc<RwLock<L1GasPrices>>,
+    /// The ratio of the STRK to ETH prices, used when the settlement chain only has ETH prices.
+    strk_per_eth: (u128, u128),
+}
+
+impl GasPriceSampler {
+    /// Creates a sampler that starts with the `initial` prices.
+    ///
+    /// An Ethereum settlement chain only provides ETH prices, so the STRK prices are derived
+    /// from them, keeping the ratio between the STRK and ETH `initial` prices.
+    pub fn new(client: SettlementClient, interval: Duration, initial: L1GasPrices) -> Self {
+        let strk_per_eth = (initial.gas.strk, initial.gas.eth.max(1));
+        let prices = Arc::new(RwLock::new(initial));
+        Self { client, interval, prices, strk_per_eth }
+    }
+
+    /// Samples the prices every `interval` until the node is stopped.
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match self.sample().await {
+                Ok(prices) => {
+                    trace!(target: LOG_TARGET, ?prices, "Sampled L1 gas prices.");
+                    *self.prices.write() = prices;
+                }
+                Err(error) => {
+                    error!(target: LOG_TARGET, %error, "Sampling L1 gas prices.");
+                }
+            }
+        }
+    }
+
+    /// Returns the current gas prices of the settlement chain.
+    pub async fn sample(&self) -> Result<L1GasPrices> {
+        match &self.client {
+            SettlementClient::Ethereum(provider) => {
+                let history = provider.get_fee_history(1, BlockNumberOrTag::Latest, &[]).await?;
+
+                let base_fee = history.next_block_base_fee().ok_or(anyhow!("missing base fee"))?;
+                // pre-Cancun chains don't have blob fees
+                let blob_fee = history.next_block_blob_base_fee().unwrap_or(base_fee);
+
+                let (strk, eth) = self.strk_per_eth;
+                let to_strk = |price: u128| price.saturating_mul(strk) / eth;
+
+                Ok(L1GasPrices {
+                    gas: GasPrices { eth: base_fee, strk: to_strk(base_fee) },
+                    data_gas: GasPrices { eth: blob_fee, strk: to_strk(blob_fee) },
+                })
+            }
+
+            SettlementClient::Starknet(client) => {
+                let block = client.get_block_with_tx_hashes(BlockId::Tag(BlockTag::Latest)).await?;
+                let MaybePendingBlockWithTxHashes::Block(block) = block else {
+                    return Err(anyhow!("expected a mined block"));
+                };
+
+                Ok(L1GasPrices {
+                    gas: to_gas_prices(&block.l1_gas_price)?,
+                    data_gas: to_gas_prices(&block.l1_data_gas_price)?,
+                })
+            }
+        }
+    }
+}
+
+fn to_gas_prices(price: &ResourcePrice) -> Result<GasPrices> {
+    Ok(GasPrices { eth: price.price_in_wei.try_into()?, strk: price.price_in_fri.try_into()? })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(eth: u128, strk: u128) -> L1GasPrices {
+        let prices = GasPrices { eth, strk };
+        L1GasPrices { gas: prices.clone(), data_gas: prices }
+    }
+
+    #[test]
+    fn fixed_prices() {
+        let oracle = GasOracle::fixed(prices(100, 200));
+        oracle.on_block_mined(1_000_000);
+        assert_eq!(oracle.current_prices(), prices(100, 200));
+    }
+
+    #[test]
+    fn fee_market() {
+        let oracle = GasOracle::fixed(prices(1000, 2000)).with_fee_market(100);
+
+        // a full block, twice the target, raises the prices by 1/8th
+        oracle.on_block_mined(200);
+        assert_eq!(oracle.current_prices(), prices(1125, 2250));
+
+        // a block at the target keeps them
+        oracle.on_block_mined(100);
+        assert_eq!(oracle.current_prices(), prices(1125, 2250));
+
+        // empty blocks lower them, but never below the L1 prices
+        for _ in 0..10 {
+            oracle.on_block_mined(0);
+        }
+        assert_eq!(oracle.current_prices(), prices(1000, 2000));
+    }
+}
@@ -1,25 +1,33 @@
 use std::sync::Arc;
+use std::time::Instant;
 
+use anyhow::Context;
 use katana_executor::{ExecutionOutput, ExecutionResult, ExecutorFactory};
-use katana_primitives::block::{
-    Block, FinalityStatus, GasPrices, Header, SealedBlock, SealedBlockWithStatus,
-};
+use katana_primitives::block::{Block, FinalityStatus, GasPrices, Header};
 use katana_primitives::chain_spec::ChainSpec;
 use katana_primitives::da::L1DataAvailabilityMode;
 use katana_primitives::env::BlockEnv;
+use katana_primitives::genesis::json::{GenesisAccountJson, GenesisJson};
 use katana_primitives::receipt::Receipt;
 use katana_primitives::transaction::{TxHash, TxWithHash};
 use katana_primitives::Felt;
-use katana_provider::traits::block::{BlockHashProvider, BlockWriter};
+use katana_provider::traits::block::{
+    BlockHashProvider, BlockNumberProvider, BlockWriter, HeaderProvider,
+};
+use katana_provider::traits::state::StateDumpProvider;
 use parking_lot::RwLock;
 use tracing::info;
 
 pub mod contract;
+pub mod da;
+pub mod gas_oracle;
 pub mod storage;
 
+use self::gas_oracle::GasOracle;
 use self::storage::Blockchain;
 use crate::env::BlockContextGenerator;
 use crate::service::block_producer::{BlockProductionError, MinedBlockOutcome};
+use crate::service::metrics::BlockProducerMetrics;
 use crate::utils::get_current_timestamp;
 
 pub(crate) const LOG_TARGET: &str = "katana::core::backend";
@@ -31,8 +39,14 @@ pub struct Backend<EF: ExecutorFactory> {
     pub blockchain: Blockchain,
     /// The block context generator.
     pub block_context_generator: RwLock<BlockContextGenerator>,
+    /// Provides the gas prices of the produced blocks.
+    pub gas_oracle: GasOracle,
 
     pub executor_factory: Arc<EF>,
+    /// Whether to compute and store the EIP-4844 blobs of the state diff of the mined blocks.
+    pub state_diff_blobs: bool,
+    /// Metrics of the produced blocks.
+    pub metrics: BlockProducerMetrics,
 }
 
 impl<EF: ExecutorFactory> Backend<EF> {
@@ -42,6 +56,8 @@ impl<EF: ExecutorFactory> Backend<EF> {
         block_env: &BlockEnv,
         execution_output: ExecutionOutput,
     ) -> Result<MinedBlockOutcome, BlockProductionError> {
+        let started_at = Instant::now();
+
         // we optimistically allocate the maximum amount possible
         let mut txs = Vec::with_capacity(execution_output.transactions.len());
         let mut traces = Vec::with_capacity(execution_output.transactions.len());
@@ -59,18 +75,30 @@ impl<EF: ExecutorFactory> Backend<EF> {
         let tx_count = txs.len() as u32;
         let tx_hashes = txs.iter().map(|tx| tx.hash).collect::<Vec<TxHash>>();
 
-        // create a new block and compute its commitment
-        let block = self.commit_block(block_env, txs, &receipts)?;
-        let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
-        let block_number = block.block.header.number;
+        let blobs = if self.state_diff_blobs {
+            Some(da::state_diff_blobs(execution_output.states.state_updates.clone())?)
+        } else {
+            None
+        };
+
+        // create a new block, whose state root is computed when it's stored
+        let block = self.build_block(block_env, txs, &receipts)?;
+        let block_number = block.header.number;
 
-        self.blockchain.provider().insert_block_with_states_and_receipts(
+        self.blockchain.provider().insert_block_with_trie_updates(
             block,
+            FinalityStatus::AcceptedOnL2,
             execution_output.states,
             receipts,
             traces,
+            blobs,
         )?;
 
+        self.gas_oracle.on_block_mined(execution_output.stats.l1_gas_used);
+
+        let elapsed = started_at.elapsed().as_secs_f64();
+        self.metrics.record_block(tx_count as u64, &execution_output.stats, elapsed);
+
         info!(target: LOG_TARGET, %block_number, %tx_count, "Block mined.");
         Ok(MinedBlockOutcome { block_number, txs: tx_hashes, stats: execution_output.stats })
     }
@@ -90,6 +118,7 @@ impl<EF: ExecutorFactory> Backend<EF> {
 
         block_env.number += 1;
         block_env.timestamp = timestamp;
+        self.gas_oracle.update_block_env(block_env);
     }
 
     pub fn mine_empty_block(
@@ -99,12 +128,46 @@ impl<EF: ExecutorFactory> Backend<EF> {
         self.do_mine_block(block_env, Default::default())
     }
 
-    fn commit_block(
+    /// Dumps the latest state of the chain into a [`GenesisJson`], from which a new chain
+    /// starting with the same state can be created.
+    ///
+    /// Only the state is dumped, the blocks and transactions are not. The accounts of the genesis
+    /// are kept as accounts, so that their keys remain known to the chain loading the dump.
+    pub fn dump_state(&self) -> anyhow::Result<GenesisJson> {
+        let provider = self.blockchain.provider();
+
+        let latest = provider.latest_number()?;
+        let header = provider.header(latest.into())?.context("missing latest block header")?;
+
+        let mut json = GenesisJson {
+            timestamp: header.timestamp,
+            sequencer_address: header.sequencer_address,
+            gas_prices: header.l1_gas_prices,
+            ..Default::default()
+        };
+
+        for (address, account) in self.chain_spec.genesis.accounts() {
+            let account = GenesisAccountJson {
+                public_key: account.public_key(),
+                private_key: account.private_key(),
+                balance: None,
+                nonce: None,
+                class: None,
+                storage: None,
+            };
+            json.accounts.insert(*address, account);
+        }
+
+        json.extend_with_states(provider.dump_state()?)?;
+        Ok(json)
+    }
+
+    fn build_block(
         &self,
         block_env: &BlockEnv,
         transactions: Vec<TxWithHash>,
         receipts: &[Receipt],
-    ) -> Result<SealedBlock, BlockProductionError> {
+    ) -> Result<Block, BlockProductionError> {
         // get the hash of the latest committed block
         let parent_hash = self.blockchain.provider().latest_hash()?;
         let events_count = receipts.iter().map(|r| r.events().len() as u32).sum::<u32>();
@@ -123,19 +186,18 @@ impl<EF: ExecutorFactory> Backend<EF> {
             l1_gas_prices,
             transaction_count,
             l1_data_gas_prices,
-            state_root: Felt::ZERO,
             number: block_env.number,
             events_commitment: Felt::ZERO,
             timestamp: block_env.timestamp,
             receipts_commitment: Felt::ZERO,
             state_diff_commitment: Felt::ZERO,
             transactions_commitment: Felt::ZERO,
+            state_root: Felt::ZERO,
             l1_da_mode: L1DataAvailabilityMode::Calldata,
             sequencer_address: block_env.sequencer_address,
             protocol_version: self.chain_spec.version.clone(),
         };
 
-        let sealed = Block { header, body: transactions }.seal();
-        Ok(sealed)
+        Ok(Block { header, body: transactions })
     }
 }
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Context, Result};
@@ -10,16 +11,24 @@ use katana_primitives::da::L1DataAvailabilityMode;
 use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::version::ProtocolVersion;
 use katana_provider::providers::db::DbProvider;
+use katana_provider::providers::fork::cache::ForkCache;
 use katana_provider::providers::fork::ForkedProvider;
-use katana_provider::traits::block::{BlockProvider, BlockWriter};
+use katana_provider::traits::block::{BlockProvider, BlockUnwinder, BlockWriter, HeaderProvider};
 use katana_provider::traits::contract::ContractClassWriter;
+use katana_provider::traits::da::{StateDiffBlobProvider, StateDiffBlobWriter};
 use katana_provider::traits::env::BlockEnvProvider;
-use katana_provider::traits::state::{StateFactoryProvider, StateRootProvider, StateWriter};
+use katana_provider::traits::event::EventIndexProvider;
+use katana_provider::traits::message::{L1ToL2MessageProvider, L1ToL2MessageWriter};
+use katana_provider::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use katana_provider::traits::state::{
+    StateDumpProvider, StateFactoryProvider, StateRootProvider, StateWriter,
+};
 use katana_provider::traits::state_update::StateUpdateProvider;
 use katana_provider::traits::transaction::{
     ReceiptProvider, TransactionProvider, TransactionStatusProvider, TransactionTraceProvider,
     TransactionsProviderExt,
 };
+use katana_provider::traits::trie::{StateProofProvider, TrieWriter};
 use katana_provider::BlockchainProvider;
 use num_traits::ToPrimitive;
 use starknet::core::types::{BlockStatus, MaybePendingBlockWithTxHashes};
@@ -32,17 +41,28 @@ use url::Url;
 pub trait Database:
     BlockProvider
     + BlockWriter
+    + BlockUnwinder
     + TransactionProvider
     + TransactionStatusProvider
     + TransactionTraceProvider
     + TransactionsProviderExt
     + ReceiptProvider
+    + EventIndexProvider
+    + L1ToL2MessageProvider
+    + L1ToL2MessageWriter
+    + StageCheckpointProvider
+    + StageCheckpointWriter
+    + StateDiffBlobProvider
+    + StateDiffBlobWriter
     + StateUpdateProvider
     + StateRootProvider
     + StateWriter
+    + StateDumpProvider
     + ContractClassWriter
     + StateFactoryProvider
     + BlockEnvProvider
+    + TrieWriter
+    + StateProofProvider
     + 'static
     + Send
     + Sync
@@ -53,17 +73,28 @@ pub trait Database:
 impl<T> Database for T where
     T: BlockProvider
         + BlockWriter
+        + BlockUnwinder
         + TransactionProvider
         + TransactionStatusProvider
         + TransactionTraceProvider
         + TransactionsProviderExt
         + ReceiptProvider
+        + EventIndexProvider
+        + L1ToL2MessageProvider
+        + L1ToL2MessageWriter
+        + StageCheckpointProvider
+        + StageCheckpointWriter
+        + StateDiffBlobProvider
+        + StateDiffBlobWriter
         + StateUpdateProvider
         + StateRootProvider
         + StateWriter
+        + StateDumpProvider
         + ContractClassWriter
         + StateFactoryProvider
         + BlockEnvProvider
+        + TrieWriter
+        + StateProofProvider
         + 'static
         + Send
         + Sync
@@ -88,7 +119,13 @@ impl Blockchain {
 
         match genesis_hash {
             Some(db_hash) => {
-                let genesis_hash = chain.block().header.compute_hash();
+                // the stored state root is the one derived from the genesis state, see below
+                let mut header = chain.block().header;
+                if let Some(stored) = provider.header_by_number(chain.genesis.number)? {
+                    header.state_root = stored.state_root;
+                }
+
+                let genesis_hash = header.compute_hash();
                 // check genesis should be the same
                 if db_hash == genesis_hash {
                     Ok(Self::new(provider))
@@ -100,11 +137,20 @@ impl Blockchain {
             }
 
             None => {
-                let block = chain.block().seal();
-                let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL1 };
                 let state_updates = chain.state_updates();
 
-                Self::new_with_genesis_block_and_state(provider, block, state_updates)
+                // the genesis state root is derived from the genesis state itself rather than
+                // taken from the chain spec.
+                provider.insert_block_with_trie_updates(
+                    chain.block(),
+                    FinalityStatus::AcceptedOnL1,
+                    state_updates,
+                    Vec::new(),
+                    Vec::new(),
+                    None,
+                )?;
+
+                Ok(Self::new(provider))
             }
         }
     }
@@ -115,11 +161,18 @@ impl Blockchain {
     }
 
     /// Builds a new blockchain with a forked block.
+    ///
+    /// If `cache_dir` is set, the data fetched from the forked network is persisted in a
+    /// [`ForkCache`] in that directory and reused by later runs forking the same block.
+    ///
+    /// Returns the blockchain along with its underlying [`ForkedProvider`], which can be used to
+    /// rebase the blockchain onto a newer block of the forked network.
     pub async fn new_from_forked(
         fork_url: Url,
         fork_block: Option<BlockHashOrNumber>,
+        cache_dir: Option<&Path>,
         chain: &mut ChainSpec,
-    ) -> Result<(Self, BlockNumber)> {
+    ) -> Result<(Self, Arc<ForkedProvider>, BlockNumber)> {
         let provider = JsonRpcClient::new(HttpTransport::new(fork_url));
         let chain_id = provider.chain_id().await.context("failed to fetch forked network id")?;
 
@@ -140,65 +193,27 @@ impl Blockchain {
 
         info!(chain = %parsed_id, block = %block_id, "Forking chain.");
 
-        let block = provider
-            .get_block_with_tx_hashes(BlockIdOrTag::from(block_id))
-            .await
-            .context("failed to fetch forked block")?;
-
-        let MaybePendingBlockWithTxHashes::Block(forked_block) = block else {
-            bail!("forking a pending block is not allowed")
-        };
-
-        let block_num = forked_block.block_number;
-
         chain.id = chain_id.into();
-        chain.version = ProtocolVersion::parse(&forked_block.starknet_version)?;
-
-        // adjust the genesis to match the forked block
-        chain.genesis.timestamp = forked_block.timestamp;
-        chain.genesis.number = forked_block.block_number;
-        chain.genesis.state_root = forked_block.new_root;
-        chain.genesis.parent_hash = forked_block.parent_hash;
-        chain.genesis.sequencer_address = forked_block.sequencer_address.into();
-
-        // TODO: remove gas price from genesis
-        chain.genesis.gas_prices.eth =
-            forked_block.l1_gas_price.price_in_wei.to_u128().expect("should fit in u128");
-        chain.genesis.gas_prices.strk =
-            forked_block.l1_gas_price.price_in_fri.to_u128().expect("should fit in u128");
-
-        let status = match forked_block.status {
-            BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
-            BlockStatus::AcceptedOnL2 => FinalityStatus::AcceptedOnL2,
-            // we already checked for pending block earlier. so this should never happen.
-            _ => bail!("qed; block status shouldn't be pending"),
-        };
+        let block = forked_genesis(&provider, block_id, chain).await?;
+        let block_num = block.block.header.number;
 
         // TODO: convert this to block number instead of BlockHashOrNumber so that it is easier to
         // check if the requested block is within the supported range or not.
-        let database = ForkedProvider::new(Arc::new(provider), block_id)?;
-
-        // update the genesis block with the forked block's data
-        // we dont update the `l1_gas_price` bcs its already done when we set the `gas_prices` in
-        // genesis. this flow is kinda flawed, we should probably refactor it out of the
-        // genesis.
-        let mut block = chain.block();
-        block.header.l1_data_gas_prices.eth =
-            forked_block.l1_data_gas_price.price_in_wei.to_u128().expect("should fit in u128");
-        block.header.l1_data_gas_prices.strk =
-            forked_block.l1_data_gas_price.price_in_fri.to_u128().expect("should fit in u128");
-        block.header.l1_da_mode = match forked_block.l1_da_mode {
-            starknet::core::types::L1DataAvailabilityMode::Blob => L1DataAvailabilityMode::Blob,
-            starknet::core::types::L1DataAvailabilityMode::Calldata => {
-                L1DataAvailabilityMode::Calldata
+        let provider = Arc::new(provider);
+        let database = match cache_dir {
+            Some(dir) => {
+                let cache = ForkCache::open(dir, chain.id, block_num)?;
+                ForkedProvider::new_with_cache(provider, block_id, cache)?
             }
+            None => ForkedProvider::new(provider, block_id)?,
         };
 
-        let block = block.seal_with_hash_and_status(forked_block.block_hash, status);
+        let database = Arc::new(database);
         let state_updates = chain.state_updates();
 
-        let blockchain = Self::new_with_genesis_block_and_state(database, block, state_updates)?;
-        Ok((blockchain, block_num))
+        let blockchain =
+            Self::new_with_genesis_block_and_state(database.clone(), block, state_updates)?;
+        Ok((blockchain, database, block_num))
     }
 
     pub fn provider(&self) -> &BlockchainProvider<Box<dyn Database>> {
@@ -221,6 +236,63 @@ impl Blockchain {
     }
 }
 
+/// Fetches the block `block_id` of the forked network and adjusts the genesis of `chain` to match
+/// it.
+///
+/// Returns the genesis block of a chain forked at that block.
+pub async fn forked_genesis(
+    provider: &JsonRpcClient<HttpTransport>,
+    block_id: BlockHashOrNumber,
+    chain: &mut ChainSpec,
+) -> Result<SealedBlockWithStatus> {
+    let block = provider
+        .get_block_with_tx_hashes(BlockIdOrTag::from(block_id))
+        .await
+        .context("failed to fetch forked block")?;
+
+    let MaybePendingBlockWithTxHashes::Block(forked_block) = block else {
+        bail!("forking a pending block is not allowed")
+    };
+
+    chain.version = ProtocolVersion::parse(&forked_block.starknet_version)?;
+
+    // adjust the genesis to match the forked block
+    chain.genesis.timestamp = forked_block.timestamp;
+    chain.genesis.number = forked_block.block_number;
+    chain.genesis.state_root = forked_block.new_root;
+    chain.genesis.parent_hash = forked_block.parent_hash;
+    chain.genesis.sequencer_address = forked_block.sequencer_address.into();
+
+    // TODO: remove gas price from genesis
+    chain.genesis.gas_prices.eth =
+        forked_block.l1_gas_price.price_in_wei.to_u128().expect("should fit in u128");
+    chain.genesis.gas_prices.strk =
+        forked_block.l1_gas_price.price_in_fri.to_u128().expect("should fit in u128");
+
+    let status = match forked_block.status {
+        BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
+        BlockStatus::AcceptedOnL2 => FinalityStatus::AcceptedOnL2,
+        // we already checked for pending block earlier. so this should never happen.
+        _ => bail!("qed; block status shouldn't be pending"),
+    };
+
+    // update the genesis block with the forked block's data
+    // we dont update the `l1_gas_price` bcs its already done when we set the `gas_prices` in
+    // genesis. this flow is kinda flawed, we should probably refactor it out of the
+    // genesis.
+    let mut block = chain.block();
+    block.header.l1_data_gas_prices.eth =
+        forked_block.l1_data_gas_price.price_in_wei.to_u128().expect("should fit in u128");
+    block.header.l1_data_gas_prices.strk =
+        forked_block.l1_data_gas_price.price_in_fri.to_u128().expect("should fit in u128");
+    block.header.l1_da_mode = match forked_block.l1_da_mode {
+        starknet::core::types::L1DataAvailabilityMode::Blob => L1DataAvailabilityMode::Blob,
+        starknet::core::types::L1DataAvailabilityMode::Calldata => L1DataAvailabilityMode::Calldata,
+    };
+
+    Ok(block.seal_with_hash_and_status(forked_block.block_hash, status))
+}
+
 #[cfg(test)]
 mod tests {
     use katana_primitives::block::{
@@ -10,21 +10,23 @@ use futures::stream::{Stream, StreamExt};
 use futures::FutureExt;
 use katana_executor::{BlockExecutor, ExecutionResult, ExecutionStats, ExecutorFactory};
 use katana_pool::validation::stateful::TxValidator;
-use katana_primitives::block::{BlockHashOrNumber, ExecutableBlock, PartialHeader};
-use katana_primitives::da::L1DataAvailabilityMode;
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
 use katana_primitives::receipt::Receipt;
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxHash, TxWithHash};
 use katana_provider::error::ProviderError;
-use katana_provider::traits::block::{BlockHashProvider, BlockNumberProvider};
+use katana_provider::traits::block::{BlockNumberProvider, BlockUnwinder};
 use katana_provider::traits::env::BlockEnvProvider;
 use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::{BlockchainProvider, ProviderResult};
 use katana_tasks::{BlockingTaskPool, BlockingTaskResult};
 use parking_lot::lock_api::RawMutex;
 use parking_lot::{Mutex, RwLock};
 use tokio::time::{interval_at, Instant, Interval};
 use tracing::{error, info, trace, warn};
 
+use crate::backend::storage::Database;
 use crate::backend::Backend;
 
 pub(crate) const LOG_TARGET: &str = "miner";
@@ -42,6 +44,15 @@ pub enum BlockProductionError {
 
     #[error("transaction execution error: {0}")]
     TransactionExecutionError(#[from] katana_executor::ExecutorError),
+
+    #[error("a block is currently being produced")]
+    BlockProductionInProgress,
+
+    #[error("the pending block contains transactions")]
+    PendingTransactions,
+
+    #[error("failed to compute the state diff blobs: {0}")]
+    StateDiffBlobs(#[from] c_kzg::Error),
 }
 
 #[derive(Debug, Clone)]
@@ -63,11 +74,46 @@ type ServiceFuture<T> = Pin<Box<dyn Future<Output = BlockingTaskResult<T>> + Sen
 type BlockProductionResult = Result<MinedBlockOutcome, BlockProductionError>;
 type BlockProductionFuture = ServiceFuture<Result<MinedBlockOutcome, BlockProductionError>>;
 
-type TxExecutionResult = Result<Vec<TxWithOutcome>, BlockProductionError>;
+/// The outcome of the executed transactions, and the transactions that didn't fit in the pending
+/// block.
+type TxExecutionResult =
+    Result<(Vec<TxWithOutcome>, Vec<ExecutableTxWithHash>), BlockProductionError>;
 type TxExecutionFuture = ServiceFuture<TxExecutionResult>;
 
-type BlockProductionWithTxnsFuture =
-    ServiceFuture<Result<(MinedBlockOutcome, Vec<TxWithOutcome>), BlockProductionError>>;
+/// The mined block, the outcome of its transactions, and the transactions that didn't fit in it.
+type BlockProductionWithTxnsResult = Result<
+    (MinedBlockOutcome, Vec<TxWithOutcome>, Vec<ExecutableTxWithHash>),
+    BlockProductionError,
+>;
+type BlockProductionWithTxnsFuture = ServiceFuture<BlockProductionWithTxnsResult>;
+
+const BLOCK_LISTENER_BUFFER_SIZE: usize = 256;
+
+/// Sends `item` to all the `listeners`, dropping the ones whose receiving half has been closed.
+fn notify_listeners<T: Clone>(listeners: &RwLock<Vec<Sender<T>>>, item: T) {
+    let mut listener = listeners.write();
+    // this is basically a retain but with mut reference
+    for n in (0..listener.len()).rev() {
+        let mut listener_tx = listener.swap_remove(n);
+        let retain = match listener_tx.try_send(item.clone()) {
+            Ok(()) => true,
+            Err(e) => {
+                if e.is_full() {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Unable to send new notification because channel is full.",
+                    );
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        if retain {
+            listener.push(listener_tx)
+        }
+    }
+}
 
 /// The type which responsible for block production.
 #[must_use = "BlockProducer does nothing unless polled"]
@@ -109,6 +155,16 @@ impl<EF: ExecutorFactory> BlockProducer<EF> {
         }
     }
 
+    /// Returns the number of transactions queued to be executed.
+    pub(super) fn queued_count(&self) -> usize {
+        let mode = self.producer.read();
+        let queued = match &*mode {
+            BlockProducerMode::Instant(producer) => &producer.queued,
+            BlockProducerMode::Interval(producer) => &producer.queued,
+        };
+        queued.iter().map(Vec::len).sum()
+    }
+
     pub fn validator(&self) -> TxValidator {
         let mode = self.producer.read();
         match &*mode {
@@ -117,6 +173,29 @@ impl<EF: ExecutorFactory> BlockProducer<EF> {
         }
     }
 
+    /// Registers a new listener that will be notified with the transactions executed by the
+    /// block producer.
+    ///
+    /// On _interval_ mining, the listener is notified as soon as the transactions are executed
+    /// and included in the pending block. On _instant_ mining, it is notified once the block
+    /// containing the transactions has been mined.
+    pub fn add_listener(&self) -> Receiver<Vec<TxWithOutcome>> {
+        let mode = self.producer.read();
+        match &*mode {
+            BlockProducerMode::Instant(pd) => pd.add_listener(),
+            BlockProducerMode::Interval(pd) => pd.add_listener(),
+        }
+    }
+
+    /// Registers a new listener that will be notified every time a new block is mined.
+    pub fn add_block_listener(&self) -> Receiver<MinedBlockOutcome> {
+        let mode = self.producer.read();
+        match &*mode {
+            BlockProducerMode::Instant(pd) => pd.add_block_listener(),
+            BlockProducerMode::Interval(pd) => pd.add_block_listener(),
+        }
+    }
+
     /// Returns `true` if the block producer is running in _interval_ mode. Otherwise, `fales`.
     pub fn is_interval_mining(&self) -> bool {
         matches!(*self.producer.read(), BlockProducerMode::Interval(_))
@@ -137,6 +216,49 @@ impl<EF: ExecutorFactory> BlockProducer<EF> {
         }
     }
 
+    /// Unwinds the chain to `block_number` and discards all the transactions that are queued or
+    /// in the pending block.
+    ///
+    /// Fails if a block is being produced at the time of the call.
+    pub fn unwind_to(&self, block_number: BlockNumber) -> Result<(), BlockProductionError> {
+        let mut mode = self.producer.write();
+        match &mut *mode {
+            BlockProducerMode::Instant(producer) => producer.unwind_to(block_number),
+            BlockProducerMode::Interval(producer) => producer.unwind_to(block_number),
+        }
+    }
+
+    /// Applies `update` directly to the latest state, and rebuilds the pending block and the pool
+    /// validator on top of the updated state.
+    ///
+    /// Fails if a block is being produced or if the pending block already contains transactions.
+    pub fn update_state<F>(&self, update: F) -> Result<(), BlockProductionError>
+    where
+        F: FnOnce(&BlockchainProvider<Box<dyn Database>>) -> ProviderResult<()>,
+    {
+        let mut mode = self.producer.write();
+        match &mut *mode {
+            BlockProducerMode::Instant(producer) => producer.update_state(update),
+            BlockProducerMode::Interval(producer) => producer.update_state(update),
+        }
+    }
+
+    /// Applies `updates` to the state of the pending block, so that they are committed along with
+    /// it, like the changes of a transaction. In instant mode, they are committed right away in a
+    /// block of their own.
+    ///
+    /// Fails if a block is being produced or if the pending block already contains transactions.
+    pub fn apply_state_updates(
+        &self,
+        updates: StateUpdatesWithDeclaredClasses,
+    ) -> Result<(), BlockProductionError> {
+        let mut mode = self.producer.write();
+        match &mut *mode {
+            BlockProducerMode::Instant(producer) => producer.apply_state_updates(updates),
+            BlockProducerMode::Interval(producer) => producer.apply_state_updates(updates),
+        }
+    }
+
     pub(super) fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<BlockProductionResult>> {
         let mut mode = self.producer.write();
         match &mut *mode {
@@ -190,11 +312,15 @@ pub struct IntervalBlockProducer<EF: ExecutorFactory> {
     ongoing_mining: Option<BlockProductionFuture>,
     /// Backlog of sets of transactions ready to be mined
     queued: VecDeque<Vec<ExecutableTxWithHash>>,
+    /// When the first transactions of the pending block were taken from the backlog
+    block_started_at: Option<Instant>,
     executor: PendingExecutor,
     blocking_task_spawner: BlockingTaskPool,
     ongoing_execution: Option<TxExecutionFuture>,
     /// Listeners notified when a new executed tx is added.
     tx_execution_listeners: RwLock<Vec<Sender<Vec<TxWithOutcome>>>>,
+    /// Listeners notified when a new block is mined.
+    block_listeners: RwLock<Vec<Sender<MinedBlockOutcome>>>,
 
     permit: Arc<Mutex<()>>,
 
@@ -239,8 +365,10 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
             ongoing_mining: None,
             ongoing_execution: None,
             queued: VecDeque::default(),
+            block_started_at: None,
             executor: PendingExecutor::new(executor),
             tx_execution_listeners: RwLock::new(vec![]),
+            block_listeners: RwLock::new(vec![]),
             blocking_task_spawner: BlockingTaskPool::new().unwrap(),
         }
     }
@@ -258,7 +386,13 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
 
     /// Force mine a new block. It will only able to mine if there is no ongoing mining process.
     pub fn force_mine(&mut self) {
-        match Self::do_mine(self.permit.clone(), self.executor.clone(), self.backend.clone()) {
+        let started_at = self.block_started_at.take();
+        match Self::do_mine(
+            self.permit.clone(),
+            self.executor.clone(),
+            self.backend.clone(),
+            started_at,
+        ) {
             Ok(outcome) => {
                 info!(target: LOG_TARGET, block_number = %outcome.block_number, "Force mined block.");
                 self.executor =
@@ -276,6 +410,8 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
                 // -------------------------------------------
 
                 unsafe { self.permit.raw().unlock() };
+
+                self.notify_block_listener(outcome);
             }
             Err(e) => {
                 error!(target: LOG_TARGET, error = %e, "On force mine.");
@@ -283,10 +419,93 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
         }
     }
 
+    /// Unwinds the chain to `block_number` and opens a new pending block on top of it.
+    pub fn unwind_to(&mut self, block_number: BlockNumber) -> Result<(), BlockProductionError> {
+        if self.ongoing_mining.is_some() {
+            return Err(BlockProductionError::BlockProductionInProgress);
+        }
+
+        let _permit =
+            self.permit.try_lock().ok_or(BlockProductionError::BlockProductionInProgress)?;
+
+        self.backend.blockchain.provider().unwind_to(block_number)?;
+
+        self.queued.clear();
+        self.block_started_at = None;
+        self.ongoing_execution = None;
+        self.executor = self.create_new_executor_for_next_block()?;
+
+        let provider = self.backend.blockchain.provider();
+        let state = self.executor.0.read().state();
+        let block_env = provider.block_env_at(block_number.into())?.expect("latest block env");
+        self.validator.reset(state, block_env);
+
+        info!(target: LOG_TARGET, %block_number, "Unwound chain.");
+        Ok(())
+    }
+
+    /// Applies `update` to the latest state and opens a new pending block on top of it.
+    pub fn update_state<F>(&mut self, update: F) -> Result<(), BlockProductionError>
+    where
+        F: FnOnce(&BlockchainProvider<Box<dyn Database>>) -> ProviderResult<()>,
+    {
+        if self.ongoing_mining.is_some() {
+            return Err(BlockProductionError::BlockProductionInProgress);
+        }
+
+        if self.ongoing_execution.is_some() || !self.executor.read().transactions().is_empty() {
+            return Err(BlockProductionError::PendingTransactions);
+        }
+
+        let _permit =
+            self.permit.try_lock().ok_or(BlockProductionError::BlockProductionInProgress)?;
+
+        let provider = self.backend.blockchain.provider();
+        update(provider)?;
+
+        self.executor = self.create_new_executor_for_next_block()?;
+
+        let state = self.executor.0.read().state();
+        let num = provider.latest_number()?;
+        let block_env = provider.block_env_at(num.into())?.expect("latest block env");
+        self.validator.reset(state, block_env);
+
+        Ok(())
+    }
+
+    /// Applies `updates` to the state of the pending block.
+    pub fn apply_state_updates(
+        &mut self,
+        updates: StateUpdatesWithDeclaredClasses,
+    ) -> Result<(), BlockProductionError> {
+        if self.ongoing_mining.is_some() {
+            return Err(BlockProductionError::BlockProductionInProgress);
+        }
+
+        if self.ongoing_execution.is_some() || !self.executor.read().transactions().is_empty() {
+            return Err(BlockProductionError::PendingTransactions);
+        }
+
+        let _permit =
+            self.permit.try_lock().ok_or(BlockProductionError::BlockProductionInProgress)?;
+
+        let mut executor = self.executor.write();
+        executor.apply_state_updates(updates)?;
+
+        let state = executor.state();
+        let block_env = executor.block_env();
+        self.validator.reset(state, block_env);
+
+        Ok(())
+    }
+
+    /// Commits the pending block, whose first transactions were taken at `started_at`, if it has
+    /// any.
     fn do_mine(
         permit: Arc<Mutex<()>>,
         executor: PendingExecutor,
         backend: Arc<Backend<EF>>,
+        started_at: Option<Instant>,
     ) -> Result<MinedBlockOutcome, BlockProductionError> {
         unsafe { permit.raw() }.lock();
         let executor = &mut executor.write();
@@ -297,6 +516,10 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
         let execution_output = executor.take_execution_output()?;
         let outcome = backend.do_mine_block(&block_env, execution_output)?;
 
+        if let Some(started_at) = started_at {
+            backend.metrics.record_block_production(started_at.elapsed().as_secs_f64());
+        }
+
         trace!(target: LOG_TARGET, block_number = %outcome.block_number, "Created new block.");
 
         Ok(outcome)
@@ -304,12 +527,16 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
 
     fn execute_transactions(
         executor: PendingExecutor,
-        transactions: Vec<ExecutableTxWithHash>,
+        mut transactions: Vec<ExecutableTxWithHash>,
     ) -> TxExecutionResult {
         let executor = &mut executor.write();
 
-        let new_txs_count = transactions.len();
-        executor.execute_transactions(transactions)?;
+        let (new_txs_count, limit) = executor.execute_transactions(transactions.clone())?;
+        let leftover = transactions.split_off(new_txs_count);
+
+        if let Some(resource) = limit {
+            info!(target: LOG_TARGET, %resource, leftover = %leftover.len(), "Block limit reached.");
+        }
 
         let txs = executor.transactions();
         let total_txs = txs.len();
@@ -328,7 +555,7 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
             })
             .collect::<Vec<TxWithOutcome>>();
 
-        Ok(results)
+        Ok((results, leftover))
     }
 
     fn create_new_executor_for_next_block(&self) -> Result<PendingExecutor, BlockProductionError> {
@@ -352,30 +579,20 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
         rx
     }
 
+    pub fn add_block_listener(&self) -> Receiver<MinedBlockOutcome> {
+        let (tx, rx) = channel(BLOCK_LISTENER_BUFFER_SIZE);
+        self.block_listeners.write().push(tx);
+        rx
+    }
+
     /// notifies all listeners about the transaction
     fn notify_listener(&self, txs: Vec<TxWithOutcome>) {
-        let mut listener = self.tx_execution_listeners.write();
-        // this is basically a retain but with mut reference
-        for n in (0..listener.len()).rev() {
-            let mut listener_tx = listener.swap_remove(n);
-            let retain = match listener_tx.try_send(txs.clone()) {
-                Ok(()) => true,
-                Err(e) => {
-                    if e.is_full() {
-                        warn!(
-                            target: LOG_TARGET,
-                            "Unable to send new txs notification because channel is full.",
-                        );
-                        true
-                    } else {
-                        false
-                    }
-                }
-            };
-            if retain {
-                listener.push(listener_tx)
-            }
-        }
+        notify_listeners(&self.tx_execution_listeners, txs);
+    }
+
+    /// notifies all block listeners about the newly mined block
+    fn notify_block_listener(&self, outcome: MinedBlockOutcome) {
+        notify_listeners(&self.block_listeners, outcome);
     }
 }
 
@@ -393,8 +610,10 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
                     let executor = pin.executor.clone();
                     let backend = pin.backend.clone();
                     let permit = pin.permit.clone();
+                    let started_at = pin.block_started_at.take();
 
-                    pin.blocking_task_spawner.spawn(|| Self::do_mine(permit, executor, backend))
+                    pin.blocking_task_spawner
+                        .spawn(move || Self::do_mine(permit, executor, backend, started_at))
                 }));
             }
         }
@@ -408,6 +627,7 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
 
                 let transactions: Vec<ExecutableTxWithHash> =
                     std::mem::take(&mut pin.queued).into_iter().flatten().collect();
+                pin.block_started_at.get_or_insert_with(Instant::now);
 
                 let fut = pin
                     .blocking_task_spawner
@@ -420,8 +640,28 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
             if let Some(mut execution) = pin.ongoing_execution.take() {
                 if let Poll::Ready(executor) = execution.poll_unpin(cx) {
                     match executor {
-                        Ok(Ok(txs)) => {
+                        Ok(Ok((txs, leftover))) => {
                             pin.notify_listener(txs);
+
+                            // the pending block is full, so it's closed early and the leftover
+                            // transactions are executed in the next one
+                            if !leftover.is_empty() {
+                                pin.queued.push_front(leftover);
+
+                                if pin.ongoing_mining.is_none() {
+                                    pin.ongoing_mining = Some(Box::pin({
+                                        let executor = pin.executor.clone();
+                                        let backend = pin.backend.clone();
+                                        let permit = pin.permit.clone();
+                                        let started_at = pin.block_started_at.take();
+
+                                        pin.blocking_task_spawner.spawn(move || {
+                                            Self::do_mine(permit, executor, backend, started_at)
+                                        })
+                                    }));
+                                }
+                            }
+
                             continue;
                         }
 
@@ -468,6 +708,10 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
                             Err(e) => return Poll::Ready(Some(Err(e))),
                         }
 
+                        if let Ok(outcome) = &outcome {
+                            pin.notify_block_listener(outcome.clone());
+                        }
+
                         return Poll::Ready(Some(outcome));
                     }
 
@@ -498,6 +742,8 @@ pub struct InstantBlockProducer<EF: ExecutorFactory> {
     blocking_task_pool: BlockingTaskPool,
     /// Listeners notified when a new executed tx is added.
     tx_execution_listeners: RwLock<Vec<Sender<Vec<TxWithOutcome>>>>,
+    /// Listeners notified when a new block is mined.
+    block_listeners: RwLock<Vec<Sender<MinedBlockOutcome>>>,
 
     permit: Arc<Mutex<()>>,
 
@@ -533,34 +779,132 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
             queued: VecDeque::default(),
             blocking_task_pool: BlockingTaskPool::new().unwrap(),
             tx_execution_listeners: RwLock::new(vec![]),
+            block_listeners: RwLock::new(vec![]),
         }
     }
 
     pub fn force_mine(&mut self) {
         if self.block_mining.is_none() {
             let txs = std::mem::take(&mut self.queued);
-            let _ = Self::do_mine(
+            let result = Self::do_mine(
                 self.validator.clone(),
                 self.permit.clone(),
                 self.backend.clone(),
                 txs,
             );
+
+            match result {
+                Ok((outcome, txs, leftover)) => {
+                    if !leftover.is_empty() {
+                        self.queued.push_front(leftover);
+                    }
+
+                    self.notify_listener(txs);
+                    self.notify_block_listener(outcome);
+                }
+                Err(error) => {
+                    error!(target: LOG_TARGET, %error, "On force mine.");
+                }
+            }
         } else {
             trace!(target: LOG_TARGET, "Unable to force mine while a mining process is running.")
         }
     }
 
+    /// Unwinds the chain to `block_number`.
+    pub fn unwind_to(&mut self, block_number: BlockNumber) -> Result<(), BlockProductionError> {
+        if self.block_mining.is_some() {
+            return Err(BlockProductionError::BlockProductionInProgress);
+        }
+
+        let _permit =
+            self.permit.try_lock().ok_or(BlockProductionError::BlockProductionInProgress)?;
+
+        let provider = self.backend.blockchain.provider();
+        provider.unwind_to(block_number)?;
+
+        self.queued.clear();
+
+        let state = provider.latest()?;
+        let block_env = provider.block_env_at(block_number.into())?.expect("latest block env");
+        self.validator.reset(state, block_env);
+
+        info!(target: LOG_TARGET, %block_number, "Unwound chain.");
+        Ok(())
+    }
+
+    /// Applies `update` to the latest state.
+    pub fn update_state<F>(&mut self, update: F) -> Result<(), BlockProductionError>
+    where
+        F: FnOnce(&BlockchainProvider<Box<dyn Database>>) -> ProviderResult<()>,
+    {
+        if self.block_mining.is_some() {
+            return Err(BlockProductionError::BlockProductionInProgress);
+        }
+
+        let _permit =
+            self.permit.try_lock().ok_or(BlockProductionError::BlockProductionInProgress)?;
+
+        let provider = self.backend.blockchain.provider();
+        update(provider)?;
+
+        let state = provider.latest()?;
+        let num = provider.latest_number()?;
+        let block_env = provider.block_env_at(num.into())?.expect("latest block env");
+        self.validator.reset(state, block_env);
+
+        Ok(())
+    }
+
+    /// Commits `updates` in a new block.
+    pub fn apply_state_updates(
+        &mut self,
+        updates: StateUpdatesWithDeclaredClasses,
+    ) -> Result<(), BlockProductionError> {
+        if self.block_mining.is_some() {
+            return Err(BlockProductionError::BlockProductionInProgress);
+        }
+
+        let permit =
+            self.permit.try_lock().ok_or(BlockProductionError::BlockProductionInProgress)?;
+
+        let provider = self.backend.blockchain.provider();
+        let latest_num = provider.latest_number()?;
+        let mut block_env = provider.block_env_at(latest_num.into())?.expect("latest block env");
+        self.backend.update_block_env(&mut block_env);
+
+        let state = provider.latest()?;
+        let mut executor =
+            self.backend.executor_factory.with_state_and_block_env(state, block_env.clone());
+
+        executor.apply_state_updates(updates)?;
+        let execution_output = executor.take_execution_output()?;
+        let outcome = self.backend.do_mine_block(&block_env, execution_output)?;
+
+        let state = provider.latest()?;
+        let block_env = provider.block_env_at(outcome.block_number.into())?.expect("latest");
+        self.validator.reset(state, block_env);
+
+        drop(permit);
+        self.notify_block_listener(outcome);
+
+        Ok(())
+    }
+
+    /// Mines a block with as many of `transactions` as fit in the block limits. The ones that
+    /// don't are returned, to be mined in the next block.
     fn do_mine(
         validator: TxValidator,
         permit: Arc<Mutex<()>>,
         backend: Arc<Backend<EF>>,
         transactions: VecDeque<Vec<ExecutableTxWithHash>>,
-    ) -> Result<(MinedBlockOutcome, Vec<TxWithOutcome>), BlockProductionError> {
+    ) -> BlockProductionWithTxnsResult {
+        let started_at = Instant::now();
         let _permit = permit.lock();
 
         trace!(target: LOG_TARGET, "Creating new block.");
 
-        let transactions = transactions.into_iter().flatten().collect::<Vec<_>>();
+        let mut transactions = transactions.into_iter().flatten().collect::<Vec<_>>();
 
         let provider = backend.blockchain.provider();
 
@@ -570,26 +914,16 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
         let mut block_env = provider.block_env_at(BlockHashOrNumber::Num(latest_num))?.unwrap();
         backend.update_block_env(&mut block_env);
 
-        let parent_hash = provider.latest_hash()?;
         let latest_state = provider.latest()?;
+        let mut executor =
+            backend.executor_factory.with_state_and_block_env(latest_state, block_env.clone());
 
-        let mut executor = backend.executor_factory.with_state(latest_state);
-
-        let block = ExecutableBlock {
-            body: transactions,
-            header: PartialHeader {
-                parent_hash,
-                number: block_env.number,
-                timestamp: block_env.timestamp,
-                protocol_version: backend.chain_spec.version.clone(),
-                sequencer_address: block_env.sequencer_address,
-                l1_da_mode: L1DataAvailabilityMode::Calldata,
-                l1_gas_prices: block_env.l1_gas_prices.clone(),
-                l1_data_gas_prices: block_env.l1_data_gas_prices.clone(),
-            },
-        };
+        let (executed, limit) = executor.execute_transactions(transactions.clone())?;
+        let leftover = transactions.split_off(executed);
 
-        executor.execute_block(block)?;
+        if let Some(resource) = limit {
+            info!(target: LOG_TARGET, %resource, leftover = %leftover.len(), "Block limit reached.");
+        }
 
         let execution_output = executor.take_execution_output()?;
         let txs_outcomes = execution_output
@@ -605,6 +939,7 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
             .collect::<Vec<_>>();
 
         let outcome = backend.do_mine_block(&block_env, execution_output)?;
+        backend.metrics.record_block_production(started_at.elapsed().as_secs_f64());
 
         // update pool validator state here ---------
 
@@ -618,7 +953,7 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
 
         trace!(target: LOG_TARGET, block_number = %outcome.block_number, "Created new block.");
 
-        Ok((outcome, txs_outcomes))
+        Ok((outcome, txs_outcomes, leftover))
     }
 
     pub fn add_listener(&self) -> Receiver<Vec<TxWithOutcome>> {
@@ -628,30 +963,20 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
         rx
     }
 
+    pub fn add_block_listener(&self) -> Receiver<MinedBlockOutcome> {
+        let (tx, rx) = channel(BLOCK_LISTENER_BUFFER_SIZE);
+        self.block_listeners.write().push(tx);
+        rx
+    }
+
     /// notifies all listeners about the transaction
     fn notify_listener(&self, txs: Vec<TxWithOutcome>) {
-        let mut listener = self.tx_execution_listeners.write();
-        // this is basically a retain but with mut reference
-        for n in (0..listener.len()).rev() {
-            let mut listener_tx = listener.swap_remove(n);
-            let retain = match listener_tx.try_send(txs.clone()) {
-                Ok(()) => true,
-                Err(e) => {
-                    if e.is_full() {
-                        warn!(
-                            target: LOG_TARGET,
-                            "Unable to send new txs notification because channel is full.",
-                        );
-                        true
-                    } else {
-                        false
-                    }
-                }
-            };
-            if retain {
-                listener.push(listener_tx)
-            }
-        }
+        notify_listeners(&self.tx_execution_listeners, txs);
+    }
+
+    /// notifies all block listeners about the newly mined block
+    fn notify_block_listener(&self, outcome: MinedBlockOutcome) {
+        notify_listeners(&self.block_listeners, outcome);
     }
 }
 
@@ -679,8 +1004,14 @@ impl<EF: ExecutorFactory> Stream for InstantBlockProducer<EF> {
         if let Some(mut mining) = pin.block_mining.take() {
             if let Poll::Ready(outcome) = mining.poll_unpin(cx) {
                 match outcome {
-                    Ok(Ok((outcome, txs))) => {
+                    Ok(Ok((outcome, txs, leftover))) => {
+                        // the block is full, so the leftover transactions are mined in the next one
+                        if !leftover.is_empty() {
+                            pin.queued.push_front(leftover);
+                        }
+
                         pin.notify_listener(txs);
+                        pin.notify_block_listener(outcome.clone());
                         return Poll::Ready(Some(Ok(outcome)));
                     }
 
@@ -0,0 +1,356 @@
+//! Keeps a forked chain in sync with the network it was forked from.
+//!
+//! The [`ForkFollower`] periodically checks the head of the forked network and, when it has moved
+//! past the current fork block, rebases the local chain onto it: the locally produced blocks are
+//! discarded, the chain is re-forked at the new head, and the transactions that were included in
+//! the discarded blocks are executed again on top of it.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use katana_executor::{
+    BlockExecutor, ExecutionOutput, ExecutionResult, ExecutorFactory, ExecutorResult,
+};
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
+use katana_primitives::transaction::{
+    DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, Tx, TxHash,
+};
+use katana_provider::error::ProviderError;
+use katana_provider::providers::fork::ForkedProvider;
+use katana_provider::traits::block::BlockNumberProvider;
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::transaction::{ReceiptProvider, TransactionProvider};
+use katana_provider::ProviderResult;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider};
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use super::block_producer::BlockProducer;
+use crate::backend::storage::forked_genesis;
+use crate::backend::Backend;
+
+pub(crate) const LOG_TARGET: &str = "fork";
+
+/// The outcome of rebasing the forked chain onto a newer block of the forked network.
+#[derive(Debug, Clone, Default)]
+pub struct RebaseOutcome {
+    /// The new fork block.
+    pub block_number: BlockNumber,
+    /// The local transactions that were executed again on top of the new fork block.
+    pub replayed: Vec<TxHash>,
+    /// The local transactions whose outcome changed on top of the new fork block.
+    pub conflicts: Vec<RebaseConflict>,
+}
+
+/// A local transaction that couldn't be replayed the same way on top of the new fork block.
+#[derive(Debug, Clone)]
+pub struct RebaseConflict {
+    pub hash: TxHash,
+    /// Why the transaction conflicts with the new state of the forked network.
+    pub reason: String,
+}
+
+/// A locally produced transaction that is to be replayed after a rebase.
+struct LocalTx {
+    tx: ExecutableTxWithHash,
+    reverted: bool,
+}
+
+/// Rebases the forked chain onto the head of the forked network every `interval`.
+#[allow(missing_debug_implementations)]
+pub struct ForkFollower<EF: ExecutorFactory> {
+    backend: Arc<Backend<EF>>,
+    block_producer: BlockProducer<EF>,
+    client: JsonRpcClient<HttpTransport>,
+    provider: Arc<ForkedProvider>,
+    /// The block of the forked network that the chain is currently forked from.
+    fork_block: BlockNumber,
+    interval: Duration,
+    listeners: Vec<UnboundedSender<RebaseOutcome>>,
+}
+
+impl<EF: ExecutorFactory> ForkFollower<EF> {
+    pub fn new(
+        backend: Arc<Backend<EF>>,
+        block_producer: BlockProducer<EF>,
+        client: JsonRpcClient<HttpTransport>,
+        provider: Arc<ForkedProvider>,
+        fork_block: BlockNumber,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            backend,
+            client,
+            provider,
+            interval,
+            fork_block,
+            block_producer,
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Returns a receiver of the outcome of every rebase.
+    pub fn add_listener(&mut self) -> UnboundedReceiver<RebaseOutcome> {
+        let (tx, rx) = unbounded();
+        self.listeners.push(tx);
+        rx
+    }
+
+    /// Runs the follower until the node is stopped.
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // the first tick completes immediately
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+            if let Err(error) = self.follow().await {
+                error!(target: LOG_TARGET, %error, "Following forked network.");
+            }
+        }
+    }
+
+    /// Rebases the chain if the head of the forked network has moved past the fork block.
+    async fn follow(&mut self) -> Result<()> {
+        let head = self.client.block_number().await?;
+        if head <= self.fork_block {
+            return Ok(());
+        }
+
+        let outcome = self.rebase(head).await?;
+
+        for conflict in &outcome.conflicts {
+            warn!(target: LOG_TARGET, hash = %format!("{:#x}", conflict.hash), reason = %conflict.reason, "Conflicting transaction after rebase.");
+        }
+
+        info!(
+            target: LOG_TARGET,
+            block = %outcome.block_number,
+            replayed = %outcome.replayed.len(),
+            conflicts = %outcome.conflicts.len(),
+            "Rebased onto forked network."
+        );
+
+        self.fork_block = outcome.block_number;
+        self.listeners.retain(|listener| listener.unbounded_send(outcome.clone()).is_ok());
+
+        Ok(())
+    }
+
+    /// Rebases the chain onto block `block` of the forked network and replays the local
+    /// transactions on top of it.
+    async fn rebase(&self, block: BlockNumber) -> Result<RebaseOutcome> {
+        let mut chain = self.backend.chain_spec.clone();
+        let genesis = forked_genesis(&self.client, block.into(), &mut chain).await?;
+        let states = chain.state_updates();
+
+        let mut outcome = RebaseOutcome { block_number: block, ..Default::default() };
+
+        self.block_producer.update_state(|provider| {
+            let local_txs = self.local_transactions()?;
+            self.provider.rebase(block, genesis, states)?;
+
+            if local_txs.is_empty() {
+                return Ok(());
+            }
+
+            let state = provider.latest()?;
+            let mut block_env = provider.block_env_at(block.into())?.expect("genesis must exist");
+            self.backend.update_block_env(&mut block_env);
+
+            let executor =
+                self.backend.executor_factory.with_state_and_block_env(state, block_env.clone());
+            let output = replay(executor, local_txs, &mut outcome)
+                .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+            self.backend
+                .do_mine_block(&block_env, output)
+                .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+            Ok(())
+        })?;
+
+        Ok(outcome)
+    }
+
+    /// Returns the transactions of the blocks produced locally on top of the fork block.
+    fn local_transactions(&self) -> ProviderResult<Vec<LocalTx>> {
+        let provider = self.backend.blockchain.provider();
+        let state = provider.latest()?;
+        let latest = provider.latest_number()?;
+
+        let mut local_txs = Vec::new();
+
+        for num in self.fork_block + 1..=latest {
+            let txs = provider.transactions_by_block(BlockHashOrNumber::Num(num))?;
+
+            for tx in txs.unwrap_or_default() {
+                let reverted = provider
+                    .receipt_by_hash(tx.hash)?
+                    .map(|receipt| receipt.is_reverted())
+                    .unwrap_or_default();
+
+                let transaction = match tx.transaction {
+                    Tx::Invoke(tx) => ExecutableTx::Invoke(tx),
+                    Tx::L1Handler(tx) => ExecutableTx::L1Handler(tx),
+                    Tx::DeployAccount(tx) => ExecutableTx::DeployAccount(tx),
+                    Tx::Declare(tx) => {
+                        let hash = tx.class_hash();
+                        let compiled_class =
+                            state.class(hash)?.ok_or(ProviderError::MissingCompiledClass(hash))?;
+                        let sierra_class = state.sierra_class(hash)?;
+                        ExecutableTx::Declare(DeclareTxWithClass {
+                            sierra_class,
+                            compiled_class,
+                            transaction: tx,
+                        })
+                    }
+                };
+
+                let tx = ExecutableTxWithHash { hash: tx.hash, transaction };
+                local_txs.push(LocalTx { tx, reverted });
+            }
+        }
+
+        Ok(local_txs)
+    }
+}
+
+/// Executes the local transactions with `executor`, recording in `outcome` the ones that were
+/// replayed and the ones that conflict with the new state of the forked network.
+fn replay<'a>(
+    mut executor: Box<dyn BlockExecutor<'a> + 'a>,
+    local_txs: Vec<LocalTx>,
+    outcome: &mut RebaseOutcome,
+) -> ExecutorResult<ExecutionOutput> {
+    let reverted = local_txs.iter().map(|tx| tx.reverted).collect::<Vec<_>>();
+    let txs = local_txs.into_iter().map(|tx| tx.tx).collect::<Vec<_>>();
+    let hashes = txs.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+    let (executed, limit) = executor.execute_transactions(txs)?;
+
+    let output = executor.take_execution_output()?;
+
+    for ((tx, res), was_reverted) in output.transactions.iter().zip(reverted) {
+        let reason = match res {
+            ExecutionResult::Failed { error } => Some(error.to_string()),
+            ExecutionResult::Success { receipt, .. } => {
+                if receipt.is_reverted() && !was_reverted {
+                    receipt.revert_reason().map(ToString::to_string)
+                } else {
+                    outcome.replayed.push(tx.hash);
+                    None
+                }
+            }
+        };
+
+        if let Some(reason) = reason {
+            outcome.conflicts.push(RebaseConflict { hash: tx.hash, reason });
+        }
+    }
+
+    // the transactions that don't fit in the rebased block are dropped
+    if let Some(resource) = limit {
+        for hash in &hashes[executed..] {
+            let reason = format!("Block limit on {resource} reached");
+            outcome.conflicts.push(RebaseConflict { hash: *hash, reason });
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_executor::implementation::blockifier::BlockifierFactory;
+    use katana_executor::{BlockLimits, ExecutionFlags, ExecutorFactory};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::env::{BlockEnv, CfgEnv, FeeTokenAddressses};
+    use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+    use katana_primitives::transaction::{
+        ExecutableTx, ExecutableTxWithHash, InvokeTx, InvokeTxV1,
+    };
+    use katana_primitives::{chain_spec, Felt};
+    use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::state::StateFactoryProvider;
+    use starknet::macros::selector;
+
+    use super::{replay, LocalTx, RebaseOutcome};
+    use crate::backend::storage::Blockchain;
+
+    /// Returns a local transfer of `amount` of ETH from `sender`.
+    fn transfer(sender: ContractAddress, nonce: u64, amount: u128, reverted: bool) -> LocalTx {
+        let calldata = vec![
+            Felt::ONE,
+            DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+            selector!("transfer"),
+            Felt::THREE,
+            Felt::ONE,
+            amount.into(),
+            amount.into(),
+        ];
+
+        let tx = InvokeTx::V1(InvokeTxV1 {
+            chain_id: chain_spec::DEV.id,
+            sender_address: sender,
+            nonce: nonce.into(),
+            calldata,
+            signature: vec![],
+            max_fee: 0,
+        });
+
+        LocalTx { tx: ExecutableTxWithHash::new(ExecutableTx::Invoke(tx)), reverted }
+    }
+
+    #[test]
+    fn replay_local_transactions() {
+        let chain = &chain_spec::DEV;
+        let blockchain = Blockchain::new_with_chain(DbProvider::new_ephemeral(), chain).unwrap();
+        let state = blockchain.provider().latest().unwrap();
+
+        let cfg = CfgEnv {
+            chain_id: chain.id,
+            invoke_tx_max_n_steps: 10_000_000,
+            validate_max_n_steps: 1_000_000,
+            max_recursion_depth: 100,
+            fee_token_addresses: FeeTokenAddressses {
+                eth: chain.fee_contracts.eth,
+                strk: chain.fee_contracts.strk,
+            },
+        };
+        let flags = ExecutionFlags::new().with_account_validation(false).with_fee(false);
+        // only three transactions fit in the rebased block
+        let limits = BlockLimits { tx_count: 3, ..BlockLimits::max() };
+        let factory = BlockifierFactory::new(cfg, flags).with_block_limits(limits);
+
+        let block_env = BlockEnv { number: 1, ..Default::default() };
+        let executor = factory.with_state_and_block_env(state, block_env);
+
+        let accounts = chain.genesis.accounts().map(|(a, _)| *a).collect::<Vec<_>>();
+        let local_txs = vec![
+            transfer(accounts[0], 0, 1, false),
+            // the nonce has already been used by the transaction before it
+            transfer(accounts[0], 0, 1, false),
+            // the transfers of more than the balance revert
+            transfer(accounts[1], 0, u128::MAX, true),
+            transfer(accounts[2], 0, u128::MAX, false),
+            transfer(accounts[3], 0, 1, false),
+        ];
+        let hashes = local_txs.iter().map(|tx| tx.tx.hash).collect::<Vec<_>>();
+
+        let mut outcome = RebaseOutcome::default();
+        let output = replay(executor, local_txs, &mut outcome).unwrap();
+
+        assert_eq!(output.transactions.len(), 4);
+        assert_eq!(outcome.replayed, vec![hashes[0], hashes[2]]);
+
+        let conflicts = outcome.conflicts.iter().map(|c| c.hash).collect::<Vec<_>>();
+        assert_eq!(conflicts, vec![hashes[1], hashes[3], hashes[4]]);
+        assert!(outcome.conflicts[2].reason.starts_with("Block limit"));
+    }
+}
@@ -11,6 +11,7 @@ use alloy_sol_types::{sol, SolEvent};
 use anyhow::Result;
 use async_trait::async_trait;
 use katana_primitives::chain::ChainId;
+use katana_primitives::message::{L1ToL2MessageHash, MessageCancellation};
 use katana_primitives::receipt::MessageToL1;
 use katana_primitives::transaction::L1HandlerTx;
 use katana_primitives::utils::transaction::{
@@ -20,7 +21,7 @@ use katana_primitives::Felt;
 use starknet::core::types::EthAddress;
 use tracing::{debug, trace, warn};
 
-use super::{Error, MessagingConfig, Messenger, MessengerResult, LOG_TARGET};
+use super::{Error, MessagingConfig, MessagingEvent, Messenger, MessengerResult, LOG_TARGET};
 
 sol! {
     #[sol(rpc, rename_all = "snakecase")]
@@ -44,10 +45,35 @@ sol! {
     }
 }
 
+sol! {
+    #[sol(rpc)]
+    contract MessageToL2Cancellation {
+        #[derive(Debug, PartialEq)]
+        event MessageToL2CancellationStarted(
+            address indexed from_address,
+            uint256 indexed to_address,
+            uint256 indexed selector,
+            uint256[] payload,
+            uint256 nonce
+        );
+
+        #[derive(Debug, PartialEq)]
+        event MessageToL2Canceled(
+            address indexed from_address,
+            uint256 indexed to_address,
+            uint256 indexed selector,
+            uint256[] payload,
+            uint256 nonce
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct EthereumMessaging {
     provider: Arc<ReqwestProvider<Ethereum>>,
     messaging_contract_address: Address,
+    /// The number of blocks to wait on top of a block before gathering its messages.
+    confirmations: u64,
 }
 
 impl EthereumMessaging {
@@ -57,11 +83,12 @@ impl EthereumMessaging {
                 &config.rpc_url,
             )?)),
             messaging_contract_address: config.contract_address.parse::<Address>()?,
+            confirmations: config.confirmations,
         })
     }
 
-    /// Fetches logs in given block range and returns a `HashMap` with the list of logs mapped to
-    /// their block number.
+    /// Fetches the logs of the messages sent to L2, and of their cancellations, in given block
+    /// range and returns a `HashMap` with the list of logs mapped to their block number.
     ///
     /// There is not pagination in ethereum, and no hard limit on block range.
     /// Fetching too much block may result in RPC request error.
@@ -83,14 +110,14 @@ impl EthereumMessaging {
             },
             address: FilterSet::<Address>::from(self.messaging_contract_address),
             topics: [
-                Topic::from(
+                Topic::from(vec![
                     //  LogMessageToL2 (index_topic_1 address fromAddress, index_topic_2 uint256
                     // toAddress,  index_topic_3 uint256 selector, uint256[]
                     // payload, uint256 nonce, uint256 fee)
-                    "0xdb80dd488acf86d17c747445b0eabb5d57c541d3bd7b6b87af987858e5066b2b"
-                        .parse::<U256>()
-                        .unwrap(),
-                ),
+                    LogMessageToL2::LogMessageToL2Event::SIGNATURE_HASH,
+                    MessageToL2Cancellation::MessageToL2CancellationStarted::SIGNATURE_HASH,
+                    MessageToL2Cancellation::MessageToL2Canceled::SIGNATURE_HASH,
+                ]),
                 Default::default(),
                 Default::default(),
                 Default::default(),
@@ -120,10 +147,17 @@ impl Messenger for EthereumMessaging {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
-        let chain_latest_block: u64 = self.provider.get_block_number().await?;
+    ) -> MessengerResult<(u64, Vec<MessagingEvent<Self::MessageTransaction>>)> {
+        // only the blocks with enough confirmations are considered
+        let chain_latest_block: u64 =
+            self.provider.get_block_number().await?.saturating_sub(self.confirmations);
         trace!(target: LOG_TARGET, from_block, max_blocks, ?chain_id, latest_block = chain_latest_block, "Gathering messages ethereum.");
 
+        if from_block > chain_latest_block {
+            // Nothing to fetch, we can skip waiting the next tick.
+            return Ok((chain_latest_block, vec![]));
+        }
+
         // +1 as the from_block counts as 1 block fetched.
         let to_block = if from_block + max_blocks + 1 < chain_latest_block {
             from_block + max_blocks
@@ -131,22 +165,22 @@ impl Messenger for EthereumMessaging {
             chain_latest_block
         };
 
-        let mut l1_handler_txs = vec![];
+        let mut events = vec![];
 
         trace!(target: LOG_TARGET, from_block, to_block, "Fetching logs from {from_block} to {to_block}.");
-        self.fetch_logs(from_block, to_block).await?.iter().for_each(|l| {
+        self.fetch_logs(from_block, to_block).await?.into_iter().for_each(|l| {
             debug!(
                 target: LOG_TARGET,
                 log = ?l,
-                "Converting log into L1HandlerTx.",
+                "Converting log into messaging event.",
             );
 
-            if let Ok(tx) = l1_handler_tx_from_log(l.clone(), chain_id) {
-                l1_handler_txs.push(tx)
+            if let Some(event) = messaging_event_from_log(l, chain_id) {
+                events.push(event)
             }
         });
 
-        Ok((to_block, l1_handler_txs))
+        Ok((to_block, events))
     }
 
     async fn send_messages(
@@ -187,6 +221,73 @@ impl Messenger for EthereumMessaging {
     }
 }
 
+/// Converts a log of the messaging contract into the [`MessagingEvent`] it's the source of, or
+/// `None` if it isn't a messaging log.
+fn messaging_event_from_log(log: Log, chain_id: ChainId) -> Option<MessagingEvent<L1HandlerTx>> {
+    let topic = log.inner.data.topics().first().copied()?;
+    // logs of mined blocks always have the hash of their transaction
+    let l1_tx_hash = log.transaction_hash.unwrap_or_default();
+
+    if topic == LogMessageToL2::LogMessageToL2Event::SIGNATURE_HASH {
+        let tx = l1_handler_tx_from_log(log, chain_id).ok()?;
+        Some(MessagingEvent::MessageSent { l1_tx_hash, tx })
+    } else if topic == MessageToL2Cancellation::MessageToL2CancellationStarted::SIGNATURE_HASH {
+        let event = MessageToL2Cancellation::MessageToL2CancellationStarted::decode_log(
+            log.as_ref(),
+            false,
+        )
+        .ok()?;
+
+        let message_hash = cancelled_message_hash(
+            event.from_address,
+            event.to_address,
+            event.selector,
+            &event.payload,
+            event.nonce,
+        )?;
+
+        let cancellation = MessageCancellation::Started;
+        Some(MessagingEvent::Cancellation { message_hash, cancellation })
+    } else if topic == MessageToL2Cancellation::MessageToL2Canceled::SIGNATURE_HASH {
+        let event =
+            MessageToL2Cancellation::MessageToL2Canceled::decode_log(log.as_ref(), false).ok()?;
+
+        let message_hash = cancelled_message_hash(
+            event.from_address,
+            event.to_address,
+            event.selector,
+            &event.payload,
+            event.nonce,
+        )?;
+
+        let cancellation = MessageCancellation::Cancelled;
+        Some(MessagingEvent::Cancellation { message_hash, cancellation })
+    } else {
+        None
+    }
+}
+
+/// Computes the hash of the message whose cancellation is logged, from the fields of the log.
+fn cancelled_message_hash(
+    from_address: Address,
+    to_address: U256,
+    selector: U256,
+    payload: &[U256],
+    nonce: U256,
+) -> Option<L1ToL2MessageHash> {
+    let from_address = EthAddress::try_from(from_address.as_slice()).ok()?;
+    let payload = payload.iter().copied().map(felt_from_u256).collect::<Vec<_>>();
+    let nonce: u64 = nonce.try_into().ok()?;
+
+    Some(compute_l1_to_l2_message_hash(
+        from_address,
+        felt_from_u256(to_address),
+        felt_from_u256(selector),
+        &payload,
+        nonce,
+    ))
+}
+
 // TODO: refactor this as a method of the message log struct
 fn l1_handler_tx_from_log(log: Log, chain_id: ChainId) -> MessengerResult<L1HandlerTx> {
     let log = LogMessageToL2::LogMessageToL2Event::decode_log(log.as_ref(), false).unwrap();
@@ -315,6 +416,47 @@ mod tests {
         assert_eq!(expected_tx_hash, expected_tx.calculate_hash());
     }
 
+    #[test]
+    fn messaging_event_from_cancellation_log() {
+        let from_address = felt!("0xbe3C44c09bc1a3566F3e1CA12e5AbA0fA4Ca72Be");
+        let to_address = felt!("0x39dc79e64f4bb3289240f88e0bae7d21735bef0d1a51b2bf3c4730cb16983e1");
+        let selector = felt!("0x2f15cff7b0eed8b9beb162696cf4e3e0e35fa7032af69cd1b7d2ac67a13f40f");
+        let nonce = 783082_u64;
+
+        let event = MessageToL2Cancellation::MessageToL2Canceled::new(
+            (
+                MessageToL2Cancellation::MessageToL2Canceled::SIGNATURE_HASH,
+                address!("be3C44c09bc1a3566F3e1CA12e5AbA0fA4Ca72Be"),
+                U256::from_be_slice(&to_address.to_bytes_be()),
+                U256::from_be_slice(&selector.to_bytes_be()),
+            ),
+            (vec![U256::from(1), U256::from(2)], U256::from(nonce)),
+        );
+
+        let log = Log {
+            inner: alloy_primitives::Log::<LogData> {
+                address: address!("de29d060D45901Fb19ED6C6e959EB22d8626708e"),
+                data: LogData::from(&event),
+            },
+            ..Default::default()
+        };
+
+        let message_hash = compute_l1_to_l2_message_hash(
+            EthAddress::from_felt(&from_address).unwrap(),
+            to_address,
+            selector,
+            &[Felt::ONE, Felt::TWO],
+            nonce,
+        );
+
+        let chain_id = ChainId::Named(NamedChainId::Goerli);
+        let expected = MessagingEvent::Cancellation {
+            message_hash,
+            cancellation: MessageCancellation::Cancelled,
+        };
+        assert_eq!(messaging_event_from_log(log, chain_id), Some(expected));
+    }
+
     #[test]
     fn parse_msg_to_l1() {
         let from_address = selector!("from_address");
@@ -50,6 +50,7 @@ use ethereum::EthereumMessaging;
 use futures::StreamExt;
 use katana_executor::ExecutorFactory;
 use katana_primitives::chain::ChainId;
+use katana_primitives::message::{L1ToL2MessageHash, L1TxHash, MessageCancellation};
 use katana_primitives::receipt::MessageToL1;
 use serde::Deserialize;
 use tracing::{error, info};
@@ -60,7 +61,6 @@ use self::starknet::StarknetMessaging;
 
 pub(crate) const LOG_TARGET: &str = "messaging";
 pub(crate) const CONFIG_CHAIN_ETHEREUM: &str = "ethereum";
-#[cfg(feature = "starknet-messaging")]
 pub(crate) const CONFIG_CHAIN_STARKNET: &str = "starknet";
 
 type MessengerResult<T> = Result<T, Error>;
@@ -111,7 +111,14 @@ pub struct MessagingConfig {
     /// from/to the settlement chain.
     pub interval: u64,
     /// The block on settlement chain from where Katana will start fetching messages.
+    ///
+    /// When Katana is restarted on an existing database, it resumes from the block after the last
+    /// one whose messages were all executed instead, if it's higher.
     pub from_block: u64,
+    /// The number of blocks to wait on top of a settlement chain block before gathering its
+    /// messages, for them not to be affected by reorgs of the settlement chain.
+    #[serde(default)]
+    pub confirmations: u64,
 }
 
 impl MessagingConfig {
@@ -127,6 +134,16 @@ impl MessagingConfig {
     }
 }
 
+/// An event of the messaging contract of the settlement chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessagingEvent<T> {
+    /// A message was sent to Katana by the settlement chain transaction `l1_tx_hash`, and
+    /// converted into the transaction `tx`.
+    MessageSent { l1_tx_hash: L1TxHash, tx: T },
+    /// The sender of the message `message_hash` started or completed its cancellation.
+    Cancellation { message_hash: L1ToL2MessageHash, cancellation: MessageCancellation },
+}
+
 #[async_trait]
 pub trait Messenger {
     /// The type of the message hash.
@@ -137,8 +154,8 @@ pub trait Messenger {
     type MessageTransaction;
 
     /// Gathers messages emitted on the settlement chain and convert them to their
-    /// corresponding transaction type on Starknet, and the latest block on the settlement until
-    /// which the messages were collected.
+    /// corresponding transaction type on Starknet, along with the cancellations of messages, and
+    /// the latest block on the settlement until which the messages were collected.
     ///
     /// # Arguments
     ///
@@ -151,7 +168,7 @@ pub trait Messenger {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)>;
+    ) -> MessengerResult<(u64, Vec<MessagingEvent<Self::MessageTransaction>>)>;
 
     /// Computes the hash of the given messages and sends them to the settlement chain.
     ///
@@ -241,3 +258,17 @@ impl<EF: ExecutorFactory> Future for MessagingTask<EF> {
         Poll::Pending
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MessagingConfig;
+
+    #[test]
+    fn config_without_confirmations() {
+        let config = include_str!("../../../../contracts/messaging/anvil.messaging.json");
+        let config: MessagingConfig = serde_json::from_str(config).unwrap();
+        // the messages are gathered as soon as their block is mined by default
+        assert_eq!(config.confirmations, 0);
+        assert_eq!(config.from_block, 0);
+    }
+}
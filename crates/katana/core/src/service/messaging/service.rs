@@ -1,3 +1,4 @@
+use std::collections::{HashSet, VecDeque};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -6,22 +7,33 @@ use std::time::Duration;
 use futures::{Future, FutureExt, Stream};
 use katana_executor::ExecutorFactory;
 use katana_pool::TransactionPool;
-use katana_primitives::block::BlockHashOrNumber;
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
+use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash, MessageCancellation};
 use katana_primitives::receipt::MessageToL1;
 use katana_primitives::transaction::{ExecutableTxWithHash, L1HandlerTx, TxHash};
 use katana_provider::traits::block::BlockNumberProvider;
-use katana_provider::traits::transaction::ReceiptProvider;
+use katana_provider::traits::message::{L1ToL2MessageProvider, L1ToL2MessageWriter};
+use katana_provider::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use katana_provider::traits::transaction::{ReceiptProvider, TransactionStatusProvider};
 use tokio::time::{interval_at, Instant, Interval};
-use tracing::{error, info};
+use tracing::{debug, error, info};
 
-use super::{MessagingConfig, Messenger, MessengerMode, MessengerResult, LOG_TARGET};
+use super::{
+    MessagingConfig, MessagingEvent, Messenger, MessengerMode, MessengerResult, LOG_TARGET,
+};
 use crate::backend::Backend;
 use crate::service::TxPool;
 
 type MessagingFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
-type MessageGatheringFuture = MessagingFuture<MessengerResult<(u64, usize)>>;
+type MessageGatheringFuture = MessagingFuture<MessengerResult<(u64, Vec<GatheredTx>)>>;
+
 type MessageSettlingFuture = MessagingFuture<MessengerResult<Option<(u64, usize)>>>;
 
+/// The id of the checkpoint of the last settlement chain block messages were gathered from.
+const GATHER_CHECKPOINT: &str = "messaging/gather";
+/// The id of the checkpoint of the last local block whose messages were sent.
+const SEND_CHECKPOINT: &str = "messaging/send";
+
 #[allow(missing_debug_implementations)]
 pub struct MessagingService<EF: ExecutorFactory> {
     /// The interval at which the service will perform the messaging operations.
@@ -34,6 +46,11 @@ pub struct MessagingService<EF: ExecutorFactory> {
     gather_from_block: u64,
     /// The message gathering future.
     msg_gather_fut: Option<MessageGatheringFuture>,
+    /// The settlement chain blocks messages were gathered from, along with the transactions of
+    /// their messages that are yet to be committed in a block. The gather checkpoint only moves
+    /// past a block once all of them are, or were dropped, for the messages that weren't executed
+    /// before a restart to be gathered again.
+    unsettled_blocks: VecDeque<(u64, Vec<GatheredTx>)>,
     /// The block number of the local blockchain from which messages will be sent.
     send_from_block: u64,
     /// The message sending future.
@@ -48,7 +65,14 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
         pool: TxPool,
         backend: Arc<Backend<EF>>,
     ) -> anyhow::Result<Self> {
-        let gather_from_block = config.from_block;
+        // resume after the blocks processed before Katana was restarted, if any
+        let provider = backend.blockchain.provider();
+        let gather_from_block = match provider.checkpoint(GATHER_CHECKPOINT)? {
+            Some(block) => config.from_block.max(block + 1),
+            None => config.from_block,
+        };
+        let send_from_block = provider.checkpoint(SEND_CHECKPOINT)?.map_or(0, |block| block + 1);
+
         let interval = interval_from_seconds(config.interval);
         let messenger = match MessengerMode::from_config(config).await {
             Ok(m) => Arc::new(m),
@@ -66,8 +90,9 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
             interval,
             messenger,
             gather_from_block,
-            send_from_block: 0,
+            send_from_block,
             msg_gather_fut: None,
+            unsettled_blocks: VecDeque::new(),
             msg_send_fut: None,
         })
     }
@@ -77,47 +102,183 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
         pool: TxPool,
         backend: Arc<Backend<EF>>,
         from_block: u64,
-    ) -> MessengerResult<(u64, usize)> {
+    ) -> MessengerResult<(u64, Vec<GatheredTx>)> {
         // 200 avoids any possible rejection from RPC with possibly lot's of messages.
         // TODO: May this be configurable?
         let max_block = 200;
 
         match messenger.as_ref() {
             MessengerMode::Ethereum(inner) => {
-                let (block_num, txs) =
+                let (block_num, events) =
                     inner.gather_messages(from_block, max_block, backend.chain_spec.id).await?;
-                let txs_count = txs.len();
-
-                txs.into_iter().for_each(|tx| {
-                    let hash = tx.calculate_hash();
-                    trace_l1_handler_tx_exec(hash, &tx);
-
-                    // ignore result because L1Handler tx will always be valid
-                    let _ =
-                        pool.add_transaction(ExecutableTxWithHash { hash, transaction: tx.into() });
-                });
-
-                Ok((block_num, txs_count))
+                let txs = Self::process_events(events, &pool, &backend);
+                Ok((block_num, txs))
             }
 
             #[cfg(feature = "starknet-messaging")]
             MessengerMode::Starknet(inner) => {
-                let (block_num, txs) =
+                let (block_num, events) =
                     inner.gather_messages(from_block, max_block, backend.chain_spec.id).await?;
-                let txs_count = txs.len();
+                let txs = Self::process_events(events, &pool, &backend);
+                Ok((block_num, txs))
+            }
+        }
+    }
 
-                txs.into_iter().for_each(|tx| {
+    /// Adds the transactions of the messages sent to Katana to the pool, and records the messages
+    /// and the progress of their cancellations for their status to be queried. The transactions
+    /// of the cancelled messages are dropped from the pool. Returns the transactions added to the
+    /// pool.
+    fn process_events(
+        events: Vec<MessagingEvent<L1HandlerTx>>,
+        pool: &TxPool,
+        backend: &Backend<EF>,
+    ) -> Vec<GatheredTx> {
+        let provider = backend.blockchain.provider();
+        let mut txs = Vec::new();
+
+        // the messages may be cancelled in the same blocks they are sent in
+        let cancelled = events
+            .iter()
+            .filter_map(|event| match event {
+                MessagingEvent::Cancellation {
+                    message_hash,
+                    cancellation: MessageCancellation::Cancelled,
+                } => Some(*message_hash),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+
+        for event in events {
+            match event {
+                MessagingEvent::MessageSent { l1_tx_hash, tx } => {
                     let hash = tx.calculate_hash();
+
+                    // The blocks processed right before a restart may be gathered again, so their
+                    // messages are only added once.
+                    if is_committed(backend, hash) || pool.contains(hash) {
+                        debug!(target: LOG_TARGET, tx_hash = %format!("{:#x}", hash), "L1Handler transaction already processed.");
+                        continue;
+                    }
+
                     trace_l1_handler_tx_exec(hash, &tx);
 
-                    // ignore result because L1Handler tx will always be valid
+                    // the cancellation of a message gathered again must not be lost
+                    let cancellation = if cancelled.contains(&tx.message_hash) {
+                        Some(MessageCancellation::Cancelled)
+                    } else {
+                        provider
+                            .message(tx.message_hash)
+                            .ok()
+                            .flatten()
+                            .and_then(|message| message.cancellation)
+                    };
+
+                    let message = L1ToL2Message {
+                        l1_tx_hash,
+                        message_hash: tx.message_hash,
+                        tx_hash: hash,
+                        cancellation,
+                        dropped: false,
+                    };
+
+                    if let Err(error) = provider.insert_message(message) {
+                        error!(target: LOG_TARGET, %error, "Storing L1 to L2 message.");
+                    }
+
+                    if cancellation == Some(MessageCancellation::Cancelled) {
+                        info!(target: LOG_TARGET, tx_hash = %format!("{:#x}", hash), "L1 to L2 message cancelled, its transaction is dropped.");
+                        continue;
+                    }
+
+                    // L1Handler txs are always valid
+                    let message_hash = tx.message_hash;
                     let tx = ExecutableTxWithHash { hash, transaction: tx.into() };
-                    let _ = pool.add_transaction(tx);
-                });
+                    if pool.add_transaction(tx).is_ok() {
+                        txs.push(GatheredTx { hash, message_hash, missing_since: None });
+                    }
+                }
 
-                Ok((block_num, txs_count))
+                MessagingEvent::Cancellation { message_hash, cancellation } => {
+                    match provider.set_message_cancellation(message_hash, cancellation) {
+                        Ok(true) => {
+                            info!(
+                                target: LOG_TARGET,
+                                message_hash = %format!("{message_hash:#x}"),
+                                ?cancellation,
+                                "L1 to L2 message cancellation updated."
+                            );
+
+                            // a cancelled message can't be consumed, so its transaction is dropped
+                            // unless it's already being executed
+                            if cancellation == MessageCancellation::Cancelled {
+                                if let Ok(Some(message)) = provider.message(message_hash) {
+                                    pool.remove_transactions(&[message.tx_hash]);
+                                }
+                            }
+                        }
+                        // the message was sent before the block messages are gathered from
+                        Ok(false) => {}
+                        Err(error) => {
+                            error!(target: LOG_TARGET, %error, "Storing L1 to L2 message cancellation.");
+                        }
+                    }
+                }
             }
         }
+
+        txs
+    }
+
+    /// Moves the gather checkpoint past the gathered blocks whose messages' transactions have all
+    /// been committed in a block, or dropped as their messages were cancelled, their execution
+    /// failed or they were removed from the pool.
+    fn checkpoint_settled_blocks(&mut self) {
+        let mut settled = None;
+
+        // read before the transactions' statuses, for a block committed in between to be seen
+        let Ok(latest) = self.backend.blockchain.provider().latest_number() else { return };
+
+        while let Some((block, txs)) = self.unsettled_blocks.front_mut() {
+            txs.retain_mut(|tx| {
+                if is_committed(&self.backend, tx.hash)
+                    || is_cancelled(&self.backend, tx.message_hash)
+                {
+                    return false;
+                }
+
+                if self.pool.contains(tx.hash) {
+                    tx.missing_since = None;
+                    return true;
+                }
+
+                // A transaction taken from the pool is committed in at most the block after the
+                // next one, so it's only considered dropped once that block has been committed.
+                let missing_since = *tx.missing_since.get_or_insert(latest);
+                if latest <= missing_since + 1 {
+                    return true;
+                }
+
+                info!(target: LOG_TARGET, tx_hash = %format!("{:#x}", tx.hash), "L1Handler transaction dropped without being committed.");
+                if let Err(error) =
+                    self.backend.blockchain.provider().set_message_dropped(tx.message_hash)
+                {
+                    error!(target: LOG_TARGET, %error, "Storing dropped L1 to L2 message.");
+                }
+
+                false
+            });
+            if !txs.is_empty() {
+                break;
+            }
+
+            settled = Some(*block);
+            self.unsettled_blocks.pop_front();
+        }
+
+        if let Some(block) = settled {
+            save_checkpoint(&self.backend, GATHER_CHECKPOINT, block);
+        }
     }
 
     async fn send_messages(
@@ -159,6 +320,18 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
     }
 }
 
+/// The transaction of a gathered message that is yet to be committed in a block.
+#[derive(Debug)]
+struct GatheredTx {
+    /// The hash of the transaction.
+    hash: TxHash,
+    /// The hash of the message.
+    message_hash: L1ToL2MessageHash,
+    /// The latest block number when the transaction was first found neither in the pool nor
+    /// committed, as it may be executing in the block being produced.
+    missing_since: Option<BlockNumber>,
+}
+
 #[derive(Debug)]
 pub enum MessagingOutcome {
     Gather {
@@ -182,6 +355,8 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
         let pin = self.get_mut();
 
         if pin.interval.poll_tick(cx).is_ready() {
+            pin.checkpoint_settled_blocks();
+
             if pin.msg_gather_fut.is_none() {
                 pin.msg_gather_fut = Some(Box::pin(Self::gather_messages(
                     pin.messenger.clone(),
@@ -207,8 +382,16 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
         // Poll the gathering future.
         if let Some(mut gather_fut) = pin.msg_gather_fut.take() {
             match gather_fut.poll_unpin(cx) {
-                Poll::Ready(Ok((last_block, msg_count))) => {
-                    pin.gather_from_block = last_block + 1;
+                Poll::Ready(Ok((last_block, txs))) => {
+                    let msg_count = txs.len();
+
+                    // the settlement chain may be behind the cursor, which must not move backward
+                    if last_block >= pin.gather_from_block {
+                        pin.gather_from_block = last_block + 1;
+                        pin.unsettled_blocks.push_back((last_block, txs));
+                        pin.checkpoint_settled_blocks();
+                    }
+
                     return Poll::Ready(Some(MessagingOutcome::Gather {
                         lastest_block: last_block,
                         msg_count,
@@ -234,6 +417,7 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
                     // +1 to move to the next local block to check messages to be
                     // sent on the settlement chain.
                     pin.send_from_block += 1;
+                    save_checkpoint(&pin.backend, SEND_CHECKPOINT, block_num);
                     return Poll::Ready(Some(MessagingOutcome::Send { block_num, msg_count }));
                 }
                 Poll::Ready(Err(e)) => {
@@ -254,6 +438,26 @@ impl<EF: ExecutorFactory> Stream for MessagingService<EF> {
     }
 }
 
+/// Persists the last block processed by the messaging service, for it to resume from the next one
+/// when Katana is restarted.
+fn save_checkpoint<EF: ExecutorFactory>(backend: &Backend<EF>, id: &str, block: u64) {
+    if let Err(error) = backend.blockchain.provider().set_checkpoint(id, block) {
+        error!(target: LOG_TARGET, %error, id, block, "Saving messaging checkpoint.");
+    }
+}
+
+/// Returns whether the transaction `hash` has been committed in a block.
+fn is_committed<EF: ExecutorFactory>(backend: &Backend<EF>, hash: TxHash) -> bool {
+    backend.blockchain.provider().transaction_status(hash).is_ok_and(|s| s.is_some())
+}
+
+/// Returns whether the message `hash` has been cancelled, in which case its transaction is never
+/// executed.
+fn is_cancelled<EF: ExecutorFactory>(backend: &Backend<EF>, hash: L1ToL2MessageHash) -> bool {
+    let message = backend.blockchain.provider().message(hash);
+    message.is_ok_and(|m| m.is_some_and(|m| m.cancellation == Some(MessageCancellation::Cancelled)))
+}
+
 /// Returns an `Interval` from the given seconds.
 fn interval_from_seconds(secs: u64) -> Interval {
     let duration = Duration::from_secs(secs);
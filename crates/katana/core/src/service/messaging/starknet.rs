@@ -3,6 +3,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use async_trait::async_trait;
 use katana_primitives::chain::ChainId;
+use katana_primitives::message::L1TxHash;
 use katana_primitives::receipt::MessageToL1;
 use katana_primitives::transaction::L1HandlerTx;
 use katana_primitives::utils::transaction::compute_l2_to_l1_message_hash;
@@ -16,7 +17,7 @@ use starknet::signers::{LocalWallet, SigningKey};
 use tracing::{debug, error, trace, warn};
 use url::Url;
 
-use super::{Error, MessagingConfig, Messenger, MessengerResult, LOG_TARGET};
+use super::{Error, MessagingConfig, MessagingEvent, Messenger, MessengerResult, LOG_TARGET};
 
 /// As messaging in starknet is only possible with EthAddress in the `to_address`
 /// field, we have to set magic value to understand what the user want to do.
@@ -35,6 +36,8 @@ pub struct StarknetMessaging {
     wallet: LocalWallet,
     sender_account_address: Felt,
     messaging_contract_address: Felt,
+    /// The number of blocks to wait on top of a block before gathering its messages.
+    confirmations: u64,
 }
 
 impl StarknetMessaging {
@@ -57,6 +60,7 @@ impl StarknetMessaging {
             chain_id,
             sender_account_address,
             messaging_contract_address,
+            confirmations: config.confirmations,
         })
     }
 
@@ -166,9 +170,10 @@ impl Messenger for StarknetMessaging {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
+    ) -> MessengerResult<(u64, Vec<MessagingEvent<Self::MessageTransaction>>)> {
+        // only the blocks with enough confirmations are considered
         let chain_latest_block: u64 = match self.provider.block_number().await {
-            Ok(n) => n,
+            Ok(n) => n.saturating_sub(self.confirmations),
             Err(_) => {
                 warn!(
                     target: LOG_TARGET,
@@ -191,7 +196,7 @@ impl Messenger for StarknetMessaging {
             chain_latest_block
         };
 
-        let mut l1_handler_txs: Vec<L1HandlerTx> = vec![];
+        let mut events = vec![];
 
         self.fetch_events(BlockId::Number(from_block), BlockId::Number(to_block))
             .await
@@ -206,11 +211,12 @@ impl Messenger for StarknetMessaging {
                 );
 
                 if let Ok(tx) = l1_handler_tx_from_event(e, chain_id) {
-                    l1_handler_txs.push(tx)
+                    let l1_tx_hash = L1TxHash::from(e.transaction_hash.to_bytes_be());
+                    events.push(MessagingEvent::MessageSent { l1_tx_hash, tx })
                 }
             });
 
-        Ok((to_block, l1_handler_txs))
+        Ok((to_block, events))
     }
 
     async fn send_messages(
@@ -1,11 +1,51 @@
 use dojo_metrics::Metrics;
-use metrics::Counter;
+use katana_executor::ExecutionStats;
+use metrics::{Counter, Histogram};
 
 #[derive(Metrics)]
 #[metrics(scope = "block_producer")]
-pub(crate) struct BlockProducerMetrics {
+pub struct BlockProducerMetrics {
     /// The amount of L1 gas processed in a block.
     pub(crate) l1_gas_processed_total: Counter,
     /// The amount of Cairo steps processed in a block.
     pub(crate) cairo_steps_processed_total: Counter,
+    /// The number of blocks produced.
+    pub(crate) blocks_produced_total: Counter,
+    /// The number of transactions included in the produced blocks.
+    pub(crate) transactions_processed_total: Counter,
+    /// The time it takes to commit and store a block once its transactions are executed. The
+    /// execution of the transactions isn't included, see the executor's transaction execution
+    /// time.
+    pub(crate) block_commit_time_seconds: Histogram,
+    /// The time it takes to produce a block, from taking its transactions to its commit. In
+    /// interval mode, it includes the time the pending block stays open after its first
+    /// transactions.
+    pub(crate) block_production_time_seconds: Histogram,
+    /// The number of transactions included per block.
+    pub(crate) transactions_per_block: Histogram,
+    /// The amount of Cairo steps processed per block.
+    pub(crate) cairo_steps_per_block: Histogram,
+    /// The amount of L1 gas processed per block.
+    pub(crate) l1_gas_per_block: Histogram,
+}
+
+impl BlockProducerMetrics {
+    /// Records the metrics of a block that took `elapsed` seconds to be committed.
+    pub(crate) fn record_block(&self, tx_count: u64, stats: &ExecutionStats, elapsed: f64) {
+        self.blocks_produced_total.increment(1);
+        self.transactions_processed_total.increment(tx_count);
+        self.l1_gas_processed_total.increment(stats.l1_gas_used as u64);
+        self.cairo_steps_processed_total.increment(stats.cairo_steps_used as u64);
+
+        self.block_commit_time_seconds.record(elapsed);
+        self.transactions_per_block.record(tx_count as f64);
+        self.cairo_steps_per_block.record(stats.cairo_steps_used as f64);
+        self.l1_gas_per_block.record(stats.l1_gas_used as f64);
+    }
+
+    /// Records the production of a block that took `elapsed` seconds, from taking its
+    /// transactions to its commit.
+    pub(crate) fn record_block_production(&self, elapsed: f64) {
+        self.block_production_time_seconds.record(elapsed);
+    }
 }
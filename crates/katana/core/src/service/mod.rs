@@ -14,11 +14,11 @@ use katana_primitives::Felt;
 use tracing::{error, info};
 
 use self::block_producer::BlockProducer;
-use self::metrics::BlockProducerMetrics;
 
 pub mod block_producer;
+pub mod fork_follower;
 pub mod messaging;
-mod metrics;
+pub mod metrics;
 
 pub(crate) const LOG_TARGET: &str = "node";
 
@@ -36,13 +36,11 @@ pub struct BlockProductionTask<EF: ExecutorFactory> {
     pub(crate) miner: TransactionMiner,
     /// the pool that holds all transactions
     pub(crate) pool: TxPool,
-    /// Metrics for recording the service operations
-    metrics: BlockProducerMetrics,
 }
 
 impl<EF: ExecutorFactory> BlockProductionTask<EF> {
     pub fn new(pool: TxPool, miner: TransactionMiner, block_producer: BlockProducer<EF>) -> Self {
-        Self { block_producer, miner, pool, metrics: BlockProducerMetrics::default() }
+        Self { block_producer, miner, pool }
     }
 }
 
@@ -59,17 +57,15 @@ impl<EF: ExecutorFactory> Future for BlockProductionTask<EF> {
                 match res {
                     Ok(outcome) => {
                         info!(target: LOG_TARGET, block_number = %outcome.block_number, "Mined block.");
-
-                        let gas_used = outcome.stats.l1_gas_used;
-                        let steps_used = outcome.stats.cairo_steps_used;
-                        this.metrics.l1_gas_processed_total.increment(gas_used as u64);
-                        this.metrics.cairo_steps_processed_total.increment(steps_used as u64);
                     }
 
                     Err(error) => {
                         error!(target: LOG_TARGET, %error, "Mining block.");
                     }
                 }
+
+                // the leftover of the block are queued again, and are still pending
+                this.pool.set_taken_transactions(this.block_producer.queued_count());
             }
 
             if let Poll::Ready(pool_txs) = this.miner.poll(&this.pool, cx) {
@@ -0,0 +1,257 @@
+use std::collections::HashSet;
+
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{ContractAddress, StorageKey};
+use katana_primitives::receipt::Receipt;
+
+/// Per-block caps on the resources used by the transactions of a block.
+///
+/// The limits are enforced by the executor: a transaction that would make the block exceed any of
+/// them is not included in the block, and the execution stops there so that the block can be
+/// closed. The default limits are unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockLimits {
+    /// The maximum number of Cairo steps.
+    pub n_steps: u64,
+    /// The maximum number of builtin instances, across all builtins.
+    pub builtin_count: u64,
+    /// The maximum amount of L1 gas.
+    pub l1_gas: u64,
+    /// The maximum amount of L1 data (blob) gas.
+    pub l1_data_gas: u64,
+    /// The maximum number of state diff entries, ie storage updates, nonce and class updates of
+    /// contracts, and declared classes.
+    pub state_diff_size: u64,
+    /// The maximum number of transactions.
+    pub tx_count: u64,
+}
+
+impl BlockLimits {
+    /// Returns limits that can never be reached.
+    pub fn max() -> Self {
+        Self {
+            n_steps: u64::MAX,
+            builtin_count: u64::MAX,
+            l1_gas: u64::MAX,
+            l1_data_gas: u64::MAX,
+            state_diff_size: u64::MAX,
+            tx_count: u64::MAX,
+        }
+    }
+}
+
+impl Default for BlockLimits {
+    fn default() -> Self {
+        Self::max()
+    }
+}
+
+/// A block resource that is capped by [`BlockLimits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BlockResource {
+    #[error("Cairo steps")]
+    Steps,
+    #[error("builtins")]
+    Builtins,
+    #[error("L1 gas")]
+    L1Gas,
+    #[error("L1 data gas")]
+    L1DataGas,
+    #[error("state diff size")]
+    StateDiffSize,
+    #[error("transaction count")]
+    TxCount,
+}
+
+/// The keys of the state modified by a transaction.
+#[derive(Debug, Clone, Default)]
+pub struct StateDiffKeys {
+    pub storage: HashSet<(ContractAddress, StorageKey)>,
+    /// Contracts whose nonce or class hash has been updated.
+    pub contracts: HashSet<ContractAddress>,
+    pub declared_classes: HashSet<ClassHash>,
+}
+
+impl StateDiffKeys {
+    fn len(&self) -> u64 {
+        (self.storage.len() + self.contracts.len() + self.declared_classes.len()) as u64
+    }
+
+    pub(crate) fn extend(&mut self, other: &StateDiffKeys) {
+        self.storage.extend(other.storage.iter().copied());
+        self.contracts.extend(other.contracts.iter().copied());
+        self.declared_classes.extend(other.declared_classes.iter().copied());
+    }
+
+    /// Returns `true` if `self` and `other` have keys in common.
+    pub(crate) fn intersects(&self, other: &StateDiffKeys) -> bool {
+        !self.storage.is_disjoint(&other.storage)
+            || !self.contracts.is_disjoint(&other.contracts)
+            || !self.declared_classes.is_disjoint(&other.declared_classes)
+    }
+}
+
+/// The resources used by the transactions of a block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct BlockWeights {
+    n_steps: u64,
+    builtin_count: u64,
+    l1_gas: u64,
+    l1_data_gas: u64,
+    tx_count: u64,
+}
+
+impl BlockWeights {
+    fn from_receipt(receipt: &Receipt) -> Self {
+        let resources = receipt.resources_used();
+        let builtins = resources.vm_resources.builtin_instance_counter.values().sum::<usize>();
+        Self {
+            n_steps: resources.vm_resources.n_steps as u64,
+            builtin_count: builtins as u64,
+            l1_gas: resources.total_gas_consumed.l1_gas as u64,
+            l1_data_gas: resources.total_gas_consumed.l1_data_gas as u64,
+            tx_count: 1,
+        }
+    }
+
+    fn saturating_add(self, other: Self) -> Self {
+        Self {
+            n_steps: self.n_steps.saturating_add(other.n_steps),
+            builtin_count: self.builtin_count.saturating_add(other.builtin_count),
+            l1_gas: self.l1_gas.saturating_add(other.l1_gas),
+            l1_data_gas: self.l1_data_gas.saturating_add(other.l1_data_gas),
+            tx_count: self.tx_count.saturating_add(other.tx_count),
+        }
+    }
+}
+
+/// Keeps track of the resources used by the transactions of a block, and decides whether a new
+/// transaction still fits in the block.
+#[derive(Debug, Clone, Default)]
+pub struct Bouncer {
+    limits: BlockLimits,
+    weights: BlockWeights,
+    state_diff: StateDiffKeys,
+}
+
+impl Bouncer {
+    pub fn new(limits: BlockLimits) -> Self {
+        Self { limits, weights: BlockWeights::default(), state_diff: StateDiffKeys::default() }
+    }
+
+    /// Returns the limits enforced by the bouncer.
+    pub fn limits(&self) -> &BlockLimits {
+        &self.limits
+    }
+
+    /// Returns `true` if no transaction has been added to the block yet.
+    pub fn is_empty(&self) -> bool {
+        self.weights.tx_count == 0
+    }
+
+    /// Returns the resource whose limit has been reached if the block can't fit any more
+    /// transaction.
+    pub fn is_full(&self) -> Option<BlockResource> {
+        (self.weights.tx_count >= self.limits.tx_count).then_some(BlockResource::TxCount)
+    }
+
+    /// Adds the transaction with `receipt` and modified `state_diff` to the block, if it fits.
+    /// Otherwise, returns the resource whose limit would have been exceeded, and the block is
+    /// left untouched.
+    pub fn try_add(
+        &mut self,
+        receipt: &Receipt,
+        state_diff: &StateDiffKeys,
+    ) -> Result<(), BlockResource> {
+        let weights = self.weights.saturating_add(BlockWeights::from_receipt(receipt));
+
+        let mut block_diff = self.state_diff.clone();
+        block_diff.extend(state_diff);
+
+        let limits = &self.limits;
+        if weights.tx_count > limits.tx_count {
+            return Err(BlockResource::TxCount);
+        } else if weights.n_steps > limits.n_steps {
+            return Err(BlockResource::Steps);
+        } else if weights.builtin_count > limits.builtin_count {
+            return Err(BlockResource::Builtins);
+        } else if weights.l1_gas > limits.l1_gas {
+            return Err(BlockResource::L1Gas);
+        } else if weights.l1_data_gas > limits.l1_data_gas {
+            return Err(BlockResource::L1DataGas);
+        } else if block_diff.len() > limits.state_diff_size {
+            return Err(BlockResource::StateDiffSize);
+        }
+
+        self.weights = weights;
+        self.state_diff = block_diff;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::address;
+    use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::receipt::InvokeTxReceipt;
+    use katana_primitives::trace::TxResources;
+
+    use super::*;
+
+    fn receipt(n_steps: usize) -> Receipt {
+        let mut resources = TxResources::default();
+        resources.vm_resources.n_steps = n_steps;
+        Receipt::Invoke(InvokeTxReceipt {
+            fee: TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: 0, unit: PriceUnit::Wei },
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            revert_error: None,
+            execution_resources: resources,
+        })
+    }
+
+    fn storage_diff(keys: &[u64]) -> StateDiffKeys {
+        let storage = keys.iter().map(|key| (address!("0x1"), (*key).into())).collect();
+        StateDiffKeys { storage, ..Default::default() }
+    }
+
+    #[test]
+    fn unlimited_bouncer() {
+        let mut bouncer = Bouncer::new(BlockLimits::max());
+        for _ in 0..100 {
+            bouncer.try_add(&receipt(1_000_000), &storage_diff(&[1, 2, 3])).unwrap();
+        }
+        assert_eq!(bouncer.is_full(), None);
+    }
+
+    #[test]
+    fn bouncer_limits() {
+        let limits = BlockLimits { n_steps: 100, ..BlockLimits::max() };
+        let mut bouncer = Bouncer::new(limits);
+        bouncer.try_add(&receipt(60), &StateDiffKeys::default()).unwrap();
+        assert_eq!(
+            bouncer.try_add(&receipt(60), &StateDiffKeys::default()),
+            Err(BlockResource::Steps)
+        );
+        // the rejected transaction isn't accounted for
+        bouncer.try_add(&receipt(40), &StateDiffKeys::default()).unwrap();
+
+        let limits = BlockLimits { tx_count: 2, ..BlockLimits::max() };
+        let mut bouncer = Bouncer::new(limits);
+        assert!(bouncer.is_empty());
+        bouncer.try_add(&receipt(1), &StateDiffKeys::default()).unwrap();
+        bouncer.try_add(&receipt(1), &StateDiffKeys::default()).unwrap();
+        assert_eq!(bouncer.is_full(), Some(BlockResource::TxCount));
+
+        // keys modified by several transactions are only counted once
+        let limits = BlockLimits { state_diff_size: 3, ..BlockLimits::max() };
+        let mut bouncer = Bouncer::new(limits);
+        bouncer.try_add(&receipt(1), &storage_diff(&[1, 2])).unwrap();
+        bouncer.try_add(&receipt(1), &storage_diff(&[2, 3])).unwrap();
+        assert_eq!(
+            bouncer.try_add(&receipt(1), &storage_diff(&[4])),
+            Err(BlockResource::StateDiffSize)
+        );
+    }
+}
@@ -4,7 +4,16 @@ use katana_primitives::Felt;
 
 /// Errors that can be returned by the executor.
 #[derive(Debug, thiserror::Error)]
-pub enum ExecutorError {}
+pub enum ExecutorError {
+    /// A class of the state updates applied to the executor can't be loaded by the VM.
+    #[error("Invalid class {hash:#x}: {reason}")]
+    InvalidClass { hash: ClassHash, reason: String },
+
+    /// A storage key of the state updates applied to the executor is out of the storage address
+    /// range.
+    #[error("Invalid storage key {key:#x} of contract {address}")]
+    InvalidStorageKey { address: ContractAddress, key: Felt },
+}
 
 /// Errors that can occur during the transaction execution.
 #[derive(Debug, Clone, thiserror::Error)]
@@ -69,6 +78,9 @@ pub enum ExecutionError {
     #[error("Transaction reverted: {revert_error}")]
     TransactionReverted { revert_error: String },
 
+    #[error("Transaction exceeds the block limit on {0}")]
+    ExceedsBlockLimits(crate::BlockResource),
+
     #[error("{0}")]
     Other(String),
 }
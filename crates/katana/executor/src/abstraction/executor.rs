@@ -1,13 +1,14 @@
 use katana_primitives::block::ExecutableBlock;
 use katana_primitives::env::{BlockEnv, CfgEnv};
 use katana_primitives::fee::TxFeeInfo;
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::state::StateProvider;
 
 use crate::{
-    EntryPointCall, ExecutionError, ExecutionFlags, ExecutionOutput, ExecutionResult,
-    ExecutorResult, ResultAndStates,
+    BlockResource, EntryPointCall, ExecutionError, ExecutionFlags, ExecutionOutput,
+    ExecutionResult, ExecutorResult, ResultAndStates,
 };
 
 /// A type that can create [BlockExecutor] instance.
@@ -38,9 +39,22 @@ pub trait BlockExecutor<'a>: ExecutorExt + Send + Sync + core::fmt::Debug {
     /// Executes the given block.
     fn execute_block(&mut self, block: ExecutableBlock) -> ExecutorResult<()>;
 
+    /// Executes the given transactions in order, until the [limits](crate::BlockLimits) of the
+    /// block are reached.
+    ///
+    /// Returns the number of transactions that have been executed, and the resource whose limit
+    /// stopped the execution if not all of them could fit in the block.
     fn execute_transactions(
         &mut self,
         transactions: Vec<ExecutableTxWithHash>,
+    ) -> ExecutorResult<(usize, Option<BlockResource>)>;
+
+    /// Applies `updates` on top of the current state of the executor, as if they were made by a
+    /// transaction of the block. They are part of the state diff of the block, and visible to the
+    /// transactions executed afterwards.
+    fn apply_state_updates(
+        &mut self,
+        updates: StateUpdatesWithDeclaredClasses,
     ) -> ExecutorResult<()>;
 
     /// Takes the output state of the executor.
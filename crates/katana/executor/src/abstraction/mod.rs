@@ -1,6 +1,11 @@
+mod bouncer;
 mod error;
 mod executor;
 
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+pub use bouncer::*;
 pub use error::*;
 pub use executor::*;
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
@@ -28,11 +33,18 @@ pub struct ExecutionFlags {
     fee: bool,
     /// Determine whether to perform transaction's sender nonce check.
     nonce_check: bool,
+    /// Accounts whose validation logic is always skipped.
+    impersonated_accounts: ImpersonatedAccounts,
 }
 
 impl Default for ExecutionFlags {
     fn default() -> Self {
-        Self { account_validation: true, fee: true, nonce_check: true }
+        Self {
+            account_validation: true,
+            fee: true,
+            nonce_check: true,
+            impersonated_accounts: Default::default(),
+        }
     }
 }
 
@@ -59,6 +71,12 @@ impl ExecutionFlags {
         self
     }
 
+    /// Set the accounts whose validation logic is always skipped.
+    pub fn with_impersonated_accounts(mut self, accounts: ImpersonatedAccounts) -> Self {
+        self.impersonated_accounts = accounts;
+        self
+    }
+
     /// Returns whether the account validation is enabled.
     pub fn account_validation(&self) -> bool {
         self.account_validation
@@ -73,6 +91,36 @@ impl ExecutionFlags {
     pub fn nonce_check(&self) -> bool {
         self.nonce_check
     }
+
+    /// Returns the accounts whose validation logic is always skipped.
+    pub fn impersonated_accounts(&self) -> &ImpersonatedAccounts {
+        &self.impersonated_accounts
+    }
+}
+
+/// A set of impersonated accounts.
+///
+/// The validation logic of the impersonated accounts is skipped, which allows sending
+/// transactions on their behalf without knowing their signing keys. The set is shared between
+/// all of its clones.
+#[derive(Debug, Clone, Default)]
+pub struct ImpersonatedAccounts(Arc<RwLock<HashSet<ContractAddress>>>);
+
+impl ImpersonatedAccounts {
+    /// Starts impersonating `address`.
+    pub fn insert(&self, address: ContractAddress) {
+        self.0.write().expect("poisoned lock").insert(address);
+    }
+
+    /// Stops impersonating `address`.
+    pub fn remove(&self, address: ContractAddress) {
+        self.0.write().expect("poisoned lock").remove(&address);
+    }
+
+    /// Returns `true` if `address` is impersonated.
+    pub fn contains(&self, address: ContractAddress) -> bool {
+        self.0.read().expect("poisoned lock").contains(&address)
+    }
 }
 
 /// Stats about the transactions execution.
@@ -0,0 +1,15 @@
+use dojo_metrics::Metrics;
+use metrics::{Counter, Histogram};
+
+#[derive(Metrics)]
+#[metrics(scope = "executor")]
+pub(crate) struct ExecutorMetrics {
+    /// The number of transactions executed, whether they succeeded or not.
+    pub(crate) transactions_executed_total: Counter,
+    /// The number of transactions that were reverted.
+    pub(crate) transactions_reverted_total: Counter,
+    /// The number of transactions that failed to be executed.
+    pub(crate) transactions_failed_total: Counter,
+    /// The time it takes to execute a transaction.
+    pub(crate) transaction_execution_time_seconds: Histogram,
+}
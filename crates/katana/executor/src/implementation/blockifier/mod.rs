@@ -2,29 +2,39 @@
 pub use blockifier;
 
 mod error;
+mod metrics;
+mod parallel;
 mod state;
 pub mod utils;
 
-use std::num::NonZeroU128;
+use std::collections::HashMap;
+use std::num::{NonZeroU128, NonZeroUsize};
+use std::sync::Arc;
+use std::time::Instant;
 
 use blockifier::blockifier::block::{BlockInfo, GasPrices};
 use blockifier::context::BlockContext;
-use blockifier::state::cached_state::{self, MutRefState};
+use blockifier::state::cached_state::{self, MutRefState, StateMaps, TransactionalState};
 use blockifier::state::state_api::StateReader;
 use katana_cairo::starknet_api::block::{BlockNumber, BlockTimestamp};
+use katana_cairo::starknet_api::core::{ClassHash, CompiledClassHash, Nonce};
+use katana_cairo::starknet_api::state::StorageKey;
 use katana_primitives::block::{ExecutableBlock, GasPrices as KatanaGasPrices, PartialHeader};
 use katana_primitives::env::{BlockEnv, CfgEnv};
 use katana_primitives::fee::TxFeeInfo;
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::state::StateProvider;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use tracing::info;
 
+use self::metrics::ExecutorMetrics;
 use self::state::CachedState;
 use crate::{
-    BlockExecutor, EntryPointCall, ExecutionError, ExecutionFlags, ExecutionOutput,
-    ExecutionResult, ExecutionStats, ExecutorExt, ExecutorFactory, ExecutorResult, ResultAndStates,
-    StateProviderDb,
+    BlockExecutor, BlockLimits, BlockResource, Bouncer, EntryPointCall, ExecutionError,
+    ExecutionFlags, ExecutionOutput, ExecutionResult, ExecutionStats, ExecutorError, ExecutorExt,
+    ExecutorFactory, ExecutorResult, ResultAndStates, StateProviderDb,
 };
 
 pub(crate) const LOG_TARGET: &str = "katana::executor::blockifier";
@@ -33,12 +43,34 @@ pub(crate) const LOG_TARGET: &str = "katana::executor::blockifier";
 pub struct BlockifierFactory {
     cfg: CfgEnv,
     flags: ExecutionFlags,
+    limits: BlockLimits,
+    pool: Option<Arc<ThreadPool>>,
 }
 
 impl BlockifierFactory {
     /// Create a new factory with the given configuration and simulation flags.
     pub fn new(cfg: CfgEnv, flags: ExecutionFlags) -> Self {
-        Self { cfg, flags }
+        Self { cfg, flags, limits: BlockLimits::default(), pool: None }
+    }
+
+    /// Sets the limits of the blocks built by the executors created by the factory.
+    pub fn with_block_limits(mut self, limits: BlockLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Executes the transactions of the blocks optimistically in parallel, on `workers` threads.
+    ///
+    /// Transactions whose speculative execution conflicts with the ones before them are
+    /// re-executed, so the results are the same as those of a sequential execution.
+    pub fn with_parallel_execution(mut self, workers: NonZeroUsize) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(workers.get())
+            .thread_name(|i| format!("katana-executor-{i}"))
+            .build()
+            .expect("failed to build execution thread pool");
+        self.pool = Some(Arc::new(pool));
+        self
     }
 }
 
@@ -60,7 +92,10 @@ impl ExecutorFactory for BlockifierFactory {
     {
         let cfg_env = self.cfg.clone();
         let flags = self.flags.clone();
-        Box::new(StarknetVMProcessor::new(Box::new(state), block_env, cfg_env, flags))
+        let mut executor = StarknetVMProcessor::new(Box::new(state), block_env, cfg_env, flags)
+            .with_block_limits(self.limits);
+        executor.pool = self.pool.clone();
+        Box::new(executor)
     }
 
     fn cfg(&self) -> &CfgEnv {
@@ -80,6 +115,10 @@ pub struct StarknetVMProcessor<'a> {
     transactions: Vec<(TxWithHash, ExecutionResult)>,
     simulation_flags: ExecutionFlags,
     stats: ExecutionStats,
+    bouncer: Bouncer,
+    /// The threads executing the transactions in parallel, if enabled.
+    pool: Option<Arc<ThreadPool>>,
+    metrics: ExecutorMetrics,
 }
 
 impl<'a> StarknetVMProcessor<'a> {
@@ -92,7 +131,22 @@ impl<'a> StarknetVMProcessor<'a> {
         let transactions = Vec::new();
         let block_context = utils::block_context_from_envs(&block_env, &cfg_env);
         let state = state::CachedState::new(StateProviderDb::new(state));
-        Self { block_context, state, transactions, simulation_flags, stats: Default::default() }
+        Self {
+            block_context,
+            state,
+            transactions,
+            simulation_flags,
+            stats: Default::default(),
+            bouncer: Bouncer::default(),
+            pool: None,
+            metrics: ExecutorMetrics::default(),
+        }
+    }
+
+    /// Sets the limits of the block built by the executor.
+    pub fn with_block_limits(mut self, limits: BlockLimits) -> Self {
+        self.bouncer = Bouncer::new(limits);
+        self
     }
 
     fn fill_block_env_from_header(&mut self, header: &PartialHeader) {
@@ -159,6 +213,8 @@ impl<'a> StarknetVMProcessor<'a> {
 impl<'a> BlockExecutor<'a> for StarknetVMProcessor<'a> {
     fn execute_block(&mut self, block: ExecutableBlock) -> ExecutorResult<()> {
         self.fill_block_env_from_header(&block.header);
+        // the block has already been built, so its content is not subject to the limits
+        self.bouncer = Bouncer::default();
         self.execute_transactions(block.body)?;
         Ok(())
     }
@@ -166,12 +222,23 @@ impl<'a> BlockExecutor<'a> for StarknetVMProcessor<'a> {
     fn execute_transactions(
         &mut self,
         transactions: Vec<ExecutableTxWithHash>,
-    ) -> ExecutorResult<()> {
+    ) -> ExecutorResult<(usize, Option<BlockResource>)> {
+        if let Some(pool) = self.pool.clone() {
+            return self.execute_transactions_parallel(&pool, transactions);
+        }
+
         let block_context = &self.block_context;
         let flags = &self.simulation_flags;
         let mut state = self.state.0.lock();
+        let state = &mut *state;
+
+        let mut executed = 0;
 
         for exec_tx in transactions {
+            if let Some(resource) = self.bouncer.is_full() {
+                return Ok((executed, Some(resource)));
+            }
+
             // Collect class artifacts if its a declare tx
             let class_decl_artifacts = if let ExecutableTx::Declare(tx) = exec_tx.as_ref() {
                 let class_hash = tx.class_hash();
@@ -181,31 +248,99 @@ impl<'a> BlockExecutor<'a> for StarknetVMProcessor<'a> {
             };
 
             let tx = TxWithHash::from(&exec_tx);
-            let res = utils::transact(&mut state.inner, block_context, flags, exec_tx);
-
-            match &res {
-                ExecutionResult::Success { receipt, trace } => {
-                    self.stats.l1_gas_used += receipt.fee().gas_consumed;
-                    self.stats.cairo_steps_used +=
-                        receipt.resources_used().vm_resources.n_steps as u128;
 
-                    if let Some(reason) = receipt.revert_reason() {
-                        info!(target: LOG_TARGET, %reason, "Transaction reverted.");
-                    }
-
-                    if let Some((class_hash, compiled, sierra)) = class_decl_artifacts {
-                        state.declared_classes.insert(class_hash, (compiled, sierra));
-                    }
-
-                    crate::utils::log_resources(&trace.actual_resources);
+            // the transaction is executed on top of the block state so that its changes can be
+            // discarded if it doesn't fit in the block
+            let mut tx_state = TransactionalState::create_transactional(&mut state.inner);
+            let started_at = Instant::now();
+            let mut res = utils::transact(&mut tx_state, block_context, flags, exec_tx);
+            let elapsed = started_at.elapsed().as_secs_f64();
+
+            let fits = match &res {
+                ExecutionResult::Success { receipt, .. } => {
+                    let state_diff = utils::state_diff_keys(&mut tx_state);
+                    self.bouncer.try_add(receipt, &state_diff)
                 }
+                ExecutionResult::Failed { .. } => Ok(()),
+            };
 
-                ExecutionResult::Failed { error } => {
-                    info!(target: LOG_TARGET, %error, "Executing transaction.");
+            match fits {
+                Ok(()) => tx_state.commit(),
+                // the transaction is left for the next block
+                Err(resource) if !self.bouncer.is_empty() => {
+                    tx_state.abort();
+                    return Ok((executed, Some(resource)));
                 }
-            };
+                // the transaction wouldn't even fit in an empty block
+                Err(resource) => {
+                    tx_state.abort();
+                    let error = ExecutionError::ExceedsBlockLimits(resource);
+                    res = ExecutionResult::Failed { error };
+                }
+            }
 
+            if let (ExecutionResult::Success { .. }, Some((class_hash, compiled, sierra))) =
+                (&res, class_decl_artifacts)
+            {
+                state.declared_classes.insert(class_hash, (compiled, sierra));
+            }
+
+            self.metrics.transaction_execution_time_seconds.record(elapsed);
+            record_execution(&mut self.stats, &self.metrics, &res);
             self.transactions.push((tx, res));
+            executed += 1;
+        }
+
+        Ok((executed, None))
+    }
+
+    fn apply_state_updates(
+        &mut self,
+        updates: StateUpdatesWithDeclaredClasses,
+    ) -> ExecutorResult<()> {
+        let StateUpdatesWithDeclaredClasses {
+            state_updates,
+            declared_sierra_classes,
+            declared_compiled_classes,
+        } = updates;
+
+        let mut writes = StateMaps::default();
+
+        for (address, nonce) in state_updates.nonce_updates {
+            writes.nonces.insert(utils::to_blk_address(address), Nonce(nonce));
+        }
+
+        for (address, storage) in state_updates.storage_updates {
+            for (key, value) in storage {
+                let key = key
+                    .try_into()
+                    .map_err(|_| ExecutorError::InvalidStorageKey { address, key })?;
+                writes.storage.insert((utils::to_blk_address(address), StorageKey(key)), value);
+            }
+        }
+
+        let contracts = state_updates.deployed_contracts.into_iter();
+        for (address, class_hash) in contracts.chain(state_updates.replaced_classes) {
+            writes.class_hashes.insert(utils::to_blk_address(address), ClassHash(class_hash));
+        }
+
+        for (hash, compiled_hash) in state_updates.declared_classes {
+            writes.compiled_class_hashes.insert(ClassHash(hash), CompiledClassHash(compiled_hash));
+        }
+
+        let mut classes = HashMap::with_capacity(declared_compiled_classes.len());
+        for (hash, class) in &declared_compiled_classes {
+            let class = utils::to_class(class.clone())
+                .map_err(|e| ExecutorError::InvalidClass { hash: *hash, reason: e.to_string() })?;
+            classes.insert(ClassHash(*hash), class.contract_class());
+        }
+
+        let mut state = self.state.0.lock();
+        state.inner.apply_writes(&writes, &classes, &HashMap::new());
+
+        for (hash, class) in declared_compiled_classes {
+            let sierra = declared_sierra_classes.get(&hash).cloned();
+            state.declared_classes.insert(hash, (class, sierra));
         }
 
         Ok(())
@@ -246,6 +381,30 @@ impl<'a> BlockExecutor<'a> for StarknetVMProcessor<'a> {
     }
 }
 
+/// Adds the resources used by an executed transaction to `stats`, and logs its outcome.
+fn record_execution(stats: &mut ExecutionStats, metrics: &ExecutorMetrics, res: &ExecutionResult) {
+    metrics.transactions_executed_total.increment(1);
+
+    match res {
+        ExecutionResult::Success { receipt, trace } => {
+            stats.l1_gas_used += receipt.fee().gas_consumed;
+            stats.cairo_steps_used += receipt.resources_used().vm_resources.n_steps as u128;
+
+            if let Some(reason) = receipt.revert_reason() {
+                metrics.transactions_reverted_total.increment(1);
+                info!(target: LOG_TARGET, %reason, "Transaction reverted.");
+            }
+
+            crate::utils::log_resources(&trace.actual_resources);
+        }
+
+        ExecutionResult::Failed { error } => {
+            metrics.transactions_failed_total.increment(1);
+            info!(target: LOG_TARGET, %error, "Executing transaction.");
+        }
+    }
+}
+
 impl ExecutorExt for StarknetVMProcessor<'_> {
     fn simulate(
         &self,
@@ -0,0 +1,435 @@
+//! Optimistic parallel execution of transactions, in the style of Block-STM.
+//!
+//! The transactions are executed in batches. The transactions of a batch are first executed
+//! speculatively, in parallel, on top of the state at the start of the batch, while recording the
+//! state keys they read. They are then committed in order: a transaction that didn't read any key
+//! written by the transactions committed before it in the batch got the same inputs as it would
+//! have in a sequential execution, so its speculative writes are applied as is. Otherwise, it's
+//! re-executed on top of the committed state.
+//!
+//! Every transaction that is charged a fee writes to the sequencer's fee token balance, which
+//! would make all the transactions conflict with each other. The sequencer balances are thus
+//! excluded from the conflict detection, and the fee charged by each transaction is added to the
+//! committed balance instead. A transaction whose speculative execution changed the sequencer
+//! balance by anything else than its fee, or read it outside of its fee transfer, is re-executed.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Instant;
+
+use blockifier::abi::abi_utils::get_fee_token_var_address;
+use blockifier::context::BlockContext;
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::cached_state::{self, StateMaps, TransactionalState};
+use blockifier::state::state_api::{StateReader, StateResult, UpdatableState};
+use katana_cairo::starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use katana_cairo::starknet_api::hash::StarkHash;
+use katana_cairo::starknet_api::state::StorageKey;
+use katana_primitives::fee::PriceUnit;
+use katana_primitives::trace::CallInfo;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxWithHash};
+use katana_primitives::Felt;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use tracing::trace;
+
+use super::state::{CachedState, StateDb};
+use super::{utils, StarknetVMProcessor, LOG_TARGET};
+use crate::{
+    BlockResource, ExecutionError, ExecutionFlags, ExecutionResult, ExecutorResult, StateDiffKeys,
+};
+
+/// The number of transactions per worker thread in a batch.
+///
+/// Bigger batches have more parallelism, but more of their speculative executions are wasted when
+/// the block is full before the end of the batch.
+const BATCH_SIZE_PER_WORKER: usize = 4;
+
+impl StarknetVMProcessor<'_> {
+    /// Executes `transactions` optimistically in parallel on the threads of `pool`.
+    ///
+    /// The results are the same as those of a sequential execution.
+    pub(super) fn execute_transactions_parallel(
+        &mut self,
+        pool: &ThreadPool,
+        mut transactions: Vec<ExecutableTxWithHash>,
+    ) -> ExecutorResult<(usize, Option<BlockResource>)> {
+        let batch_size = pool.current_num_threads() * BATCH_SIZE_PER_WORKER;
+        let mut executed = 0;
+
+        while !transactions.is_empty() {
+            let rest = transactions.split_off(batch_size.min(transactions.len()));
+            let batch = std::mem::replace(&mut transactions, rest);
+
+            let (n, full) = self.execute_batch(pool, batch)?;
+            executed += n;
+
+            if full.is_some() {
+                return Ok((executed, full));
+            }
+        }
+
+        Ok((executed, None))
+    }
+
+    fn execute_batch(
+        &mut self,
+        pool: &ThreadPool,
+        transactions: Vec<ExecutableTxWithHash>,
+    ) -> ExecutorResult<(usize, Option<BlockResource>)> {
+        let block_context = &self.block_context;
+        let flags = &self.simulation_flags;
+        let balances = SequencerBalances::new(&self.state, block_context, flags);
+
+        let speculations: Vec<Option<Speculation>> = pool.install(|| {
+            transactions
+                .par_iter()
+                .map(|tx| speculate(&self.state, block_context, flags, &balances, tx))
+                .collect()
+        });
+
+        let mut state = self.state.0.lock();
+        let state = &mut *state;
+
+        // the keys written by the transactions committed so far in the batch
+        let mut written = StateDiffKeys::default();
+        let mut reexecuted = 0;
+        let mut executed = 0;
+
+        for (exec_tx, speculation) in transactions.into_iter().zip(speculations) {
+            if let Some(resource) = self.bouncer.is_full() {
+                return Ok((executed, Some(resource)));
+            }
+
+            // Collect class artifacts if its a declare tx
+            let class_decl_artifacts = if let ExecutableTx::Declare(tx) = exec_tx.as_ref() {
+                let class_hash = tx.class_hash();
+                Some((class_hash, tx.compiled_class.clone(), tx.sierra_class.clone()))
+            } else {
+                None
+            };
+
+            let tx = TxWithHash::from(&exec_tx);
+            let mut tx_state = TransactionalState::create_transactional(&mut state.inner);
+
+            let speculation = speculation
+                .filter(|speculation| !speculation.reads.intersects(&written))
+                .and_then(|speculation| {
+                    let elapsed = speculation.elapsed;
+                    let (result, writes) = balances.commit(&tx_state, speculation)?;
+                    Some((result, writes, elapsed))
+                });
+
+            // only the execution whose result is committed is timed, discarded speculations
+            // aren't
+            let (mut res, elapsed) = match speculation {
+                Some((result, writes, elapsed)) => {
+                    tx_state.apply_writes(&writes, &HashMap::new(), &HashMap::new());
+                    (result, elapsed)
+                }
+                None => {
+                    reexecuted += 1;
+                    let started_at = Instant::now();
+                    let result = utils::transact(&mut tx_state, block_context, flags, exec_tx);
+                    (result, started_at.elapsed().as_secs_f64())
+                }
+            };
+
+            let mut state_diff = utils::state_diff_keys(&mut tx_state);
+
+            let fits = match &res {
+                ExecutionResult::Success { receipt, .. } => {
+                    self.bouncer.try_add(receipt, &state_diff)
+                }
+                ExecutionResult::Failed { .. } => Ok(()),
+            };
+
+            match fits {
+                Ok(()) => tx_state.commit(),
+                // the transaction is left for the next block
+                Err(resource) if !self.bouncer.is_empty() => {
+                    tx_state.abort();
+                    return Ok((executed, Some(resource)));
+                }
+                // the transaction wouldn't even fit in an empty block
+                Err(resource) => {
+                    tx_state.abort();
+                    state_diff = StateDiffKeys::default();
+                    let error = ExecutionError::ExceedsBlockLimits(resource);
+                    res = ExecutionResult::Failed { error };
+                }
+            }
+
+            balances.untrack(&mut state_diff);
+            written.extend(&state_diff);
+
+            if let (ExecutionResult::Success { .. }, Some((class_hash, compiled, sierra))) =
+                (&res, class_decl_artifacts)
+            {
+                // legacy classes have no compiled class hash, so they're not in the state diff
+                written.declared_classes.insert(class_hash);
+                state.declared_classes.insert(class_hash, (compiled, sierra));
+            }
+
+            self.metrics.transaction_execution_time_seconds.record(elapsed);
+            super::record_execution(&mut self.stats, &self.metrics, &res);
+            self.transactions.push((tx, res));
+            executed += 1;
+        }
+
+        trace!(target: LOG_TARGET, %executed, %reexecuted, "Executed batch of transactions.");
+
+        Ok((executed, None))
+    }
+}
+
+/// The outcome of the speculative execution of a transaction.
+struct Speculation {
+    result: ExecutionResult,
+    /// The keys read by the transaction, other than the sequencer balances.
+    reads: StateDiffKeys,
+    writes: StateMaps,
+    /// The time it took to execute the transaction, in seconds.
+    elapsed: f64,
+}
+
+/// Executes `tx` on top of `state`, recording the keys it reads.
+///
+/// Declare transactions aren't executed speculatively, as their writes can't be applied without
+/// the declared class.
+fn speculate<S: StateDb>(
+    state: &CachedState<S>,
+    block_context: &BlockContext,
+    flags: &ExecutionFlags,
+    balances: &SequencerBalances,
+    tx: &ExecutableTxWithHash,
+) -> Option<Speculation> {
+    if let ExecutableTx::Declare(_) = tx.transaction {
+        return None;
+    }
+
+    let reader = ReadTrackingState { state, untracked: balances.keys(), reads: Default::default() };
+    let mut tx_state = cached_state::CachedState::new(reader);
+
+    let started_at = Instant::now();
+    let result = utils::transact(&mut tx_state, block_context, flags, tx.clone());
+    let elapsed = started_at.elapsed().as_secs_f64();
+    if !balances.only_accessed_by_fee_transfer(&result) {
+        return None;
+    }
+
+    let writes = tx_state.to_state_diff().ok()?;
+    let reads = tx_state.state.reads.into_inner();
+
+    Some(Speculation { result, reads, writes, elapsed })
+}
+
+/// A view of the state at the start of a batch, which records the keys read through it.
+struct ReadTrackingState<'s, S: StateDb> {
+    state: &'s CachedState<S>,
+    /// The storage keys whose reads aren't recorded.
+    untracked: &'s [(ContractAddress, StorageKey)],
+    reads: RefCell<StateDiffKeys>,
+}
+
+impl<S: StateDb> StateReader for ReadTrackingState<'_, S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkHash> {
+        if !self.untracked.contains(&(contract_address, key)) {
+            let key = (utils::to_address(contract_address), *key.0.key());
+            self.reads.borrow_mut().storage.insert(key);
+        }
+        self.state.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.reads.borrow_mut().contracts.insert(utils::to_address(contract_address));
+        self.state.get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.reads.borrow_mut().contracts.insert(utils::to_address(contract_address));
+        self.state.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.reads.borrow_mut().declared_classes.insert(class_hash.0);
+        self.state.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.reads.borrow_mut().declared_classes.insert(class_hash.0);
+        self.state.get_compiled_class_hash(class_hash)
+    }
+}
+
+/// The balance of the sequencer in a fee token.
+struct FeeTokenBalance {
+    unit: PriceUnit,
+    token: ContractAddress,
+    /// The storage keys of the low and high words of the `u256` balance.
+    keys: [StorageKey; 2],
+    /// The balance at the start of the batch.
+    initial: (u128, u128),
+}
+
+/// The sequencer's fee token balances, which are written by every transaction charged a fee.
+#[derive(Default)]
+struct SequencerBalances {
+    balances: Vec<FeeTokenBalance>,
+    keys: Vec<(ContractAddress, StorageKey)>,
+}
+
+impl SequencerBalances {
+    /// Returns the current sequencer balances, or no balances if no fee is charged.
+    fn new<S: StateReader>(
+        state: &S,
+        block_context: &BlockContext,
+        flags: &ExecutionFlags,
+    ) -> Self {
+        if !flags.fee() {
+            return Self::default();
+        }
+
+        let sequencer = block_context.block_info().sequencer_address;
+        let low = get_fee_token_var_address(sequencer);
+        let Ok(high) = low.next_storage_key() else { return Self::default() };
+
+        let tokens = &block_context.chain_info().fee_token_addresses;
+        let tokens = [
+            (PriceUnit::Wei, tokens.eth_fee_token_address),
+            (PriceUnit::Fri, tokens.strk_fee_token_address),
+        ];
+
+        let mut balances = Vec::new();
+        for (unit, token) in tokens {
+            // the balances are tracked like the other keys if they can't be read
+            if let Some(initial) = read_balance(state, token, [low, high]) {
+                balances.push(FeeTokenBalance { unit, token, keys: [low, high], initial });
+            }
+        }
+
+        let keys = balances.iter().flat_map(|b| b.keys.map(|key| (b.token, key))).collect();
+        Self { balances, keys }
+    }
+
+    fn keys(&self) -> &[(ContractAddress, StorageKey)] {
+        &self.keys
+    }
+
+    /// Returns whether the sequencer balances were only accessed by the fee transfer of the
+    /// transaction whose execution produced `result`.
+    ///
+    /// The reads of the balances aren't recorded, so a transaction reading them elsewhere could
+    /// have read a value that the fees of the transactions committed before it have changed.
+    /// Failed and reverted executions don't have the calls that were made, so they're assumed to
+    /// have read them.
+    fn only_accessed_by_fee_transfer(&self, result: &ExecutionResult) -> bool {
+        if self.balances.is_empty() {
+            return true;
+        }
+
+        let ExecutionResult::Success { trace, .. } = result else { return false };
+        if trace.revert_error.is_some() {
+            return false;
+        }
+
+        let mut calls: Vec<&CallInfo> =
+            trace.validate_call_info.iter().chain(&trace.execute_call_info).collect();
+
+        while let Some(call) = calls.pop() {
+            let accessed = self.keys.iter().any(|(token, key)| {
+                call.contract_address == utils::to_address(*token)
+                    && call.accessed_storage_keys.contains(key.0.key())
+            });
+
+            if accessed {
+                return false;
+            }
+
+            calls.extend(&call.inner_calls);
+        }
+
+        true
+    }
+
+    /// Returns the result and writes of `speculation`, with the sequencer balances updated on top
+    /// of their values in `state`. Returns `None` if the speculative execution changed the
+    /// balances by anything else than its fee.
+    fn commit<S: StateReader>(
+        &self,
+        state: &S,
+        speculation: Speculation,
+    ) -> Option<(ExecutionResult, StateMaps)> {
+        let Speculation { result, mut writes, .. } = speculation;
+
+        for balance in &self.balances {
+            let fee = match &result {
+                ExecutionResult::Success { receipt, .. } if receipt.fee().unit == balance.unit => {
+                    receipt.fee().overall_fee
+                }
+                _ => 0,
+            };
+
+            let [low, high] = balance.keys.map(|key| writes.storage.get(&(balance.token, key)));
+            let written = match (low, high) {
+                (None, None) => balance.initial,
+                (low, high) => (
+                    low.map_or(Some(balance.initial.0), Felt::to_u128)?,
+                    high.map_or(Some(balance.initial.1), Felt::to_u128)?,
+                ),
+            };
+
+            if written != add_to_balance(balance.initial, fee) {
+                return None;
+            }
+
+            if fee > 0 {
+                let current = read_balance(state, balance.token, balance.keys)?;
+                let (low, high) = add_to_balance(current, fee);
+                writes.storage.insert((balance.token, balance.keys[0]), low.into());
+                writes.storage.insert((balance.token, balance.keys[1]), high.into());
+            }
+        }
+
+        Some((result, writes))
+    }
+
+    /// Removes the sequencer balances from `keys`.
+    fn untrack(&self, keys: &mut StateDiffKeys) {
+        for (token, key) in &self.keys {
+            keys.storage.remove(&(utils::to_address(*token), *key.0.key()));
+        }
+    }
+}
+
+/// Reads the `u256` balance stored at `keys` in `token`.
+fn read_balance<S: StateReader>(
+    state: &S,
+    token: ContractAddress,
+    keys: [StorageKey; 2],
+) -> Option<(u128, u128)> {
+    let low = state.get_storage_at(token, keys[0]).ok()?.to_u128()?;
+    let high = state.get_storage_at(token, keys[1]).ok()?.to_u128()?;
+    Some((low, high))
+}
+
+/// Adds `amount` to a `u256` balance made of its low and high words.
+fn add_to_balance((low, high): (u128, u128), amount: u128) -> (u128, u128) {
+    let (low, carry) = low.overflowing_add(amount);
+    (low, high.wrapping_add(carry as u128))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::add_to_balance;
+
+    #[test]
+    fn balance_addition() {
+        assert_eq!(add_to_balance((1, 0), 2), (3, 0));
+        assert_eq!(add_to_balance((u128::MAX, 1), 2), (1, 2));
+    }
+}
@@ -56,10 +56,26 @@ use katana_provider::traits::contract::ContractClassProvider;
 use starknet::core::utils::parse_cairo_short_string;
 
 use super::state::{CachedState, StateDb};
-use crate::abstraction::{EntryPointCall, ExecutionFlags};
+use crate::abstraction::{EntryPointCall, ExecutionFlags, StateDiffKeys};
 use crate::utils::build_receipt;
 use crate::{ExecutionError, ExecutionResult};
 
+/// Returns the address of the account that sent the transaction, or `None` for L1 handler
+/// transactions.
+fn tx_sender(tx: &ExecutableTx) -> Option<katana_primitives::contract::ContractAddress> {
+    match tx {
+        ExecutableTx::Invoke(InvokeTx::V1(tx)) => Some(tx.sender_address),
+        ExecutableTx::Invoke(InvokeTx::V3(tx)) => Some(tx.sender_address),
+        ExecutableTx::Declare(tx) => match &tx.transaction {
+            DeclareTx::V1(tx) => Some(tx.sender_address),
+            DeclareTx::V2(tx) => Some(tx.sender_address),
+            DeclareTx::V3(tx) => Some(tx.sender_address),
+        },
+        ExecutableTx::DeployAccount(tx) => Some(tx.contract_address()),
+        ExecutableTx::L1Handler(_) => None,
+    }
+}
+
 pub fn transact<S: StateReader>(
     state: &mut cached_state::CachedState<S>,
     block_context: &BlockContext,
@@ -70,9 +86,10 @@ pub fn transact<S: StateReader>(
         state: &mut cached_state::CachedState<S>,
         block_context: &BlockContext,
         simulation_flags: &ExecutionFlags,
+        impersonated: bool,
         tx: Transaction,
     ) -> Result<(TransactionExecutionInfo, TxFeeInfo), ExecutionError> {
-        let validate = simulation_flags.account_validation();
+        let validate = simulation_flags.account_validation() && !impersonated;
         let charge_fee = simulation_flags.fee();
         // Blockifier doesn't provide a way to fully skip nonce check during the tx validation
         // stage. The `nonce_check` flag in `tx.execute()` only 'relaxes' the check for
@@ -122,7 +139,12 @@ pub fn transact<S: StateReader>(
         Ok((info, fee_info))
     }
 
-    match transact_inner(state, block_context, simulation_flags, to_executor_tx(tx.clone())) {
+    // the validation logic of impersonated accounts is always skipped
+    let impersonated = tx_sender(&tx.transaction)
+        .is_some_and(|sender| simulation_flags.impersonated_accounts().contains(sender));
+
+    let executor_tx = to_executor_tx(tx.clone());
+    match transact_inner(state, block_context, simulation_flags, impersonated, executor_tx) {
         Ok((info, fee)) => {
             // get the trace and receipt from the execution info
             let trace = to_exec_info(info, tx.r#type());
@@ -479,6 +501,31 @@ pub(super) fn state_update_from_cached_state<S: StateDb>(
     }
 }
 
+/// Returns the keys of the state modified in `state`.
+pub(super) fn state_diff_keys<S: StateReader>(
+    state: &mut cached_state::CachedState<S>,
+) -> StateDiffKeys {
+    let state_diff = state.to_state_diff().unwrap();
+
+    let storage = state_diff
+        .storage
+        .into_keys()
+        .map(|(addr, key)| (to_address(addr), *key.0.key()))
+        .collect();
+
+    let contracts = state_diff
+        .nonces
+        .into_keys()
+        .chain(state_diff.class_hashes.into_keys())
+        .map(to_address)
+        .collect();
+
+    let declared_classes =
+        state_diff.compiled_class_hashes.into_keys().map(|class_hash| class_hash.0).collect();
+
+    StateDiffKeys { storage, contracts, declared_classes }
+}
+
 fn to_api_da_mode(mode: katana_primitives::da::DataAvailabilityMode) -> DataAvailabilityMode {
     match mode {
         katana_primitives::da::DataAvailabilityMode::L1 => DataAvailabilityMode::L1,
@@ -3,6 +3,7 @@ use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, Flat
 use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
 use katana_primitives::env::{BlockEnv, CfgEnv};
 use katana_primitives::fee::TxFeeInfo;
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::contract::ContractClassProvider;
@@ -10,8 +11,8 @@ use katana_provider::traits::state::StateProvider;
 use katana_provider::ProviderResult;
 
 use crate::abstraction::{
-    BlockExecutor, EntryPointCall, ExecutionFlags, ExecutionOutput, ExecutionResult, ExecutorExt,
-    ExecutorFactory, ExecutorResult, ResultAndStates,
+    BlockExecutor, BlockResource, EntryPointCall, ExecutionFlags, ExecutionOutput, ExecutionResult,
+    ExecutorExt, ExecutorFactory, ExecutorResult, ResultAndStates,
 };
 use crate::ExecutionError;
 
@@ -101,8 +102,15 @@ impl<'a> BlockExecutor<'a> for NoopExecutor {
     fn execute_transactions(
         &mut self,
         transactions: Vec<ExecutableTxWithHash>,
+    ) -> ExecutorResult<(usize, Option<BlockResource>)> {
+        Ok((transactions.len(), None))
+    }
+
+    fn apply_state_updates(
+        &mut self,
+        updates: StateUpdatesWithDeclaredClasses,
     ) -> ExecutorResult<()> {
-        let _ = transactions;
+        let _ = updates;
         Ok(())
     }
 
@@ -321,8 +321,22 @@ fn test_executor_with_valid_blocks_impl<EF: ExecutorFactory>(
 
 #[cfg(feature = "blockifier")]
 mod blockifier {
+    use std::num::NonZeroUsize;
+
     use fixtures::blockifier::factory;
+    use fixtures::{chain, legacy_contract_class};
     use katana_executor::implementation::blockifier::BlockifierFactory;
+    use katana_primitives::block::{GasPrices, PartialHeader};
+    use katana_primitives::chain::ChainId;
+    use katana_primitives::chain_spec::ChainSpec;
+    use katana_primitives::da::L1DataAvailabilityMode;
+    use katana_primitives::trace::TxExecInfo;
+    use katana_primitives::transaction::{
+        DeclareTx, DeclareTxV1, DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, InvokeTx,
+        InvokeTxV1,
+    };
+    use katana_primitives::version::CURRENT_STARKNET_VERSION;
+    use starknet::core::utils::get_selector_from_name;
 
     use super::*;
 
@@ -334,4 +348,160 @@ mod blockifier {
     ) {
         test_executor_with_valid_blocks_impl(factory, state, blocks)
     }
+
+    #[rstest::rstest]
+    fn test_parallel_executor_with_valid_blocks(
+        factory: BlockifierFactory,
+        #[from(state_provider)] state: Box<dyn StateProvider>,
+        #[from(valid_blocks)] blocks: [ExecutableBlock; 3],
+    ) {
+        let factory = factory.with_parallel_execution(NonZeroUsize::new(4).unwrap());
+        test_executor_with_valid_blocks_impl(factory, state, blocks)
+    }
+
+    /// Transactions of the same batch that read the keys written by the ones before them must get
+    /// the same results in parallel as they do sequentially.
+    #[rstest::rstest]
+    fn parallel_execution_of_contended_transactions(
+        chain: &ChainSpec,
+        #[from(factory)] sequential: BlockifierFactory,
+        #[from(factory)] parallel: BlockifierFactory,
+        #[from(state_provider)] sequential_state: Box<dyn StateProvider>,
+        #[from(state_provider)] parallel_state: Box<dyn StateProvider>,
+    ) {
+        let block = contended_block(chain);
+        let parallel = parallel.with_parallel_execution(NonZeroUsize::new(4).unwrap());
+
+        let mut executor = sequential.with_state(sequential_state);
+        executor.execute_block(block.clone()).unwrap();
+        let expected = executor.take_execution_output().unwrap();
+
+        let mut executor = parallel.with_state(parallel_state);
+        executor.execute_block(block).unwrap();
+        let actual = executor.take_execution_output().unwrap();
+
+        assert_eq!(actual.transactions.len(), expected.transactions.len());
+
+        for (i, ((_, actual), (_, expected))) in
+            actual.transactions.iter().zip(&expected.transactions).enumerate()
+        {
+            let (
+                ExecutionResult::Success { receipt, trace },
+                ExecutionResult::Success { receipt: expected_receipt, trace: expected_trace },
+            ) = (actual, expected)
+            else {
+                panic!("tx {i} failed: {actual:?} {expected:?}");
+            };
+
+            assert!(!expected_receipt.is_reverted(), "tx {i} reverted: {expected_receipt:?}");
+            assert_eq!(receipt, expected_receipt, "receipt of tx {i}");
+
+            // the fee transfer reads the sequencer balance of the start of the batch when it's
+            // executed speculatively, so only the results of the execution are compared
+            let retdata = |trace: &TxExecInfo| trace.execute_call_info.clone().map(|c| c.retdata);
+            assert_eq!(retdata(trace), retdata(expected_trace), "retdata of tx {i}");
+        }
+
+        assert_eq!(actual.states.state_updates, expected.states.state_updates);
+        assert_eq!(
+            actual.states.declared_compiled_classes.keys().collect::<Vec<_>>(),
+            expected.states.declared_compiled_classes.keys().collect::<Vec<_>>()
+        );
+    }
+
+    /// Returns a block whose transactions all fit in a single parallel batch, and conflict with
+    /// the ones before them:
+    ///
+    /// 1. two transfers to the same recipient, writing the same balance.
+    /// 2. a read of the sequencer balance, which is written by the fees of the transfers.
+    /// 3. the declaration of a legacy class, and its deployment by another account.
+    fn contended_block(chain: &ChainSpec) -> ExecutableBlock {
+        let chain_id = ChainId::parse("KATANA").unwrap();
+        let sequencer_address = address!("0x1");
+        let recipient = felt!("0x1234");
+        let legacy_class_hash = felt!("0x1e9a");
+
+        let accounts: Vec<ContractAddress> = chain.genesis.accounts().map(|(a, _)| *a).collect();
+
+        let invoke = |sender_address: ContractAddress, calldata: Vec<Felt>| {
+            ExecutableTxWithHash::new(ExecutableTx::Invoke(InvokeTx::V1(InvokeTxV1 {
+                chain_id,
+                sender_address,
+                calldata,
+                max_fee: 100_000_000_000_000_000,
+                signature: vec![],
+                nonce: Felt::ZERO,
+            })))
+        };
+
+        let transfer = |sender: ContractAddress| {
+            let selector = get_selector_from_name("transfer").unwrap();
+            let calldata = vec![recipient, felt!("0x100"), Felt::ZERO];
+            invoke(
+                sender,
+                [
+                    vec![felt!("0x1"), DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), selector, felt!("3")],
+                    calldata,
+                ]
+                .concat(),
+            )
+        };
+
+        let balance_of = invoke(
+            accounts[2],
+            vec![
+                felt!("0x1"),
+                DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+                get_selector_from_name("balanceOf").unwrap(),
+                felt!("1"),
+                sequencer_address.into(),
+            ],
+        );
+
+        let declare = ExecutableTxWithHash::new(ExecutableTx::Declare(DeclareTxWithClass {
+            compiled_class: legacy_contract_class(),
+            sierra_class: None,
+            transaction: DeclareTx::V1(DeclareTxV1 {
+                chain_id,
+                sender_address: accounts[3],
+                nonce: Felt::ZERO,
+                signature: vec![],
+                class_hash: legacy_class_hash,
+                max_fee: 100_000_000_000_000_000,
+            }),
+        }));
+
+        let deploy = invoke(
+            accounts[4],
+            vec![
+                felt!("0x1"),
+                DEFAULT_UDC_ADDRESS.into(),
+                get_selector_from_name("deployContract").unwrap(),
+                felt!("6"),
+                // --- udc::deployContract arguments
+                legacy_class_hash,
+                felt!("0x5a17"), // salt
+                felt!("0x0"),    // unique
+                felt!("0x2"),    // constructor calldata length
+                felt!("0x1"),    // constructor calldata
+                felt!("0x2"),
+            ],
+        );
+
+        let gas_prices = GasPrices { eth: 100 * u128::pow(10, 9), strk: 100 * u128::pow(10, 9) };
+
+        ExecutableBlock {
+            header: PartialHeader {
+                protocol_version: CURRENT_STARKNET_VERSION,
+                number: 1,
+                timestamp: 100,
+                sequencer_address,
+                parent_hash: 123u64.into(),
+                l1_gas_prices: gas_prices.clone(),
+                l1_data_gas_prices: gas_prices,
+                l1_da_mode: L1DataAvailabilityMode::Calldata,
+            },
+            body: vec![transfer(accounts[0]), transfer(accounts[1]), balance_of, declare, deploy],
+        }
+    }
 }
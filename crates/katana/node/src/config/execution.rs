@@ -1,3 +1,5 @@
+use std::num::NonZeroUsize;
+
 pub const MAX_RECURSION_DEPTH: usize = 1000;
 
 pub const DEFAULT_INVOCATION_MAX_STEPS: u32 = 10_000_000;
@@ -8,6 +10,9 @@ pub struct ExecutionConfig {
     pub invocation_max_steps: u32,
     pub validation_max_steps: u32,
     pub max_recursion_depth: usize,
+    /// The number of threads executing the transactions of a block optimistically in parallel.
+    /// If `None`, the transactions are executed sequentially.
+    pub parallel_workers: Option<NonZeroUsize>,
 }
 
 impl std::default::Default for ExecutionConfig {
@@ -16,6 +21,7 @@ impl std::default::Default for ExecutionConfig {
             max_recursion_depth: MAX_RECURSION_DEPTH,
             invocation_max_steps: DEFAULT_INVOCATION_MAX_STEPS,
             validation_max_steps: DEFAULT_VALIDATION_MAX_STEPS,
+            parallel_workers: None,
         }
     }
 }
@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
 use katana_primitives::block::BlockHashOrNumber;
 use starknet::providers::Url;
 
@@ -8,4 +11,10 @@ pub struct ForkingConfig {
     pub url: Url,
     /// The block number to fork from. If `None`, the latest block will be used.
     pub block: Option<BlockHashOrNumber>,
+    /// The directory of the persistent cache of the data fetched from the forked network. If
+    /// `None`, the fetched data is only cached in memory.
+    pub cache_dir: Option<PathBuf>,
+    /// The interval at which the head of the forked network is checked. If set, the node is
+    /// rebased onto the head whenever it has moved, instead of staying at the fork block.
+    pub follow: Option<Duration>,
 }
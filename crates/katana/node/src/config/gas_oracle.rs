@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+/// Gas price oracle configurations.
+///
+/// By default, the L1 gas prices are the fixed prices of the chain spec.
+#[derive(Debug, Clone, Default)]
+pub struct GasOracleConfig {
+    /// The interval at which the L1 gas prices are sampled from the settlement chain of the
+    /// messaging configuration. If `None`, the L1 gas prices are fixed.
+    pub l1_sampling: Option<Duration>,
+    /// The target amount of L1 gas used per block of the L2 fee market. If set, the gas prices of
+    /// a block go up when the previous block used more gas than the target, and down (to the L1
+    /// prices at most) when it used less.
+    pub l2_target_gas: Option<u128>,
+}
@@ -2,17 +2,24 @@ pub mod db;
 pub mod dev;
 pub mod execution;
 pub mod fork;
+pub mod gas_oracle;
 pub mod metrics;
+pub mod pool;
 pub mod rpc;
+pub mod sync;
 
 use db::DbConfig;
 use dev::DevConfig;
 use execution::ExecutionConfig;
 use fork::ForkingConfig;
+use gas_oracle::GasOracleConfig;
 use katana_core::service::messaging::MessagingConfig;
+use katana_executor::BlockLimits;
 use katana_primitives::chain_spec::ChainSpec;
 use metrics::MetricsConfig;
+use pool::PoolConfig;
 use rpc::RpcConfig;
+use sync::SyncConfig;
 
 /// Node configurations.
 ///
@@ -43,6 +50,17 @@ pub struct Config {
     /// Sequencing options.
     pub sequencing: SequencingConfig,
 
+    /// Transaction pool options.
+    pub pool: PoolConfig,
+
+    /// Gas price oracle options.
+    pub gas_oracle: GasOracleConfig,
+
+    /// Sync options.
+    ///
+    /// If set, the node syncs the blocks of another network instead of producing its own.
+    pub sync: Option<SyncConfig>,
+
     /// Development options.
     pub dev: DevConfig,
 }
@@ -57,4 +75,14 @@ pub struct SequencingConfig {
     ///
     /// Allowing block to only be produced manually.
     pub no_mining: bool,
+
+    /// Limits on the resources used by the transactions of a block. When a limit is reached,
+    /// the block is closed early.
+    ///
+    /// Only enforced when blocks are produced at interval or on demand.
+    pub block_limits: BlockLimits,
+
+    /// Compute and store the EIP-4844 blobs of the state diff of every produced block, along
+    /// with their KZG commitments.
+    pub state_diff_blobs: bool,
 }
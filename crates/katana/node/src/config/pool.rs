@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+
+use katana_pool::ordering::OrderingKind;
+use katana_pool::pool::PoolLimits;
+
+/// Transaction pool configurations.
+#[derive(Debug, Clone, Default)]
+pub struct PoolConfig {
+    /// The order in which the transactions in the pool are included in a block.
+    pub ordering: OrderingKind,
+    /// The size and lifetime limits of the pool.
+    pub limits: PoolLimits,
+    /// The file of the policy that the transactions added to the pool must comply with. If
+    /// `None`, all valid transactions are accepted.
+    pub policy_file: Option<PathBuf>,
+}
@@ -3,6 +3,8 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 /// The default maximum number of concurrent RPC connections.
 pub const DEFAULT_RPC_MAX_CONNECTIONS: u32 = 100;
+/// The default maximum size, in bytes, of the body of an RPC request.
+pub const DEFAULT_RPC_MAX_REQUEST_BODY_SIZE: u32 = 10 * 1024 * 1024;
 pub const DEFAULT_RPC_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 pub const DEFAULT_RPC_PORT: u16 = 5050;
 
@@ -10,11 +12,15 @@ pub const DEFAULT_RPC_PORT: u16 = 5050;
 #[derive(
     Debug, Copy, Clone, PartialEq, Eq, Hash, strum_macros::EnumString, strum_macros::Display,
 )]
+#[strum(ascii_case_insensitive)]
 pub enum ApiKind {
     Starknet,
     Torii,
     Dev,
     Saya,
+    TxPool,
+    Admin,
+    Katana,
 }
 
 /// Configuration for the RPC server.
@@ -23,6 +29,7 @@ pub struct RpcConfig {
     pub addr: IpAddr,
     pub port: u16,
     pub max_connections: u32,
+    pub max_request_body_size: u32,
     pub allowed_origins: Option<Vec<String>>,
     pub apis: HashSet<ApiKind>,
 }
@@ -41,6 +48,7 @@ impl Default for RpcConfig {
             addr: DEFAULT_RPC_ADDR,
             port: DEFAULT_RPC_PORT,
             max_connections: DEFAULT_RPC_MAX_CONNECTIONS,
+            max_request_body_size: DEFAULT_RPC_MAX_REQUEST_BODY_SIZE,
             apis: HashSet::from([ApiKind::Starknet]),
         }
     }
@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+use starknet::providers::Url;
+
+/// The default interval at which the synced network is polled for new blocks.
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Configurations for running the node as a read-only replica of an existing network.
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// The JSON-RPC URL of the network to sync from.
+    pub url: Url,
+    /// The interval at which the network is polled for new blocks once the node is synced.
+    pub interval: Duration,
+}
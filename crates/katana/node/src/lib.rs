@@ -4,45 +4,72 @@ pub mod config;
 pub mod exit;
 pub mod version;
 
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
 use std::future::IntoFuture;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use config::fork::ForkingConfig;
 use config::metrics::MetricsConfig;
+use config::pool::PoolConfig;
 use config::rpc::{ApiKind, RpcConfig};
+use config::sync::SyncConfig;
 use config::{Config, SequencingConfig};
 use dojo_metrics::exporters::prometheus::PrometheusRecorder;
 use dojo_metrics::{Report, Server as MetricsServer};
+use futures::StreamExt;
 use hyper::{Method, Uri};
 use jsonrpsee::server::middleware::proxy_get_request::ProxyGetRequestLayer;
-use jsonrpsee::server::{AllowHosts, ServerBuilder, ServerHandle};
+use jsonrpsee::server::{AllowHosts, RandomIntegerIdProvider, ServerBuilder, ServerHandle};
 use jsonrpsee::RpcModule;
+use katana_core::backend::gas_oracle::{GasOracle, GasPriceSampler, L1GasPrices, SettlementClient};
 use katana_core::backend::storage::Blockchain;
 use katana_core::backend::Backend;
 use katana_core::env::BlockContextGenerator;
 use katana_core::service::block_producer::BlockProducer;
+use katana_core::service::fork_follower::ForkFollower;
 use katana_core::service::messaging::MessagingConfig;
+use katana_core::service::metrics::BlockProducerMetrics;
 use katana_db::mdbx::DbEnv;
 use katana_executor::implementation::blockifier::BlockifierFactory;
 use katana_executor::{ExecutionFlags, ExecutorFactory};
+use katana_pipeline::stage::sync::JsonRpcSource;
 use katana_pipeline::{stage, Pipeline};
-use katana_pool::ordering::FiFo;
+use katana_pool::ordering::TxOrdering;
+use katana_pool::validation::policy::TxPolicy;
 use katana_pool::validation::stateful::TxValidator;
 use katana_pool::TxPool;
+use katana_primitives::block::BlockNumber;
 use katana_primitives::env::{CfgEnv, FeeTokenAddressses};
+use katana_provider::providers::db::DbProvider;
+use katana_provider::traits::block::{BlockNumberProvider, HeaderProvider};
+use katana_rpc::admin::AdminApi;
 use katana_rpc::dev::DevApi;
+use katana_rpc::katana::KatanaApi;
 use katana_rpc::metrics::RpcServerMetrics;
 use katana_rpc::saya::SayaApi;
 use katana_rpc::starknet::forking::ForkedClient;
 use katana_rpc::starknet::StarknetApi;
 use katana_rpc::torii::ToriiApi;
+use katana_rpc::txpool::TxPoolApi;
+use katana_rpc::versioning::RpcVersionLayer;
+use katana_rpc_api::admin::AdminApiServer;
 use katana_rpc_api::dev::DevApiServer;
+use katana_rpc_api::katana::KatanaApiServer;
 use katana_rpc_api::saya::SayaApiServer;
-use katana_rpc_api::starknet::{StarknetApiServer, StarknetTraceApiServer, StarknetWriteApiServer};
+use katana_rpc_api::starknet::{
+    StarknetApiServer, StarknetApiV0_8Server, StarknetTraceApiServer, StarknetWriteApiServer,
+    StarknetWriteApiV0_8Server, StarknetWsApiServer,
+};
 use katana_rpc_api::torii::ToriiApiServer;
+use katana_rpc_api::txpool::TxPoolApiServer;
 use katana_tasks::TaskManager;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::JsonRpcClient;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tracing::info;
 
@@ -87,8 +114,13 @@ pub struct Node {
     pub rpc_config: RpcConfig,
     pub metrics_config: Option<MetricsConfig>,
     pub sequencing_config: SequencingConfig,
+    pub pool_config: PoolConfig,
     pub messaging_config: Option<MessagingConfig>,
+    pub sync_config: Option<SyncConfig>,
     forked_client: Option<ForkedClient>,
+    fork_follower: Option<ForkFollower<BlockifierFactory>>,
+    gas_price_sampler: Option<GasPriceSampler>,
+    sync_source: Option<JsonRpcSource>,
 }
 
 impl Node {
@@ -119,20 +151,50 @@ impl Node {
         let block_producer = self.block_producer.clone();
         let validator = self.block_producer.validator().clone();
 
-        // --- build sequencing stage
+        // --- start following the forked network
+
+        if let Some(mut follower) = self.fork_follower.take() {
+            let mut rebases = follower.add_listener();
+            let client = self.forked_client.clone();
+
+            let spawner = self.task_manager.task_spawner();
+            spawner.build_task().name("Fork follower").spawn(follower.run());
+            spawner.build_task().spawn(async move {
+                while let Some(outcome) = rebases.next().await {
+                    if let Some(client) = &client {
+                        client.set_block(outcome.block_number);
+                    }
+                }
+            });
+        }
+
+        // --- start sampling the L1 gas prices
 
-        let sequencing = stage::Sequencing::new(
-            pool.clone(),
-            backend.clone(),
-            self.task_manager.task_spawner(),
-            block_producer.clone(),
-            self.messaging_config.clone(),
-        );
+        if let Some(sampler) = self.gas_price_sampler.take() {
+            let spawner = self.task_manager.task_spawner();
+            spawner.build_task().name("Gas price sampler").spawn(sampler.run());
+        }
 
         // --- build and start the pipeline
 
-        let mut pipeline = Pipeline::new();
-        pipeline.add_stage(Box::new(sequencing));
+        let pipeline = if let (Some(source), Some(cfg), Some(db)) =
+            (self.sync_source.take(), &self.sync_config, &self.db)
+        {
+            // a replica only follows the synced network, so it doesn't produce any block
+            sync_pipeline(source, db, None).with_interval(cfg.interval)
+        } else {
+            let sequencing = stage::Sequencing::new(
+                pool.clone(),
+                backend.clone(),
+                self.task_manager.task_spawner(),
+                block_producer.clone(),
+                self.messaging_config.clone(),
+            );
+
+            let mut pipeline = Pipeline::new();
+            pipeline.add_stage(Box::new(sequencing));
+            pipeline
+        };
 
         self.task_manager
             .task_spawner()
@@ -141,8 +203,11 @@ impl Node {
             .name("Pipeline")
             .spawn(pipeline.into_future());
 
+        // a replica doesn't produce blocks, so the transactions it would accept are never included
+        let read_only = self.sync_config.is_some();
         let node_components = (pool, backend, block_producer, validator, self.forked_client.take());
-        let rpc = spawn(node_components, self.rpc_config.clone()).await?;
+        let rpc =
+            spawn(node_components, self.rpc_config.clone(), &self.pool_config, read_only).await?;
 
         Ok(LaunchedNode { node: self, rpc })
     }
@@ -176,18 +241,54 @@ pub async fn build(mut config: Config) -> Result<Node> {
         .with_account_validation(config.dev.account_validation)
         .with_fee(config.dev.fee);
 
-    let executor_factory = Arc::new(BlockifierFactory::new(cfg_env, execution_flags));
+    let mut executor_factory = BlockifierFactory::new(cfg_env, execution_flags)
+        .with_block_limits(config.sequencing.block_limits);
+
+    if let Some(workers) = config.execution.parallel_workers {
+        executor_factory = executor_factory.with_parallel_execution(workers);
+    }
+
+    let executor_factory = Arc::new(executor_factory);
 
     // --- build backend
 
+    let mut sync_source = None;
+    let mut forked_provider = None;
+
     let (blockchain, db, forked_client) = if let Some(cfg) = &config.forking {
-        let (bc, block_num) =
-            Blockchain::new_from_forked(cfg.url.clone(), cfg.block, &mut config.chain).await?;
+        let (bc, provider, block_num) = Blockchain::new_from_forked(
+            cfg.url.clone(),
+            cfg.block,
+            cfg.cache_dir.as_deref(),
+            &mut config.chain,
+        )
+        .await?;
 
         // TODO: it'd bee nice if the client can be shared on both the rpc and forked backend side
         let forked_client = ForkedClient::new_http(cfg.url.clone(), block_num);
+        forked_provider = Some((provider, block_num));
 
         (bc, None, Some(forked_client))
+    } else if let Some(cfg) = &config.sync {
+        let source = JsonRpcSource::connect(cfg.url.clone()).await?;
+        config.chain.id = source.chain_id();
+
+        let db = match &config.db.dir {
+            Some(db_path) => katana_db::init_db(db_path)?,
+            None => katana_db::init_ephemeral_db()?,
+        };
+
+        // the genesis block is synced from the network instead of being created locally, and is
+        // synced before the node starts as the block producer requires an existing block. the rest
+        // of the chain is synced in the background once the node is launched.
+        info!(url = %cfg.url, "Syncing genesis block.");
+        sync_pipeline(source.clone(), &db, Some(0)).run().await?;
+        sync_source = Some(source);
+
+        // the dev api can't be used to modify the state of a replica
+        config.rpc.apis.remove(&ApiKind::Dev);
+
+        (Blockchain::new(DbProvider::new(db.clone())), Some(db), None)
     } else if let Some(db_path) = &config.db.dir {
         let db = katana_db::init_db(db_path)?;
         (Blockchain::new_with_db(db.clone(), &config.chain)?, Some(db), None)
@@ -196,12 +297,38 @@ pub async fn build(mut config: Config) -> Result<Node> {
         (Blockchain::new_with_db(db.clone(), &config.chain)?, Some(db), None)
     };
 
+    // --- build gas oracle
+
+    // the prices of the latest block are kept until the settlement chain is sampled
+    let latest = blockchain.provider().latest_number()?;
+    let header = blockchain.provider().header(latest.into())?.context("missing latest header")?;
+    let prices = L1GasPrices { gas: header.l1_gas_prices, data_gas: header.l1_data_gas_prices };
+
+    let (gas_oracle, gas_price_sampler) = if let Some(interval) = config.gas_oracle.l1_sampling {
+        let messaging = config.messaging.as_ref().context(
+            "L1 gas price sampling requires a settlement chain, see the messaging config",
+        )?;
+        let client = SettlementClient::from_messaging(messaging)?;
+        let sampler = GasPriceSampler::new(client, interval, prices);
+        (GasOracle::sampled(&sampler), Some(sampler))
+    } else {
+        (GasOracle::fixed(prices), None)
+    };
+
+    let gas_oracle = match config.gas_oracle.l2_target_gas {
+        Some(target_gas) => gas_oracle.with_fee_market(target_gas),
+        None => gas_oracle,
+    };
+
     let block_context_generator = BlockContextGenerator::default().into();
     let backend = Arc::new(Backend {
         blockchain,
+        gas_oracle,
         executor_factory,
         block_context_generator,
         chain_spec: config.chain,
+        state_diff_blobs: config.sequencing.state_diff_blobs,
+        metrics: BlockProducerMetrics::default(),
     });
 
     // --- build block producer
@@ -216,27 +343,71 @@ pub async fn build(mut config: Config) -> Result<Node> {
         BlockProducer::instant(Arc::clone(&backend))
     };
 
+    // --- build fork follower
+
+    let fork_follower = match (&config.forking, forked_provider) {
+        (Some(ForkingConfig { url, follow: Some(interval), .. }), Some((provider, block))) => {
+            let client = JsonRpcClient::new(HttpTransport::new(url.clone()));
+            Some(ForkFollower::new(
+                Arc::clone(&backend),
+                block_producer.clone(),
+                client,
+                provider,
+                block,
+                *interval,
+            ))
+        }
+        _ => None,
+    };
+
     // --- build transaction pool
 
     let validator = block_producer.validator();
-    let pool = TxPool::new(validator.clone(), FiFo::new());
+    if let Some(path) = &config.pool.policy_file {
+        let policy = TxPolicy::load(path).context("failed to load transaction policy")?;
+        validator.set_policy(Some(policy));
+    }
+
+    let ordering = TxOrdering::new(config.pool.ordering);
+    let pool = TxPool::with_limits(validator.clone(), ordering, config.pool.limits.clone());
 
     let node = Node {
         db,
         pool,
         backend,
+        sync_source,
         forked_client,
+        fork_follower,
         block_producer,
+        gas_price_sampler,
         rpc_config: config.rpc,
         metrics_config: config.metrics,
         messaging_config: config.messaging,
         sequencing_config: config.sequencing,
+        pool_config: config.pool,
+        sync_config: config.sync,
         task_manager: TaskManager::current(),
     };
 
     Ok(node)
 }
 
+/// Builds a pipeline that syncs the chain from `source` into `db`, up to the `target` block if
+/// any.
+fn sync_pipeline(source: JsonRpcSource, db: &DbEnv, target: Option<BlockNumber>) -> Pipeline {
+    let mut headers = stage::Headers::new(source.clone(), DbProvider::new(db.clone()));
+    if let Some(target) = target {
+        headers = headers.with_target(target);
+    }
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add_stage(Box::new(headers));
+    pipeline.add_stage(Box::new(stage::Bodies::new(source.clone(), DbProvider::new(db.clone()))));
+    pipeline.add_stage(Box::new(stage::States::new(source.clone(), DbProvider::new(db.clone()))));
+    pipeline.add_stage(Box::new(stage::Classes::new(source, DbProvider::new(db.clone()))));
+    pipeline
+}
+
 // Moved from `katana_rpc` crate
 pub async fn spawn<EF: ExecutorFactory>(
     node_components: (
@@ -247,12 +418,22 @@ pub async fn spawn<EF: ExecutorFactory>(
         Option<ForkedClient>,
     ),
     config: RpcConfig,
+    pool_config: &PoolConfig,
+    read_only: bool,
 ) -> Result<RpcServer> {
     let (pool, backend, block_producer, validator, forked_client) = node_components;
 
     let mut methods = RpcModule::new(());
     methods.register_method("health", |_, _| Ok(serde_json::json!({ "health": true })))?;
 
+    // the names of the methods of the other spec versions, served under their own route
+    let mut versioned_methods = Vec::new();
+
+    if config.apis.contains(&ApiKind::Admin) {
+        let policy_file = pool_config.policy_file.clone();
+        methods.merge(AdminApi::new(validator.clone(), policy_file).into_rpc())?;
+    }
+
     if config.apis.contains(&ApiKind::Starknet) {
         let server = if let Some(client) = forked_client {
             StarknetApi::new_forked(
@@ -267,12 +448,24 @@ pub async fn spawn<EF: ExecutorFactory>(
         };
 
         methods.merge(StarknetApiServer::into_rpc(server.clone()))?;
-        methods.merge(StarknetWriteApiServer::into_rpc(server.clone()))?;
-        methods.merge(StarknetTraceApiServer::into_rpc(server))?;
+        if !read_only {
+            methods.merge(StarknetWriteApiServer::into_rpc(server.clone()))?;
+
+            let v0_8 = StarknetWriteApiV0_8Server::into_rpc(server.clone());
+            versioned_methods.extend(v0_8.method_names());
+            methods.merge(v0_8)?;
+        }
+        methods.merge(StarknetTraceApiServer::into_rpc(server.clone()))?;
+        methods.merge(StarknetWsApiServer::into_rpc(server.clone()))?;
+
+        let v0_8 = StarknetApiV0_8Server::into_rpc(server);
+        versioned_methods.extend(v0_8.method_names());
+        methods.merge(v0_8)?;
     }
 
     if config.apis.contains(&ApiKind::Dev) {
-        methods.merge(DevApi::new(backend.clone(), block_producer.clone()).into_rpc())?;
+        methods
+            .merge(DevApi::new(backend.clone(), pool.clone(), block_producer.clone()).into_rpc())?;
     }
 
     if config.apis.contains(&ApiKind::Torii) {
@@ -285,6 +478,14 @@ pub async fn spawn<EF: ExecutorFactory>(
         methods.merge(SayaApi::new(backend.clone(), block_producer.clone()).into_rpc())?;
     }
 
+    if config.apis.contains(&ApiKind::TxPool) {
+        methods.merge(TxPoolApi::new(pool.clone()).into_rpc())?;
+    }
+
+    if config.apis.contains(&ApiKind::Katana) {
+        methods.merge(KatanaApi::new(backend.clone()).into_rpc())?;
+    }
+
     let cors = CorsLayer::new()
             // Allow `POST` when accessing the resource
             .allow_methods([Method::POST, Method::GET])
@@ -308,13 +509,18 @@ pub async fn spawn<EF: ExecutorFactory>(
     let middleware = tower::ServiceBuilder::new()
         .option_layer(cors)
         .layer(ProxyGetRequestLayer::new("/", "health")?)
+        .layer(RpcVersionLayer::new(versioned_methods, config.max_request_body_size))
         .timeout(Duration::from_secs(20));
 
+    // `starknet_unsubscribe` doesn't know the connection it's called from, so the subscription
+    // ids must be random for a connection not to close the subscriptions of the others.
     let server = ServerBuilder::new()
+        .set_id_provider(RandomIntegerIdProvider)
         .set_logger(RpcServerMetrics::new(&methods))
         .set_host_filtering(AllowHosts::Any)
         .set_middleware(middleware)
         .max_connections(config.max_connections)
+        .max_request_body_size(config.max_request_body_size)
         .build(config.socket_addr())
         .await?;
 
@@ -0,0 +1,250 @@
+//! An in-process Katana node for tests.
+//!
+//! Unlike `katana-node-bindings`, which spawns the `katana` binary and parses its output, the
+//! node is started inside the test's Tokio runtime. Tests don't require a prebuilt binary and can
+//! be debugged like any other Rust code.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use katana_core::backend::Backend;
+use katana_core::constants::DEFAULT_SEQUENCER_ADDRESS;
+use katana_core::service::messaging::MessagingConfig;
+use katana_executor::implementation::blockifier::BlockifierFactory;
+use katana_primitives::chain::ChainId;
+use katana_primitives::chain_spec::ChainSpec;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::Felt;
+use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::{BlockId, BlockTag};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Url};
+use starknet::signers::{LocalWallet, SigningKey};
+
+use crate::config::dev::DevConfig;
+use crate::config::fork::ForkingConfig;
+use crate::config::rpc::{ApiKind, RpcConfig};
+use crate::config::{Config, SequencingConfig};
+use crate::LaunchedNode;
+
+/// An account of the genesis block of a [`TestNode`], connected to the node.
+pub type TestAccount = SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>;
+
+/// Builder of a [`TestNode`].
+///
+/// Every option of the node's [`Config`] can be set, either with the dedicated methods or with
+/// [`TestNodeBuilder::configure`].
+#[derive(Debug, Clone)]
+pub struct TestNodeBuilder {
+    config: Config,
+}
+
+impl TestNodeBuilder {
+    /// Creates a builder with the default test configuration: the node listens on an ephemeral
+    /// port, serves every API except the admin one, and doesn't charge fees.
+    pub fn new() -> Self {
+        let mut chain = ChainSpec { id: ChainId::SEPOLIA, ..Default::default() };
+        chain.genesis.sequencer_address = *DEFAULT_SEQUENCER_ADDRESS;
+
+        let rpc = RpcConfig {
+            port: 0,
+            apis: HashSet::from([
+                ApiKind::Starknet,
+                ApiKind::Dev,
+                ApiKind::Saya,
+                ApiKind::Torii,
+                ApiKind::TxPool,
+                ApiKind::Katana,
+            ]),
+            ..Default::default()
+        };
+
+        let dev = DevConfig { fee: false, account_validation: true };
+        Self { config: Config { chain, rpc, dev, ..Default::default() } }
+    }
+
+    /// Creates a builder that starts the node with the given configuration, as is.
+    pub fn from_config(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// Sets the chain specification.
+    pub fn chain(mut self, chain: ChainSpec) -> Self {
+        self.config.chain = chain;
+        self
+    }
+
+    /// Sets the block production options.
+    pub fn sequencing(mut self, sequencing: SequencingConfig) -> Self {
+        self.config.sequencing = sequencing;
+        self
+    }
+
+    /// Produces blocks at interval, every `block_time` milliseconds.
+    pub fn block_time(mut self, block_time: u64) -> Self {
+        self.config.sequencing.block_time = Some(block_time);
+        self
+    }
+
+    /// Only produces blocks on demand, eg. with the `dev_generateBlock` method.
+    pub fn no_mining(mut self) -> Self {
+        self.config.sequencing.no_mining = true;
+        self
+    }
+
+    /// Sets the development options.
+    pub fn dev(mut self, dev: DevConfig) -> Self {
+        self.config.dev = dev;
+        self
+    }
+
+    /// Whether the senders of the transactions are charged fees.
+    pub fn fee(mut self, enabled: bool) -> Self {
+        self.config.dev.fee = enabled;
+        self
+    }
+
+    /// Persists the chain in the database at `dir`, instead of keeping it in memory.
+    pub fn db_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.db.dir = Some(dir.into());
+        self
+    }
+
+    /// Forks the network described by `forking`.
+    pub fn forking(mut self, forking: ForkingConfig) -> Self {
+        self.config.forking = Some(forking);
+        self
+    }
+
+    /// Enables the messaging with the settlement chain.
+    pub fn messaging(mut self, messaging: MessagingConfig) -> Self {
+        self.config.messaging = Some(messaging);
+        self
+    }
+
+    /// Sets the APIs served by the node.
+    pub fn apis(mut self, apis: impl IntoIterator<Item = ApiKind>) -> Self {
+        self.config.rpc.apis = apis.into_iter().collect();
+        self
+    }
+
+    /// Modifies the configuration in place, for the options without a dedicated method.
+    pub fn configure(mut self, f: impl FnOnce(&mut Config)) -> Self {
+        f(&mut self.config);
+        self
+    }
+
+    /// Returns the configuration the node will be started with.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Builds and launches the node in the current Tokio runtime.
+    pub async fn start(self) -> Result<TestNode> {
+        let handle = crate::build(self.config).await?.launch().await?;
+        let url = Url::parse(&format!("http://{}", handle.rpc.addr))?;
+        Ok(TestNode { handle, url })
+    }
+}
+
+impl Default for TestNodeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A Katana node running in the current process.
+///
+/// Construct this using [`TestNodeBuilder`].
+#[allow(missing_debug_implementations)]
+pub struct TestNode {
+    handle: LaunchedNode,
+    url: Url,
+}
+
+impl TestNode {
+    /// Starts a node with the default test configuration. See [`TestNodeBuilder::new`].
+    pub async fn start() -> Result<Self> {
+        TestNodeBuilder::new().start().await
+    }
+
+    /// Returns a builder of a node with the default test configuration.
+    pub fn builder() -> TestNodeBuilder {
+        TestNodeBuilder::new()
+    }
+
+    /// Returns the address the RPC server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.handle.rpc.addr
+    }
+
+    /// Returns the HTTP endpoint of the RPC server.
+    pub fn url(&self) -> Url {
+        self.url.clone()
+    }
+
+    /// Returns the handle of the launched node.
+    pub fn handle(&self) -> &LaunchedNode {
+        &self.handle
+    }
+
+    /// Returns the backend of the node, giving direct access to its storage.
+    pub fn backend(&self) -> &Arc<Backend<BlockifierFactory>> {
+        &self.handle.node.backend
+    }
+
+    /// Returns a Starknet JSON-RPC client connected to the node.
+    pub fn provider(&self) -> JsonRpcClient<HttpTransport> {
+        JsonRpcClient::new(HttpTransport::new(self.url()))
+    }
+
+    /// Returns a client of the Katana specific APIs, to be used with the client traits of
+    /// `katana-rpc-api` - eg. `DevApiClient` or `KatanaApiClient`.
+    pub fn dev_client(&self) -> HttpClient {
+        HttpClientBuilder::default().build(self.url.as_str()).expect("valid url")
+    }
+
+    /// Returns the addresses and private keys of the genesis accounts whose private key is known.
+    pub fn accounts(&self) -> Vec<(ContractAddress, Felt)> {
+        let genesis = &self.backend().chain_spec.genesis;
+        genesis
+            .accounts()
+            .filter_map(|(address, account)| Some((*address, account.private_key()?)))
+            .collect()
+    }
+
+    /// Returns the first genesis account. See [`TestNode::account_at`].
+    pub fn account(&self) -> TestAccount {
+        self.account_at(0)
+    }
+
+    /// Returns the genesis account at `index` in [`TestNode::accounts`], which sends its
+    /// transactions against the pending block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such account.
+    pub fn account_at(&self, index: usize) -> TestAccount {
+        let (address, private_key) = self.accounts()[index];
+
+        let mut account = SingleOwnerAccount::new(
+            self.provider(),
+            LocalWallet::from_signing_key(SigningKey::from_secret_scalar(private_key)),
+            address.into(),
+            self.backend().chain_spec.id.into(),
+            ExecutionEncoding::New,
+        );
+
+        account.set_block_id(BlockId::Tag(BlockTag::Pending));
+        account
+    }
+
+    /// Stops the node.
+    pub async fn stop(&self) -> Result<()> {
+        self.handle.stop().await
+    }
+}
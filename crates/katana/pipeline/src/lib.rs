@@ -3,10 +3,11 @@
 pub mod stage;
 
 use core::future::IntoFuture;
+use core::time::Duration;
 
 use futures::future::BoxFuture;
 use stage::Stage;
-use tracing::{error, info};
+use tracing::{debug, error, info};
 
 /// The result of a pipeline execution.
 pub type PipelineResult = Result<(), Error>;
@@ -23,19 +24,27 @@ pub enum Error {
 /// Manages the execution of stages.
 ///
 /// The pipeline drives the execution of stages, running each stage to completion in the order they
-/// were added.
+/// were added. If an interval is set, the stages are run again after every interval once they have
+/// all completed, which is how the sync stages keep following the chain.
 ///
 /// Inspired by [`reth`]'s staged sync pipeline.
 ///
 /// [`reth`]: https://github.com/paradigmxyz/reth/blob/c7aebff0b6bc19cd0b73e295497d3c5150d40ed8/crates/stages/api/src/pipeline/mod.rs#L66
 pub struct Pipeline {
     stages: Vec<Box<dyn Stage>>,
+    interval: Option<Duration>,
 }
 
 impl Pipeline {
     /// Create a new empty pipeline.
     pub fn new() -> Self {
-        Self { stages: Vec::new() }
+        Self { stages: Vec::new(), interval: None }
+    }
+
+    /// Run the stages again after `interval` every time they have all completed.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
     }
 
     /// Insert a new stage into the pipeline.
@@ -45,10 +54,16 @@ impl Pipeline {
 
     /// Start the pipeline.
     pub async fn run(&mut self) -> PipelineResult {
-        for stage in &mut self.stages {
-            info!(target: "pipeline", id = %stage.id(), "Executing stage.");
-            stage.execute().await?;
+        loop {
+            for stage in &mut self.stages {
+                debug!(target: "pipeline", id = %stage.id(), "Executing stage.");
+                stage.execute().await?;
+            }
+
+            let Some(interval) = self.interval else { break };
+            tokio::time::sleep(interval).await;
         }
+
         info!(target: "pipeline", "Pipeline finished.");
         Ok(())
     }
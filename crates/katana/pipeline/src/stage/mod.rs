@@ -1,6 +1,9 @@
 mod sequencing;
+pub mod sync;
 
+use katana_provider::error::ProviderError;
 pub use sequencing::Sequencing;
+pub use sync::{Bodies, Classes, Headers, States};
 
 /// The result type of a stage execution. See [Stage::execute].
 pub type StageResult = Result<(), Error>;
@@ -8,18 +11,29 @@ pub type StageResult = Result<(), Error>;
 #[derive(Debug, Clone, Copy)]
 pub enum StageId {
     Sequencing,
+    Headers,
+    Bodies,
+    States,
+    Classes,
 }
 
 impl core::fmt::Display for StageId {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             StageId::Sequencing => write!(f, "Sequencing"),
+            StageId::Headers => write!(f, "Headers"),
+            StageId::Bodies => write!(f, "Bodies"),
+            StageId::States => write!(f, "States"),
+            StageId::Classes => write!(f, "Classes"),
         }
     }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -0,0 +1,85 @@
+use anyhow::{ensure, Context, Result};
+use katana_primitives::block::BlockNumber;
+use katana_provider::traits::block::{BlockPartsWriter, BlockProvider, HeaderProvider};
+use katana_provider::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use tracing::info;
+
+use super::{checkpoint, pending_blocks, set_checkpoint, Source};
+use crate::stage::{Stage, StageId, StageResult};
+
+/// Downloads the transactions and receipts of the blocks whose headers have been synced.
+#[derive(Debug)]
+pub struct Bodies<S, P> {
+    source: S,
+    provider: P,
+}
+
+impl<S, P> Bodies<S, P>
+where
+    S: Source,
+    P: BlockPartsWriter + BlockProvider + HeaderProvider,
+{
+    pub fn new(source: S, provider: P) -> Self {
+        Self { source, provider }
+    }
+
+    async fn sync_block(&self, block_number: BlockNumber) -> Result<()> {
+        // the body may have been stored before the checkpoint could be updated, in which case
+        // storing it again would assign new numbers to its transactions.
+        if self.provider.block_body_indices(block_number.into())?.is_some() {
+            return Ok(());
+        }
+
+        let header = self
+            .provider
+            .header_by_number(block_number)?
+            .with_context(|| format!("Missing header of block {block_number}"))?;
+
+        let (transactions, receipts) = self.source.body(block_number).await?;
+        ensure!(
+            transactions.len() == header.transaction_count as usize
+                && receipts.len() == transactions.len(),
+            "Block {block_number} should have {} transactions, got {} transactions and {} \
+             receipts.",
+            header.transaction_count,
+            transactions.len(),
+            receipts.len()
+        );
+
+        self.provider.insert_body(block_number, transactions, receipts)?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, P> Stage for Bodies<S, P>
+where
+    S: Source,
+    P: BlockPartsWriter
+        + BlockProvider
+        + HeaderProvider
+        + StageCheckpointProvider
+        + StageCheckpointWriter,
+{
+    fn id(&self) -> StageId {
+        StageId::Bodies
+    }
+
+    #[tracing::instrument(skip(self), name = "Stage", fields(id = %self.id()))]
+    async fn execute(&mut self) -> StageResult {
+        let checkpoint = checkpoint(&self.provider, self.id())?;
+        let target = super::checkpoint(&self.provider, StageId::Headers)?;
+
+        let blocks = pending_blocks(checkpoint, target);
+        if !blocks.is_empty() {
+            info!(target: "pipeline", from = %blocks.start(), to = %blocks.end(), "Syncing bodies.");
+        }
+
+        for block_number in blocks {
+            self.sync_block(block_number).await?;
+            set_checkpoint(&self.provider, self.id(), block_number)?;
+        }
+
+        Ok(())
+    }
+}
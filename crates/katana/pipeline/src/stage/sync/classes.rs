@@ -0,0 +1,80 @@
+use anyhow::Result;
+use katana_primitives::block::BlockNumber;
+use katana_provider::traits::contract::{ContractClassProvider, ContractClassWriter};
+use katana_provider::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
+use tracing::info;
+
+use super::{checkpoint, pending_blocks, set_checkpoint, Source};
+use crate::stage::{Stage, StageId, StageResult};
+
+/// Downloads the definitions of the classes declared in the blocks whose states have been synced.
+#[derive(Debug)]
+pub struct Classes<S, P> {
+    source: S,
+    provider: P,
+}
+
+impl<S, P> Classes<S, P>
+where
+    S: Source,
+    P: StateUpdateProvider + StateFactoryProvider + ContractClassWriter,
+{
+    pub fn new(source: S, provider: P) -> Self {
+        Self { source, provider }
+    }
+
+    async fn sync_block(&self, block_number: BlockNumber) -> Result<()> {
+        let state_updates = self.provider.state_update(block_number.into())?.unwrap_or_default();
+
+        for class_hash in state_updates.declared_classes.into_keys() {
+            // the class may have been stored before the checkpoint could be updated
+            if self.provider.latest()?.class(class_hash)?.is_some() {
+                continue;
+            }
+
+            let (class, sierra) = self.source.class(class_hash).await?;
+            self.provider.set_class(class_hash, class)?;
+
+            if let Some(sierra) = sierra {
+                self.provider.set_sierra_class(class_hash, sierra)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, P> Stage for Classes<S, P>
+where
+    S: Source,
+    P: StateUpdateProvider
+        + StateFactoryProvider
+        + ContractClassWriter
+        + StageCheckpointProvider
+        + StageCheckpointWriter,
+{
+    fn id(&self) -> StageId {
+        StageId::Classes
+    }
+
+    #[tracing::instrument(skip(self), name = "Stage", fields(id = %self.id()))]
+    async fn execute(&mut self) -> StageResult {
+        let checkpoint = checkpoint(&self.provider, self.id())?;
+        let target = super::checkpoint(&self.provider, StageId::States)?;
+
+        let blocks = pending_blocks(checkpoint, target);
+        if !blocks.is_empty() {
+            info!(target: "pipeline", from = %blocks.start(), to = %blocks.end(), "Syncing classes.");
+        }
+
+        for block_number in blocks {
+            self.sync_block(block_number).await?;
+            set_checkpoint(&self.provider, self.id(), block_number)?;
+        }
+
+        Ok(())
+    }
+}
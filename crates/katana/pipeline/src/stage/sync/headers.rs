@@ -0,0 +1,90 @@
+use anyhow::{ensure, Context, Result};
+use katana_primitives::block::BlockNumber;
+use katana_provider::traits::block::{BlockHashProvider, BlockPartsWriter};
+use katana_provider::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use tracing::info;
+
+use super::{checkpoint, pending_blocks, set_checkpoint, Source};
+use crate::stage::{Stage, StageId, StageResult};
+
+/// Downloads the block headers up to the latest block of the source, or up to the target block if
+/// one is set.
+///
+/// Fails if a header doesn't extend the previously synced one, as reorgs of the source chain are
+/// not supported.
+#[derive(Debug)]
+pub struct Headers<S, P> {
+    source: S,
+    provider: P,
+    target: Option<BlockNumber>,
+}
+
+impl<S, P> Headers<S, P>
+where
+    S: Source,
+    P: BlockPartsWriter + BlockHashProvider,
+{
+    pub fn new(source: S, provider: P) -> Self {
+        Self { source, provider, target: None }
+    }
+
+    /// Stops syncing at `block_number`, even if the source has more blocks.
+    ///
+    /// As the other stages only process the blocks whose headers have been synced, this sets the
+    /// last block synced by the whole pipeline.
+    pub fn with_target(mut self, block_number: BlockNumber) -> Self {
+        self.target = Some(block_number);
+        self
+    }
+
+    async fn sync_block(&self, block_number: BlockNumber) -> Result<()> {
+        let (hash, header, status) = self.source.header(block_number).await?;
+
+        if let Some(parent) = block_number.checked_sub(1) {
+            let parent_hash = self
+                .provider
+                .block_hash_by_num(parent)?
+                .with_context(|| format!("Missing hash of block {parent}"))?;
+
+            ensure!(
+                parent_hash == header.parent_hash,
+                "Block {block_number} doesn't extend the synced chain. Expected parent hash \
+                 {parent_hash:#x}, got {:#x}.",
+                header.parent_hash
+            );
+        }
+
+        self.provider.insert_header(hash, header, status)?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, P> Stage for Headers<S, P>
+where
+    S: Source,
+    P: BlockPartsWriter + BlockHashProvider + StageCheckpointProvider + StageCheckpointWriter,
+{
+    fn id(&self) -> StageId {
+        StageId::Headers
+    }
+
+    #[tracing::instrument(skip(self), name = "Stage", fields(id = %self.id()))]
+    async fn execute(&mut self) -> StageResult {
+        let checkpoint = checkpoint(&self.provider, self.id())?;
+        let latest = self.source.latest_block_number().await?;
+        let target = self.target.map_or(latest, |target| target.min(latest));
+
+        let blocks = pending_blocks(checkpoint, Some(target));
+        if !blocks.is_empty() {
+            info!(target: "pipeline", from = %blocks.start(), to = %blocks.end(), "Syncing headers.");
+        }
+
+        for block_number in blocks {
+            self.sync_block(block_number).await?;
+            set_checkpoint(&self.provider, self.id(), block_number)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,377 @@
+//! Stages for syncing an existing chain into the local database.
+//!
+//! Instead of producing blocks, a node running these stages downloads the blocks of another
+//! network from a [`Source`] and serves them as a read-only replica. Each stage downloads a
+//! different part of the blocks and records the last block it has processed as its checkpoint, so
+//! that syncing can resume where it left off. The stages must be run in the following order as
+//! each one only processes the blocks already processed by the stage before it:
+//!
+//! 1. [`Headers`]
+//! 2. [`Bodies`]
+//! 3. [`States`]
+//! 4. [`Classes`]
+
+mod bodies;
+mod classes;
+mod headers;
+mod rpc;
+mod states;
+
+use std::ops::RangeInclusive;
+
+pub use bodies::Bodies;
+pub use classes::Classes;
+pub use headers::Headers;
+use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
+use katana_primitives::class::{ClassHash, CompiledClass, FlattenedSierraClass};
+use katana_primitives::receipt::Receipt;
+use katana_primitives::state::StateUpdates;
+use katana_primitives::transaction::TxWithHash;
+use katana_provider::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use katana_provider::ProviderResult;
+pub use rpc::JsonRpcSource;
+pub use states::States;
+
+use super::StageId;
+
+/// The network from which the chain is synced.
+#[async_trait::async_trait]
+pub trait Source: Send + Sync {
+    /// Returns the number of the latest block of the chain.
+    async fn latest_block_number(&self) -> anyhow::Result<BlockNumber>;
+
+    /// Returns the hash, header and finality status of a block.
+    async fn header(
+        &self,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<(BlockHash, Header, FinalityStatus)>;
+
+    /// Returns the transactions of a block along with their receipts.
+    async fn body(
+        &self,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<(Vec<TxWithHash>, Vec<Receipt>)>;
+
+    /// Returns the state updates of a block.
+    async fn state_updates(&self, block_number: BlockNumber) -> anyhow::Result<StateUpdates>;
+
+    /// Returns the definition of a class, along with its Sierra class if it isn't a legacy class.
+    async fn class(
+        &self,
+        hash: ClassHash,
+    ) -> anyhow::Result<(CompiledClass, Option<FlattenedSierraClass>)>;
+}
+
+/// Returns the blocks that a stage has yet to process, given its checkpoint and the last block it
+/// should process.
+fn pending_blocks(
+    checkpoint: Option<BlockNumber>,
+    target: Option<BlockNumber>,
+) -> RangeInclusive<BlockNumber> {
+    let start = checkpoint.map_or(0, |block| block + 1);
+    match target {
+        Some(target) => start..=target,
+        // an empty range, as there is no block to process
+        None => 1..=0,
+    }
+}
+
+fn checkpoint<P: StageCheckpointProvider>(
+    provider: &P,
+    id: StageId,
+) -> ProviderResult<Option<BlockNumber>> {
+    provider.checkpoint(&id.to_string())
+}
+
+fn set_checkpoint<P: StageCheckpointWriter>(
+    provider: &P,
+    id: StageId,
+    block_number: BlockNumber,
+) -> ProviderResult<()> {
+    provider.set_checkpoint(&id.to_string(), block_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Context;
+    use katana_primitives::block::{
+        BlockHash, BlockHashOrNumber, BlockNumber, FinalityStatus, Header,
+    };
+    use katana_primitives::class::{ClassHash, CompiledClass, FlattenedSierraClass};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::genesis::constant::{
+        DEFAULT_LEGACY_ERC20_CASM, DEFAULT_LEGACY_ERC20_CLASS_HASH, DEFAULT_LEGACY_UDC_CASM,
+        DEFAULT_LEGACY_UDC_CLASS_HASH,
+    };
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::state::StateUpdates;
+    use katana_primitives::transaction::{InvokeTx, Tx, TxWithHash};
+    use katana_primitives::{address, felt, Felt};
+    use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::block::{BlockNumberProvider, BlockPartsWriter, BlockProvider};
+    use katana_provider::traits::contract::ContractClassProvider;
+    use katana_provider::traits::stage::StageCheckpointProvider;
+    use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+    use katana_provider::traits::state_update::StateUpdateProvider;
+    use katana_provider::traits::transaction::ReceiptProvider;
+    use katana_provider::traits::trie::TrieWriter;
+
+    use super::rpc::from_rpc_state_diff;
+    use super::{Bodies, Classes, Headers, Source, States};
+    use crate::Pipeline;
+
+    struct MockBlock {
+        hash: BlockHash,
+        header: Header,
+        body: (Vec<TxWithHash>, Vec<Receipt>),
+        state_updates: StateUpdates,
+    }
+
+    /// A source serving a chain built in memory, whose state roots are computed by a separate
+    /// provider.
+    #[derive(Clone)]
+    struct MockSource {
+        blocks: Arc<Mutex<Vec<MockBlock>>>,
+        reference: Arc<DbProvider>,
+    }
+
+    impl MockSource {
+        fn new() -> Self {
+            let source = Self {
+                blocks: Default::default(),
+                reference: Arc::new(DbProvider::new_ephemeral()),
+            };
+
+            let mut genesis = StateUpdates::default();
+            genesis.deprecated_declared_classes.insert(DEFAULT_LEGACY_UDC_CLASS_HASH);
+            genesis.deployed_contracts.insert(address!("0x1337"), DEFAULT_LEGACY_UDC_CLASS_HASH);
+            source.push_block(Vec::new(), genesis);
+
+            source
+        }
+
+        fn push_block(&self, transactions: Vec<TxWithHash>, state_updates: StateUpdates) {
+            let mut blocks = self.blocks.lock().unwrap();
+
+            let number = blocks.len() as BlockNumber;
+            let parent_hash = blocks.last().map(|block| block.hash).unwrap_or_default();
+            let state_root =
+                self.reference.trie_insert_state_updates(number, &state_updates).unwrap();
+            self.reference.insert_state_updates(number, state_updates.clone()).unwrap();
+
+            let header = Header {
+                number,
+                parent_hash,
+                state_root,
+                transaction_count: transactions.len() as u32,
+                ..Default::default()
+            };
+
+            let receipts = transactions.iter().map(|_| receipt()).collect();
+            let hash = Felt::from(0x100 + number);
+            let body = (transactions, receipts);
+            blocks.push(MockBlock { hash, header, body, state_updates });
+        }
+
+        fn block<T>(
+            &self,
+            number: BlockNumber,
+            f: impl FnOnce(&MockBlock) -> T,
+        ) -> anyhow::Result<T> {
+            let blocks = self.blocks.lock().unwrap();
+            blocks.get(number as usize).map(f).with_context(|| format!("block {number} not found"))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Source for MockSource {
+        async fn latest_block_number(&self) -> anyhow::Result<BlockNumber> {
+            Ok(self.blocks.lock().unwrap().len() as BlockNumber - 1)
+        }
+
+        async fn header(
+            &self,
+            block_number: BlockNumber,
+        ) -> anyhow::Result<(BlockHash, Header, FinalityStatus)> {
+            self.block(block_number, |b| (b.hash, b.header.clone(), FinalityStatus::AcceptedOnL2))
+        }
+
+        async fn body(
+            &self,
+            block_number: BlockNumber,
+        ) -> anyhow::Result<(Vec<TxWithHash>, Vec<Receipt>)> {
+            self.block(block_number, |b| b.body.clone())
+        }
+
+        async fn state_updates(&self, block_number: BlockNumber) -> anyhow::Result<StateUpdates> {
+            self.block(block_number, |b| b.state_updates.clone())
+        }
+
+        async fn class(
+            &self,
+            hash: ClassHash,
+        ) -> anyhow::Result<(CompiledClass, Option<FlattenedSierraClass>)> {
+            match hash {
+                DEFAULT_LEGACY_UDC_CLASS_HASH => Ok((DEFAULT_LEGACY_UDC_CASM.clone(), None)),
+                DEFAULT_LEGACY_ERC20_CLASS_HASH => Ok((DEFAULT_LEGACY_ERC20_CASM.clone(), None)),
+                _ => anyhow::bail!("class {hash:#x} not found"),
+            }
+        }
+    }
+
+    fn receipt() -> Receipt {
+        Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+            fee: TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: 0, unit: PriceUnit::Wei },
+        })
+    }
+
+    fn sync_pipeline(source: &MockSource, provider: &Arc<DbProvider>) -> Pipeline {
+        let headers = Headers::new(source.clone(), provider.clone());
+        sync_pipeline_with_headers(source, provider, headers)
+    }
+
+    fn sync_pipeline_with_headers(
+        source: &MockSource,
+        provider: &Arc<DbProvider>,
+        headers: Headers<MockSource, Arc<DbProvider>>,
+    ) -> Pipeline {
+        let mut pipeline = Pipeline::new();
+        pipeline.add_stage(Box::new(headers));
+        pipeline.add_stage(Box::new(Bodies::new(source.clone(), provider.clone())));
+        pipeline.add_stage(Box::new(States::new(source.clone(), provider.clone())));
+        pipeline.add_stage(Box::new(Classes::new(source.clone(), provider.clone())));
+        pipeline
+    }
+
+    #[tokio::test]
+    async fn sync_from_source() {
+        let source = MockSource::new();
+
+        let tx = TxWithHash {
+            hash: felt!("0xabc"),
+            transaction: Tx::Invoke(InvokeTx::V1(Default::default())),
+        };
+        let mut state_updates = StateUpdates::default();
+        state_updates.nonce_updates.insert(address!("0x1337"), felt!("0x1"));
+        state_updates
+            .storage_updates
+            .insert(address!("0x1337"), BTreeMap::from([(felt!("0x1"), felt!("0x2"))]));
+        source.push_block(vec![tx], state_updates);
+        source.push_block(Vec::new(), StateUpdates::default());
+
+        let provider = Arc::new(DbProvider::new_ephemeral());
+        sync_pipeline(&source, &provider).run().await.unwrap();
+
+        assert_eq!(provider.latest_number().unwrap(), 2);
+        for id in ["Headers", "Bodies", "States", "Classes"] {
+            assert_eq!(provider.checkpoint(id).unwrap(), Some(2));
+        }
+
+        let block = provider.block_by_number(1).unwrap().unwrap();
+        assert_eq!(block.header.parent_hash, felt!("0x100"));
+        assert_eq!(block.body.len(), 1);
+        assert_eq!(provider.receipt_by_hash(felt!("0xabc")).unwrap(), Some(receipt()));
+
+        let state = provider.latest().unwrap();
+        assert_eq!(state.nonce(address!("0x1337")).unwrap(), Some(felt!("0x1")));
+        assert_eq!(state.storage(address!("0x1337"), felt!("0x1")).unwrap(), Some(felt!("0x2")));
+        assert!(state.class(DEFAULT_LEGACY_UDC_CLASS_HASH).unwrap().is_some());
+
+        // the stages resume from their checkpoints once the chain has progressed
+        let mut state_updates = StateUpdates::default();
+        state_updates
+            .storage_updates
+            .insert(address!("0x1337"), BTreeMap::from([(felt!("0x1"), felt!("0x3"))]));
+        source.push_block(Vec::new(), state_updates);
+
+        sync_pipeline(&source, &provider).run().await.unwrap();
+
+        assert_eq!(provider.latest_number().unwrap(), 3);
+        let state = provider.latest().unwrap();
+        assert_eq!(state.storage(address!("0x1337"), felt!("0x1")).unwrap(), Some(felt!("0x3")));
+    }
+
+    #[tokio::test]
+    async fn sync_up_to_target() {
+        let source = MockSource::new();
+        source.push_block(Vec::new(), StateUpdates::default());
+
+        // only the genesis block is synced
+        let provider = Arc::new(DbProvider::new_ephemeral());
+        let headers = Headers::new(source.clone(), provider.clone()).with_target(0);
+        sync_pipeline_with_headers(&source, &provider, headers).run().await.unwrap();
+
+        assert_eq!(provider.latest_number().unwrap(), 0);
+        for id in ["Headers", "Bodies", "States", "Classes"] {
+            assert_eq!(provider.checkpoint(id).unwrap(), Some(0));
+        }
+
+        // the rest of the chain is synced once the target is lifted
+        sync_pipeline(&source, &provider).run().await.unwrap();
+        assert_eq!(provider.latest_number().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn sync_legacy_declarations_and_replaced_classes() {
+        let json = include_str!("test-data/state_update.json");
+        let update: starknet::core::types::StateUpdate = serde_json::from_str(json).unwrap();
+        let state_updates = from_rpc_state_diff(update.state_diff);
+
+        assert_eq!(
+            state_updates.deprecated_declared_classes,
+            BTreeSet::from([DEFAULT_LEGACY_ERC20_CLASS_HASH])
+        );
+        assert_eq!(
+            state_updates.replaced_classes,
+            BTreeMap::from([(address!("0x1337"), DEFAULT_LEGACY_ERC20_CLASS_HASH)])
+        );
+        assert_eq!(
+            state_updates.deployed_contracts,
+            BTreeMap::from([(address!("0x1338"), DEFAULT_LEGACY_UDC_CLASS_HASH)])
+        );
+        assert!(state_updates.declared_classes.is_empty());
+
+        let source = MockSource::new();
+        source.push_block(Vec::new(), state_updates);
+
+        let provider = Arc::new(DbProvider::new_ephemeral());
+        sync_pipeline(&source, &provider).run().await.unwrap();
+
+        let state = provider.latest().unwrap();
+        let class_hash = state.class_hash_of_contract(address!("0x1337")).unwrap();
+        assert_eq!(class_hash, Some(DEFAULT_LEGACY_ERC20_CLASS_HASH));
+        let class_hash = state.class_hash_of_contract(address!("0x1338")).unwrap();
+        assert_eq!(class_hash, Some(DEFAULT_LEGACY_UDC_CLASS_HASH));
+        assert!(state.class(DEFAULT_LEGACY_ERC20_CLASS_HASH).unwrap().is_some());
+
+        // the contract still had its original class at the previous block
+        let historical = provider.historical(BlockHashOrNumber::Num(0)).unwrap().unwrap();
+        let class_hash = historical.class_hash_of_contract(address!("0x1337")).unwrap();
+        assert_eq!(class_hash, Some(DEFAULT_LEGACY_UDC_CLASS_HASH));
+
+        let stored = provider.state_update(BlockHashOrNumber::Num(1)).unwrap().unwrap();
+        assert_eq!(stored.deployed_contracts.len(), 2);
+        assert!(stored.declared_classes.contains_key(&DEFAULT_LEGACY_ERC20_CLASS_HASH));
+    }
+
+    #[tokio::test]
+    async fn reject_state_root_mismatch() {
+        let source = MockSource::new();
+        source.blocks.lock().unwrap()[0].header.state_root = felt!("0x1");
+
+        let provider = Arc::new(DbProvider::new_ephemeral());
+        let result = sync_pipeline(&source, &provider).run().await;
+
+        assert!(result.is_err());
+        assert_eq!(provider.checkpoint("Bodies").unwrap(), Some(0));
+        assert_eq!(provider.checkpoint("States").unwrap(), None);
+    }
+}
@@ -0,0 +1,461 @@
+use std::sync::Arc;
+
+use anyhow::{bail, ensure, Context, Result};
+use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
+use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, GasPrices, Header};
+use katana_primitives::chain::ChainId;
+use katana_primitives::class::{ClassHash, CompiledClass, FlattenedSierraClass};
+use katana_primitives::conversion::rpc::{
+    flattened_sierra_to_compiled_class, legacy_rpc_to_compiled_class,
+};
+use katana_primitives::da::{DataAvailabilityMode, L1DataAvailabilityMode};
+use katana_primitives::fee::{PriceUnit, ResourceBounds, ResourceBoundsMapping, TxFeeInfo};
+use katana_primitives::receipt::{
+    DeclareTxReceipt, DeployAccountTxReceipt, Event, InvokeTxReceipt, L1HandlerTxReceipt,
+    MessageToL1, Receipt,
+};
+use katana_primitives::state::StateUpdates;
+use katana_primitives::trace::{ExecutionResources, L1Gas, TxResources};
+use katana_primitives::transaction::{
+    DeclareTx, DeclareTxV1, DeclareTxV2, DeclareTxV3, DeployAccountTx, DeployAccountTxV1,
+    DeployAccountTxV3, InvokeTx, InvokeTxV1, InvokeTxV3, L1HandlerTx, Tx, TxWithHash,
+};
+use katana_primitives::version::ProtocolVersion;
+use katana_primitives::Felt;
+use num_traits::ToPrimitive;
+use starknet::core::types::{
+    BlockId, BlockStatus, BlockTag, ContractClass, DeclareTransaction, DeployAccountTransaction,
+    ExecutionResult, FeePayment, InvokeTransaction, MaybePendingBlockWithTxHashes,
+    MaybePendingBlockWithTxs, MaybePendingStateUpdate, ResourcePrice, StateDiff, Transaction,
+    TransactionReceipt,
+};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider};
+use url::Url;
+
+use super::Source;
+
+/// A [`Source`] that syncs the chain from a Starknet JSON-RPC node.
+///
+/// Only the data exposed by the JSON-RPC API is synced. The block commitments, the gas usage of the
+/// transactions and the fee paid on L1 by the L1 handler transactions are left empty, and the
+/// legacy transaction types (ie. `INVOKE` v0, `DECLARE` v0 and `DEPLOY`) are not supported.
+#[derive(Debug, Clone)]
+pub struct JsonRpcSource {
+    client: Arc<JsonRpcClient<HttpTransport>>,
+    chain_id: ChainId,
+}
+
+impl JsonRpcSource {
+    /// Creates a new source from the node at `url`, fetching the id of its chain.
+    pub async fn connect(url: Url) -> Result<Self> {
+        let client = JsonRpcClient::new(HttpTransport::new(url));
+        let chain_id = client.chain_id().await.context("failed to fetch chain id")?;
+        Ok(Self { client: Arc::new(client), chain_id: chain_id.into() })
+    }
+
+    /// Returns the id of the synced chain.
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+}
+
+#[async_trait::async_trait]
+impl Source for JsonRpcSource {
+    async fn latest_block_number(&self) -> Result<BlockNumber> {
+        Ok(self.client.block_number().await?)
+    }
+
+    async fn header(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<(BlockHash, Header, FinalityStatus)> {
+        let block = self.client.get_block_with_tx_hashes(BlockId::Number(block_number)).await?;
+        let MaybePendingBlockWithTxHashes::Block(block) = block else {
+            bail!("Block {block_number} is pending")
+        };
+
+        let status = match block.status {
+            BlockStatus::AcceptedOnL1 => FinalityStatus::AcceptedOnL1,
+            BlockStatus::AcceptedOnL2 => FinalityStatus::AcceptedOnL2,
+            status => bail!("Block {block_number} has an unexpected status: {status:?}"),
+        };
+
+        let l1_da_mode = match block.l1_da_mode {
+            starknet::core::types::L1DataAvailabilityMode::Blob => L1DataAvailabilityMode::Blob,
+            starknet::core::types::L1DataAvailabilityMode::Calldata => {
+                L1DataAvailabilityMode::Calldata
+            }
+        };
+
+        // the commitments and the number of events are not part of the JSON-RPC block
+        let header = Header {
+            l1_da_mode,
+            parent_hash: block.parent_hash,
+            number: block.block_number,
+            state_root: block.new_root,
+            timestamp: block.timestamp,
+            transaction_count: block.transactions.len() as u32,
+            sequencer_address: block.sequencer_address.into(),
+            l1_gas_prices: from_rpc_gas_prices(block.l1_gas_price)?,
+            l1_data_gas_prices: from_rpc_gas_prices(block.l1_data_gas_price)?,
+            protocol_version: ProtocolVersion::parse(&block.starknet_version)?,
+            ..Default::default()
+        };
+
+        Ok((block.block_hash, header, status))
+    }
+
+    async fn body(&self, block_number: BlockNumber) -> Result<(Vec<TxWithHash>, Vec<Receipt>)> {
+        let block = self.client.get_block_with_txs(BlockId::Number(block_number)).await?;
+        let MaybePendingBlockWithTxs::Block(block) = block else {
+            bail!("Block {block_number} is pending")
+        };
+
+        let mut transactions = Vec::with_capacity(block.transactions.len());
+        let mut receipts = Vec::with_capacity(block.transactions.len());
+
+        for tx in block.transactions {
+            let hash = *tx.transaction_hash();
+            let receipt = self.client.get_transaction_receipt(hash).await?.receipt;
+            let receipt = from_rpc_receipt(receipt)?;
+
+            let transaction = from_rpc_tx(tx, &receipt, self.chain_id)?;
+            transactions.push(TxWithHash { hash, transaction });
+            receipts.push(receipt);
+        }
+
+        Ok((transactions, receipts))
+    }
+
+    async fn state_updates(&self, block_number: BlockNumber) -> Result<StateUpdates> {
+        let update = self.client.get_state_update(BlockId::Number(block_number)).await?;
+        let MaybePendingStateUpdate::Update(update) = update else {
+            bail!("Block {block_number} is pending")
+        };
+
+        Ok(from_rpc_state_diff(update.state_diff))
+    }
+
+    async fn class(
+        &self,
+        hash: ClassHash,
+    ) -> Result<(CompiledClass, Option<FlattenedSierraClass>)> {
+        let class = self.client.get_class(BlockId::Tag(BlockTag::Latest), hash).await?;
+
+        let (class_hash, class, sierra) = match class {
+            ContractClass::Legacy(class) => {
+                let (class_hash, class) = legacy_rpc_to_compiled_class(&class)?;
+                (class_hash, class, None)
+            }
+
+            ContractClass::Sierra(sierra) => {
+                let (class_hash, _, class) = flattened_sierra_to_compiled_class(&sierra)?;
+                (class_hash, class, Some(sierra))
+            }
+        };
+
+        ensure!(
+            class_hash == hash,
+            "Class hash mismatch. Expected {hash:#x}, got {class_hash:#x}."
+        );
+        Ok((class, sierra))
+    }
+}
+
+pub(super) fn from_rpc_state_diff(diff: StateDiff) -> StateUpdates {
+    let mut state_updates = StateUpdates::default();
+
+    for item in diff.nonces {
+        state_updates.nonce_updates.insert(item.contract_address.into(), item.nonce);
+    }
+
+    for item in diff.storage_diffs {
+        let entries = item.storage_entries.into_iter().map(|e| (e.key, e.value)).collect();
+        state_updates.storage_updates.insert(item.address.into(), entries);
+    }
+
+    for item in diff.deployed_contracts {
+        state_updates.deployed_contracts.insert(item.address.into(), item.class_hash);
+    }
+
+    for item in diff.replaced_classes {
+        state_updates.replaced_classes.insert(item.contract_address.into(), item.class_hash);
+    }
+
+    for item in diff.declared_classes {
+        state_updates.declared_classes.insert(item.class_hash, item.compiled_class_hash);
+    }
+
+    state_updates.deprecated_declared_classes.extend(diff.deprecated_declared_classes);
+    state_updates
+}
+
+fn from_rpc_tx(tx: Transaction, receipt: &Receipt, chain_id: ChainId) -> Result<Tx> {
+    let tx = match tx {
+        Transaction::Invoke(InvokeTransaction::V1(tx)) => Tx::Invoke(InvokeTx::V1(InvokeTxV1 {
+            chain_id,
+            nonce: tx.nonce,
+            calldata: tx.calldata,
+            signature: tx.signature,
+            max_fee: from_rpc_felt(tx.max_fee)?,
+            sender_address: tx.sender_address.into(),
+        })),
+
+        Transaction::Invoke(InvokeTransaction::V3(tx)) => Tx::Invoke(InvokeTx::V3(InvokeTxV3 {
+            chain_id,
+            tip: tx.tip,
+            nonce: tx.nonce,
+            calldata: tx.calldata,
+            signature: tx.signature,
+            paymaster_data: tx.paymaster_data,
+            sender_address: tx.sender_address.into(),
+            account_deployment_data: tx.account_deployment_data,
+            resource_bounds: from_rpc_resource_bounds(tx.resource_bounds),
+            nonce_data_availability_mode: from_rpc_da_mode(tx.nonce_data_availability_mode),
+            fee_data_availability_mode: from_rpc_da_mode(tx.fee_data_availability_mode),
+        })),
+
+        Transaction::Declare(DeclareTransaction::V1(tx)) => {
+            Tx::Declare(DeclareTx::V1(DeclareTxV1 {
+                chain_id,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                max_fee: from_rpc_felt(tx.max_fee)?,
+                sender_address: tx.sender_address.into(),
+            }))
+        }
+
+        Transaction::Declare(DeclareTransaction::V2(tx)) => {
+            Tx::Declare(DeclareTx::V2(DeclareTxV2 {
+                chain_id,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                max_fee: from_rpc_felt(tx.max_fee)?,
+                sender_address: tx.sender_address.into(),
+                compiled_class_hash: tx.compiled_class_hash,
+            }))
+        }
+
+        Transaction::Declare(DeclareTransaction::V3(tx)) => {
+            Tx::Declare(DeclareTx::V3(DeclareTxV3 {
+                chain_id,
+                tip: tx.tip,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                paymaster_data: tx.paymaster_data,
+                sender_address: tx.sender_address.into(),
+                compiled_class_hash: tx.compiled_class_hash,
+                account_deployment_data: tx.account_deployment_data,
+                resource_bounds: from_rpc_resource_bounds(tx.resource_bounds),
+                nonce_data_availability_mode: from_rpc_da_mode(tx.nonce_data_availability_mode),
+                fee_data_availability_mode: from_rpc_da_mode(tx.fee_data_availability_mode),
+            }))
+        }
+
+        Transaction::L1Handler(tx) => {
+            let Receipt::L1Handler(receipt) = receipt else {
+                bail!("L1 handler transaction {:#x} has a mismatched receipt", tx.transaction_hash)
+            };
+
+            Tx::L1Handler(L1HandlerTx {
+                chain_id,
+                // the fee paid on L1 is not exposed by the JSON-RPC API
+                paid_fee_on_l1: 0,
+                version: tx.version,
+                nonce: tx.nonce.into(),
+                calldata: tx.calldata,
+                message_hash: receipt.message_hash,
+                contract_address: tx.contract_address.into(),
+                entry_point_selector: tx.entry_point_selector,
+            })
+        }
+
+        Transaction::DeployAccount(DeployAccountTransaction::V1(tx)) => {
+            let Receipt::DeployAccount(receipt) = receipt else {
+                bail!(
+                    "Deploy account transaction {:#x} has a mismatched receipt",
+                    tx.transaction_hash
+                )
+            };
+
+            Tx::DeployAccount(DeployAccountTx::V1(DeployAccountTxV1 {
+                chain_id,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                max_fee: from_rpc_felt(tx.max_fee)?,
+                contract_address: receipt.contract_address,
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+            }))
+        }
+
+        Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => {
+            let Receipt::DeployAccount(receipt) = receipt else {
+                bail!(
+                    "Deploy account transaction {:#x} has a mismatched receipt",
+                    tx.transaction_hash
+                )
+            };
+
+            Tx::DeployAccount(DeployAccountTx::V3(DeployAccountTxV3 {
+                chain_id,
+                tip: tx.tip,
+                nonce: tx.nonce,
+                signature: tx.signature,
+                class_hash: tx.class_hash,
+                paymaster_data: tx.paymaster_data,
+                contract_address: receipt.contract_address,
+                contract_address_salt: tx.contract_address_salt,
+                constructor_calldata: tx.constructor_calldata,
+                resource_bounds: from_rpc_resource_bounds(tx.resource_bounds),
+                nonce_data_availability_mode: from_rpc_da_mode(tx.nonce_data_availability_mode),
+                fee_data_availability_mode: from_rpc_da_mode(tx.fee_data_availability_mode),
+            }))
+        }
+
+        tx => bail!("Unsupported legacy transaction {:#x}", tx.transaction_hash()),
+    };
+
+    Ok(tx)
+}
+
+fn from_rpc_receipt(receipt: TransactionReceipt) -> Result<Receipt> {
+    let receipt = match receipt {
+        TransactionReceipt::Invoke(rct) => Receipt::Invoke(InvokeTxReceipt {
+            fee: from_rpc_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(from_rpc_event).collect(),
+            messages_sent: rct.messages_sent.into_iter().map(from_rpc_message).collect(),
+            revert_error: from_rpc_execution_result(rct.execution_result),
+            execution_resources: from_rpc_resources(rct.execution_resources),
+        }),
+
+        TransactionReceipt::Declare(rct) => Receipt::Declare(DeclareTxReceipt {
+            fee: from_rpc_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(from_rpc_event).collect(),
+            messages_sent: rct.messages_sent.into_iter().map(from_rpc_message).collect(),
+            revert_error: from_rpc_execution_result(rct.execution_result),
+            execution_resources: from_rpc_resources(rct.execution_resources),
+        }),
+
+        TransactionReceipt::L1Handler(rct) => Receipt::L1Handler(L1HandlerTxReceipt {
+            fee: from_rpc_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(from_rpc_event).collect(),
+            message_hash: (*rct.message_hash.as_bytes()).into(),
+            messages_sent: rct.messages_sent.into_iter().map(from_rpc_message).collect(),
+            revert_error: from_rpc_execution_result(rct.execution_result),
+            execution_resources: from_rpc_resources(rct.execution_resources),
+        }),
+
+        TransactionReceipt::DeployAccount(rct) => Receipt::DeployAccount(DeployAccountTxReceipt {
+            fee: from_rpc_fee(rct.actual_fee)?,
+            events: rct.events.into_iter().map(from_rpc_event).collect(),
+            messages_sent: rct.messages_sent.into_iter().map(from_rpc_message).collect(),
+            revert_error: from_rpc_execution_result(rct.execution_result),
+            execution_resources: from_rpc_resources(rct.execution_resources),
+            contract_address: rct.contract_address.into(),
+        }),
+
+        TransactionReceipt::Deploy(rct) => {
+            bail!("Unsupported legacy transaction {:#x}", rct.transaction_hash)
+        }
+    };
+
+    Ok(receipt)
+}
+
+fn from_rpc_fee(fee: FeePayment) -> Result<TxFeeInfo> {
+    let unit = match fee.unit {
+        starknet::core::types::PriceUnit::Wei => PriceUnit::Wei,
+        starknet::core::types::PriceUnit::Fri => PriceUnit::Fri,
+    };
+
+    // the gas usage of the transaction is not part of the JSON-RPC receipt
+    Ok(TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: from_rpc_felt(fee.amount)?, unit })
+}
+
+fn from_rpc_event(event: starknet::core::types::Event) -> Event {
+    Event { from_address: event.from_address.into(), keys: event.keys, data: event.data }
+}
+
+fn from_rpc_message(message: starknet::core::types::MsgToL1) -> MessageToL1 {
+    MessageToL1 {
+        from_address: message.from_address.into(),
+        to_address: message.to_address,
+        payload: message.payload,
+    }
+}
+
+fn from_rpc_execution_result(result: ExecutionResult) -> Option<String> {
+    match result {
+        ExecutionResult::Succeeded => None,
+        ExecutionResult::Reverted { reason } => Some(reason),
+    }
+}
+
+fn from_rpc_resources(resources: starknet::core::types::ExecutionResources) -> TxResources {
+    let computation = resources.computation_resources;
+    let data = resources.data_resources.data_availability;
+
+    let builtin_instance_counter = [
+        (BuiltinName::ec_op, computation.ec_op_builtin_applications),
+        (BuiltinName::ecdsa, computation.ecdsa_builtin_applications),
+        (BuiltinName::keccak, computation.keccak_builtin_applications),
+        (BuiltinName::bitwise, computation.bitwise_builtin_applications),
+        (BuiltinName::pedersen, computation.pedersen_builtin_applications),
+        (BuiltinName::poseidon, computation.poseidon_builtin_applications),
+        (BuiltinName::range_check, computation.range_check_builtin_applications),
+        (BuiltinName::segment_arena, computation.segment_arena_builtin),
+    ]
+    .into_iter()
+    .filter_map(|(name, count)| count.map(|count| (name, count as usize)))
+    .collect();
+
+    TxResources {
+        n_reverted_steps: 0,
+        vm_resources: ExecutionResources {
+            builtin_instance_counter,
+            n_steps: computation.steps as usize,
+            n_memory_holes: computation.memory_holes.unwrap_or_default() as usize,
+        },
+        data_availability: L1Gas {
+            l1_gas: data.l1_gas as u128,
+            l1_data_gas: data.l1_data_gas as u128,
+        },
+        total_gas_consumed: L1Gas::default(),
+    }
+}
+
+fn from_rpc_gas_prices(price: ResourcePrice) -> Result<GasPrices> {
+    Ok(GasPrices::new(from_rpc_felt(price.price_in_wei)?, from_rpc_felt(price.price_in_fri)?))
+}
+
+fn from_rpc_resource_bounds(
+    bounds: starknet::core::types::ResourceBoundsMapping,
+) -> ResourceBoundsMapping {
+    ResourceBoundsMapping {
+        l1_gas: ResourceBounds {
+            max_amount: bounds.l1_gas.max_amount,
+            max_price_per_unit: bounds.l1_gas.max_price_per_unit,
+        },
+        l2_gas: ResourceBounds {
+            max_amount: bounds.l2_gas.max_amount,
+            max_price_per_unit: bounds.l2_gas.max_price_per_unit,
+        },
+    }
+}
+
+fn from_rpc_da_mode(mode: starknet::core::types::DataAvailabilityMode) -> DataAvailabilityMode {
+    match mode {
+        starknet::core::types::DataAvailabilityMode::L1 => DataAvailabilityMode::L1,
+        starknet::core::types::DataAvailabilityMode::L2 => DataAvailabilityMode::L2,
+    }
+}
+
+fn from_rpc_felt(value: Felt) -> Result<u128> {
+    value.to_u128().with_context(|| format!("{value:#x} doesn't fit in u128"))
+}
@@ -0,0 +1,82 @@
+use anyhow::{ensure, Context, Result};
+use katana_primitives::block::BlockNumber;
+use katana_provider::traits::block::{BlockPartsWriter, HeaderProvider};
+use katana_provider::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use katana_provider::traits::trie::TrieWriter;
+use tracing::info;
+
+use super::{checkpoint, pending_blocks, set_checkpoint, Source};
+use crate::stage::{Stage, StageId, StageResult};
+
+/// Downloads and applies the state updates of the blocks whose bodies have been synced.
+///
+/// The state updates are also applied to the tries, and the resulting state root must match the
+/// one in the block header.
+#[derive(Debug)]
+pub struct States<S, P> {
+    source: S,
+    provider: P,
+}
+
+impl<S, P> States<S, P>
+where
+    S: Source,
+    P: BlockPartsWriter + HeaderProvider + TrieWriter,
+{
+    pub fn new(source: S, provider: P) -> Self {
+        Self { source, provider }
+    }
+
+    async fn sync_block(&self, block_number: BlockNumber) -> Result<()> {
+        let header = self
+            .provider
+            .header_by_number(block_number)?
+            .with_context(|| format!("Missing header of block {block_number}"))?;
+
+        let state_updates = self.source.state_updates(block_number).await?;
+
+        let state_root = self.provider.trie_insert_state_updates(block_number, &state_updates)?;
+        ensure!(
+            state_root == header.state_root,
+            "State root mismatch at block {block_number}. Expected {:#x}, computed \
+             {state_root:#x}.",
+            header.state_root
+        );
+
+        self.provider.insert_state_updates(block_number, state_updates)?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<S, P> Stage for States<S, P>
+where
+    S: Source,
+    P: BlockPartsWriter
+        + HeaderProvider
+        + TrieWriter
+        + StageCheckpointProvider
+        + StageCheckpointWriter,
+{
+    fn id(&self) -> StageId {
+        StageId::States
+    }
+
+    #[tracing::instrument(skip(self), name = "Stage", fields(id = %self.id()))]
+    async fn execute(&mut self) -> StageResult {
+        let checkpoint = checkpoint(&self.provider, self.id())?;
+        let target = super::checkpoint(&self.provider, StageId::Bodies)?;
+
+        let blocks = pending_blocks(checkpoint, target);
+        if !blocks.is_empty() {
+            info!(target: "pipeline", from = %blocks.start(), to = %blocks.end(), "Syncing states.");
+        }
+
+        for block_number in blocks {
+            self.sync_block(block_number).await?;
+            set_checkpoint(&self.provider, self.id(), block_number)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod metrics;
 pub mod ordering;
 pub mod pool;
 pub mod tx;
@@ -8,8 +9,9 @@ pub mod validation;
 use std::sync::Arc;
 
 use futures::channel::mpsc::Receiver;
+use katana_primitives::contract::ContractAddress;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxHash};
-use ordering::{FiFo, PoolOrd};
+use ordering::{PoolOrd, TxOrdering};
 use pool::Pool;
 use tx::{PendingTx, PoolTransaction};
 use validation::error::InvalidTransactionError;
@@ -17,7 +19,7 @@ use validation::stateful::TxValidator;
 use validation::Validator;
 
 /// Katana default transacstion pool type.
-pub type TxPool = Pool<ExecutableTxWithHash, TxValidator, FiFo<ExecutableTxWithHash>>;
+pub type TxPool = Pool<ExecutableTxWithHash, TxValidator, TxOrdering<ExecutableTxWithHash>>;
 
 pub type PoolResult<T> = Result<T, PoolError>;
 
@@ -25,6 +27,14 @@ pub type PoolResult<T> = Result<T, PoolError>;
 pub enum PoolError {
     #[error("Invalid transaction: {0}")]
     InvalidTransaction(Box<InvalidTransactionError>),
+    #[error("Transaction {0:#x} already exists in the pool")]
+    DuplicateTransaction(TxHash),
+    #[error("Replacement transaction underpriced: tip must be at least {min_tip}")]
+    ReplacementUnderpriced { min_tip: u64 },
+    #[error("Transaction pool is full")]
+    PoolFull,
+    #[error("Too many queued transactions for account {0}")]
+    AccountLimitExceeded(ContractAddress),
     #[error("Internal error: {0}")]
     Internal(Box<dyn std::error::Error>),
 }
@@ -54,9 +64,21 @@ pub trait TransactionPool {
     /// Get a transaction from the pool by its hash.
     fn get(&self, hash: TxHash) -> Option<Arc<Self::Transaction>>;
 
+    /// Removes the transactions with the given hashes from the pool, eg. when they can't be
+    /// executed anymore. The pending transactions of their senders with higher nonces are queued
+    /// again.
+    fn remove_transactions(&self, hashes: &[TxHash]);
+
+    /// Get the transactions that are ready to be included in a block, sorted by their priority.
+    fn pending_transactions(&self) -> Vec<Arc<Self::Transaction>>;
+
+    /// Get the transactions that are waiting for the transactions with the preceding nonces of
+    /// their sender, sorted by sender and nonce.
+    fn queued_transactions(&self) -> Vec<Arc<Self::Transaction>>;
+
     fn add_listener(&self) -> Receiver<TxHash>;
 
-    /// Get the total number of transactions in the pool.
+    /// Get the total number of transactions in the pool, pending and queued.
     fn size(&self) -> usize;
 
     /// Get a reference to the pool's validator.
@@ -0,0 +1,54 @@
+use dojo_metrics::Metrics;
+use metrics::{Counter, Gauge};
+
+use crate::validation::error::InvalidTransactionError;
+use crate::PoolError;
+
+#[derive(Metrics)]
+#[metrics(scope = "pool")]
+pub(crate) struct PoolMetrics {
+    /// The number of transactions added to the pool.
+    pub(crate) transactions_added_total: Counter,
+    /// The number of transactions ready to be included in a block, including the ones taken by
+    /// the block producer that aren't mined yet.
+    pub(crate) pending_transactions: Gauge,
+    /// The number of transactions waiting for the transactions with the preceding nonces of
+    /// their sender.
+    pub(crate) queued_transactions: Gauge,
+}
+
+/// Metrics of the transactions rejected by the pool, labeled by the reason of the rejection.
+#[derive(Metrics)]
+#[metrics(scope = "pool")]
+pub(crate) struct PoolRejectionMetrics {
+    /// The number of transactions rejected by the pool.
+    pub(crate) transactions_rejected_total: Counter,
+}
+
+impl PoolRejectionMetrics {
+    /// Records the rejection of a transaction because of `error`.
+    pub(crate) fn record(error: &PoolError) {
+        let metrics = Self::new_with_labels(&[("reason", rejection_reason(error))]);
+        metrics.transactions_rejected_total.increment(1);
+    }
+}
+
+/// Returns the label of the reason a transaction was rejected with `error`.
+fn rejection_reason(error: &PoolError) -> &'static str {
+    match error {
+        PoolError::InvalidTransaction(error) => match error.as_ref() {
+            InvalidTransactionError::InsufficientFunds { .. } => "insufficient_funds",
+            InvalidTransactionError::IntrinsicFeeTooLow { .. } => "intrinsic_fee_too_low",
+            InvalidTransactionError::ValidationFailure { .. } => "validation_failure",
+            InvalidTransactionError::NonAccount { .. } => "non_account",
+            InvalidTransactionError::InvalidNonce { .. } => "invalid_nonce",
+            InvalidTransactionError::ClassAlreadyDeclared { .. } => "class_already_declared",
+            InvalidTransactionError::NotPermitted(_) => "not_permitted",
+        },
+        PoolError::DuplicateTransaction(_) => "duplicate",
+        PoolError::ReplacementUnderpriced { .. } => "replacement_underpriced",
+        PoolError::PoolFull => "pool_full",
+        PoolError::AccountLimitExceeded(_) => "account_limit_exceeded",
+        PoolError::Internal(_) => "internal",
+    }
+}
@@ -73,8 +73,8 @@ impl PartialEq for TxSubmissionNonce {
 
 /// Tip-based ordering implementation.
 ///
-/// This ordering implementation uses the transaction's tip as the priority value. We don't have a
-/// use case for this ordering implementation yet, but it's mostly used for testing.
+/// This ordering implementation uses the transaction's tip as the priority value, so transactions
+/// with higher tips are included first.
 #[derive(Debug)]
 pub struct TipOrdering<T>(PhantomData<T>);
 
@@ -122,6 +122,60 @@ impl<T> Default for TipOrdering<T> {
     }
 }
 
+/// The orderings that can be selected when configuring the node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OrderingKind {
+    /// See [`FiFo`].
+    #[default]
+    FiFo,
+    /// See [`TipOrdering`].
+    Tip,
+}
+
+/// An ordering whose implementation is chosen at runtime.
+#[derive(Debug)]
+pub enum TxOrdering<T> {
+    FiFo(FiFo<T>),
+    Tip(TipOrdering<T>),
+}
+
+impl<T> TxOrdering<T> {
+    pub fn new(kind: OrderingKind) -> Self {
+        match kind {
+            OrderingKind::FiFo => Self::FiFo(FiFo::new()),
+            OrderingKind::Tip => Self::Tip(TipOrdering::new()),
+        }
+    }
+}
+
+/// The priority value of [`TxOrdering`].
+///
+/// All the transactions of a pool have the same variant, so the order between variants doesn't
+/// matter.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TxPriority {
+    FiFo(TxSubmissionNonce),
+    Tip(Tip),
+}
+
+impl<T: PoolTransaction> PoolOrd for TxOrdering<T> {
+    type Transaction = T;
+    type PriorityValue = TxPriority;
+
+    fn priority(&self, tx: &Self::Transaction) -> Self::PriorityValue {
+        match self {
+            Self::FiFo(ordering) => TxPriority::FiFo(ordering.priority(tx)),
+            Self::Tip(ordering) => TxPriority::Tip(ordering.priority(tx)),
+        }
+    }
+}
+
+impl<T> Default for TxOrdering<T> {
+    fn default() -> Self {
+        Self::new(OrderingKind::default())
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,19 +1,54 @@
 use core::fmt;
-use std::collections::btree_set::IntoIter;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use katana_primitives::transaction::TxHash;
 use parking_lot::RwLock;
 use tracing::{error, info, warn};
 
+use crate::metrics::{PoolMetrics, PoolRejectionMetrics};
 use crate::ordering::PoolOrd;
-use crate::tx::{PendingTx, PoolTransaction, TxId};
+use crate::tx::{PendingTx, PoolTransaction, QueuedTx, TxId};
 use crate::validation::error::InvalidTransactionError;
 use crate::validation::{ValidationOutcome, Validator};
 use crate::{PoolError, PoolResult, TransactionPool};
 
+/// The default maximum number of transactions in the pool.
+pub const DEFAULT_MAX_TXS: usize = 10_000;
+/// The default maximum number of queued transactions of a single account.
+pub const DEFAULT_MAX_QUEUED_PER_ACCOUNT: usize = 64;
+/// The default minimum tip increase, in percent, for a transaction to be replaced.
+pub const DEFAULT_REPLACEMENT_TIP_BUMP: u64 = 10;
+
+/// Limits of the [Pool].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolLimits {
+    /// The maximum number of transactions in the pool, pending and queued combined.
+    pub max_txs: usize,
+    /// The maximum number of queued transactions of a single account.
+    pub max_queued_per_account: usize,
+    /// How long a transaction can stay in the pool before being dropped. If `None`, transactions
+    /// are kept until they're included in a block.
+    pub tx_ttl: Option<Duration>,
+    /// The minimum tip increase, in percent, for a transaction to replace the one with the same
+    /// sender and nonce.
+    pub replacement_tip_bump: u64,
+}
+
+impl Default for PoolLimits {
+    fn default() -> Self {
+        Self {
+            tx_ttl: None,
+            max_txs: DEFAULT_MAX_TXS,
+            max_queued_per_account: DEFAULT_MAX_QUEUED_PER_ACCOUNT,
+            replacement_tip_bump: DEFAULT_REPLACEMENT_TIP_BUMP,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Pool<T, V, O>
 where
@@ -26,9 +61,14 @@ where
 
 #[derive(Debug)]
 struct Inner<T, V, O: PoolOrd> {
-    /// List of all valid txs in the pool.
+    /// List of all valid txs in the pool that can be included in a block.
     transactions: RwLock<BTreeSet<PendingTx<T, O>>>,
 
+    /// Txs whose nonce is ahead of their sender's next nonce, sorted by sender and nonce.
+    ///
+    /// Lock ordering: when both locks are needed, `transactions` must be acquired first.
+    queued: RwLock<BTreeMap<TxId, QueuedTx<T>>>,
+
     /// listeners for incoming txs
     listeners: RwLock<Vec<Sender<TxHash>>>,
 
@@ -37,6 +77,15 @@ struct Inner<T, V, O: PoolOrd> {
 
     /// the ordering mechanism used to order the txs in the pool
     ordering: O,
+
+    /// the pool limits
+    limits: PoolLimits,
+
+    /// metrics of the pool's transactions
+    metrics: PoolMetrics,
+
+    /// the number of transactions taken from the pool that aren't included in a block yet
+    taken: AtomicUsize,
 }
 
 impl<T, V, O> Pool<T, V, O>
@@ -47,16 +96,46 @@ where
 {
     /// Creates a new [Pool] with the given [Validator] and [PoolOrd] mechanism.
     pub fn new(validator: V, ordering: O) -> Self {
+        Self::with_limits(validator, ordering, PoolLimits::default())
+    }
+
+    /// Creates a new [Pool] with the given [Validator], [PoolOrd] mechanism and limits.
+    pub fn with_limits(validator: V, ordering: O, limits: PoolLimits) -> Self {
         Self {
             inner: Arc::new(Inner {
+                limits,
                 ordering,
                 validator,
+                queued: Default::default(),
                 transactions: Default::default(),
                 listeners: Default::default(),
+                metrics: Default::default(),
+                taken: Default::default(),
             }),
         }
     }
 
+    /// Returns the limits of the pool.
+    pub fn limits(&self) -> &PoolLimits {
+        &self.inner.limits
+    }
+
+    /// Sets the number of transactions taken from the pool that aren't included in a block yet,
+    /// eg. the leftover of a full block. They are still counted as pending in the metrics.
+    pub fn set_taken_transactions(&self, count: usize) {
+        self.inner.taken.store(count, AtomicOrdering::Relaxed);
+        self.update_size_metrics();
+    }
+
+    /// Updates the metrics of the number of transactions in the pool.
+    fn update_size_metrics(&self) {
+        let taken = self.inner.taken.load(AtomicOrdering::Relaxed);
+        let pending = self.inner.transactions.read().len() + taken;
+        let queued = self.inner.queued.read().len();
+        self.inner.metrics.pending_transactions.set(pending as f64);
+        self.inner.metrics.queued_transactions.set(queued as f64);
+    }
+
     /// Notifies all listeners about the new incoming transaction.
     fn notify_listener(&self, hash: TxHash) {
         let mut listener = self.inner.listeners.write();
@@ -83,36 +162,229 @@ where
             }
         }
     }
+
+    /// Returns the hash and tip of the transaction in the pool with the given id, if any.
+    fn find(&self, id: &TxId) -> Option<(TxHash, u64)> {
+        let pending = self.inner.transactions.read();
+        if let Some(tx) = pending.iter().find(|tx| &tx.id == id) {
+            return Some((tx.tx.hash(), tx.tx.tip()));
+        }
+
+        self.inner.queued.read().get(id).map(|tx| (tx.tx.hash(), tx.tx.tip()))
+    }
+
+    /// Removes the transaction with the given id from the pool.
+    fn remove(&self, id: &TxId) {
+        self.inner.transactions.write().retain(|tx| &tx.id != id);
+        self.inner.queued.write().remove(id);
+    }
+
+    /// Drops the transactions that have been in the pool for longer than the configured ttl.
+    fn remove_expired(&self) {
+        let Some(ttl) = self.inner.limits.tx_ttl else { return };
+
+        let mut pending = self.inner.transactions.write();
+        let mut queued = self.inner.queued.write();
+
+        let is_expired = |added_at: &Instant| added_at.elapsed() > ttl;
+        let total = pending.len() + queued.len();
+
+        let expired_pending = pending
+            .iter()
+            .filter(|tx| is_expired(&tx.added_at))
+            .map(|tx| tx.id.clone())
+            .collect::<Vec<_>>();
+
+        pending.retain(|tx| !is_expired(&tx.added_at));
+        queued.retain(|_, tx| !is_expired(&tx.added_at));
+
+        let expired = total - pending.len() - queued.len();
+        if expired > 0 {
+            info!(%expired, "Dropped expired transactions.");
+        }
+
+        self.requeue_descendants(&mut pending, &mut queued, &expired_pending);
+    }
+
+    /// Handles the removal of the pending transactions with ids `removed` from the pool.
+    ///
+    /// The pending transactions of the same sender with higher nonces can't be executed anymore, so
+    /// they're moved back to the queued set until the missing nonces are added again. The next
+    /// nonce of the sender in the validator is rolled back accordingly.
+    fn requeue_descendants(
+        &self,
+        pending: &mut BTreeSet<PendingTx<T, O>>,
+        queued: &mut BTreeMap<TxId, QueuedTx<T>>,
+        removed: &[TxId],
+    ) {
+        // the lowest removed nonce of every sender
+        let mut gaps = BTreeMap::new();
+        for id in removed {
+            let nonce = gaps.entry(id.sender()).or_insert(id.nonce());
+            *nonce = (*nonce).min(id.nonce());
+        }
+
+        let is_descendant =
+            |id: &TxId| gaps.get(&id.sender()).is_some_and(|nonce| id.nonce() > *nonce);
+
+        let descendants =
+            pending.iter().filter(|tx| is_descendant(&tx.id)).cloned().collect::<Vec<_>>();
+        pending.retain(|tx| !is_descendant(&tx.id));
+
+        for tx in descendants {
+            info!(hash = format!("{:#x}", tx.tx.hash()), "Pending transaction requeued.");
+            queued.insert(tx.id.clone(), QueuedTx { id: tx.id, tx: tx.tx, added_at: tx.added_at });
+        }
+
+        for (sender, nonce) in gaps {
+            self.inner.validator.rollback_nonce(sender, nonce);
+        }
+    }
+
+    /// Inserts a validated transaction in the pending set, making room for it if the pool is full.
+    fn insert_pending(&self, id: TxId, tx: T) -> PoolResult<()> {
+        let priority = self.inner.ordering.priority(&tx);
+        let tx = PendingTx::new(id, tx, priority);
+
+        let mut pending = self.inner.transactions.write();
+        let mut queued = self.inner.queued.write();
+
+        if pending.len() + queued.len() >= self.inner.limits.max_txs {
+            // a tx can't evict one of its sender's txs with a lower nonce, as it depends on it
+            let evictable = |last: &PendingTx<T, O>| {
+                tx.priority < last.priority
+                    && !(last.id.sender() == tx.id.sender() && last.id.nonce() < tx.id.nonce())
+            };
+
+            // queued txs are the least likely to be included soon, so they're evicted first
+            if let Some(id) = queued.keys().next_back().cloned() {
+                let evicted = queued.remove(&id).expect("qed; key exists");
+                warn!(hash = format!("{:#x}", evicted.tx.hash()), "Evicted queued transaction.");
+            } else if pending.last().is_some_and(evictable) {
+                let evicted = pending.pop_last().expect("qed; not empty");
+                warn!(hash = format!("{:#x}", evicted.tx.hash()), "Evicted pending transaction.");
+                self.requeue_descendants(&mut pending, &mut queued, &[evicted.id]);
+            } else {
+                // the tx has already been accounted for by the validator
+                self.requeue_descendants(&mut pending, &mut queued, &[tx.id]);
+                return Err(PoolError::PoolFull);
+            }
+        }
+
+        pending.insert(tx);
+        Ok(())
+    }
+
+    /// Inserts a transaction whose nonce is ahead of its sender's next nonce.
+    fn insert_queued(&self, id: TxId, tx: T) -> PoolResult<()> {
+        let pending = self.inner.transactions.read();
+        let mut queued = self.inner.queued.write();
+
+        if pending.len() + queued.len() >= self.inner.limits.max_txs {
+            return Err(PoolError::PoolFull);
+        }
+
+        let sender = id.sender();
+        let sender_queued = queued.keys().filter(|id| id.sender() == sender).count();
+        if sender_queued >= self.inner.limits.max_queued_per_account {
+            return Err(PoolError::AccountLimitExceeded(sender));
+        }
+
+        queued.insert(id.clone(), QueuedTx::new(id, tx));
+        Ok(())
+    }
+
+    /// Moves the queued transactions that follow `id` to the pending set, as long as their nonces
+    /// are contiguous and they are valid.
+    fn promote_queued(&self, mut id: TxId) {
+        loop {
+            let Some(queued) = self.inner.queued.write().remove(&id) else { break };
+
+            let tx = Arc::unwrap_or_clone(queued.tx);
+            let hash = tx.hash();
+
+            match self.inner.validator.validate(tx) {
+                Ok(ValidationOutcome::Valid(tx)) => {
+                    if let Err(error) = self.insert_pending(id.clone(), tx) {
+                        warn!(hash = format!("{hash:#x}"), %error, "Dropped queued transaction.");
+                        break;
+                    }
+
+                    info!(hash = format!("{hash:#x}"), "Queued transaction promoted.");
+                    self.notify_listener(hash);
+                    id = id.descendent();
+                }
+
+                Ok(ValidationOutcome::Dependent { tx, .. }) => {
+                    let tx =
+                        QueuedTx { id: id.clone(), tx: Arc::new(tx), added_at: queued.added_at };
+                    self.inner.queued.write().insert(id, tx);
+                    break;
+                }
+
+                Ok(ValidationOutcome::Invalid { error, .. }) => {
+                    warn!(hash = format!("{hash:#x}"), %error, "Dropped invalid queued transaction.");
+                    break;
+                }
+
+                Err(e) => {
+                    error!(hash = format!("{hash:#x}"), %e, "Failed to validate queued transaction.");
+                    break;
+                }
+            }
+        }
+    }
 }
 
-impl<T, V, O> TransactionPool for Pool<T, V, O>
+impl<T, V, O> Pool<T, V, O>
 where
     T: PoolTransaction + fmt::Debug,
     V: Validator<Transaction = T>,
     O: PoolOrd<Transaction = T>,
 {
-    type Transaction = T;
-    type Validator = V;
-    type Ordering = O;
-
-    fn add_transaction(&self, tx: T) -> PoolResult<TxHash> {
+    /// Validates `tx` and adds it to the pool, either as pending or queued.
+    fn try_add_transaction(&self, tx: T) -> PoolResult<TxHash> {
         let hash = tx.hash();
         let id = TxId::new(tx.sender(), tx.nonce());
 
         info!(hash = format!("{hash:#x}"), "Transaction received.");
 
+        self.remove_expired();
+
+        if self.contains(hash) {
+            return Err(PoolError::DuplicateTransaction(hash));
+        }
+
+        // a tx with the same sender and nonce can only be replaced by one paying a higher tip
+        let replaced = self.find(&id);
+        if let Some((_, tip)) = replaced {
+            let bump = tip.saturating_mul(self.inner.limits.replacement_tip_bump) / 100;
+            let min_tip = tip.saturating_add(bump.max(1));
+            if tx.tip() < min_tip {
+                return Err(PoolError::ReplacementUnderpriced { min_tip });
+            }
+        }
+
         match self.inner.validator.validate(tx) {
             Ok(outcome) => {
                 match outcome {
                     ValidationOutcome::Valid(tx) => {
-                        // get the priority of the validated tx
-                        let priority = self.inner.ordering.priority(&tx);
-                        let tx = PendingTx::new(id, tx, priority);
+                        if let Some((old, _)) = replaced {
+                            info!(
+                                hash = format!("{hash:#x}"),
+                                replaced = format!("{old:#x}"),
+                                "Replacing transaction."
+                            );
+                            self.remove(&id);
+                        }
 
                         // insert the tx in the pool
-                        self.inner.transactions.write().insert(tx);
+                        self.insert_pending(id.clone(), tx)?;
                         self.notify_listener(hash);
 
+                        // the tx may fill the nonce gap of the sender's queued txs
+                        self.promote_queued(id.descendent());
+
                         Ok(hash)
                     }
 
@@ -121,16 +393,27 @@ where
                         Err(PoolError::InvalidTransaction(Box::new(error)))
                     }
 
-                    // return as error for now but ideally we should kept the tx in a separate
-                    // queue and revalidate it when the parent tx is added to the pool
+                    // the tx is kept in a separate queue and revalidated when the txs with the
+                    // missing nonces are added to the pool
                     ValidationOutcome::Dependent { tx, tx_nonce, current_nonce } => {
-                        info!(hash = format!("{hash:#x}"), "Dependent transaction.");
-                        let err = InvalidTransactionError::InvalidNonce {
-                            address: tx.sender(),
-                            current_nonce,
-                            tx_nonce,
-                        };
-                        Err(PoolError::InvalidTransaction(Box::new(err)))
+                        if let Some((old, _)) = replaced {
+                            info!(
+                                hash = format!("{hash:#x}"),
+                                replaced = format!("{old:#x}"),
+                                "Replacing transaction."
+                            );
+                            self.remove(&id);
+                        }
+
+                        info!(
+                            hash = format!("{hash:#x}"),
+                            %tx_nonce,
+                            %current_nonce,
+                            "Dependent transaction queued."
+                        );
+
+                        self.insert_queued(id, tx)?;
+                        Ok(hash)
                     }
                 }
             }
@@ -141,12 +424,38 @@ where
             }
         }
     }
+}
+
+impl<T, V, O> TransactionPool for Pool<T, V, O>
+where
+    T: PoolTransaction + fmt::Debug,
+    V: Validator<Transaction = T>,
+    O: PoolOrd<Transaction = T>,
+{
+    type Transaction = T;
+    type Validator = V;
+    type Ordering = O;
+
+    fn add_transaction(&self, tx: T) -> PoolResult<TxHash> {
+        let result = self.try_add_transaction(tx);
+
+        match &result {
+            Ok(_) => self.inner.metrics.transactions_added_total.increment(1),
+            Err(error) => PoolRejectionMetrics::record(error),
+        }
+
+        self.update_size_metrics();
+        result
+    }
 
     fn take_transactions(&self) -> impl Iterator<Item = PendingTx<T, O>> {
+        self.remove_expired();
         // take all the transactions
-        PendingTransactions {
-            all: std::mem::take(&mut *self.inner.transactions.write()).into_iter(),
-        }
+        let all = std::mem::take(&mut *self.inner.transactions.write());
+        // they are pending until they are mined, see `set_taken_transactions`
+        self.inner.taken.fetch_add(all.len(), AtomicOrdering::Relaxed);
+        self.update_size_metrics();
+        PendingTransactions::new(all)
     }
 
     // check if a tx is in the pool
@@ -155,12 +464,41 @@ where
     }
 
     fn get(&self, hash: TxHash) -> Option<Arc<T>> {
-        self.inner
-            .transactions
-            .read()
-            .iter()
-            .find(|tx| tx.tx.hash() == hash)
-            .map(|t| Arc::clone(&t.tx))
+        let pending = self.inner.transactions.read();
+        if let Some(tx) = pending.iter().find(|tx| tx.tx.hash() == hash) {
+            return Some(Arc::clone(&tx.tx));
+        }
+
+        let queued = self.inner.queued.read();
+        queued.values().find(|tx| tx.tx.hash() == hash).map(|tx| Arc::clone(&tx.tx))
+    }
+
+    fn remove_transactions(&self, hashes: &[TxHash]) {
+        {
+            let mut pending = self.inner.transactions.write();
+            let mut queued = self.inner.queued.write();
+
+            let removed = pending
+                .iter()
+                .filter(|tx| hashes.contains(&tx.tx.hash()))
+                .map(|tx| tx.id.clone())
+                .collect::<Vec<_>>();
+
+            pending.retain(|tx| !hashes.contains(&tx.tx.hash()));
+            queued.retain(|_, tx| !hashes.contains(&tx.tx.hash()));
+
+            self.requeue_descendants(&mut pending, &mut queued, &removed);
+        }
+
+        self.update_size_metrics();
+    }
+
+    fn pending_transactions(&self) -> Vec<Arc<T>> {
+        self.inner.transactions.read().iter().map(|tx| Arc::clone(&tx.tx)).collect()
+    }
+
+    fn queued_transactions(&self) -> Vec<Arc<T>> {
+        self.inner.queued.read().values().map(|tx| Arc::clone(&tx.tx)).collect()
     }
 
     fn add_listener(&self) -> Receiver<TxHash> {
@@ -171,7 +509,8 @@ where
     }
 
     fn size(&self) -> usize {
-        self.inner.transactions.read().len()
+        let pending = self.inner.transactions.read();
+        pending.len() + self.inner.queued.read().len()
     }
 
     fn validator(&self) -> &Self::Validator {
@@ -192,8 +531,37 @@ where
 
 /// an iterator that yields transactions from the pool that can be included in a block, sorted by
 /// by its priority.
+///
+/// The transactions of the same sender are always yielded in nonce order, regardless of their
+/// priority, as a transaction can't be executed before the ones with lower nonces.
 struct PendingTransactions<T, O: PoolOrd> {
-    all: IntoIter<PendingTx<T, O>>,
+    /// The txs that can be yielded next, ie the tx with the lowest nonce of every sender.
+    independent: BTreeSet<PendingTx<T, O>>,
+    /// The txs that can only be yielded after the tx with the preceding nonce.
+    dependent: BTreeMap<TxId, PendingTx<T, O>>,
+}
+
+impl<T, O> PendingTransactions<T, O>
+where
+    T: PoolTransaction,
+    O: PoolOrd<Transaction = T>,
+{
+    fn new(all: BTreeSet<PendingTx<T, O>>) -> Self {
+        let ids = all.iter().map(|tx| tx.id.clone()).collect::<BTreeSet<_>>();
+
+        let mut independent = BTreeSet::new();
+        let mut dependent = BTreeMap::new();
+
+        for tx in all {
+            if tx.id.parent().is_some_and(|parent| ids.contains(&parent)) {
+                dependent.insert(tx.id.clone(), tx);
+            } else {
+                independent.insert(tx);
+            }
+        }
+
+        Self { independent, dependent }
+    }
 }
 
 impl<T, O> Iterator for PendingTransactions<T, O>
@@ -204,19 +572,27 @@ where
     type Item = PendingTx<T, O>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.all.next()
+        let tx = self.independent.pop_first()?;
+        if let Some(next) = self.dependent.remove(&tx.id.descendent()) {
+            self.independent.insert(next);
+        }
+        Some(tx)
     }
 }
 
 #[cfg(test)]
 pub(crate) mod test_utils {
 
+    use std::collections::HashMap;
+
     use katana_primitives::contract::{ContractAddress, Nonce};
     use katana_primitives::Felt;
+    use parking_lot::Mutex;
     use rand::Rng;
 
     use super::*;
     use crate::tx::PoolTransaction;
+    use crate::validation::ValidationResult;
 
     fn random_bytes<const SIZE: usize>() -> [u8; SIZE] {
         let mut bytes = [0u8; SIZE];
@@ -264,6 +640,40 @@ pub(crate) mod test_utils {
         }
     }
 
+    /// A validator that only checks the nonce of the txs against the nonces of the previously
+    /// validated txs of the same sender.
+    #[derive(Debug, Default)]
+    pub struct NonceValidator(Mutex<HashMap<ContractAddress, Nonce>>);
+
+    impl Validator for NonceValidator {
+        type Transaction = PoolTx;
+
+        fn validate(&self, tx: PoolTx) -> ValidationResult<PoolTx> {
+            let mut nonces = self.0.lock();
+            let current_nonce = nonces.get(&tx.sender()).copied().unwrap_or_default();
+
+            if tx.nonce() > current_nonce {
+                Ok(ValidationOutcome::Dependent { current_nonce, tx_nonce: tx.nonce(), tx })
+            } else {
+                nonces.insert(tx.sender(), current_nonce.max(tx.nonce() + Felt::ONE));
+                Ok(ValidationOutcome::Valid(tx))
+            }
+        }
+
+        fn rollback_nonce(&self, sender: ContractAddress, nonce: Nonce) {
+            if let Some(current) = self.0.lock().get_mut(&sender) {
+                *current = (*current).min(nonce);
+            }
+        }
+    }
+
+    impl NonceValidator {
+        /// Returns the next nonce of `sender`, if any of its txs has been validated.
+        pub fn nonce(&self, sender: ContractAddress) -> Option<Nonce> {
+            self.0.lock().get(&sender).copied()
+        }
+    }
+
     impl PoolTransaction for PoolTx {
         fn hash(&self) -> TxHash {
             self.hash
@@ -290,15 +700,18 @@ pub(crate) mod test_utils {
 #[cfg(test)]
 mod tests {
 
+    use std::time::Duration;
+
     use katana_primitives::contract::{ContractAddress, Nonce};
     use katana_primitives::Felt;
+    use rand::seq::SliceRandom;
 
     use super::test_utils::*;
-    use super::Pool;
-    use crate::ordering::FiFo;
+    use super::{Pool, PoolLimits};
+    use crate::ordering::{FiFo, TipOrdering};
     use crate::tx::PoolTransaction;
     use crate::validation::NoopValidator;
-    use crate::TransactionPool;
+    use crate::{PoolError, TransactionPool};
 
     /// Tx pool that uses a noop validator and a first-come-first-serve ordering.
     type TestPool = Pool<PoolTx, NoopValidator<PoolTx>, FiFo<PoolTx>>;
@@ -395,9 +808,8 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "Txs dependency management not fully implemented yet"]
     fn dependent_txs_linear_insertion() {
-        let pool = TestPool::test();
+        let pool = Pool::new(NonceValidator::default(), FiFo::new());
 
         // Create 100 transactions with the same sender but increasing nonce
         let total = 100u128;
@@ -425,6 +837,227 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "Txs dependency management not fully implemented yet"]
-    fn dependent_txs_random_insertion() {}
+    fn dependent_txs_random_insertion() {
+        let total = 100u128;
+        let limits = PoolLimits { max_queued_per_account: total as usize, ..Default::default() };
+        let pool = Pool::with_limits(NonceValidator::default(), FiFo::new(), limits);
+
+        let sender = ContractAddress::from(Felt::from_hex("0x1337").unwrap());
+        let mut txs: Vec<PoolTx> = (0..total)
+            .map(|i| PoolTx::new().with_sender(sender).with_nonce(Nonce::from(i)))
+            .collect();
+
+        // Add all transactions except the first one in random order
+        let first = txs.remove(0);
+        txs.shuffle(&mut rand::thread_rng());
+        txs.iter().for_each(|tx| {
+            pool.add_transaction(tx.clone()).unwrap();
+        });
+
+        // All of them are queued, waiting for the tx with nonce 0
+        assert_eq!(pool.size(), total as usize - 1);
+        assert!(pool.pending_transactions().is_empty());
+        assert_eq!(pool.queued_transactions().len(), total as usize - 1);
+
+        // Adding the missing tx promotes all the queued txs
+        pool.add_transaction(first).unwrap();
+        assert!(pool.queued_transactions().is_empty());
+
+        let pending = pool.take_transactions().collect::<Vec<_>>();
+        assert_eq!(pending.len(), total as usize);
+
+        for (i, pending_tx) in pending.iter().enumerate() {
+            assert_eq!(pending_tx.tx.nonce(), Nonce::from(i as u128));
+        }
+    }
+
+    #[test]
+    fn nonce_order_is_kept_regardless_of_priority() {
+        let pool = Pool::new(NonceValidator::default(), TipOrdering::new());
+
+        let sender = ContractAddress::from(Felt::from_hex("0x1337").unwrap());
+        let other = ContractAddress::from(Felt::from_hex("0x1338").unwrap());
+
+        let txs = [
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO).with_tip(1),
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::ONE).with_tip(10),
+            PoolTx::new().with_sender(other).with_nonce(Nonce::ZERO).with_tip(5),
+        ];
+
+        txs.iter().for_each(|tx| {
+            pool.add_transaction(tx.clone()).unwrap();
+        });
+
+        // the second tx of `sender` has the highest tip, but it can only be executed after its
+        // first tx
+        let pending = pool.take_transactions().map(|tx| tx.tx.hash()).collect::<Vec<_>>();
+        assert_eq!(pending, vec![txs[2].hash(), txs[0].hash(), txs[1].hash()]);
+    }
+
+    #[test]
+    fn replace_by_tip() {
+        let pool = Pool::new(NonceValidator::default(), TipOrdering::new());
+
+        let sender = ContractAddress::from(Felt::from_hex("0x1337").unwrap());
+        let tx = PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO).with_tip(100);
+        pool.add_transaction(tx.clone()).unwrap();
+
+        // the same tx can't be added twice
+        let result = pool.add_transaction(tx.clone());
+        assert!(matches!(result, Err(PoolError::DuplicateTransaction(hash)) if hash == tx.hash()));
+
+        // the tip must be bumped by at least 10%
+        let underpriced = PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO).with_tip(109);
+        let result = pool.add_transaction(underpriced);
+        assert!(matches!(result, Err(PoolError::ReplacementUnderpriced { min_tip: 110 })));
+
+        let replacement = PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO).with_tip(110);
+        pool.add_transaction(replacement.clone()).unwrap();
+
+        assert_eq!(pool.size(), 1);
+        assert!(pool.get(tx.hash()).is_none());
+        assert!(pool.get(replacement.hash()).is_some());
+    }
+
+    #[test]
+    fn pool_limits() {
+        let limits = PoolLimits { max_txs: 3, max_queued_per_account: 1, ..Default::default() };
+        let pool = Pool::with_limits(NonceValidator::default(), FiFo::new(), limits);
+
+        let sender = ContractAddress::from(Felt::from_hex("0x1337").unwrap());
+
+        // only one queued tx per account
+        let queued = PoolTx::new().with_sender(sender).with_nonce(Nonce::from(5u8));
+        pool.add_transaction(queued.clone()).unwrap();
+        let result =
+            pool.add_transaction(PoolTx::new().with_sender(sender).with_nonce(Nonce::from(6u8)));
+        assert!(
+            matches!(result, Err(PoolError::AccountLimitExceeded(address)) if address == sender)
+        );
+
+        let txs = [
+            PoolTx::new().with_nonce(Nonce::ZERO),
+            PoolTx::new().with_nonce(Nonce::ZERO),
+            PoolTx::new().with_nonce(Nonce::ZERO),
+        ];
+
+        pool.add_transaction(txs[0].clone()).unwrap();
+        pool.add_transaction(txs[1].clone()).unwrap();
+        assert_eq!(pool.size(), 3);
+
+        // the queued tx is evicted to make room for a pending one
+        pool.add_transaction(txs[2].clone()).unwrap();
+        assert_eq!(pool.size(), 3);
+        assert!(pool.get(queued.hash()).is_none());
+
+        // with fifo ordering, a new tx never has a higher priority than the pending ones
+        let result = pool.add_transaction(PoolTx::new().with_nonce(Nonce::ZERO));
+        assert!(matches!(result, Err(PoolError::PoolFull)));
+    }
+
+    #[test]
+    fn expired_txs_are_dropped() {
+        let limits = PoolLimits { tx_ttl: Some(Duration::from_millis(50)), ..Default::default() };
+        let pool = Pool::with_limits(NonceValidator::default(), FiFo::new(), limits);
+
+        pool.add_transaction(PoolTx::new().with_nonce(Nonce::ZERO)).unwrap();
+        pool.add_transaction(PoolTx::new().with_nonce(Nonce::ONE)).unwrap();
+        assert_eq!(pool.size(), 2);
+
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(pool.take_transactions().count(), 0);
+        assert_eq!(pool.size(), 0);
+    }
+
+    #[test]
+    fn evicted_tx_requeues_descendants() {
+        let limits = PoolLimits { max_txs: 4, ..Default::default() };
+        let pool = Pool::with_limits(NonceValidator::default(), TipOrdering::new(), limits);
+
+        let sender = ContractAddress::from(Felt::from_hex("0x1337").unwrap());
+        let txs = [
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO).with_tip(10),
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::ONE).with_tip(1),
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::TWO).with_tip(10),
+            PoolTx::new().with_nonce(Nonce::ZERO).with_tip(10),
+        ];
+
+        txs.iter().for_each(|tx| {
+            pool.add_transaction(tx.clone()).unwrap();
+        });
+        assert_eq!(pool.validator().nonce(sender), Some(Nonce::THREE));
+
+        // a sender's tx can't evict the tx it depends on
+        let result = pool.add_transaction(
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::THREE).with_tip(5),
+        );
+        assert!(matches!(result, Err(PoolError::PoolFull)));
+        assert_eq!(pool.validator().nonce(sender), Some(Nonce::THREE));
+
+        // the tx with nonce 1 has the lowest tip, so it's evicted and the tx with nonce 2 can't be
+        // executed until a tx with nonce 1 is added again
+        let other = PoolTx::new().with_nonce(Nonce::ZERO).with_tip(5);
+        pool.add_transaction(other.clone()).unwrap();
+
+        assert!(pool.get(txs[1].hash()).is_none());
+        let queued = pool.queued_transactions().iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        assert_eq!(queued, vec![txs[2].hash()]);
+        assert_eq!(pool.validator().nonce(sender), Some(Nonce::ONE));
+
+        let pending = pool.take_transactions().map(|tx| tx.tx.hash()).collect::<Vec<_>>();
+        assert_eq!(pending, vec![txs[0].hash(), txs[3].hash(), other.hash()]);
+    }
+
+    #[test]
+    fn remove_transactions() {
+        let pool = Pool::new(NonceValidator::default(), FiFo::new());
+
+        let sender = ContractAddress::from(Felt::from_hex("0x1337").unwrap());
+        let txs = [
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO),
+            PoolTx::new().with_sender(sender).with_nonce(Nonce::ONE),
+            PoolTx::new().with_nonce(Nonce::ZERO),
+        ];
+
+        txs.iter().for_each(|tx| {
+            pool.add_transaction(tx.clone()).unwrap();
+        });
+
+        pool.remove_transactions(&[txs[0].hash(), txs[2].hash()]);
+
+        assert!(pool.get(txs[0].hash()).is_none());
+        assert!(pool.get(txs[2].hash()).is_none());
+        assert!(pool.pending_transactions().is_empty());
+        assert_eq!(pool.queued_transactions()[0].hash(), txs[1].hash());
+        assert_eq!(pool.validator().nonce(sender), Some(Nonce::ZERO));
+    }
+
+    #[test]
+    fn expired_tx_requeues_descendants() {
+        let limits = PoolLimits { tx_ttl: Some(Duration::from_millis(200)), ..Default::default() };
+        let pool = Pool::with_limits(NonceValidator::default(), FiFo::new(), limits);
+
+        let sender = ContractAddress::from(Felt::from_hex("0x1337").unwrap());
+        let first = PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO);
+        let second = PoolTx::new().with_sender(sender).with_nonce(Nonce::ONE);
+
+        pool.add_transaction(first.clone()).unwrap();
+        std::thread::sleep(Duration::from_millis(120));
+        pool.add_transaction(second.clone()).unwrap();
+        std::thread::sleep(Duration::from_millis(120));
+
+        // only the first tx has expired, but the second one can't be executed without it
+        assert_eq!(pool.take_transactions().count(), 0);
+        assert!(pool.get(first.hash()).is_none());
+        assert_eq!(pool.queued_transactions()[0].hash(), second.hash());
+        assert_eq!(pool.validator().nonce(sender), Some(Nonce::ZERO));
+
+        // adding the expired tx again promotes the requeued one
+        let first = PoolTx::new().with_sender(sender).with_nonce(Nonce::ZERO);
+        pool.add_transaction(first.clone()).unwrap();
+
+        let pending = pool.take_transactions().map(|tx| tx.tx.hash()).collect::<Vec<_>>();
+        assert_eq!(pending, vec![first.hash(), second.hash()]);
+    }
 }
@@ -39,6 +39,14 @@ impl TxId {
         Self { sender, nonce }
     }
 
+    pub fn sender(&self) -> ContractAddress {
+        self.sender
+    }
+
+    pub fn nonce(&self) -> Nonce {
+        self.nonce
+    }
+
     pub fn parent(&self) -> Option<Self> {
         if self.nonce == Nonce::ZERO {
             None
@@ -66,6 +74,21 @@ impl<T, O: PoolOrd> PendingTx<T, O> {
     }
 }
 
+/// A transaction whose nonce is ahead of the next nonce of its sender. It is kept in the pool until
+/// the transactions with the missing nonces are added.
+#[derive(Debug)]
+pub struct QueuedTx<T> {
+    pub id: TxId,
+    pub tx: Arc<T>,
+    pub added_at: std::time::Instant,
+}
+
+impl<T> QueuedTx<T> {
+    pub fn new(id: TxId, tx: T) -> Self {
+        Self { id, tx: Arc::new(tx), added_at: Instant::now() }
+    }
+}
+
 // We can't just derive these traits because the derive implementation would require that
 // the generics also implement these traits, which is not necessary.
 
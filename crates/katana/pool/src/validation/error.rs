@@ -2,6 +2,8 @@ use katana_primitives::class::ClassHash;
 use katana_primitives::contract::{ContractAddress, Nonce};
 use katana_primitives::Felt;
 
+use super::policy::PolicyViolation;
+
 // TODO: figure out how to combine this with ExecutionError
 #[derive(Debug, thiserror::Error)]
 pub enum InvalidTransactionError {
@@ -68,4 +70,9 @@ pub enum InvalidTransactionError {
     /// declared.
     #[error("Class with hash {class_hash:#x} has already been declared.")]
     ClassAlreadyDeclared { class_hash: ClassHash },
+
+    /// Error when the transaction isn't permitted by the pool's
+    /// [policy](super::policy::TxPolicy).
+    #[error(transparent)]
+    NotPermitted(#[from] PolicyViolation),
 }
@@ -1,8 +1,9 @@
 pub mod error;
+pub mod policy;
 pub mod stateful;
 
 use error::InvalidTransactionError;
-use katana_primitives::contract::Nonce;
+use katana_primitives::contract::{ContractAddress, Nonce};
 use katana_primitives::transaction::TxHash;
 
 use crate::tx::PoolTransaction;
@@ -36,6 +37,13 @@ pub trait Validator {
     /// transaction is invalid. For that purpose, use the [`ValidationOutcome::Invalid`] enum.
     fn validate(&self, tx: Self::Transaction) -> ValidationResult<Self::Transaction>;
 
+    /// Rolls the next nonce of `sender` back to `nonce`, if it's ahead of it.
+    ///
+    /// This is called when the transactions of `sender` from `nonce` onwards are removed from the
+    /// pool without being included in a block, so that a transaction with `nonce` is accepted
+    /// again.
+    fn rollback_nonce(&self, _sender: ContractAddress, _nonce: Nonce) {}
+
     /// Validate a batch of transactions.
     fn validate_all(
         &self,
@@ -0,0 +1,343 @@
+//! Permissions of the transactions accepted by the pool, for permissioned chains.
+//!
+//! A policy is defined in a TOML file:
+//!
+//! ```toml
+//! # accounts that aren't subject to the policy
+//! admins = ["0x1"]
+//! # only let the admins declare classes
+//! admin-only-declare = true
+//!
+//! # the accounts allowed to send transactions. all accounts are allowed if `allow` is not set,
+//! # unless the called contracts or selectors are restricted.
+//! [senders]
+//! allow = ["0x2", "0x3"]
+//!
+//! # the contracts that can be called by invoke transactions
+//! [contracts]
+//! deny = ["0x4"]
+//!
+//! # the entry point selectors that can be called by invoke transactions
+//! [selectors]
+//! deny = ["0x83afd3f4caedc6eebf44246fe54e38c95e3179a5ec9ea81740eca5b482d12e"]
+//! ```
+//!
+//! The calls of an invoke transaction are decoded from its calldata, assuming it follows the
+//! calldata format of the standard Cairo 1 accounts, ie `[n_calls, (to, selector, len,
+//! data...)...]`. Transactions whose calldata can't be decoded are rejected when the called
+//! contracts or selectors are restricted. But an account with a custom `__execute__` could encode
+//! different calls in a calldata that decodes fine, so restricting the calls requires
+//! `senders.allow` to only list standard accounts.
+
+use std::collections::HashSet;
+use std::fs;
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, InvokeTx};
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+
+use crate::tx::PoolTransaction;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TxPolicyError {
+    #[error("Failed to read policy file at path {path}: {source}")]
+    FileNotFound { source: std::io::Error, path: PathBuf },
+
+    #[error(transparent)]
+    Parsing(#[from] toml::de::Error),
+
+    #[error("Restricting the called contracts or selectors requires a sender allowlist")]
+    MissingSenderAllowlist,
+}
+
+/// Error when a transaction isn't permitted by the [`TxPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PolicyViolation {
+    #[error("Sender {0} is not allowed to send transactions")]
+    SenderNotAllowed(ContractAddress),
+
+    #[error("Contract {0} is not allowed to be called")]
+    ContractNotAllowed(ContractAddress),
+
+    #[error("Entry point {0:#x} is not allowed to be called")]
+    SelectorNotAllowed(Felt),
+
+    #[error("Only admin accounts are allowed to declare classes")]
+    DeclareNotAllowed,
+
+    #[error("Unrecognized calldata format, the called contracts can't be checked")]
+    UnknownCalldata,
+}
+
+/// A list of allowed and denied items.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccessList<T: Eq + Hash> {
+    /// The allowed items. If `None`, all the items that aren't denied are allowed.
+    #[serde(default)]
+    pub allow: Option<HashSet<T>>,
+    /// The denied items. Takes precedence over `allow`.
+    #[serde(default = "HashSet::new")]
+    pub deny: HashSet<T>,
+}
+
+impl<T: Eq + Hash> AccessList<T> {
+    /// Returns `true` if `item` is allowed.
+    pub fn permits(&self, item: &T) -> bool {
+        !self.deny.contains(item) && self.allow.as_ref().map_or(true, |allow| allow.contains(item))
+    }
+
+    /// Returns `true` if all items are allowed.
+    pub fn is_unrestricted(&self) -> bool {
+        self.allow.is_none() && self.deny.is_empty()
+    }
+}
+
+impl<T: Eq + Hash> Default for AccessList<T> {
+    fn default() -> Self {
+        Self { allow: None, deny: HashSet::new() }
+    }
+}
+
+/// The permissions of the transactions accepted by the pool.
+///
+/// The default policy permits every transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TxPolicy {
+    /// The accounts whose transactions are always permitted.
+    #[serde(default)]
+    pub admins: HashSet<ContractAddress>,
+    /// Whether only the admins can declare classes.
+    #[serde(default)]
+    pub admin_only_declare: bool,
+    /// The accounts that can send transactions.
+    #[serde(default)]
+    pub senders: AccessList<ContractAddress>,
+    /// The contracts that invoke transactions can call.
+    #[serde(default)]
+    pub contracts: AccessList<ContractAddress>,
+    /// The entry point selectors that invoke transactions can call.
+    #[serde(default)]
+    pub selectors: AccessList<Felt>,
+}
+
+impl TxPolicy {
+    /// Loads the policy from the TOML file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TxPolicyError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|source| TxPolicyError::FileNotFound { source, path: path.into() })?;
+        let policy: Self = toml::from_str(&content)?;
+
+        // the calls are only decoded correctly for the standard accounts
+        let restricts_calls =
+            !policy.contracts.is_unrestricted() || !policy.selectors.is_unrestricted();
+        if restricts_calls && policy.senders.allow.is_none() {
+            return Err(TxPolicyError::MissingSenderAllowlist);
+        }
+
+        Ok(policy)
+    }
+
+    /// Checks that `tx` is permitted by the policy.
+    ///
+    /// L1 handler transactions aren't sent by accounts, so they are always permitted.
+    pub fn check(&self, tx: &ExecutableTxWithHash) -> Result<(), PolicyViolation> {
+        if let ExecutableTx::L1Handler(_) = tx.transaction {
+            return Ok(());
+        }
+
+        let sender = tx.sender();
+        if self.admins.contains(&sender) {
+            return Ok(());
+        }
+
+        if !self.senders.permits(&sender) {
+            return Err(PolicyViolation::SenderNotAllowed(sender));
+        }
+
+        match &tx.transaction {
+            ExecutableTx::Declare(_) if self.admin_only_declare => {
+                Err(PolicyViolation::DeclareNotAllowed)
+            }
+            ExecutableTx::Invoke(tx) => self.check_calls(invoke_calldata(tx)),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_calls(&self, calldata: &[Felt]) -> Result<(), PolicyViolation> {
+        if self.contracts.is_unrestricted() && self.selectors.is_unrestricted() {
+            return Ok(());
+        }
+
+        for (to, selector) in decode_calls(calldata).ok_or(PolicyViolation::UnknownCalldata)? {
+            if !self.contracts.permits(&to) {
+                return Err(PolicyViolation::ContractNotAllowed(to));
+            } else if !self.selectors.permits(&selector) {
+                return Err(PolicyViolation::SelectorNotAllowed(selector));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn invoke_calldata(tx: &InvokeTx) -> &[Felt] {
+    match tx {
+        InvokeTx::V1(tx) => &tx.calldata,
+        InvokeTx::V3(tx) => &tx.calldata,
+    }
+}
+
+/// Decodes the called contracts and selectors of the calldata of a Cairo 1 account's
+/// `__execute__`. Returns `None` if the calldata isn't well formed.
+fn decode_calls(calldata: &[Felt]) -> Option<Vec<(ContractAddress, Felt)>> {
+    let (n_calls, mut rest) = calldata.split_first()?;
+    let n_calls = u64::try_from(*n_calls).ok()?;
+
+    let mut calls = Vec::new();
+    for _ in 0..n_calls {
+        let [to, selector, len, tail @ ..] = rest else { return None };
+        let len = usize::try_from(u64::try_from(*len).ok()?).ok()?;
+        if len > tail.len() {
+            return None;
+        }
+
+        calls.push((ContractAddress::from(*to), *selector));
+        rest = &tail[len..];
+    }
+
+    rest.is_empty().then_some(calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::transaction::{DeployAccountTx, DeployAccountTxV1, InvokeTxV1};
+    use katana_primitives::{address, felt};
+
+    use super::*;
+
+    fn invoke(sender: ContractAddress, calldata: Vec<Felt>) -> ExecutableTxWithHash {
+        let tx = InvokeTxV1 { sender_address: sender, calldata, ..Default::default() };
+        ExecutableTxWithHash::new(ExecutableTx::Invoke(InvokeTx::V1(tx)))
+    }
+
+    #[test]
+    fn default_policy() {
+        let policy = TxPolicy::default();
+        let tx = invoke(address!("0x1"), vec![felt!("0x1"), felt!("0x2")]);
+        assert_eq!(policy.check(&tx), Ok(()));
+    }
+
+    #[test]
+    fn load_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.toml");
+        fs::write(
+            &path,
+            r#"
+            admins = ["0x1"]
+            admin-only-declare = true
+
+            [senders]
+            allow = ["0x1", "0x2"]
+            deny = ["0x3"]
+
+            [selectors]
+            deny = ["0x5"]
+            "#,
+        )
+        .unwrap();
+
+        let policy = TxPolicy::load(&path).unwrap();
+        assert_eq!(policy.admins, HashSet::from([address!("0x1")]));
+        assert!(policy.admin_only_declare);
+        assert_eq!(policy.senders.allow, Some(HashSet::from([address!("0x1"), address!("0x2")])));
+        assert_eq!(policy.senders.deny, HashSet::from([address!("0x3")]));
+        assert!(policy.contracts.is_unrestricted());
+        assert_eq!(policy.selectors.deny, HashSet::from([felt!("0x5")]));
+
+        fs::write(&path, "unknown = true").unwrap();
+        assert!(TxPolicy::load(&path).is_err());
+
+        fs::write(&path, "[contracts]\ndeny = [\"0x4\"]").unwrap();
+        assert!(matches!(TxPolicy::load(&path), Err(TxPolicyError::MissingSenderAllowlist)));
+    }
+
+    #[test]
+    fn sender_permissions() {
+        let policy = TxPolicy {
+            admins: HashSet::from([address!("0x1")]),
+            senders: AccessList {
+                allow: Some(HashSet::from([address!("0x2"), address!("0x3")])),
+                deny: HashSet::from([address!("0x3")]),
+            },
+            ..Default::default()
+        };
+
+        // admins are permitted even if they aren't in the allowlist
+        assert_eq!(policy.check(&invoke(address!("0x1"), vec![])), Ok(()));
+        assert_eq!(policy.check(&invoke(address!("0x2"), vec![])), Ok(()));
+        // the denylist takes precedence over the allowlist
+        assert_eq!(
+            policy.check(&invoke(address!("0x3"), vec![])),
+            Err(PolicyViolation::SenderNotAllowed(address!("0x3")))
+        );
+        assert_eq!(
+            policy.check(&invoke(address!("0x4"), vec![])),
+            Err(PolicyViolation::SenderNotAllowed(address!("0x4")))
+        );
+
+        let tx = DeployAccountTxV1 { contract_address: address!("0x4"), ..Default::default() };
+        let tx = ExecutableTxWithHash::new(ExecutableTx::DeployAccount(DeployAccountTx::V1(tx)));
+        assert_eq!(policy.check(&tx), Err(PolicyViolation::SenderNotAllowed(address!("0x4"))));
+    }
+
+    #[test]
+    fn call_permissions() {
+        let policy = TxPolicy {
+            contracts: AccessList {
+                allow: Some(HashSet::from([address!("0x10"), address!("0x20")])),
+                ..Default::default()
+            },
+            selectors: AccessList { deny: HashSet::from([felt!("0xbad")]), ..Default::default() },
+            ..Default::default()
+        };
+
+        let sender = address!("0x1");
+
+        // two calls: 0x10.0x1(0xa, 0xb) and 0x20.0x2()
+        let calldata = vec![
+            felt!("2"),
+            felt!("0x10"),
+            felt!("0x1"),
+            felt!("2"),
+            felt!("0xa"),
+            felt!("0xb"),
+            felt!("0x20"),
+            felt!("0x2"),
+            felt!("0"),
+        ];
+        assert_eq!(policy.check(&invoke(sender, calldata)), Ok(()));
+
+        let calldata = vec![felt!("1"), felt!("0x30"), felt!("0x1"), felt!("0")];
+        assert_eq!(
+            policy.check(&invoke(sender, calldata)),
+            Err(PolicyViolation::ContractNotAllowed(address!("0x30")))
+        );
+
+        let calldata = vec![felt!("1"), felt!("0x10"), felt!("0xbad"), felt!("0")];
+        assert_eq!(
+            policy.check(&invoke(sender, calldata)),
+            Err(PolicyViolation::SelectorNotAllowed(felt!("0xbad")))
+        );
+
+        // the length of the call's data exceeds the calldata
+        let calldata = vec![felt!("1"), felt!("0x10"), felt!("0x1"), felt!("5"), felt!("0xa")];
+        assert_eq!(policy.check(&invoke(sender, calldata)), Err(PolicyViolation::UnknownCalldata));
+    }
+}
@@ -21,6 +21,7 @@ use katana_provider::error::ProviderError;
 use katana_provider::traits::state::StateProvider;
 use parking_lot::Mutex;
 
+use super::policy::TxPolicy;
 use super::{Error, InvalidTransactionError, ValidationOutcome, ValidationResult, Validator};
 use crate::tx::PoolTransaction;
 
@@ -39,6 +40,7 @@ struct Inner {
     state: Arc<Box<dyn StateProvider>>,
 
     pool_nonces: HashMap<ContractAddress, Nonce>,
+    policy: Option<TxPolicy>,
 }
 
 impl TxValidator {
@@ -55,6 +57,7 @@ impl TxValidator {
             execution_flags,
             state: Arc::new(state),
             pool_nonces: HashMap::new(),
+            policy: None,
         }));
         Self { permit, inner }
     }
@@ -67,6 +70,27 @@ impl TxValidator {
         this.state = Arc::new(new_state);
     }
 
+    /// Same as [`TxValidator::update`], but also discards the nonces of the transactions that
+    /// have been validated but not yet included in a block. This is used when the pending
+    /// transactions are dropped, eg. when the chain is unwound.
+    pub fn reset(&self, new_state: Box<dyn StateProvider>, block_env: BlockEnv) {
+        let mut this = self.inner.lock();
+        this.block_env = block_env;
+        this.state = Arc::new(new_state);
+        this.pool_nonces.clear();
+    }
+
+    /// Sets the policy that the validated transactions must comply with. The transactions that
+    /// are already in the pool aren't affected.
+    pub fn set_policy(&self, policy: Option<TxPolicy>) {
+        self.inner.lock().policy = policy;
+    }
+
+    /// Returns the policy that the validated transactions must comply with, if any.
+    pub fn policy(&self) -> Option<TxPolicy> {
+        self.inner.lock().policy.clone()
+    }
+
     // NOTE:
     // If you check the get_nonce method of StatefulValidator in blockifier, under the hood it
     // unwraps the Option to get the state of the TransactionExecutor struct. StatefulValidator
@@ -102,6 +126,13 @@ impl Validator for TxValidator {
         let tx_nonce = tx.nonce();
         let address = tx.sender();
 
+        if let Some(policy) = &this.policy {
+            if let Err(violation) = policy.check(&tx) {
+                let error = InvalidTransactionError::NotPermitted(violation);
+                return Ok(ValidationOutcome::Invalid { tx, error });
+            }
+        }
+
         // For declare transactions, perform a static check if there's already an existing class
         // with the same hash.
         if let ExecutableTx::Declare(ref declare_tx) = tx.transaction {
@@ -138,25 +169,38 @@ impl Validator for TxValidator {
             _ => tx.nonce() == Nonce::ONE && current_nonce == Nonce::ZERO,
         };
 
+        // Impersonated accounts can send transactions without a valid signature.
+        let impersonated = this.execution_flags.impersonated_accounts().contains(address);
+
         // prepare a stateful validator and run the account validation logic (ie __validate__
         // entrypoint)
         let result = validate(
             this.prepare(),
             tx,
-            !this.execution_flags.account_validation() || skip_validate,
+            !this.execution_flags.account_validation() || skip_validate || impersonated,
             !this.execution_flags.fee(),
         );
 
         match result {
             res @ Ok(ValidationOutcome::Valid { .. }) => {
-                // update the nonce of the account in the pool only for valid tx
-                let updated_nonce = current_nonce + Felt::ONE;
+                // update the nonce of the account in the pool only for valid tx. the tx nonce may
+                // be lower than the current one if it replaces a tx that is already in the pool.
+                let updated_nonce = current_nonce.max(tx_nonce + Felt::ONE);
                 this.pool_nonces.insert(address, updated_nonce);
                 res
             }
             _ => result,
         }
     }
+
+    fn rollback_nonce(&self, sender: ContractAddress, nonce: Nonce) {
+        let mut this = self.inner.lock();
+        if let Some(current) = this.pool_nonces.get_mut(&sender) {
+            if *current > nonce {
+                *current = nonce;
+            }
+        }
+    }
 }
 
 // perform validation on the pool transaction using the provided stateful validator
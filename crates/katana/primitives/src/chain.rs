@@ -140,6 +140,14 @@ impl std::fmt::Display for ChainId {
     }
 }
 
+impl std::str::FromStr for ChainId {
+    type Err = ParseChainIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl From<Felt> for ChainId {
     fn from(value: Felt) -> Self {
         NamedChainId::try_from(value).map(ChainId::Named).unwrap_or(ChainId::Id(value))
@@ -0,0 +1,311 @@
+//! The file representation of a [`ChainSpec`]. Used to configure a Katana-based appchain.
+//!
+//! A chain spec file is a versioned TOML file. The paths in it are relative to the file itself.
+//!
+//! ```toml
+//! version = 1
+//! id = "MY_APPCHAIN"
+//! # the genesis file of the chain, in the same format as the one used by `--genesis`
+//! genesis = "genesis.json"
+//! # classes to declare in the genesis block, on top of those of the genesis file
+//! classes = ["classes/world.contract_class.json"]
+//!
+//! [fee-contracts]
+//! eth = "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"
+//! strk = "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"
+//!
+//! [gas-prices]
+//! eth = 100000000000
+//! strk = 100000000000
+//!
+//! [settlement]
+//! kind = "starknet"
+//! rpc-url = "http://localhost:5050"
+//! core-contract = "0x1234"
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+use super::{ChainSpec, FeeContracts, SettlementLayer};
+use crate::block::GasPrices;
+use crate::chain::ChainId;
+use crate::genesis::constant::{DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_STRK_FEE_TOKEN_ADDRESS};
+use crate::genesis::json::{GenesisClassJson, GenesisJson, GenesisJsonError, PathOrFullArtifact};
+use crate::genesis::Genesis;
+use crate::version::CURRENT_STARKNET_VERSION;
+
+/// The version of the chain spec file format that is currently supported.
+pub const CURRENT_CHAIN_SPEC_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChainSpecFileError {
+    #[error("Failed to read chain spec file at path {path}: {source}")]
+    FileNotFound { source: std::io::Error, path: PathBuf },
+
+    #[error(transparent)]
+    Parsing(#[from] toml::de::Error),
+
+    #[error(transparent)]
+    Encoding(#[from] toml::ser::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Genesis(#[from] GenesisJsonError),
+
+    #[error("Unsupported chain spec version {0}, expected version {CURRENT_CHAIN_SPEC_VERSION}")]
+    UnsupportedVersion(u32),
+
+    #[error("Fee token contract {0} is not deployed in the genesis")]
+    MissingFeeContract(crate::contract::ContractAddress),
+
+    #[error("The {0} gas price must be greater than zero")]
+    ZeroGasPrice(&'static str),
+
+    #[error("Unsupported settlement layer RPC url scheme '{0}'")]
+    InvalidSettlementUrl(String),
+}
+
+/// The L1 gas prices of the chain. Stored as `u64` as TOML integers are limited to 64 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasPricesFile {
+    /// The ETH gas price, denominated in wei.
+    pub eth: u64,
+    /// The STRK gas price, denominated in fri.
+    pub strk: u64,
+}
+
+/// The file representation of a [`ChainSpec`].
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ChainSpecFile {
+    /// The version of the file format.
+    pub version: u32,
+    /// The chain id, either as a Cairo short string or a hex string.
+    #[serde_as(as = "DisplayFromStr")]
+    pub id: ChainId,
+    /// The path of the genesis file. If not set, the default genesis is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis: Option<PathBuf>,
+    /// The paths of the classes to declare in the genesis block.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<PathBuf>,
+    pub fee_contracts: FeeContracts,
+    pub gas_prices: GasPricesFile,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub settlement: Option<SettlementLayer>,
+}
+
+impl ChainSpecFile {
+    /// Creates a chain spec file for chain `id` with the default fee contracts and genesis.
+    pub fn new(id: ChainId, gas_prices: GasPricesFile) -> Self {
+        Self {
+            id,
+            gas_prices,
+            version: CURRENT_CHAIN_SPEC_VERSION,
+            genesis: None,
+            classes: Vec::new(),
+            settlement: None,
+            fee_contracts: FeeContracts {
+                eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+                strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+            },
+        }
+    }
+
+    /// Reads the chain spec file at `path`.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ChainSpecFileError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|source| ChainSpecFileError::FileNotFound { source, path: path.into() })?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Writes the chain spec file to `path`.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), ChainSpecFileError> {
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Builds the [`ChainSpec`] described by the file. `base_path` is the directory the paths
+    /// of the file are relative to.
+    pub fn into_chain_spec(
+        self,
+        base_path: impl AsRef<Path>,
+    ) -> Result<ChainSpec, ChainSpecFileError> {
+        if self.version != CURRENT_CHAIN_SPEC_VERSION {
+            return Err(ChainSpecFileError::UnsupportedVersion(self.version));
+        }
+
+        let base_path = base_path.as_ref();
+
+        let mut json = match &self.genesis {
+            Some(path) => GenesisJson::load(base_path.join(path))?,
+            None => GenesisJson::default(),
+        };
+
+        json.classes.extend(self.classes.into_iter().map(|path| GenesisClassJson {
+            class: PathOrFullArtifact::Path(path),
+            class_hash: None,
+            name: None,
+        }));
+        json.resolve_class_artifacts(base_path)?;
+
+        let mut genesis = if self.genesis.is_some() {
+            Genesis::try_from(json)?
+        } else {
+            // the default genesis comes with the fee token and UDC classes
+            let mut genesis = Genesis::default();
+            genesis.classes.extend(Genesis::try_from(json)?.classes);
+            genesis
+        };
+
+        genesis.gas_prices =
+            GasPrices { eth: self.gas_prices.eth.into(), strk: self.gas_prices.strk.into() };
+
+        let chain = ChainSpec {
+            genesis,
+            id: self.id,
+            fee_contracts: self.fee_contracts,
+            settlement: self.settlement,
+            version: CURRENT_STARKNET_VERSION,
+        };
+
+        validate(&chain)?;
+        Ok(chain)
+    }
+}
+
+impl ChainSpec {
+    /// Loads and validates the chain spec from the chain spec file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ChainSpecFileError> {
+        let path = path.as_ref();
+        let file = ChainSpecFile::read(path)?;
+        file.into_chain_spec(path.parent().unwrap_or(Path::new("")))
+    }
+}
+
+/// Checks that the chain described by `chain` can be started.
+fn validate(chain: &ChainSpec) -> Result<(), ChainSpecFileError> {
+    // the default fee tokens are only deployed at their default addresses, so fee tokens at other
+    // addresses must be allocated in the genesis
+    let FeeContracts { eth, strk } = chain.fee_contracts;
+    for (address, default) in
+        [(eth, DEFAULT_ETH_FEE_TOKEN_ADDRESS), (strk, DEFAULT_STRK_FEE_TOKEN_ADDRESS)]
+    {
+        if address != default && !chain.genesis.allocations.contains_key(&address) {
+            return Err(ChainSpecFileError::MissingFeeContract(address));
+        }
+    }
+
+    if chain.genesis.gas_prices.eth == 0 {
+        return Err(ChainSpecFileError::ZeroGasPrice("ETH"));
+    }
+
+    if chain.genesis.gas_prices.strk == 0 {
+        return Err(ChainSpecFileError::ZeroGasPrice("STRK"));
+    }
+
+    if let Some(settlement) = &chain.settlement {
+        let scheme = settlement.rpc_url().scheme();
+        if scheme != "http" && scheme != "https" {
+            return Err(ChainSpecFileError::InvalidSettlementUrl(scheme.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::address;
+
+    fn gas_prices() -> GasPricesFile {
+        GasPricesFile { eth: 100, strk: 200 }
+    }
+
+    #[test]
+    fn write_and_load_chain_spec() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.toml");
+
+        let mut file = ChainSpecFile::new(ChainId::parse("APPCHAIN").unwrap(), gas_prices());
+        file.settlement = Some(SettlementLayer::Starknet {
+            rpc_url: "http://localhost:5050".parse().unwrap(),
+            core_contract: address!("0x1234"),
+        });
+        file.write(&path).unwrap();
+
+        assert_eq!(ChainSpecFile::read(&path).unwrap(), file);
+
+        let chain = ChainSpec::load(&path).unwrap();
+        assert_eq!(chain.id, ChainId::parse("APPCHAIN").unwrap());
+        assert_eq!(chain.genesis.gas_prices, GasPrices { eth: 100, strk: 200 });
+        assert_eq!(chain.settlement, file.settlement);
+        assert_eq!(chain.fee_contracts, file.fee_contracts);
+        // the default genesis classes are kept
+        assert_eq!(chain.genesis.classes.len(), Genesis::default().classes.len());
+    }
+
+    #[test]
+    fn parse_chain_spec() {
+        let content = r#"
+            version = 1
+            id = "0x1337"
+
+            [fee-contracts]
+            eth = "0x49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"
+            strk = "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"
+
+            [gas-prices]
+            eth = 1
+            strk = 2
+
+            [settlement]
+            kind = "ethereum"
+            rpc-url = "http://localhost:8545"
+            core-contract = "0x5fbdb2315678afecb367f032d93f642f64180aa3"
+        "#;
+
+        let file: ChainSpecFile = toml::from_str(content).unwrap();
+        assert_eq!(file.id, ChainId::Id(felt!("0x1337")));
+        assert_eq!(file.gas_prices, GasPricesFile { eth: 1, strk: 2 });
+        assert!(matches!(file.settlement, Some(SettlementLayer::Ethereum { .. })));
+    }
+
+    #[test]
+    fn invalid_chain_spec() {
+        let id = ChainId::parse("APPCHAIN").unwrap();
+
+        let mut file = ChainSpecFile::new(id, gas_prices());
+        file.version = 2;
+        let err = file.into_chain_spec("").unwrap_err();
+        assert!(matches!(err, ChainSpecFileError::UnsupportedVersion(2)));
+
+        let mut file = ChainSpecFile::new(id, gas_prices());
+        file.fee_contracts.eth = address!("0x1");
+        let err = file.into_chain_spec("").unwrap_err();
+        assert!(matches!(err, ChainSpecFileError::MissingFeeContract(_)));
+
+        let file = ChainSpecFile::new(id, GasPricesFile { eth: 0, strk: 1 });
+        let err = file.into_chain_spec("").unwrap_err();
+        assert!(matches!(err, ChainSpecFileError::ZeroGasPrice("ETH")));
+
+        let mut file = ChainSpecFile::new(id, gas_prices());
+        file.settlement = Some(SettlementLayer::Starknet {
+            rpc_url: "ws://localhost:5050".parse().unwrap(),
+            core_contract: address!("0x1234"),
+        });
+        let err = file.into_chain_spec("").unwrap_err();
+        assert!(matches!(err, ChainSpecFileError::InvalidSettlementUrl(_)));
+    }
+}
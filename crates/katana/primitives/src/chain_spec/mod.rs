@@ -1,9 +1,13 @@
+#[cfg(feature = "serde")]
+pub mod file;
+
 use std::collections::BTreeMap;
 
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use lazy_static::lazy_static;
 use starknet::core::utils::cairo_short_string_to_felt;
 use starknet_crypto::Felt;
+use url::Url;
 
 use crate::block::{Block, Header};
 use crate::chain::ChainId;
@@ -12,9 +16,9 @@ use crate::contract::ContractAddress;
 use crate::da::L1DataAvailabilityMode;
 use crate::genesis::allocation::{DevAllocationsGenerator, GenesisAllocation};
 use crate::genesis::constant::{
-    get_fee_token_balance_base_storage_address, DEFAULT_ACCOUNT_CLASS_PUBKEY_STORAGE_SLOT,
-    DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CASM, DEFAULT_LEGACY_ERC20_CLASS_HASH,
-    DEFAULT_LEGACY_UDC_CASM, DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
+    get_fee_token_balance_base_storage_address, DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+    DEFAULT_LEGACY_ERC20_CASM, DEFAULT_LEGACY_ERC20_CLASS_HASH, DEFAULT_LEGACY_UDC_CASM,
+    DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
     DEFAULT_PREFUNDED_ACCOUNT_BALANCE, DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS,
     ERC20_DECIMAL_STORAGE_SLOT, ERC20_NAME_STORAGE_SLOT, ERC20_SYMBOL_STORAGE_SLOT,
     ERC20_TOTAL_SUPPLY_STORAGE_SLOT,
@@ -37,12 +41,15 @@ pub struct ChainSpec {
     pub fee_contracts: FeeContracts,
     /// The protocol version.
     pub version: ProtocolVersion,
+    /// The layer the chain settles on, if any.
+    pub settlement: Option<SettlementLayer>,
 }
 
 /// Tokens that can be used for transaction fee payments in the chain. As
 /// supported on Starknet.
 // TODO: include both l1 and l2 addresses
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FeeContracts {
     /// L2 ETH fee token address. Used for paying pre-V3 transactions.
     pub eth: ContractAddress,
@@ -50,6 +57,37 @@ pub struct FeeContracts {
     pub strk: ContractAddress,
 }
 
+/// The layer a chain settles on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", rename_all = "lowercase", rename_all_fields = "kebab-case")
+)]
+pub enum SettlementLayer {
+    Ethereum {
+        /// The RPC url of the Ethereum network.
+        rpc_url: Url,
+        /// The address of the core contract of the chain.
+        core_contract: Address,
+    },
+    Starknet {
+        /// The RPC url of the Starknet network.
+        rpc_url: Url,
+        /// The address of the core contract of the chain.
+        core_contract: ContractAddress,
+    },
+}
+
+impl SettlementLayer {
+    /// Returns the RPC url of the settlement network.
+    pub fn rpc_url(&self) -> &Url {
+        match self {
+            Self::Ethereum { rpc_url, .. } | Self::Starknet { rpc_url, .. } => rpc_url,
+        }
+    }
+}
+
 impl ChainSpec {
     pub fn block(&self) -> Block {
         let header = Header {
@@ -74,37 +112,7 @@ impl ChainSpec {
 
     // this method will include the the ETH and STRK fee tokens, and the UDC
     pub fn state_updates(&self) -> StateUpdatesWithDeclaredClasses {
-        let mut states = StateUpdatesWithDeclaredClasses::default();
-
-        for (class_hash, class) in &self.genesis.classes {
-            let class_hash = *class_hash;
-
-            states.state_updates.declared_classes.insert(class_hash, class.compiled_class_hash);
-            states.declared_compiled_classes.insert(class_hash, class.casm.as_ref().clone());
-
-            if let Some(sierra) = &class.sierra {
-                states.declared_sierra_classes.insert(class_hash, sierra.as_ref().clone());
-            }
-        }
-
-        for (address, alloc) in &self.genesis.allocations {
-            let address = *address;
-
-            if let Some(hash) = alloc.class_hash() {
-                states.state_updates.deployed_contracts.insert(address, hash);
-            }
-
-            if let Some(nonce) = alloc.nonce() {
-                states.state_updates.nonce_updates.insert(address, nonce);
-            }
-
-            let mut storage = alloc.storage().cloned().unwrap_or_default();
-            if let Some(pub_key) = alloc.public_key() {
-                storage.insert(DEFAULT_ACCOUNT_CLASS_PUBKEY_STORAGE_SLOT, pub_key);
-            }
-
-            states.state_updates.storage_updates.insert(address, storage);
-        }
+        let mut states = self.genesis.state_updates();
 
         //-- Fee tokens
         add_default_fee_tokens(&mut states, &self.genesis);
@@ -141,7 +149,7 @@ lazy_static! {
         let id = ChainId::parse("KATANA").unwrap();
         let genesis = Genesis::default();
         let fee_contracts = FeeContracts { eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS, strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS };
-        ChainSpec { id, genesis, fee_contracts, version: CURRENT_STARKNET_VERSION }
+        ChainSpec { id, genesis, fee_contracts, version: CURRENT_STARKNET_VERSION, settlement: None }
     };
 }
 
@@ -184,6 +192,11 @@ fn add_fee_token(
     class_hash: ClassHash,
     allocations: &BTreeMap<ContractAddress, GenesisAllocation>,
 ) {
+    // a fee token allocated explicitly in the genesis (eg, by a state dump) is kept as it is
+    if allocations.contains_key(&address) {
+        return;
+    }
+
     let mut storage = BTreeMap::new();
     let mut total_supply = U256::ZERO;
 
@@ -352,6 +365,7 @@ mod tests {
         let chain_spec = ChainSpec {
             id: ChainId::SEPOLIA,
             version: CURRENT_STARKNET_VERSION,
+            settlement: None,
             genesis: Genesis {
                 classes,
                 allocations: BTreeMap::from(allocations.clone()),
@@ -2,7 +2,15 @@ use num_bigint::BigUint;
 use num_traits::Num;
 
 use super::eip4844::{BLOB_LEN, BLS_MODULUS, GENERATOR};
+use super::encoding::encode_state_updates;
 use super::math::{fft, ifft};
+use crate::state::StateUpdates;
+
+/// The size of a field element of a blob, in bytes.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
+/// The size of a blob, in bytes.
+pub const BYTES_PER_BLOB: usize = BLOB_LEN * BYTES_PER_FIELD_ELEMENT;
 
 /// Recovers the original data from a given blob.
 ///
@@ -39,3 +47,33 @@ pub fn transform(data: Vec<BigUint>) -> Vec<BigUint> {
 
     fft(data, xs, &BLS_MODULUS)
 }
+
+/// Encodes the state updates into the blobs that would be posted to the settlement chain.
+///
+/// The encoded state updates are split into chunks of [`BLOB_LEN`] field elements, the last one
+/// being padded with zeros, and each chunk is then converted to its evaluation form.
+pub fn from_state_updates(state_updates: StateUpdates) -> Vec<Vec<BigUint>> {
+    let encoded = encode_state_updates(state_updates);
+
+    encoded
+        .chunks(BLOB_LEN)
+        .map(|chunk| {
+            let mut data = chunk.to_vec();
+            data.resize(BLOB_LEN, BigUint::ZERO);
+            transform(data)
+        })
+        .collect()
+}
+
+/// Serializes the field elements of a blob, each as [`BYTES_PER_FIELD_ELEMENT`] big-endian bytes.
+pub fn to_bytes(data: &[BigUint]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(data.len() * BYTES_PER_FIELD_ELEMENT);
+
+    for element in data {
+        let be = element.to_bytes_be();
+        bytes.resize(bytes.len() + BYTES_PER_FIELD_ELEMENT - be.len(), 0);
+        bytes.extend_from_slice(&be);
+    }
+
+    bytes
+}
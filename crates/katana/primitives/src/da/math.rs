@@ -5,17 +5,48 @@ lazy_static! {
     pub static ref TWO: BigUint = 2u32.to_biguint().unwrap();
 }
 
+/// Performs the Fast Fourier Transform on a vector of `BigUint`.
+///
+/// This is the inverse of [`ifft`], the evaluation points must be ordered such that every odd
+/// point is the opposite of the point preceding it (eg. the bit-reversed powers of a root of
+/// unity).
+///
+/// # Arguments
+///
+/// * `elements` - A vector of `BigUint` representing the coefficients of the polynomial.
+/// * `xs` - A vector of `BigUint` representing the evaluation points.
+/// * `p` - The modulus as a `BigUint`.
+///
+/// # Returns
+///
+/// A vector of `BigUint` representing the evaluations of the polynomial at `xs`.
 pub fn fft(elements: Vec<BigUint>, xs: Vec<BigUint>, p: &BigUint) -> Vec<BigUint> {
-    let n = elements.len();
-    let mut transform = vec![BigUint::ZERO; n];
+    // Base case: a constant polynomial evaluates to itself
+    if elements.len() == 1 {
+        return elements;
+    }
+
+    let n = elements.len() / 2;
+    let mut even = Vec::with_capacity(n);
+    let mut odd = Vec::with_capacity(n);
+    let mut new_xs = Vec::with_capacity(n);
+
+    for i in 0..n {
+        even.push(elements[2 * i].clone());
+        odd.push(elements[2 * i + 1].clone());
+        new_xs.push(xs[2 * i].modpow(&TWO.clone(), p));
+    }
+
+    // Recursive calls
+    let even = fft(even, new_xs.clone(), p);
+    let odd = fft(odd, new_xs, p);
 
+    // f(x) = even(x^2) + x * odd(x^2) and f(-x) = even(x^2) - x * odd(x^2)
+    let mut transform = Vec::with_capacity(elements.len());
     for i in 0..n {
-        for j in (0..n).rev() {
-            let value = &transform[i] * &xs[i];
-            let value = value + &elements[j];
-            let value = value % p;
-            transform[i] = value;
-        }
+        let value = &xs[2 * i] * &odd[i] % p;
+        transform.push((&even[i] + &value) % p);
+        transform.push((&even[i] + p - value) % p);
     }
 
     transform
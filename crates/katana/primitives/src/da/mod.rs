@@ -4,6 +4,8 @@ pub mod encoding;
 pub mod math;
 pub mod serde;
 
+use alloy_primitives::{Bytes, FixedBytes, B256};
+
 /// L1 da mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "arbitrary", derive(::arbitrary::Arbitrary))]
@@ -24,3 +26,22 @@ pub enum DataAvailabilityMode {
     #[serde(rename = "L2")]
     L2,
 }
+
+/// A KZG commitment or proof, as a compressed BLS12-381 G1 point.
+pub type KzgBytes = FixedBytes<48>;
+
+/// A blob of the state diff of a block, as it would be posted to the settlement chain in an
+/// EIP-4844 transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(::arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct StateDiffBlob {
+    /// The blob data, ie. the evaluation form of the encoded state diff. See [`blob::transform`].
+    pub data: Bytes,
+    /// The KZG commitment to the blob.
+    pub commitment: KzgBytes,
+    /// The KZG proof of the blob against its commitment.
+    pub proof: KzgBytes,
+    /// The versioned hash of the commitment, as referenced by the blob transaction.
+    pub versioned_hash: B256,
+}
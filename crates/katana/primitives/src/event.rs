@@ -1,6 +1,8 @@
 use core::fmt;
 use std::num::ParseIntError;
 
+use crate::contract::ContractAddress;
+use crate::receipt::Event;
 use crate::Felt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -12,6 +14,51 @@ pub struct OrderedEvent {
     pub data: Vec<Felt>,
 }
 
+/// An object to specify how events should be filtered.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EventFilter {
+    /// The contract address to filter by.
+    ///
+    /// If `None`, all events are considered. If `Some`, only events emitted by the specified
+    /// contract are considered.
+    pub address: Option<ContractAddress>,
+    /// The keys to filter by.
+    ///
+    /// Per key (by position), designate the possible values to be matched for events to be
+    /// returned. An empty list designates 'any' value.
+    pub keys: Option<Vec<Vec<Felt>>>,
+}
+
+impl EventFilter {
+    /// Returns `true` if the event matches the filter.
+    pub fn matches(&self, event: &Event) -> bool {
+        // Check if the event matches the address filter
+        if !self.address.map_or(true, |addr| addr == event.from_address) {
+            return false;
+        }
+
+        // Check if the event matches the keys filter
+        match &self.keys {
+            None => true,
+            Some(filters) => filters.iter().enumerate().all(|(i, keys)| {
+                // Lets say we want to filter events which are either named `Event1` or `Event2`
+                // and custom key `0x1` or `0x2` Filter:
+                // [[sn_keccak("Event1"), sn_keccak("Event2")], ["0x1", "0x2"]]
+
+                // This checks: number of keys in event >= number of keys in filter (we check > i
+                // and not >= i because i is zero indexed) because otherwise this event doesn't
+                // contain all the keys we requested
+                event.keys.len() > i &&
+                    // This checks: Empty array desginates 'any' value
+                    (keys.is_empty()
+                    ||
+                    // This checks: If this events i'th value is one of the requested value in filter_keys[i]
+                    keys.contains(&event.keys[i]))
+            }),
+        }
+    }
+}
+
 /// Represents a continuation token for implementing paging in event queries.
 ///
 /// This struct stores the necessary information to resume fetching events
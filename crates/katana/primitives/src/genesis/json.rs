@@ -34,9 +34,10 @@ use super::constant::{
 };
 use super::{Genesis, GenesisAllocation};
 use crate::block::{BlockHash, BlockNumber, GasPrices};
-use crate::class::{ClassHash, CompiledClass, SierraClass};
+use crate::class::{ClassHash, CompiledClass, FlattenedSierraClass, SierraClass};
 use crate::contract::{ContractAddress, StorageKey, StorageValue};
 use crate::genesis::GenesisClass;
+use crate::state::StateUpdatesWithDeclaredClasses;
 use crate::utils::class::{parse_compiled_class_v1, parse_deprecated_compiled_class};
 use crate::Felt;
 
@@ -259,6 +260,65 @@ impl GenesisJson {
         Ok(genesis)
     }
 
+    /// Extends the genesis with `states`, so that a chain started from it begins with that state.
+    ///
+    /// Every class in `states` is embedded as a full artifact along with its class hash, and every
+    /// contract is added to `contracts`, unless it's already in `accounts` in which case only its
+    /// nonce and storage are updated.
+    pub fn extend_with_states(
+        &mut self,
+        states: StateUpdatesWithDeclaredClasses,
+    ) -> Result<(), GenesisJsonError> {
+        let StateUpdatesWithDeclaredClasses {
+            state_updates,
+            mut declared_sierra_classes,
+            declared_compiled_classes,
+        } = states;
+
+        for (class_hash, class) in declared_compiled_classes {
+            let artifact = match class {
+                CompiledClass::Deprecated(class) => serde_json::to_value(class)?,
+                CompiledClass::Class(_) => {
+                    let sierra = declared_sierra_classes
+                        .remove(&class_hash)
+                        .ok_or(GenesisJsonError::MissingClass(class_hash))?;
+                    sierra_class_artifact(sierra)?
+                }
+            };
+
+            let class = PathOrFullArtifact::Artifact(artifact);
+            self.classes.push(GenesisClassJson { class, class_hash: Some(class_hash), name: None });
+        }
+
+        let mut storage_updates = state_updates.storage_updates;
+        let mut nonce_updates = state_updates.nonce_updates;
+
+        for (address, class_hash) in state_updates.deployed_contracts {
+            let storage = storage_updates.remove(&address);
+            let nonce = nonce_updates.remove(&address);
+
+            if let Some(account) = self.accounts.get_mut(&address) {
+                account.class = Some(ClassNameOrHash::Hash(class_hash));
+                account.storage = storage;
+                account.nonce = nonce;
+            } else {
+                let class = Some(ClassNameOrHash::Hash(class_hash));
+                let contract = GenesisContractJson { class, balance: None, nonce, storage };
+                self.contracts.insert(address, contract);
+            }
+        }
+
+        // storage of addresses with no contract deployed on them, eg set with `dev_setStorageAt`
+        for (address, storage) in storage_updates {
+            let nonce = nonce_updates.remove(&address);
+            let contract =
+                GenesisContractJson { class: None, balance: None, nonce, storage: Some(storage) };
+            self.contracts.insert(address, contract);
+        }
+
+        Ok(())
+    }
+
     /// Resolves the paths of the class files to their corresponding class definitions. The
     /// `base_path` is used to calculate the paths of the class files, which are relative to the
     /// JSON file itself.
@@ -488,6 +548,23 @@ impl FromStr for GenesisJson {
     }
 }
 
+/// Rebuilds the JSON artifact of a Sierra class from its flattened form. The debug info isn't part
+/// of the flattened class, so it is left empty.
+fn sierra_class_artifact(class: FlattenedSierraClass) -> Result<Value, GenesisJsonError> {
+    let abi: Value = serde_json::from_str(&class.abi)?;
+    Ok(serde_json::json!({
+        "sierra_program": class.sierra_program,
+        "sierra_program_debug_info": {
+            "type_names": [],
+            "libfunc_names": [],
+            "user_func_names": []
+        },
+        "contract_class_version": class.contract_class_version,
+        "entry_points_by_type": class.entry_points_by_type,
+        "abi": abi
+    }))
+}
+
 /// A helper function to conveniently resolve the artifacts in the genesis json if they
 /// weren't already resolved and then serialize it to base64 encoding.
 ///
@@ -1022,4 +1099,34 @@ mod tests {
             res.unwrap_err().to_string().contains(&format!("Class name '{name}' already exists"))
         )
     }
+
+    #[test]
+    fn genesis_from_dumped_states() {
+        let states = crate::chain_spec::DEV.state_updates();
+
+        let mut json = GenesisJson::default();
+        json.extend_with_states(states.clone()).unwrap();
+
+        // the dumped json must survive a round trip through its serialized form
+        let json = GenesisJson::from_str(&serde_json::to_string(&json).unwrap()).unwrap();
+        let actual = Genesis::try_from(json).unwrap().state_updates();
+
+        for (hash, compiled_hash) in &states.state_updates.declared_classes {
+            assert_eq!(actual.state_updates.declared_classes.get(hash), Some(compiled_hash));
+        }
+
+        for (hash, class) in &states.declared_sierra_classes {
+            assert_eq!(actual.declared_sierra_classes.get(hash), Some(class));
+        }
+
+        assert_eq!(
+            actual.state_updates.deployed_contracts,
+            states.state_updates.deployed_contracts
+        );
+        assert_eq!(actual.state_updates.nonce_updates, states.state_updates.nonce_updates);
+
+        for (address, storage) in &states.state_updates.storage_updates {
+            assert_eq!(actual.state_updates.storage_updates.get(address), Some(storage));
+        }
+    }
 }
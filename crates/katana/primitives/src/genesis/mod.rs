@@ -13,7 +13,8 @@ use serde::Serialize;
 
 use self::allocation::{GenesisAccountAlloc, GenesisAllocation, GenesisContractAlloc};
 use self::constant::{
-    DEFAULT_ACCOUNT_CLASS_CASM, DEFAULT_ACCOUNT_CLASS_HASH, DEFAULT_ACCOUNT_COMPILED_CLASS_HASH,
+    DEFAULT_ACCOUNT_CLASS_CASM, DEFAULT_ACCOUNT_CLASS_HASH,
+    DEFAULT_ACCOUNT_CLASS_PUBKEY_STORAGE_SLOT, DEFAULT_ACCOUNT_COMPILED_CLASS_HASH,
     DEFAULT_LEGACY_ERC20_CASM, DEFAULT_LEGACY_ERC20_CLASS_HASH,
     DEFAULT_LEGACY_ERC20_COMPILED_CLASS_HASH, DEFAULT_LEGACY_UDC_CASM,
     DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_COMPILED_CLASS_HASH,
@@ -21,6 +22,7 @@ use self::constant::{
 use crate::block::{BlockHash, BlockNumber, GasPrices};
 use crate::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use crate::contract::ContractAddress;
+use crate::state::StateUpdatesWithDeclaredClasses;
 use crate::Felt;
 
 #[derive(Clone, Serialize, PartialEq, Eq)]
@@ -76,6 +78,46 @@ impl Genesis {
         self.allocations.extend(allocs);
     }
 
+    /// Returns the state updates of the classes and the allocations of the genesis.
+    ///
+    /// Unlike [`ChainSpec::state_updates`](crate::chain_spec::ChainSpec::state_updates), this
+    /// doesn't include the default fee tokens and UDC.
+    pub fn state_updates(&self) -> StateUpdatesWithDeclaredClasses {
+        let mut states = StateUpdatesWithDeclaredClasses::default();
+
+        for (class_hash, class) in &self.classes {
+            let class_hash = *class_hash;
+
+            states.state_updates.declared_classes.insert(class_hash, class.compiled_class_hash);
+            states.declared_compiled_classes.insert(class_hash, class.casm.as_ref().clone());
+
+            if let Some(sierra) = &class.sierra {
+                states.declared_sierra_classes.insert(class_hash, sierra.as_ref().clone());
+            }
+        }
+
+        for (address, alloc) in &self.allocations {
+            let address = *address;
+
+            if let Some(hash) = alloc.class_hash() {
+                states.state_updates.deployed_contracts.insert(address, hash);
+            }
+
+            if let Some(nonce) = alloc.nonce() {
+                states.state_updates.nonce_updates.insert(address, nonce);
+            }
+
+            let mut storage = alloc.storage().cloned().unwrap_or_default();
+            if let Some(pub_key) = alloc.public_key() {
+                storage.insert(DEFAULT_ACCOUNT_CLASS_PUBKEY_STORAGE_SLOT, pub_key);
+            }
+
+            states.state_updates.storage_updates.insert(address, storage);
+        }
+
+        states
+    }
+
     /// Returns an iterator over the generic (non-account) contracts.
     pub fn contracts(&self) -> impl Iterator<Item = &GenesisContractAlloc> {
         self.allocations.values().filter_map(|allocation| {
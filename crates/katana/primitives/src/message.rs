@@ -1,6 +1,15 @@
+use alloy_primitives::B256;
+
 use crate::contract::ContractAddress;
+use crate::transaction::TxHash;
 use crate::Felt;
 
+/// The hash of a transaction of the settlement chain.
+pub type L1TxHash = B256;
+
+/// The hash of an L1 to L2 message.
+pub type L1ToL2MessageHash = B256;
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "arbitrary", derive(::arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -10,3 +19,36 @@ pub struct OrderedL2ToL1Message {
     pub to_address: Felt,
     pub payload: Vec<Felt>,
 }
+
+/// An L1 to L2 message gathered from the settlement chain.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "arbitrary", derive(::arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct L1ToL2Message {
+    /// The hash of the settlement chain transaction that sent the message.
+    pub l1_tx_hash: L1TxHash,
+    /// The hash of the message.
+    pub message_hash: L1ToL2MessageHash,
+    /// The hash of the L1 handler transaction consuming the message on the local chain.
+    pub tx_hash: TxHash,
+    /// The progress of the cancellation of the message on the settlement chain, if any.
+    pub cancellation: Option<MessageCancellation>,
+    /// Whether the L1 handler transaction was dropped without being committed in a block, either
+    /// because its execution failed or because it was removed from the pool.
+    pub dropped: bool,
+}
+
+/// The progress of the cancellation of an L1 to L2 message by its sender.
+///
+/// See <https://docs.starknet.io/architecture-and-concepts/network-architecture/messaging-mechanism/#l2-l1_message_cancellation>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(::arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
+pub enum MessageCancellation {
+    /// The sender started the cancellation, which can be completed once the cancellation delay
+    /// has elapsed.
+    Started,
+    /// The message was cancelled and can't be consumed anymore.
+    Cancelled,
+}
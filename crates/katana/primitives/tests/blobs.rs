@@ -1,4 +1,5 @@
 use anyhow::Result;
+use katana_primitives::da::blob::BYTES_PER_BLOB;
 use katana_primitives::da::eip4844::BLOB_LEN;
 use katana_primitives::da::encoding::encode_state_updates;
 use katana_primitives::da::serde::parse_str_to_blob_data;
@@ -40,3 +41,22 @@ fn parse_blobs_rt(#[case] blob: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[rstest]
+#[case("./tests/test-data/blobs/block_636262.txt")]
+#[case("./tests/test-data/blobs/block_636263.txt")]
+#[case("./tests/test-data/blobs/block_636264.txt")]
+fn state_updates_to_blobs(#[case] blob: &str) -> Result<()> {
+    let fftd = read(blob);
+    let state_update = encoding::decode_state_updates(&blob::recover(fftd.clone()))?;
+
+    let blobs = blob::from_state_updates(state_update);
+    assert_eq!(blobs.len(), 1);
+    similar_asserts::assert_eq!(fftd, blobs[0]);
+
+    let bytes = blob::to_bytes(&blobs[0]);
+    assert_eq!(bytes.len(), BYTES_PER_BLOB);
+    assert_eq!(parse_str_to_blob_data(&alloy_primitives::hex::encode(&bytes)), fftd);
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_rpc_types::admin::TxPolicy;
+
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "admin"))]
+pub trait AdminApi {
+    /// Returns the policy that the transactions sent to the node must comply with, if any.
+    #[method(name = "getPolicy")]
+    async fn get_policy(&self) -> RpcResult<Option<TxPolicy>>;
+
+    /// Reloads the transaction policy from the policy file the node was started with, and
+    /// returns the new policy.
+    #[method(name = "reloadPolicy")]
+    async fn reload_policy(&self) -> RpcResult<TxPolicy>;
+}
@@ -1,7 +1,10 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::transaction::TxHash;
 use katana_primitives::Felt;
 use katana_rpc_types::account::Account;
+use katana_rpc_types::profile::{Profile, ProfileFormat, TxProfile};
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "dev"))]
 #[cfg_attr(feature = "client", rpc(client, server, namespace = "dev"))]
@@ -22,6 +25,68 @@ pub trait DevApi {
     async fn set_storage_at(&self, contract_address: Felt, key: Felt, value: Felt)
     -> RpcResult<()>;
 
+    /// Sets the ETH and STRK fee token balances of an account.
+    #[method(name = "setBalance")]
+    async fn set_balance(&self, address: Felt, amount: Felt) -> RpcResult<()>;
+
+    /// Sets the nonce of an account.
+    #[method(name = "setNonce")]
+    async fn set_nonce(&self, address: Felt, nonce: Felt) -> RpcResult<()>;
+
+    /// Sets the class hash of a contract.
+    #[method(name = "setClassHashAt")]
+    async fn set_class_hash_at(&self, address: Felt, class_hash: Felt) -> RpcResult<()>;
+
+    /// Skips the signature validation of transactions sent by `address`, allowing them to be sent
+    /// without the account's private key.
+    #[method(name = "impersonateAccount")]
+    async fn impersonate_account(&self, address: Felt) -> RpcResult<()>;
+
+    /// Stops impersonating `address`.
+    #[method(name = "stopImpersonatingAccount")]
+    async fn stop_impersonating_account(&self, address: Felt) -> RpcResult<()>;
+
+    /// Takes a snapshot of the current chain and returns its id, which can later be passed to
+    /// `dev_revert` to restore the chain to its state at the time of the snapshot.
+    #[method(name = "snapshot")]
+    async fn snapshot(&self) -> RpcResult<u64>;
+
+    /// Reverts the chain to the snapshot with the given id. Returns `false` if the snapshot
+    /// doesn't exist.
+    ///
+    /// The snapshot, and every snapshot taken after it, are removed once reverted to.
+    #[method(name = "revert")]
+    async fn revert(&self, id: u64) -> RpcResult<bool>;
+
+    /// Removes the `depth` most recent blocks from the chain, reverting their transactions and
+    /// state changes. Returns the number of the new latest block.
+    ///
+    /// The pending block and the transactions in the pool are discarded.
+    #[method(name = "reorg")]
+    async fn reorg(&self, depth: u64) -> RpcResult<u64>;
+
+    /// Returns the latest state of the chain - its contracts, storage, nonces and classes - in the
+    /// genesis file format. The returned value can be passed to `dev_loadState`, or used as the
+    /// genesis file of another node.
+    #[method(name = "dumpState")]
+    async fn dump_state(&self) -> RpcResult<GenesisJson>;
+
+    /// Loads a state returned by `dev_dumpState` on top of the latest state of the chain.
+    ///
+    /// The classes and contracts of the loaded state are added to the chain, overwriting the
+    /// storage slots and nonces of existing contracts. Other contracts are left untouched.
+    #[method(name = "loadState")]
+    async fn load_state(&self, state: GenesisJson) -> RpcResult<()>;
+
     #[method(name = "predeployedAccounts")]
     async fn predeployed_accounts(&self) -> RpcResult<Vec<Account>>;
+
+    /// Returns the resources used by each call of an executed transaction - its Cairo steps,
+    /// builtins, syscalls and gas - in the given format. Defaults to the call tree format.
+    #[method(name = "profileTransaction")]
+    async fn profile_transaction(
+        &self,
+        transaction_hash: TxHash,
+        format: Option<ProfileFormat>,
+    ) -> RpcResult<Profile<TxProfile>>;
 }
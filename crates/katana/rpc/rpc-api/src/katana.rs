@@ -0,0 +1,31 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_primitives::block::BlockIdOrTag;
+use katana_rpc_types::da::BlockStateDiffBlob;
+use katana_rpc_types::profile::{BlockProfile, Profile, ProfileFormat};
+
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "katana"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "katana"))]
+pub trait KatanaApi {
+    /// Returns the resources used by each call of the transactions of a block, in the given
+    /// format. Defaults to the call tree format.
+    ///
+    /// The pending tag resolves to the latest block, as the transactions of the pending block
+    /// aren't stored yet.
+    #[method(name = "profileBlock")]
+    async fn profile_block(
+        &self,
+        block_id: BlockIdOrTag,
+        format: Option<ProfileFormat>,
+    ) -> RpcResult<Profile<BlockProfile>>;
+
+    /// Returns the EIP-4844 blobs of the state diff of a block, along with their KZG commitments
+    /// and proofs, ie. the exact payload that would be posted to the settlement chain.
+    ///
+    /// The blobs are only available if the node computes them, see the `--state-diff-blobs` flag.
+    #[method(name = "getBlockStateDiffBlob")]
+    async fn get_block_state_diff_blob(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<BlockStateDiffBlob>;
+}
@@ -1,4 +1,7 @@
+pub mod admin;
 pub mod dev;
+pub mod katana;
 pub mod saya;
 pub mod starknet;
 pub mod torii;
+pub mod txpool;
@@ -3,22 +3,24 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use katana_primitives::block::{BlockIdOrTag, BlockNumber};
+use katana_primitives::message::L1TxHash;
 use katana_primitives::transaction::TxHash;
 use katana_primitives::Felt;
 use katana_rpc_types::block::{
-    BlockHashAndNumber, BlockTxCount, MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes,
-    MaybePendingBlockWithTxs,
+    BlockHashAndNumber, BlockHeader, BlockTxCount, MaybePendingBlockWithReceipts,
+    MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
 };
-use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
-use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::event::{EmittedEvent, EventFilterWithPage, EventsPage};
+use katana_rpc_types::message::{MessageStatus, MsgFromL1};
 use katana_rpc_types::receipt::TxReceiptWithBlockInfo;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::{
     BroadcastedDeclareTx, BroadcastedDeployAccountTx, BroadcastedInvokeTx, BroadcastedTx,
-    DeclareTxResult, DeployAccountTxResult, InvokeTxResult, Tx,
+    DeclareTxResult, DeployAccountTxResult, InvokeTxResult, PendingTx, Tx, TxStatusUpdate,
 };
+use katana_rpc_types::trie::{ContractStorageKeys, GetStorageProofResponse};
 use katana_rpc_types::{
-    ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlag,
+    v0_8, ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlag,
     SimulationFlagForEstimateFee, SyncingStatus,
 };
 use starknet::core::types::{
@@ -72,6 +74,16 @@ pub trait StarknetApi {
         block_id: BlockIdOrTag,
     ) -> RpcResult<FeltAsHex>;
 
+    /// Get merkle paths in one of the state tries: global state, classes, individual contract.
+    #[method(name = "getStorageProof")]
+    async fn get_storage_proof(
+        &self,
+        block_id: BlockIdOrTag,
+        class_hashes: Option<Vec<Felt>>,
+        contract_addresses: Option<Vec<Felt>>,
+        contracts_storage_keys: Option<Vec<ContractStorageKeys>>,
+    ) -> RpcResult<GetStorageProofResponse>;
+
     /// Gets the transaction status (possibly reflecting that the tx is still in the mempool, or
     /// dropped from it).
     #[method(name = "getTransactionStatus")]
@@ -150,6 +162,14 @@ pub trait StarknetApi {
         block_id: BlockIdOrTag,
     ) -> RpcResult<FeeEstimate>;
 
+    /// Given an L1 transaction hash, returns the statuses of the L1 handler transactions of the
+    /// messages it sent to L2, sorted by the hashes of the messages.
+    #[method(name = "getMessagesStatus")]
+    async fn get_messages_status(
+        &self,
+        transaction_hash: L1TxHash,
+    ) -> RpcResult<Vec<MessageStatus>>;
+
     /// Get the most recent accepted block number.
     #[method(name = "blockNumber")]
     async fn block_number(&self) -> RpcResult<BlockNumber>;
@@ -231,3 +251,185 @@ pub trait StarknetTraceApi {
         block_id: BlockIdOrTag,
     ) -> RpcResult<Vec<TransactionTraceWithHash>>;
 }
+
+/// WebSocket API.
+///
+/// Subscriptions are only available over WebSocket connections. Any subscription can be closed
+/// with `starknet_unsubscribe`.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "starknet"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "starknet"))]
+pub trait StarknetWsApi {
+    /// Creates a WebSocket stream which will fire events for new block headers.
+    ///
+    /// If `block_id` is provided, the headers of all the blocks starting from `block_id` up to the
+    /// latest block are sent first.
+    #[subscription(
+        name = "subscribeNewHeads" => "subscriptionNewHeads",
+        unsubscribe = "unsubscribeNewHeads",
+        item = BlockHeader
+    )]
+    fn subscribe_new_heads(&self, block_id: Option<BlockIdOrTag>);
+
+    /// Creates a WebSocket stream which will fire events for new Starknet events with applied
+    /// filters.
+    #[subscription(
+        name = "subscribeEvents" => "subscriptionEvents",
+        unsubscribe = "unsubscribeEvents",
+        item = EmittedEvent
+    )]
+    fn subscribe_events(
+        &self,
+        from_address: Option<Felt>,
+        keys: Option<Vec<Vec<Felt>>>,
+        block_id: Option<BlockIdOrTag>,
+    );
+
+    /// Creates a WebSocket stream which will fire events when a transaction status is updated.
+    #[subscription(
+        name = "subscribeTransactionStatus" => "subscriptionTransactionStatus",
+        unsubscribe = "unsubscribeTransactionStatus",
+        item = TxStatusUpdate
+    )]
+    fn subscribe_transaction_status(&self, transaction_hash: TxHash);
+
+    /// Creates a WebSocket stream which will fire events when a new pending transaction is added.
+    ///
+    /// Only the transaction hashes are sent unless `transaction_details` is `true`.
+    #[subscription(
+        name = "subscribePendingTransactions" => "subscriptionPendingTransactions",
+        unsubscribe = "unsubscribePendingTransactions",
+        item = PendingTx
+    )]
+    fn subscribe_pending_transactions(
+        &self,
+        transaction_details: Option<bool>,
+        sender_address: Option<Vec<Felt>>,
+    );
+
+    /// Closes a previously opened subscription.
+    ///
+    /// The subscription is looked up by its id, which is random and only known to the connection
+    /// that opened it. The `unsubscribe*` method of each subscription is also restricted to the
+    /// connection that opened it.
+    #[method(name = "unsubscribe")]
+    fn unsubscribe(&self, subscription_id: u64) -> RpcResult<bool>;
+}
+
+/// The methods of the 0.8 specification whose types differ from the 0.7 ones.
+///
+/// They are served under the `/rpc/v0_8` route with their `starknet_` names, which are mapped to
+/// the `starknetV0_8_` ones. The `starknetV0_8_` names can't be called directly. All the other
+/// methods are shared by both versions.
+#[rpc(server, namespace = "starknetV0_8")]
+pub trait StarknetApiV0_8 {
+    /// Returns the version of the Starknet JSON-RPC specification being used.
+    #[method(name = "specVersion")]
+    async fn spec_version(&self) -> RpcResult<String> {
+        Ok(v0_8::RPC_SPEC_VERSION.into())
+    }
+
+    /// Get block information with transaction hashes given the block id.
+    #[method(name = "getBlockWithTxHashes")]
+    async fn get_block_with_tx_hashes(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<v0_8::MaybePendingBlockWithTxHashes>;
+
+    /// Get block information with full transactions given the block id.
+    #[method(name = "getBlockWithTxs")]
+    async fn get_block_with_txs(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<v0_8::MaybePendingBlockWithTxs>;
+
+    /// Get block information with full transactions and receipts given the block id.
+    #[method(name = "getBlockWithReceipts")]
+    async fn get_block_with_receipts(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<v0_8::MaybePendingBlockWithReceipts>;
+
+    /// Get the details and status of a submitted transaction.
+    #[method(name = "getTransactionByHash")]
+    async fn get_transaction_by_hash(&self, transaction_hash: TxHash) -> RpcResult<v0_8::Tx>;
+
+    /// Get the details of a transaction by a given block id and index.
+    #[method(name = "getTransactionByBlockIdAndIndex")]
+    async fn get_transaction_by_block_id_and_index(
+        &self,
+        block_id: BlockIdOrTag,
+        index: u64,
+    ) -> RpcResult<v0_8::Tx>;
+
+    /// Get the transaction receipt by the transaction hash.
+    #[method(name = "getTransactionReceipt")]
+    async fn get_transaction_receipt(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<v0_8::TxReceiptWithBlockInfo>;
+
+    /// Estimate the fee for of StarkNet transactions.
+    #[method(name = "estimateFee")]
+    async fn estimate_fee(
+        &self,
+        request: Vec<v0_8::BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlagForEstimateFee>,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<v0_8::FeeEstimate>>;
+
+    /// Estimate the L2 fee of a message sent on L1.
+    #[method(name = "estimateMessageFee")]
+    async fn estimate_message_fee(
+        &self,
+        message: MsgFromL1,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<v0_8::FeeEstimate>;
+
+    /// Returns the execution trace of the transaction designated by the input hash.
+    #[method(name = "traceTransaction")]
+    async fn trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<v0_8::TxTrace>;
+
+    /// Simulates a list of transactions on the provided block.
+    #[method(name = "simulateTransactions")]
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockIdOrTag,
+        transactions: Vec<v0_8::BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<v0_8::SimulatedTx>>;
+
+    /// Returns the execution traces of all transactions included in the given block.
+    #[method(name = "traceBlockTransactions")]
+    async fn trace_block_transactions(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<v0_8::TxTraceWithHash>>;
+}
+
+/// The write methods of the 0.8 specification, whose V3 transactions include the L1 data gas
+/// bounds.
+///
+/// They are served like the [`StarknetApiV0_8`] methods, unless the node is read-only.
+#[rpc(server, namespace = "starknetV0_8")]
+pub trait StarknetWriteApiV0_8 {
+    /// Submit a new transaction to be added to the chain.
+    #[method(name = "addInvokeTransaction")]
+    async fn add_invoke_transaction(
+        &self,
+        invoke_transaction: v0_8::BroadcastedInvokeTx,
+    ) -> RpcResult<InvokeTxResult>;
+
+    /// Submit a new class declaration transaction.
+    #[method(name = "addDeclareTransaction")]
+    async fn add_declare_transaction(
+        &self,
+        declare_transaction: v0_8::BroadcastedDeclareTx,
+    ) -> RpcResult<DeclareTxResult>;
+
+    /// Submit a new deploy account transaction.
+    #[method(name = "addDeployAccountTransaction")]
+    async fn add_deploy_account_transaction(
+        &self,
+        deploy_account_transaction: v0_8::BroadcastedDeployAccountTx,
+    ) -> RpcResult<DeployAccountTxResult>;
+}
@@ -0,0 +1,15 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_rpc_types::txpool::{TxPoolContent, TxPoolStatus};
+
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "txpool"))]
+pub trait TxPoolApi {
+    /// Returns the number of pending and queued transactions in the pool.
+    #[method(name = "status")]
+    async fn txpool_status(&self) -> RpcResult<TxPoolStatus>;
+
+    /// Returns the pending and queued transactions in the pool.
+    #[method(name = "content")]
+    async fn txpool_content(&self) -> RpcResult<TxPoolContent>;
+}
@@ -0,0 +1 @@
+pub use katana_pool::validation::policy::TxPolicy;
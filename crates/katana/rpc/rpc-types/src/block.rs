@@ -3,7 +3,10 @@ use katana_primitives::block::{
 };
 use katana_primitives::receipt::Receipt;
 use katana_primitives::transaction::{TxHash, TxWithHash};
+use katana_primitives::Felt;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
 use starknet::core::types::{
     BlockStatus, L1DataAvailabilityMode, ResourcePrice, TransactionWithReceipt,
 };
@@ -15,7 +18,7 @@ pub type BlockTxCount = u64;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct BlockWithTxs(starknet::core::types::BlockWithTxs);
+pub struct BlockWithTxs(pub starknet::core::types::BlockWithTxs);
 
 impl BlockWithTxs {
     pub fn new(block_hash: BlockHash, block: Block, finality_status: FinalityStatus) -> Self {
@@ -59,7 +62,7 @@ impl BlockWithTxs {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct PendingBlockWithTxs(starknet::core::types::PendingBlockWithTxs);
+pub struct PendingBlockWithTxs(pub starknet::core::types::PendingBlockWithTxs);
 
 impl PendingBlockWithTxs {
     pub fn new(header: PartialHeader, transactions: Vec<TxWithHash>) -> Self {
@@ -110,7 +113,7 @@ impl From<starknet::core::types::MaybePendingBlockWithTxs> for MaybePendingBlock
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct BlockWithTxHashes(starknet::core::types::BlockWithTxHashes);
+pub struct BlockWithTxHashes(pub starknet::core::types::BlockWithTxHashes);
 
 impl BlockWithTxHashes {
     pub fn new(
@@ -155,7 +158,7 @@ impl BlockWithTxHashes {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct PendingBlockWithTxHashes(starknet::core::types::PendingBlockWithTxHashes);
+pub struct PendingBlockWithTxHashes(pub starknet::core::types::PendingBlockWithTxHashes);
 
 impl PendingBlockWithTxHashes {
     pub fn new(header: PartialHeader, transactions: Vec<TxHash>) -> Self {
@@ -225,7 +228,7 @@ impl From<(BlockHash, BlockNumber)> for BlockHashAndNumber {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct BlockWithReceipts(starknet::core::types::BlockWithReceipts);
+pub struct BlockWithReceipts(pub starknet::core::types::BlockWithReceipts);
 
 impl BlockWithReceipts {
     pub fn new(
@@ -274,7 +277,7 @@ impl BlockWithReceipts {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
-pub struct PendingBlockWithReceipts(starknet::core::types::PendingBlockWithReceipts);
+pub struct PendingBlockWithReceipts(pub starknet::core::types::PendingBlockWithReceipts);
 
 impl PendingBlockWithReceipts {
     pub fn new(
@@ -325,6 +328,17 @@ pub enum MaybePendingBlockWithReceipts {
     Block(BlockWithReceipts),
 }
 
+impl MaybePendingBlockWithReceipts {
+    /// Returns the hashes of the transactions of the block.
+    pub fn transaction_hashes(&self) -> impl Iterator<Item = TxHash> + '_ {
+        let transactions = match self {
+            MaybePendingBlockWithReceipts::Pending(block) => &block.0.transactions,
+            MaybePendingBlockWithReceipts::Block(block) => &block.0.transactions,
+        };
+        transactions.iter().map(|tx| *tx.receipt.transaction_hash())
+    }
+}
+
 impl From<starknet::core::types::MaybePendingBlockWithReceipts> for MaybePendingBlockWithReceipts {
     fn from(value: starknet::core::types::MaybePendingBlockWithReceipts) -> Self {
         match value {
@@ -337,3 +351,57 @@ impl From<starknet::core::types::MaybePendingBlockWithReceipts> for MaybePending
         }
     }
 }
+
+/// The header of a block, as defined by the `BLOCK_HEADER` type of the Starknet 0.8 spec.
+///
+/// This is the item sent to subscribers of the `starknet_subscribeNewHeads` subscription.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeader {
+    #[serde_as(as = "UfeHex")]
+    pub block_hash: BlockHash,
+    #[serde_as(as = "UfeHex")]
+    pub parent_hash: BlockHash,
+    pub block_number: BlockNumber,
+    #[serde_as(as = "UfeHex")]
+    pub new_root: Felt,
+    pub timestamp: u64,
+    #[serde_as(as = "UfeHex")]
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub starknet_version: String,
+}
+
+impl BlockHeader {
+    pub fn new(block_hash: BlockHash, header: Header) -> Self {
+        let l1_gas_price = ResourcePrice {
+            price_in_wei: header.l1_gas_prices.eth.into(),
+            price_in_fri: header.l1_gas_prices.strk.into(),
+        };
+
+        let l1_data_gas_price = ResourcePrice {
+            price_in_wei: header.l1_data_gas_prices.eth.into(),
+            price_in_fri: header.l1_data_gas_prices.strk.into(),
+        };
+
+        Self {
+            block_hash,
+            l1_gas_price,
+            l1_data_gas_price,
+            new_root: header.state_root,
+            timestamp: header.timestamp,
+            block_number: header.number,
+            parent_hash: header.parent_hash,
+            sequencer_address: header.sequencer_address.into(),
+            starknet_version: header.protocol_version.to_string(),
+            l1_da_mode: match header.l1_da_mode {
+                katana_primitives::da::L1DataAvailabilityMode::Blob => L1DataAvailabilityMode::Blob,
+                katana_primitives::da::L1DataAvailabilityMode::Calldata => {
+                    L1DataAvailabilityMode::Calldata
+                }
+            },
+        }
+    }
+}
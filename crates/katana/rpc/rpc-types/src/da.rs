@@ -0,0 +1,14 @@
+//! Data availability related types.
+
+use katana_primitives::block::BlockNumber;
+use katana_primitives::da::StateDiffBlob;
+use serde::{Deserialize, Serialize};
+
+/// The EIP-4844 blobs of the state diff of a block, as they would be posted to the settlement
+/// chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockStateDiffBlob {
+    pub block_number: BlockNumber,
+    /// The blobs, usually only one unless the state diff doesn't fit in a single blob.
+    pub blobs: Vec<StateDiffBlob>,
+}
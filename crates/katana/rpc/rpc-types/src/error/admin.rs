@@ -0,0 +1,33 @@
+use jsonrpsee::core::Error;
+use jsonrpsee::types::error::CallError;
+use jsonrpsee::types::ErrorObject;
+use katana_pool::validation::policy::TxPolicyError;
+
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum AdminApiError {
+    #[error("The node wasn't started with a policy file.")]
+    NoPolicyFile,
+    #[error("Invalid policy file: {reason}")]
+    InvalidPolicy { reason: String },
+}
+
+impl AdminApiError {
+    fn code(&self) -> i32 {
+        match self {
+            AdminApiError::NoPolicyFile => 0,
+            AdminApiError::InvalidPolicy { .. } => 1,
+        }
+    }
+}
+
+impl From<AdminApiError> for Error {
+    fn from(err: AdminApiError) -> Self {
+        Error::Call(CallError::Custom(ErrorObject::owned(err.code(), err.to_string(), None::<()>)))
+    }
+}
+
+impl From<TxPolicyError> for AdminApiError {
+    fn from(value: TxPolicyError) -> Self {
+        AdminApiError::InvalidPolicy { reason: value.to_string() }
+    }
+}
@@ -1,16 +1,46 @@
 use jsonrpsee::core::Error;
 use jsonrpsee::types::error::CallError;
 use jsonrpsee::types::ErrorObject;
+use katana_provider::error::ProviderError;
 
-#[derive(thiserror::Error, Clone, Copy, Debug)]
+#[derive(thiserror::Error, Clone, Debug)]
 #[allow(clippy::enum_variant_names)]
 pub enum DevApiError {
     #[error("Wait for pending transactions.")]
     PendingTransactions,
+    #[error("Wait for the ongoing block production to finish.")]
+    BlockProductionInProgress,
+    #[error("An unexpected error occured: {reason}")]
+    UnexpectedError { reason: String },
+    #[error("Reorg depth {depth} exceeds the latest block number {latest}.")]
+    InvalidReorgDepth { depth: u64, latest: u64 },
+    #[error("Invalid state: {reason}")]
+    InvalidState { reason: String },
+    #[error("Transaction not found.")]
+    TxnHashNotFound,
+}
+
+impl DevApiError {
+    fn code(&self) -> i32 {
+        match self {
+            DevApiError::PendingTransactions => 0,
+            DevApiError::BlockProductionInProgress => 1,
+            DevApiError::UnexpectedError { .. } => 2,
+            DevApiError::InvalidReorgDepth { .. } => 3,
+            DevApiError::InvalidState { .. } => 4,
+            DevApiError::TxnHashNotFound => 5,
+        }
+    }
 }
 
 impl From<DevApiError> for Error {
     fn from(err: DevApiError) -> Self {
-        Error::Call(CallError::Custom(ErrorObject::owned(err as i32, err.to_string(), None::<()>)))
+        Error::Call(CallError::Custom(ErrorObject::owned(err.code(), err.to_string(), None::<()>)))
+    }
+}
+
+impl From<ProviderError> for DevApiError {
+    fn from(value: ProviderError) -> Self {
+        DevApiError::UnexpectedError { reason: value.to_string() }
     }
 }
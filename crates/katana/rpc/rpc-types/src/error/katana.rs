@@ -11,6 +11,14 @@ pub enum KatanaApiError {
     FailedToDumpState = 2,
     #[error("Failed to update storage.")]
     FailedToUpdateStorage = 3,
+    #[error("Block not found.")]
+    BlockNotFound = 4,
+    #[error("Failed to build the profile.")]
+    FailedToBuildProfile = 5,
+    #[error("State diff blob not found.")]
+    StateDiffBlobNotFound = 6,
+    #[error("Failed to get the state diff blob.")]
+    FailedToGetStateDiffBlob = 7,
 }
 
 impl From<KatanaApiError> for Error {
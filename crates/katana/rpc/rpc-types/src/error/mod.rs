@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod dev;
 pub mod katana;
 pub mod saya;
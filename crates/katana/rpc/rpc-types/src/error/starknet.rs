@@ -1,6 +1,6 @@
 use jsonrpsee::core::Error;
 use jsonrpsee::types::error::CallError;
-use jsonrpsee::types::ErrorObject;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
 use katana_pool::validation::error::InvalidTransactionError;
 use katana_pool::PoolError;
 use katana_primitives::event::ContinuationTokenError;
@@ -39,6 +39,8 @@ pub enum StarknetApiError {
     InvalidContinuationToken,
     #[error("Contract error")]
     ContractError { revert_error: String },
+    #[error("The node doesn't support storage proofs for blocks that are too far in the past")]
+    StorageProofNotSupported,
     #[error("Transaction execution error")]
     TransactionExecutionError {
         /// The index of the first transaction failing in a sequence of given transactions.
@@ -83,6 +85,12 @@ pub enum StarknetApiError {
     TooManyKeysInFilter,
     #[error("Failed to fetch pending transactions")]
     FailedToFetchPendingTransactions,
+    #[error("Invalid subscription id")]
+    InvalidSubscriptionId,
+    #[error("Too many addresses in filter sender_address filter")]
+    TooManyAddressesInFilter,
+    #[error("Cannot go back more than 1024 blocks")]
+    TooManyBlocksBack,
 }
 
 impl StarknetApiError {
@@ -103,6 +111,7 @@ impl StarknetApiError {
             StarknetApiError::FailedToFetchPendingTransactions => 38,
             StarknetApiError::ContractError { .. } => 40,
             StarknetApiError::TransactionExecutionError { .. } => 41,
+            StarknetApiError::StorageProofNotSupported => 42,
             StarknetApiError::InvalidContractClass => 50,
             StarknetApiError::ClassAlreadyDeclared => 51,
             StarknetApiError::InvalidTransactionNonce { .. } => 52,
@@ -117,6 +126,9 @@ impl StarknetApiError {
             StarknetApiError::UnsupportedTransactionVersion => 61,
             StarknetApiError::UnsupportedContractClassVersion => 62,
             StarknetApiError::UnexpectedError { .. } => 63,
+            StarknetApiError::InvalidSubscriptionId => 66,
+            StarknetApiError::TooManyAddressesInFilter => 67,
+            StarknetApiError::TooManyBlocksBack => 68,
             StarknetApiError::ProofLimitExceeded => 10000,
         }
     }
@@ -143,7 +155,13 @@ impl StarknetApiError {
 
 impl From<StarknetApiError> for Error {
     fn from(err: StarknetApiError) -> Self {
-        Error::Call(CallError::Custom(ErrorObject::owned(err.code(), err.message(), err.data())))
+        Error::Call(CallError::Custom(err.into()))
+    }
+}
+
+impl From<StarknetApiError> for ErrorObjectOwned {
+    fn from(err: StarknetApiError) -> Self {
+        ErrorObject::owned(err.code(), err.message(), err.data())
     }
 }
 impl From<ProviderError> for StarknetApiError {
@@ -173,6 +191,12 @@ impl From<PoolError> for StarknetApiError {
     fn from(error: PoolError) -> Self {
         match error {
             PoolError::InvalidTransaction(err) => err.into(),
+            PoolError::DuplicateTransaction(_) => StarknetApiError::DuplicateTransaction,
+            PoolError::ReplacementUnderpriced { .. }
+            | PoolError::PoolFull
+            | PoolError::AccountLimitExceeded(_) => {
+                StarknetApiError::UnexpectedError { reason: error.to_string() }
+            }
             PoolError::Internal(err) => {
                 StarknetApiError::UnexpectedError { reason: err.to_string() }
             }
@@ -193,6 +217,9 @@ impl From<Box<InvalidTransactionError>> for StarknetApiError {
             InvalidTransactionError::ValidationFailure { error, .. } => {
                 Self::ValidationFailure { reason: error.to_string() }
             }
+            InvalidTransactionError::NotPermitted(violation) => {
+                Self::ValidationFailure { reason: violation.to_string() }
+            }
         }
     }
 }
@@ -284,9 +311,17 @@ mod tests {
     #[case(StarknetApiError::NonAccount, 58, "Sender address in not an account contract")]
     #[case(StarknetApiError::InvalidTxnIndex, 27, "Invalid transaction index in a block")]
     #[case(StarknetApiError::ProofLimitExceeded, 10000, "Too many storage keys requested")]
+    #[case(
+        StarknetApiError::StorageProofNotSupported,
+        42,
+        "The node doesn't support storage proofs for blocks that are too far in the past"
+    )]
     #[case(StarknetApiError::TooManyKeysInFilter, 34, "Too many keys provided in a filter")]
+    #[case(StarknetApiError::TooManyBlocksBack, 68, "Cannot go back more than 1024 blocks")]
     #[case(StarknetApiError::ContractClassSizeIsTooLarge, 57, "Contract class size is too large")]
     #[case(StarknetApiError::FailedToFetchPendingTransactions, 38, "Failed to fetch pending transactions")]
+    #[case(StarknetApiError::InvalidSubscriptionId, 66, "Invalid subscription id")]
+    #[case(StarknetApiError::TooManyAddressesInFilter, 67, "Too many addresses in filter sender_address filter")]
     #[case(StarknetApiError::UnsupportedTransactionVersion, 61, "The transaction version is not supported")]
     #[case(StarknetApiError::UnsupportedContractClassVersion, 62, "The contract class version is not supported")]
     #[case(StarknetApiError::InvalidContinuationToken, 33, "The supplied continuation token is invalid or unknown")]
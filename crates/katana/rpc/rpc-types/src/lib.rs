@@ -4,15 +4,21 @@
 //! `starknet-rs`.
 
 pub mod account;
+pub mod admin;
 pub mod block;
+pub mod da;
 pub mod error;
 pub mod event;
 pub mod message;
+pub mod profile;
 pub mod receipt;
 pub mod state_update;
 pub mod trace;
 pub mod transaction;
+pub mod trie;
+pub mod txpool;
 mod utils;
+pub mod v0_8;
 
 use std::ops::Deref;
 
@@ -1,8 +1,12 @@
 use katana_primitives::chain::ChainId;
-use katana_primitives::transaction::L1HandlerTx;
+use katana_primitives::message::MessageCancellation;
+use katana_primitives::transaction::{L1HandlerTx, TxHash};
 use katana_primitives::utils::transaction::compute_l2_to_l1_message_hash;
 use katana_primitives::Felt;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{SequencerTransactionStatus, TransactionExecutionStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MsgFromL1(starknet::core::types::MsgFromL1);
@@ -44,3 +48,24 @@ impl MsgFromL1 {
         }
     }
 }
+
+/// The status of an L1 to L2 message, ie of the L1 handler transaction consuming it.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageStatus {
+    /// The hash of the L1 handler transaction consuming the message.
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    pub finality_status: SequencerTransactionStatus,
+    /// The execution status of the transaction, if it was executed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+    /// The reason of the failure of the transaction, if it was reverted or rejected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    /// The progress of the cancellation of the message on L1, if its sender started it.
+    ///
+    /// This is a Katana extension of the `starknet_getMessagesStatus` response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancellation: Option<MessageCancellation>,
+}
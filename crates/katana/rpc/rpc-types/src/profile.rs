@@ -0,0 +1,226 @@
+//! Profiles of the resources used by transactions, broken down per call.
+
+use std::collections::BTreeMap;
+
+use katana_primitives::block::BlockNumber;
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::trace::{CallInfo, CallType, ExecutionResources};
+use katana_primitives::transaction::TxHash;
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+
+/// The format of a profile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileFormat {
+    /// The call trees, with the resources used by each call.
+    #[default]
+    CallTree,
+    /// The Cairo steps of the calls, in the folded stacks format used to generate flamegraphs.
+    Folded,
+    /// The Cairo steps of the calls, as a base64 encoded, gzipped pprof profile.
+    Pprof,
+}
+
+/// A profile in one of the [`ProfileFormat`]s.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum Profile<T> {
+    CallTree(T),
+    Folded { stacks: String },
+    Pprof { profile: String },
+}
+
+/// The profile of the transactions of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockProfile {
+    pub block_number: BlockNumber,
+    pub transactions: Vec<TxProfile>,
+}
+
+/// The profile of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxProfile {
+    pub transaction_hash: TxHash,
+    /// The resources the transaction is charged for.
+    pub resources: TxProfileResources,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate: Option<CallProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execute: Option<CallProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer: Option<CallProfile>,
+}
+
+impl TxProfile {
+    /// Returns the root calls of the transaction, with the name of their phase.
+    pub fn phases(&self) -> impl Iterator<Item = (&'static str, &CallProfile)> {
+        [
+            ("validate", &self.validate),
+            ("execute", &self.execute),
+            ("fee_transfer", &self.fee_transfer),
+        ]
+        .into_iter()
+        .filter_map(|(phase, call)| call.as_ref().map(|call| (phase, call)))
+    }
+}
+
+/// The resources a transaction is charged for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxProfileResources {
+    pub steps: u64,
+    pub l1_gas: u128,
+    pub l1_data_gas: u128,
+}
+
+/// The profile of a call and of its inner calls.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallProfile {
+    pub contract_address: ContractAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<ClassHash>,
+    pub entry_point_selector: Felt,
+    /// The name of the entry point, if it's found in the ABI of the class.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_point_name: Option<String>,
+    pub library_call: bool,
+    /// The resources used by the call, including its inner calls.
+    pub total: CallResources,
+    /// The resources used by the call itself, excluding its inner calls.
+    #[serde(rename = "self")]
+    pub own: CallResources,
+    pub inner_calls: Vec<CallProfile>,
+}
+
+impl CallProfile {
+    /// Creates the profile of `call`. `resolve` returns the class hash and the name of the entry
+    /// point of a call, if they are known.
+    pub fn new<F>(call: &CallInfo, resolve: &mut F) -> Self
+    where
+        F: FnMut(&CallInfo) -> (Option<ClassHash>, Option<String>),
+    {
+        let inner_calls: Vec<_> = call.inner_calls.iter().map(|c| Self::new(c, resolve)).collect();
+        let (class_hash, entry_point_name) = resolve(call);
+
+        let total = CallResources::new(call);
+        let own =
+            inner_calls.iter().fold(total.clone(), |own, inner| own.saturating_sub(&inner.total));
+
+        Self {
+            contract_address: call.contract_address,
+            class_hash,
+            entry_point_selector: call.entry_point_selector,
+            entry_point_name,
+            library_call: call.call_type == CallType::Delegate,
+            total,
+            own,
+            inner_calls,
+        }
+    }
+
+    /// Returns the name of the call in stack traces: the entry point name or selector, prefixed
+    /// by the contract address.
+    pub fn frame_name(&self) -> String {
+        match &self.entry_point_name {
+            Some(name) => format!("{}::{name}", self.contract_address),
+            None => format!("{}::{:#x}", self.contract_address, self.entry_point_selector),
+        }
+    }
+}
+
+/// The resources used by a call.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallResources {
+    pub steps: u64,
+    pub memory_holes: u64,
+    pub builtins: BTreeMap<String, u64>,
+    pub syscalls: SyscallCounts,
+    /// The L2 gas consumed by the call.
+    pub l2_gas: u128,
+}
+
+impl CallResources {
+    fn new(call: &CallInfo) -> Self {
+        let ExecutionResources { n_steps, n_memory_holes, builtin_instance_counter } =
+            &call.execution_resources;
+
+        let builtins = builtin_instance_counter
+            .iter()
+            .map(|(name, count)| (name.to_str().to_string(), *count as u64))
+            .collect();
+
+        Self {
+            steps: *n_steps as u64,
+            memory_holes: *n_memory_holes as u64,
+            builtins,
+            syscalls: SyscallCounts::new(call),
+            l2_gas: call.gas_consumed,
+        }
+    }
+
+    fn saturating_sub(mut self, other: &Self) -> Self {
+        self.steps = self.steps.saturating_sub(other.steps);
+        self.memory_holes = self.memory_holes.saturating_sub(other.memory_holes);
+        self.l2_gas = self.l2_gas.saturating_sub(other.l2_gas);
+        for (name, count) in &other.builtins {
+            if let Some(own) = self.builtins.get_mut(name) {
+                *own = own.saturating_sub(*count);
+            }
+        }
+        self.builtins.retain(|_, count| *count > 0);
+        self.syscalls = self.syscalls.saturating_sub(&other.syscalls);
+        self
+    }
+}
+
+/// The number of syscalls made by a call.
+///
+/// The counts are derived from the execution trace, which doesn't record the storage writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyscallCounts {
+    pub call_contract: u64,
+    pub library_call: u64,
+    pub storage_read: u64,
+    pub emit_event: u64,
+    pub send_message_to_l1: u64,
+}
+
+impl SyscallCounts {
+    /// Returns the syscalls made by `call` and its inner calls.
+    fn new(call: &CallInfo) -> Self {
+        let own = Self {
+            call_contract: 0,
+            library_call: 0,
+            storage_read: call.storage_read_values.len() as u64,
+            emit_event: call.events.len() as u64,
+            send_message_to_l1: call.l2_to_l1_messages.len() as u64,
+        };
+
+        call.inner_calls.iter().fold(own, |mut total, inner| {
+            match inner.call_type {
+                CallType::Call => total.call_contract += 1,
+                CallType::Delegate => total.library_call += 1,
+            }
+            total.add(&Self::new(inner))
+        })
+    }
+
+    fn add(mut self, other: &Self) -> Self {
+        self.call_contract += other.call_contract;
+        self.library_call += other.library_call;
+        self.storage_read += other.storage_read;
+        self.emit_event += other.emit_event;
+        self.send_message_to_l1 += other.send_message_to_l1;
+        self
+    }
+
+    fn saturating_sub(mut self, other: &Self) -> Self {
+        self.call_contract = self.call_contract.saturating_sub(other.call_contract);
+        self.library_call = self.library_call.saturating_sub(other.library_call);
+        self.storage_read = self.storage_read.saturating_sub(other.storage_read);
+        self.emit_event = self.emit_event.saturating_sub(other.emit_event);
+        self.send_message_to_l1 = self.send_message_to_l1.saturating_sub(other.send_message_to_l1);
+        self
+    }
+}
@@ -18,14 +18,18 @@ use katana_primitives::transaction::{
 use katana_primitives::Felt;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
 use starknet::core::types::{
     BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
     BroadcastedInvokeTransaction, DeclareTransactionResult, DeployAccountTransactionResult,
     DeployAccountTransactionV1, DeployAccountTransactionV3, InvokeTransactionResult,
+    TransactionStatus,
 };
 use starknet::core::utils::get_contract_address;
 
 use crate::receipt::TxReceiptWithBlockInfo;
+use crate::FeltAsHex;
 
 pub const CHUNK_SIZE_DEFAULT: u64 = 100;
 
@@ -540,6 +544,29 @@ pub struct TransactionsPage {
     pub cursor: TransactionsPageCursor,
 }
 
+/// A transaction status update, as defined by the `NEW_TXN_STATUS` type of the Starknet 0.8 spec.
+///
+/// This is the item sent to subscribers of the `starknet_subscribeTransactionStatus`
+/// subscription.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxStatusUpdate {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    pub status: TransactionStatus,
+}
+
+/// The item sent to subscribers of the `starknet_subscribePendingTransactions` subscription.
+///
+/// Depending on the subscription parameters, either only the hash or the full transaction is
+/// sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PendingTx {
+    Hash(FeltAsHex),
+    Full(Tx),
+}
+
 // TODO: find a solution to avoid doing this conversion, this is not pretty at all. the reason why
 // we had to do this in the first place is because of the orphan rule. i think eventually we should
 // not rely on `starknet-rs` rpc types anymore and should instead define the types ourselves to have
@@ -0,0 +1,128 @@
+//! Types of the `starknet_getStorageProof` method, as defined by the Starknet 0.8 spec.
+
+use katana_primitives::block::BlockHash;
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{Nonce, StorageKey};
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+
+/// The storage keys of a contract whose values should be proven.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContractStorageKeys {
+    #[serde_as(as = "UfeHex")]
+    pub contract_address: Felt,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub storage_keys: Vec<StorageKey>,
+}
+
+/// A node of a Merkle-Patricia trie.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MerkleNode {
+    Binary {
+        #[serde_as(as = "UfeHex")]
+        left: Felt,
+        #[serde_as(as = "UfeHex")]
+        right: Felt,
+    },
+    Edge {
+        #[serde_as(as = "UfeHex")]
+        path: Felt,
+        length: u8,
+        #[serde_as(as = "UfeHex")]
+        child: Felt,
+    },
+}
+
+impl From<katana_trie::Node> for MerkleNode {
+    fn from(node: katana_trie::Node) -> Self {
+        match node {
+            katana_trie::Node::Binary { left, right } => Self::Binary { left, right },
+            katana_trie::Node::Edge { child, path } => {
+                Self::Edge { path: path.value, length: path.len, child }
+            }
+        }
+    }
+}
+
+impl From<MerkleNode> for katana_trie::Node {
+    fn from(node: MerkleNode) -> Self {
+        match node {
+            MerkleNode::Binary { left, right } => Self::Binary { left, right },
+            MerkleNode::Edge { path, length, child } => {
+                Self::Edge { child, path: katana_trie::Path { value: path, len: length } }
+            }
+        }
+    }
+}
+
+/// A trie node paired with its hash.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NodeWithHash {
+    #[serde_as(as = "UfeHex")]
+    pub node_hash: Felt,
+    pub node: MerkleNode,
+}
+
+/// The nodes of a proof, indexed by their hashes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Nodes(pub Vec<NodeWithHash>);
+
+impl From<katana_trie::MultiProof> for Nodes {
+    fn from(proof: katana_trie::MultiProof) -> Self {
+        Self(
+            proof
+                .into_iter()
+                .map(|(node_hash, node)| NodeWithHash { node_hash, node: node.into() })
+                .collect(),
+        )
+    }
+}
+
+/// The data of a contract's leaf in the contracts trie.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContractLeafData {
+    #[serde_as(as = "UfeHex")]
+    pub nonce: Nonce,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: ClassHash,
+    #[serde_as(as = "UfeHex")]
+    pub storage_root: Felt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContractsProof {
+    /// The nodes of the union of the paths from the contracts trie root to the requested leaves.
+    pub nodes: Nodes,
+    /// The data of the requested contracts' leaves, in the same order as the requested addresses.
+    pub contract_leaves_data: Vec<ContractLeafData>,
+}
+
+/// The roots of the global state at a block.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GlobalRoots {
+    #[serde_as(as = "UfeHex")]
+    pub contracts_tree_root: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub classes_tree_root: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub block_hash: BlockHash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GetStorageProofResponse {
+    pub classes_proof: Nodes,
+    pub contracts_proof: ContractsProof,
+    /// The storage proofs of the requested contracts, in the same order as the requested
+    /// contracts.
+    pub contracts_storage_proofs: Vec<Nodes>,
+    pub global_roots: GlobalRoots,
+}
@@ -0,0 +1,47 @@
+use katana_pool::tx::PoolTransaction;
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+
+use crate::FeltAsHex;
+
+/// A transaction in the pool.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TxPoolTransaction {
+    pub hash: FeltAsHex,
+    pub nonce: FeltAsHex,
+    pub sender: FeltAsHex,
+    pub max_fee: u128,
+    pub tip: u64,
+}
+
+impl TxPoolTransaction {
+    pub fn new<T: PoolTransaction>(tx: &T) -> Self {
+        Self {
+            hash: tx.hash().into(),
+            nonce: tx.nonce().into(),
+            sender: Felt::from(tx.sender()).into(),
+            max_fee: tx.max_fee(),
+            tip: tx.tip(),
+        }
+    }
+}
+
+/// The number of transactions in the pool.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TxPoolStatus {
+    /// Transactions that are ready to be included in a block.
+    pub pending: u64,
+    /// Transactions waiting for the transactions with lower nonces of the same sender.
+    pub queued: u64,
+}
+
+/// The transactions in the pool.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TxPoolContent {
+    /// Transactions that are ready to be included in a block, sorted by their priority. The
+    /// transactions of the same sender are always included in nonce order, regardless of their
+    /// priority.
+    pub pending: Vec<TxPoolTransaction>,
+    /// Transactions waiting for the transactions with lower nonces of the same sender.
+    pub queued: Vec<TxPoolTransaction>,
+}
@@ -0,0 +1,931 @@
+//! Types of the 0.8 Starknet JSON-RPC specification whose shape differs from their 0.7
+//! counterpart.
+//!
+//! Katana implements the 0.7 types, so the types here are converted from and to them. Katana
+//! doesn't price L2 gas separately from L1 gas, so the L2 gas prices and amounts are always zero.
+
+use katana_primitives::trace::{CallInfo, TxExecInfo, TxResources};
+use katana_primitives::transaction::TxHash;
+use katana_primitives::Felt;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use serde_with::serde_as;
+use starknet::core::serde::num_hex::NumAsHex;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{
+    BlockStatus, CallType, DataAvailabilityMode, DeclareTransaction, DeployAccountTransaction,
+    EntryPointType, Event, ExecutionResult, FeePayment, Hash256, InvokeTransaction,
+    L1DataAvailabilityMode, MsgToL1, OrderedEvent, OrderedMessage, PriceUnit, ReceiptBlock,
+    ResourceBounds, ResourcePrice, Transaction, TransactionExecutionStatus,
+    TransactionFinalityStatus, TransactionReceipt, TransactionWithReceipt,
+};
+
+use crate::block::{
+    MaybePendingBlockWithReceipts as BlockWithReceiptsV0_7,
+    MaybePendingBlockWithTxHashes as BlockWithTxHashesV0_7,
+    MaybePendingBlockWithTxs as BlockWithTxsV0_7,
+};
+use crate::receipt::TxReceiptWithBlockInfo as TxReceiptWithBlockInfoV0_7;
+use crate::transaction::{
+    BroadcastedDeclareTx as BroadcastedDeclareTxV0_7,
+    BroadcastedDeployAccountTx as BroadcastedDeployAccountTxV0_7,
+    BroadcastedInvokeTx as BroadcastedInvokeTxV0_7, BroadcastedTx as BroadcastedTxV0_7,
+    Tx as TxV0_7,
+};
+use crate::FeltAsHex;
+
+/// The version of the Starknet JSON-RPC specification of the types in this module.
+pub const RPC_SPEC_VERSION: &str = "0.8.0";
+
+/// The resources consumed by a transaction, as defined by the `EXECUTION_RESOURCES` type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ExecutionResources {
+    pub l1_gas: u128,
+    pub l1_data_gas: u128,
+    pub l2_gas: u128,
+}
+
+impl From<&TxResources> for ExecutionResources {
+    fn from(value: &TxResources) -> Self {
+        Self {
+            l1_gas: value.total_gas_consumed.l1_gas,
+            l1_data_gas: value.total_gas_consumed.l1_data_gas,
+            l2_gas: 0,
+        }
+    }
+}
+
+/// The resources consumed by a call, as defined by the `INNER_CALL_EXECUTION_RESOURCES` type.
+///
+/// Katana doesn't compute the gas consumed by the individual calls, so they're always zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct InnerCallExecutionResources {
+    pub l1_gas: u128,
+    pub l2_gas: u128,
+}
+
+/// The resource bounds of a V3 transaction, which include the L1 data gas.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceBoundsMapping {
+    pub l1_gas: ResourceBounds,
+    pub l1_data_gas: ResourceBounds,
+    pub l2_gas: ResourceBounds,
+}
+
+impl From<starknet::core::types::ResourceBoundsMapping> for ResourceBoundsMapping {
+    fn from(value: starknet::core::types::ResourceBoundsMapping) -> Self {
+        Self {
+            l1_gas: value.l1_gas,
+            l1_data_gas: ResourceBounds { max_amount: 0, max_price_per_unit: 0 },
+            l2_gas: value.l2_gas,
+        }
+    }
+}
+
+/// A transaction. Only the resource bounds of the V3 transactions differ from the 0.7 ones.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum Tx {
+    V3(TxV3),
+    Other(TxV0_7),
+}
+
+impl From<TxV0_7> for Tx {
+    fn from(value: TxV0_7) -> Self {
+        match value.0 {
+            Transaction::Invoke(InvokeTransaction::V3(tx)) => Tx::V3(TxV3::Invoke(InvokeTxV3 {
+                transaction_hash: tx.transaction_hash,
+                version: Felt::THREE,
+                sender_address: tx.sender_address,
+                calldata: tx.calldata,
+                signature: tx.signature,
+                nonce: tx.nonce,
+                resource_bounds: tx.resource_bounds.into(),
+                tip: tx.tip,
+                paymaster_data: tx.paymaster_data,
+                account_deployment_data: tx.account_deployment_data,
+                nonce_data_availability_mode: tx.nonce_data_availability_mode,
+                fee_data_availability_mode: tx.fee_data_availability_mode,
+            })),
+
+            Transaction::Declare(DeclareTransaction::V3(tx)) => {
+                Tx::V3(TxV3::Declare(DeclareTxV3 {
+                    transaction_hash: tx.transaction_hash,
+                    version: Felt::THREE,
+                    sender_address: tx.sender_address,
+                    compiled_class_hash: tx.compiled_class_hash,
+                    signature: tx.signature,
+                    nonce: tx.nonce,
+                    class_hash: tx.class_hash,
+                    resource_bounds: tx.resource_bounds.into(),
+                    tip: tx.tip,
+                    paymaster_data: tx.paymaster_data,
+                    account_deployment_data: tx.account_deployment_data,
+                    nonce_data_availability_mode: tx.nonce_data_availability_mode,
+                    fee_data_availability_mode: tx.fee_data_availability_mode,
+                }))
+            }
+
+            Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => {
+                Tx::V3(TxV3::DeployAccount(DeployAccountTxV3 {
+                    transaction_hash: tx.transaction_hash,
+                    version: Felt::THREE,
+                    signature: tx.signature,
+                    nonce: tx.nonce,
+                    contract_address_salt: tx.contract_address_salt,
+                    constructor_calldata: tx.constructor_calldata,
+                    class_hash: tx.class_hash,
+                    resource_bounds: tx.resource_bounds.into(),
+                    tip: tx.tip,
+                    paymaster_data: tx.paymaster_data,
+                    nonce_data_availability_mode: tx.nonce_data_availability_mode,
+                    fee_data_availability_mode: tx.fee_data_availability_mode,
+                }))
+            }
+
+            tx => Tx::Other(TxV0_7(tx)),
+        }
+    }
+}
+
+/// A V3 transaction.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TxV3 {
+    Invoke(InvokeTxV3),
+    Declare(DeclareTxV3),
+    DeployAccount(DeployAccountTxV3),
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct InvokeTxV3 {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    #[serde_as(as = "UfeHex")]
+    pub version: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub sender_address: Felt,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub calldata: Vec<Felt>,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<Felt>,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: Felt,
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde_as(as = "NumAsHex")]
+    pub tip: u64,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub paymaster_data: Vec<Felt>,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct DeclareTxV3 {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    #[serde_as(as = "UfeHex")]
+    pub version: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub sender_address: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub compiled_class_hash: Felt,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<Felt>,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: Felt,
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde_as(as = "NumAsHex")]
+    pub tip: u64,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub paymaster_data: Vec<Felt>,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub account_deployment_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployAccountTxV3 {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    #[serde_as(as = "UfeHex")]
+    pub version: Felt,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub signature: Vec<Felt>,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub contract_address_salt: Felt,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub constructor_calldata: Vec<Felt>,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: Felt,
+    pub resource_bounds: ResourceBoundsMapping,
+    #[serde_as(as = "NumAsHex")]
+    pub tip: u64,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub paymaster_data: Vec<Felt>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+}
+
+/// The type of a transaction, as found in its receipt and trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TxType {
+    Invoke,
+    Declare,
+    Deploy,
+    DeployAccount,
+    L1Handler,
+}
+
+/// The receipt of a transaction, with its resources as defined by the 0.8 spec.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct TxReceipt {
+    #[serde(rename = "type")]
+    pub r#type: TxType,
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    pub actual_fee: FeePayment,
+    pub finality_status: TransactionFinalityStatus,
+    pub messages_sent: Vec<MsgToL1>,
+    pub events: Vec<Event>,
+    pub execution_resources: ExecutionResources,
+    pub execution_status: TransactionExecutionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// The address of the deployed contract, for the `DEPLOY` and `DEPLOY_ACCOUNT` receipts.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Felt>,
+    /// The hash of the L1 message, for the `L1_HANDLER` receipts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_hash: Option<Hash256>,
+}
+
+/// Builds a [`TxReceipt`] from the fields shared by all the 0.7 receipts.
+macro_rules! receipt {
+    ($receipt:expr, $type:ident, $resources:expr) => {{
+        let (execution_status, revert_reason) = match $receipt.execution_result {
+            ExecutionResult::Succeeded => (TransactionExecutionStatus::Succeeded, None),
+            ExecutionResult::Reverted { reason } => {
+                (TransactionExecutionStatus::Reverted, Some(reason))
+            }
+        };
+
+        TxReceipt {
+            r#type: TxType::$type,
+            transaction_hash: $receipt.transaction_hash,
+            actual_fee: $receipt.actual_fee,
+            finality_status: $receipt.finality_status,
+            messages_sent: $receipt.messages_sent,
+            events: $receipt.events,
+            execution_resources: $resources,
+            execution_status,
+            revert_reason,
+            contract_address: None,
+            message_hash: None,
+        }
+    }};
+}
+
+impl TxReceipt {
+    pub fn new(receipt: TransactionReceipt, execution_resources: ExecutionResources) -> Self {
+        match receipt {
+            TransactionReceipt::Invoke(r) => receipt!(r, Invoke, execution_resources),
+            TransactionReceipt::Declare(r) => receipt!(r, Declare, execution_resources),
+            TransactionReceipt::Deploy(r) => TxReceipt {
+                contract_address: Some(r.contract_address),
+                ..receipt!(r, Deploy, execution_resources)
+            },
+            TransactionReceipt::DeployAccount(r) => TxReceipt {
+                contract_address: Some(r.contract_address),
+                ..receipt!(r, DeployAccount, execution_resources)
+            },
+            TransactionReceipt::L1Handler(r) => TxReceipt {
+                message_hash: Some(r.message_hash),
+                ..receipt!(r, L1Handler, execution_resources)
+            },
+        }
+    }
+}
+
+/// The receipt of a transaction, with the block it's included in.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct TxReceiptWithBlockInfo {
+    #[serde(flatten)]
+    pub receipt: TxReceipt,
+    /// The hash of the block, or `None` if it's pending.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<Felt>,
+    /// The number of the block, or `None` if it's pending.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+}
+
+impl TxReceiptWithBlockInfo {
+    pub fn new(
+        receipt: TxReceiptWithBlockInfoV0_7,
+        execution_resources: ExecutionResources,
+    ) -> Self {
+        let (block_hash, block_number) = match receipt.0.block {
+            ReceiptBlock::Pending => (None, None),
+            ReceiptBlock::Block { block_hash, block_number } => {
+                (Some(block_hash), Some(block_number))
+            }
+        };
+
+        let receipt = TxReceipt::new(receipt.0.receipt, execution_resources);
+        Self { receipt, block_hash, block_number }
+    }
+}
+
+/// A transaction of a block, with its receipt.
+#[derive(Debug, Clone, Serialize)]
+pub struct TxWithReceipt {
+    pub transaction: Tx,
+    pub receipt: TxReceipt,
+}
+
+impl TxWithReceipt {
+    pub fn new(tx: TransactionWithReceipt, execution_resources: ExecutionResources) -> Self {
+        Self {
+            transaction: TxV0_7(tx.transaction).into(),
+            receipt: TxReceipt::new(tx.receipt, execution_resources),
+        }
+    }
+}
+
+/// The header of a block, which includes the L2 gas price.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockHeader {
+    #[serde_as(as = "UfeHex")]
+    pub block_hash: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub parent_hash: Felt,
+    pub block_number: u64,
+    #[serde_as(as = "UfeHex")]
+    pub new_root: Felt,
+    pub timestamp: u64,
+    #[serde_as(as = "UfeHex")]
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l2_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub starknet_version: String,
+}
+
+/// The header of the pending block, which includes the L2 gas price.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingBlockHeader {
+    #[serde_as(as = "UfeHex")]
+    pub parent_hash: Felt,
+    pub timestamp: u64,
+    #[serde_as(as = "UfeHex")]
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l2_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    pub l1_da_mode: L1DataAvailabilityMode,
+    pub starknet_version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Block<T> {
+    pub status: BlockStatus,
+    #[serde(flatten)]
+    pub header: BlockHeader,
+    pub transactions: Vec<T>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingBlock<T> {
+    #[serde(flatten)]
+    pub header: PendingBlockHeader,
+    pub transactions: Vec<T>,
+}
+
+/// A block, whose transactions are of type `T`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum MaybePendingBlock<T> {
+    Pending(PendingBlock<T>),
+    Block(Block<T>),
+}
+
+/// A block with the hashes of its transactions.
+pub type MaybePendingBlockWithTxHashes = MaybePendingBlock<FeltAsHex>;
+
+/// A block with its transactions.
+pub type MaybePendingBlockWithTxs = MaybePendingBlock<Tx>;
+
+/// A block with its transactions and their receipts.
+pub type MaybePendingBlockWithReceipts = MaybePendingBlock<TxWithReceipt>;
+
+/// Builds a [`MaybePendingBlock`] from a 0.7 block, mapping its transactions with `$tx`.
+macro_rules! block {
+    ($block:expr, $tx:expr) => {{
+        let block = $block;
+        MaybePendingBlock::Block(Block {
+            status: block.status,
+            header: BlockHeader {
+                block_hash: block.block_hash,
+                parent_hash: block.parent_hash,
+                block_number: block.block_number,
+                new_root: block.new_root,
+                timestamp: block.timestamp,
+                sequencer_address: block.sequencer_address,
+                l1_gas_price: block.l1_gas_price,
+                l2_gas_price: zero_price(),
+                l1_data_gas_price: block.l1_data_gas_price,
+                l1_da_mode: block.l1_da_mode,
+                starknet_version: block.starknet_version,
+            },
+            transactions: block.transactions.into_iter().map($tx).collect(),
+        })
+    }};
+}
+
+/// Builds a [`MaybePendingBlock`] from a 0.7 pending block, mapping its transactions with `$tx`.
+macro_rules! pending_block {
+    ($block:expr, $tx:expr) => {{
+        let block = $block;
+        MaybePendingBlock::Pending(PendingBlock {
+            header: PendingBlockHeader {
+                parent_hash: block.parent_hash,
+                timestamp: block.timestamp,
+                sequencer_address: block.sequencer_address,
+                l1_gas_price: block.l1_gas_price,
+                l2_gas_price: zero_price(),
+                l1_data_gas_price: block.l1_data_gas_price,
+                l1_da_mode: block.l1_da_mode,
+                starknet_version: block.starknet_version,
+            },
+            transactions: block.transactions.into_iter().map($tx).collect(),
+        })
+    }};
+}
+
+impl From<BlockWithTxHashesV0_7> for MaybePendingBlockWithTxHashes {
+    fn from(value: BlockWithTxHashesV0_7) -> Self {
+        match value {
+            BlockWithTxHashesV0_7::Pending(block) => pending_block!(block.0, FeltAsHex::from),
+            BlockWithTxHashesV0_7::Block(block) => block!(block.0, FeltAsHex::from),
+        }
+    }
+}
+
+impl From<BlockWithTxsV0_7> for MaybePendingBlockWithTxs {
+    fn from(value: BlockWithTxsV0_7) -> Self {
+        let tx = |tx| Tx::from(TxV0_7(tx));
+        match value {
+            BlockWithTxsV0_7::Pending(block) => pending_block!(block.0, tx),
+            BlockWithTxsV0_7::Block(block) => block!(block.0, tx),
+        }
+    }
+}
+
+impl MaybePendingBlockWithReceipts {
+    /// Converts a 0.7 block, whose transactions consumed `execution_resources`, in the same
+    /// order.
+    pub fn new(block: BlockWithReceiptsV0_7, execution_resources: Vec<ExecutionResources>) -> Self {
+        let mut resources = execution_resources.into_iter();
+        let tx = |tx| TxWithReceipt::new(tx, resources.next().unwrap_or_default());
+        match block {
+            BlockWithReceiptsV0_7::Pending(block) => pending_block!(block.0, tx),
+            BlockWithReceiptsV0_7::Block(block) => block!(block.0, tx),
+        }
+    }
+}
+
+fn zero_price() -> ResourcePrice {
+    ResourcePrice { price_in_fri: Felt::ZERO, price_in_wei: Felt::ZERO }
+}
+
+/// A fee estimate, which separates the L1 and L2 gas.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FeeEstimate {
+    #[serde_as(as = "UfeHex")]
+    pub l1_gas_consumed: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub l1_gas_price: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub l2_gas_consumed: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub l2_gas_price: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub l1_data_gas_consumed: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub l1_data_gas_price: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub overall_fee: Felt,
+    pub unit: PriceUnit,
+}
+
+impl From<crate::FeeEstimate> for FeeEstimate {
+    fn from(value: crate::FeeEstimate) -> Self {
+        Self {
+            l1_gas_consumed: value.gas_consumed,
+            l1_gas_price: value.gas_price,
+            l2_gas_consumed: Default::default(),
+            l2_gas_price: Default::default(),
+            l1_data_gas_consumed: value.data_gas_consumed,
+            l1_data_gas_price: value.data_gas_price,
+            overall_fee: value.overall_fee,
+            unit: value.unit,
+        }
+    }
+}
+
+/// A call made during the execution of a transaction.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionInvocation {
+    #[serde_as(as = "UfeHex")]
+    pub contract_address: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub entry_point_selector: Felt,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub calldata: Vec<Felt>,
+    #[serde_as(as = "UfeHex")]
+    pub caller_address: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: Felt,
+    pub entry_point_type: EntryPointType,
+    pub call_type: CallType,
+    #[serde_as(as = "Vec<UfeHex>")]
+    pub result: Vec<Felt>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<OrderedEvent>,
+    pub messages: Vec<OrderedMessage>,
+    pub execution_resources: InnerCallExecutionResources,
+    pub is_reverted: bool,
+}
+
+impl From<CallInfo> for FunctionInvocation {
+    fn from(mut info: CallInfo) -> Self {
+        let calls = std::mem::take(&mut info.inner_calls).into_iter().map(Self::from).collect();
+        let is_reverted = info.failed;
+        let invocation = crate::trace::FunctionInvocation::from(info).0;
+
+        Self {
+            contract_address: invocation.contract_address,
+            entry_point_selector: invocation.entry_point_selector,
+            calldata: invocation.calldata,
+            caller_address: invocation.caller_address,
+            class_hash: invocation.class_hash,
+            entry_point_type: invocation.entry_point_type,
+            call_type: invocation.call_type,
+            result: invocation.result,
+            calls,
+            events: invocation.events,
+            messages: invocation.messages,
+            execution_resources: InnerCallExecutionResources::default(),
+            is_reverted,
+        }
+    }
+}
+
+/// The execution of an invoke transaction, which is either a call or the reason it reverted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ExecuteInvocation {
+    Success(FunctionInvocation),
+    Reverted(RevertedInvocation),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RevertedInvocation {
+    pub revert_reason: String,
+}
+
+/// The execution trace of a transaction, with its resources as defined by the 0.8 spec.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TxTrace {
+    Invoke(InvokeTxTrace),
+    Declare(DeclareTxTrace),
+    DeployAccount(DeployAccountTxTrace),
+    L1Handler(L1HandlerTxTrace),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvokeTxTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub execute_invocation: ExecuteInvocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeclareTxTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployAccountTxTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub constructor_invocation: FunctionInvocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub execution_resources: ExecutionResources,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct L1HandlerTxTrace {
+    pub function_invocation: FunctionInvocation,
+    pub execution_resources: ExecutionResources,
+}
+
+impl From<TxExecInfo> for TxTrace {
+    fn from(trace: TxExecInfo) -> Self {
+        use katana_primitives::transaction::TxType;
+
+        let execution_resources = ExecutionResources::from(&trace.actual_resources);
+        let validate_invocation = trace.validate_call_info.map(FunctionInvocation::from);
+        let execute_invocation = trace.execute_call_info.map(FunctionInvocation::from);
+        let fee_transfer_invocation = trace.fee_transfer_call_info.map(FunctionInvocation::from);
+
+        match trace.r#type {
+            TxType::Invoke => {
+                let execute_invocation = if let Some(revert_reason) = trace.revert_error {
+                    ExecuteInvocation::Reverted(RevertedInvocation { revert_reason })
+                } else {
+                    let invocation = execute_invocation.expect("should exist if not reverted");
+                    ExecuteInvocation::Success(invocation)
+                };
+
+                TxTrace::Invoke(InvokeTxTrace {
+                    validate_invocation,
+                    execute_invocation,
+                    fee_transfer_invocation,
+                    execution_resources,
+                })
+            }
+
+            TxType::Declare => TxTrace::Declare(DeclareTxTrace {
+                validate_invocation,
+                fee_transfer_invocation,
+                execution_resources,
+            }),
+
+            TxType::DeployAccount => TxTrace::DeployAccount(DeployAccountTxTrace {
+                validate_invocation,
+                constructor_invocation: execute_invocation.expect("should exist if not reverted"),
+                fee_transfer_invocation,
+                execution_resources,
+            }),
+
+            TxType::L1Handler => TxTrace::L1Handler(L1HandlerTxTrace {
+                function_invocation: execute_invocation.expect("should exist if not reverted"),
+                execution_resources,
+            }),
+        }
+    }
+}
+
+/// The execution trace of a transaction of a block.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct TxTraceWithHash {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: TxHash,
+    pub trace_root: TxTrace,
+}
+
+/// The trace and fee estimate of a simulated transaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedTx {
+    pub transaction_trace: TxTrace,
+    pub fee_estimation: FeeEstimate,
+}
+
+/// Defines a transaction sent to the node, which only differs from its 0.7 counterpart by the L1
+/// data gas bounds of its V3 variant.
+macro_rules! broadcasted_tx {
+    ($(#[$attr:meta])* $name:ident($inner:ty)) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone)]
+        pub struct $name(pub $inner);
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                from_broadcasted_v0_8(deserializer).map(Self)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+broadcasted_tx! {
+    /// An invoke transaction sent to the node.
+    BroadcastedInvokeTx(BroadcastedInvokeTxV0_7)
+}
+
+broadcasted_tx! {
+    /// A declare transaction sent to the node.
+    BroadcastedDeclareTx(BroadcastedDeclareTxV0_7)
+}
+
+broadcasted_tx! {
+    /// A deploy account transaction sent to the node.
+    BroadcastedDeployAccountTx(BroadcastedDeployAccountTxV0_7)
+}
+
+broadcasted_tx! {
+    /// A transaction sent to the node for its fee to be estimated or its execution simulated.
+    BroadcastedTx(BroadcastedTxV0_7)
+}
+
+/// Deserializes a 0.8 transaction as its 0.7 counterpart.
+///
+/// The resource bounds of the V3 transactions must include the L1 data gas. Katana doesn't price
+/// the L1 data gas separately from the L1 gas, so its bounds are dropped, and aren't part of the
+/// transaction hash either.
+fn from_broadcasted_v0_8<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let mut tx = Value::deserialize(deserializer)?;
+
+    if let Some(bounds) = tx.get_mut("resource_bounds").and_then(Value::as_object_mut) {
+        if bounds.remove("l1_data_gas").is_none() {
+            return Err(D::Error::missing_field("l1_data_gas"));
+        }
+    }
+
+    serde_json::from_value(tx).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::da::DataAvailabilityMode;
+    use katana_primitives::fee::ResourceBoundsMapping;
+    use katana_primitives::transaction::{InvokeTx, InvokeTxV1, InvokeTxV3, Tx, TxWithHash};
+    use serde_json::json;
+    use starknet::core::types::{ComputationResources, DataAvailabilityResources, DataResources};
+
+    use super::{
+        BroadcastedInvokeTx, BroadcastedTx, BroadcastedTxV0_7, ExecutionResources, ExecutionResult,
+        FeePayment, Felt, PriceUnit, TransactionFinalityStatus, TransactionReceipt, TxReceipt,
+        TxV0_7,
+    };
+
+    fn invoke_v3() -> InvokeTxV3 {
+        InvokeTxV3 {
+            chain_id: Default::default(),
+            sender_address: Felt::ONE.into(),
+            nonce: Felt::ZERO,
+            calldata: vec![],
+            signature: vec![],
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: katana_primitives::fee::ResourceBounds {
+                    max_amount: 0x10,
+                    max_price_per_unit: 0x20,
+                },
+                l2_gas: katana_primitives::fee::ResourceBounds {
+                    max_amount: 0,
+                    max_price_per_unit: 0,
+                },
+            },
+            tip: 0,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+        }
+    }
+
+    #[test]
+    fn tx_resource_bounds() {
+        let tx = TxWithHash { hash: Felt::TWO, transaction: Tx::Invoke(InvokeTx::V3(invoke_v3())) };
+        let tx = serde_json::to_value(super::Tx::from(TxV0_7::from(tx))).unwrap();
+
+        assert_eq!(tx["type"], "INVOKE");
+        assert_eq!(tx["version"], "0x3");
+        assert_eq!(tx["transaction_hash"], "0x2");
+        assert_eq!(
+            tx["resource_bounds"]["l1_data_gas"],
+            json!({ "max_amount": "0x0", "max_price_per_unit": "0x0" })
+        );
+        assert_eq!(tx["resource_bounds"]["l1_gas"]["max_amount"], "0x10");
+
+        // transactions without resource bounds are the same as in 0.7
+        let tx = TxV0_7::from(TxWithHash {
+            hash: Felt::TWO,
+            transaction: Tx::Invoke(InvokeTx::V1(InvokeTxV1::default())),
+        });
+        let expected = serde_json::to_value(&tx).unwrap();
+        assert_eq!(serde_json::to_value(super::Tx::from(tx)).unwrap(), expected);
+    }
+
+    #[test]
+    fn receipt_resources() {
+        let receipt = TransactionReceipt::Invoke(starknet::core::types::InvokeTransactionReceipt {
+            transaction_hash: Felt::ONE,
+            actual_fee: FeePayment { amount: Felt::ONE, unit: PriceUnit::Wei },
+            finality_status: TransactionFinalityStatus::AcceptedOnL2,
+            messages_sent: vec![],
+            events: vec![],
+            execution_resources: starknet::core::types::ExecutionResources {
+                computation_resources: ComputationResources {
+                    steps: 100,
+                    memory_holes: None,
+                    range_check_builtin_applications: None,
+                    pedersen_builtin_applications: None,
+                    poseidon_builtin_applications: None,
+                    ec_op_builtin_applications: None,
+                    ecdsa_builtin_applications: None,
+                    bitwise_builtin_applications: None,
+                    keccak_builtin_applications: None,
+                    segment_arena_builtin: None,
+                },
+                data_resources: DataResources {
+                    data_availability: DataAvailabilityResources { l1_gas: 1, l1_data_gas: 2 },
+                },
+            },
+            execution_result: ExecutionResult::Reverted { reason: "oops".into() },
+        });
+
+        let resources = ExecutionResources { l1_gas: 10, l1_data_gas: 2, l2_gas: 0 };
+        let receipt = serde_json::to_value(TxReceipt::new(receipt, resources)).unwrap();
+
+        assert_eq!(receipt["type"], "INVOKE");
+        assert_eq!(receipt["execution_status"], "REVERTED");
+        assert_eq!(receipt["revert_reason"], "oops");
+        assert_eq!(
+            receipt["execution_resources"],
+            json!({ "l1_gas": 10, "l1_data_gas": 2, "l2_gas": 0 })
+        );
+        assert!(receipt.get("contract_address").is_none());
+    }
+
+    #[test]
+    fn broadcasted_tx_resource_bounds() {
+        let bounds = |amount: &str| json!({ "max_amount": amount, "max_price_per_unit": "0x1" });
+        let mut tx = json!({
+            "type": "INVOKE",
+            "version": "0x3",
+            "sender_address": "0x1",
+            "calldata": [],
+            "signature": [],
+            "nonce": "0x0",
+            "resource_bounds": {
+                "l1_gas": bounds("0x10"),
+                "l1_data_gas": bounds("0x20"),
+                "l2_gas": bounds("0x0"),
+            },
+            "tip": "0x0",
+            "paymaster_data": [],
+            "account_deployment_data": [],
+            "nonce_data_availability_mode": "L1",
+            "fee_data_availability_mode": "L1",
+        });
+
+        let invoke: BroadcastedInvokeTx = serde_json::from_value(tx.clone()).unwrap();
+        assert!(!invoke.0.is_query());
+
+        let BroadcastedTx(BroadcastedTxV0_7::Invoke(invoke)) =
+            serde_json::from_value(tx.clone()).unwrap()
+        else {
+            panic!("expected an invoke transaction");
+        };
+        let invoke = serde_json::to_value(invoke).unwrap();
+        assert_eq!(invoke["resource_bounds"]["l1_gas"]["max_amount"], "0x10");
+        assert!(invoke["resource_bounds"].get("l1_data_gas").is_none());
+
+        // the L1 data gas bounds are required by the 0.8 spec
+        tx["resource_bounds"].as_object_mut().unwrap().remove("l1_data_gas");
+        assert!(serde_json::from_value::<BroadcastedInvokeTx>(tx).is_err());
+    }
+}
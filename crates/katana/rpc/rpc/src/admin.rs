@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_pool::validation::policy::TxPolicy;
+use katana_pool::validation::stateful::TxValidator;
+use katana_rpc_api::admin::AdminApiServer;
+use katana_rpc_types::error::admin::AdminApiError;
+use tracing::info;
+
+#[allow(missing_debug_implementations)]
+pub struct AdminApi {
+    validator: TxValidator,
+    policy_file: Option<PathBuf>,
+}
+
+impl AdminApi {
+    pub fn new(validator: TxValidator, policy_file: Option<PathBuf>) -> Self {
+        Self { validator, policy_file }
+    }
+}
+
+#[async_trait]
+impl AdminApiServer for AdminApi {
+    async fn get_policy(&self) -> RpcResult<Option<TxPolicy>> {
+        Ok(self.validator.policy())
+    }
+
+    async fn reload_policy(&self) -> RpcResult<TxPolicy> {
+        let path = self.policy_file.as_ref().ok_or(AdminApiError::NoPolicyFile)?;
+        let policy = TxPolicy::load(path).map_err(AdminApiError::from)?;
+
+        self.validator.set_policy(Some(policy.clone()));
+        info!(path = %path.display(), "Transaction policy reloaded.");
+
+        Ok(policy)
+    }
+}
@@ -1,23 +1,64 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use alloy_primitives::U256;
 use jsonrpsee::core::{async_trait, Error};
 use katana_core::backend::Backend;
-use katana_core::service::block_producer::{BlockProducer, BlockProducerMode, PendingExecutor};
-use katana_executor::ExecutorFactory;
+use katana_core::env::BlockContextGenerator;
+use katana_core::service::block_producer::{
+    BlockProducer, BlockProducerMode, BlockProductionError, PendingExecutor,
+};
+use katana_executor::{ExecutorFactory, ImpersonatedAccounts};
+use katana_pool::{TransactionPool, TxPool};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::chain_spec::FeeContracts;
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
+use katana_primitives::genesis::constant::get_fee_token_balance_base_storage_address;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::genesis::Genesis;
+use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
+use katana_primitives::transaction::TxHash;
+use katana_primitives::utils::split_u256;
 use katana_primitives::Felt;
+use katana_provider::traits::block::BlockNumberProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::transaction::TransactionTraceProvider;
 use katana_rpc_api::dev::DevApiServer;
 use katana_rpc_types::account::Account;
 use katana_rpc_types::error::dev::DevApiError;
+use katana_rpc_types::profile::{Profile, ProfileFormat, TxProfile};
+use parking_lot::Mutex;
+
+use crate::profile::{self, Profiler};
 
 #[allow(missing_debug_implementations)]
 pub struct DevApi<EF: ExecutorFactory> {
     backend: Arc<Backend<EF>>,
+    pool: TxPool,
     block_producer: BlockProducer<EF>,
+    snapshots: Mutex<Snapshots>,
+}
+
+/// The snapshots taken with `dev_snapshot`.
+#[derive(Debug, Default)]
+struct Snapshots {
+    /// The id of the next snapshot.
+    next_id: u64,
+    entries: BTreeMap<u64, Snapshot>,
+}
+
+#[derive(Debug)]
+struct Snapshot {
+    /// The latest block number at the time the snapshot was taken.
+    block_number: BlockNumber,
+    /// The block context generator at the time the snapshot was taken.
+    block_context_generator: BlockContextGenerator,
 }
 
 impl<EF: ExecutorFactory> DevApi<EF> {
-    pub fn new(backend: Arc<Backend<EF>>, block_producer: BlockProducer<EF>) -> Self {
-        Self { backend, block_producer }
+    pub fn new(backend: Arc<Backend<EF>>, pool: TxPool, block_producer: BlockProducer<EF>) -> Self {
+        Self { backend, pool, block_producer, snapshots: Mutex::new(Snapshots::default()) }
     }
 
     /// Returns the pending state if the sequencer is running in _interval_ mode. Otherwise `None`.
@@ -57,6 +98,178 @@ impl<EF: ExecutorFactory> DevApi<EF> {
 
         Ok(())
     }
+
+    pub fn snapshot(&self) -> Result<u64, DevApiError> {
+        let block_number = self.backend.blockchain.provider().latest_number()?;
+        let block_context_generator = self.backend.block_context_generator.read().clone();
+
+        let mut snapshots = self.snapshots.lock();
+        let id = snapshots.next_id;
+        snapshots.next_id += 1;
+        snapshots.entries.insert(id, Snapshot { block_number, block_context_generator });
+
+        Ok(id)
+    }
+
+    /// Reverts the chain to the snapshot `id`. The blocks mined after the snapshot, the pending
+    /// block and the transactions in the pool are all discarded.
+    pub fn revert(&self, id: u64) -> Result<bool, DevApiError> {
+        let mut snapshots = self.snapshots.lock();
+        let Some(snapshot) = snapshots.entries.get(&id) else { return Ok(false) };
+
+        // the block context generator must be restored before unwinding, as it is used to build
+        // the env of the next block.
+        let generator = snapshot.block_context_generator.clone();
+        let prev = std::mem::replace(&mut *self.backend.block_context_generator.write(), generator);
+
+        if let Err(error) = self.block_producer.unwind_to(snapshot.block_number) {
+            *self.backend.block_context_generator.write() = prev;
+            return Err(map_block_production_error(error));
+        }
+
+        // drop all the transactions that haven't been picked up by the block producer yet
+        let _ = self.pool.take_transactions();
+        self.pool.set_taken_transactions(0);
+
+        let _ = snapshots.entries.split_off(&id);
+        Ok(true)
+    }
+
+    /// Unwinds the `depth` most recent blocks. Snapshots of the unwound blocks are removed.
+    pub fn reorg(&self, depth: u64) -> Result<BlockNumber, DevApiError> {
+        let latest = self.backend.blockchain.provider().latest_number()?;
+        if depth > latest {
+            return Err(DevApiError::InvalidReorgDepth { depth, latest });
+        }
+
+        let block_number = latest - depth;
+        self.block_producer.unwind_to(block_number).map_err(map_block_production_error)?;
+
+        // drop all the transactions that haven't been picked up by the block producer yet
+        let _ = self.pool.take_transactions();
+        self.pool.set_taken_transactions(0);
+
+        self.snapshots.lock().entries.retain(|_, snapshot| snapshot.block_number <= block_number);
+        Ok(block_number)
+    }
+
+    pub fn set_storage_at(
+        &self,
+        address: ContractAddress,
+        key: StorageKey,
+        value: StorageValue,
+    ) -> Result<(), DevApiError> {
+        let mut updates = StateUpdates::default();
+        updates.storage_updates.entry(address).or_default().insert(key, value);
+        self.apply_state_updates(updates)
+    }
+
+    /// Sets the balance of `address` in both the ETH and STRK fee tokens.
+    pub fn set_balance(&self, address: ContractAddress, amount: Felt) -> Result<(), DevApiError> {
+        let amount = U256::from_be_bytes(amount.to_bytes_be());
+        let fee_contracts = &self.backend.chain_spec.fee_contracts;
+
+        let mut updates = StateUpdates::default();
+        write_balance(&mut updates, fee_contracts, address, amount);
+        self.apply_state_updates(updates)
+    }
+
+    pub fn set_nonce(&self, address: ContractAddress, nonce: Nonce) -> Result<(), DevApiError> {
+        let mut updates = StateUpdates::default();
+        updates.nonce_updates.insert(address, nonce);
+        self.apply_state_updates(updates)
+    }
+
+    pub fn set_class_hash_at(
+        &self,
+        address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> Result<(), DevApiError> {
+        let mut updates = StateUpdates::default();
+        updates.deployed_contracts.insert(address, class_hash);
+        self.apply_state_updates(updates)
+    }
+
+    /// Applies `updates` to the pending block, so that they are committed with their history and
+    /// in the state tries like the changes of a transaction.
+    fn apply_state_updates(&self, updates: StateUpdates) -> Result<(), DevApiError> {
+        let updates =
+            StateUpdatesWithDeclaredClasses { state_updates: updates, ..Default::default() };
+        self.block_producer.apply_state_updates(updates).map_err(map_block_production_error)
+    }
+
+    pub fn dump_state(&self) -> Result<GenesisJson, DevApiError> {
+        self.backend
+            .dump_state()
+            .map_err(|error| DevApiError::UnexpectedError { reason: error.to_string() })
+    }
+
+    /// Applies the classes and allocations of `state` on top of the latest state, as a state diff
+    /// committed in a new block.
+    pub fn load_state(&self, state: GenesisJson) -> Result<(), DevApiError> {
+        let genesis = Genesis::try_from(state)
+            .map_err(|error| DevApiError::InvalidState { reason: error.to_string() })?;
+
+        let mut states = genesis.state_updates();
+        let fee_contracts = &self.backend.chain_spec.fee_contracts;
+
+        for (address, alloc) in &genesis.allocations {
+            if let Some(balance) = alloc.balance() {
+                write_balance(&mut states.state_updates, fee_contracts, *address, balance);
+            }
+        }
+
+        self.block_producer.apply_state_updates(states).map_err(map_block_production_error)
+    }
+
+    pub fn profile_transaction(
+        &self,
+        hash: TxHash,
+        format: ProfileFormat,
+    ) -> Result<Profile<TxProfile>, DevApiError> {
+        let provider = self.backend.blockchain.provider();
+        let exec = provider.transaction_execution(hash)?.ok_or(DevApiError::TxnHashNotFound)?;
+
+        let mut profiler = Profiler::new(provider.latest()?);
+        let profile = profiler.profile(hash, &exec)?;
+
+        Ok(profile::encode(std::slice::from_ref(&profile), format, || profile.clone()))
+    }
+
+    /// Returns the set of accounts whose transactions are executed without validation.
+    fn impersonated_accounts(&self) -> &ImpersonatedAccounts {
+        self.backend.executor_factory.execution_flags().impersonated_accounts()
+    }
+}
+
+/// Writes `amount` as the balance of `address` in both the ETH and STRK fee tokens.
+fn write_balance(
+    updates: &mut StateUpdates,
+    fee_contracts: &FeeContracts,
+    address: ContractAddress,
+    amount: U256,
+) {
+    let (low, high) = split_u256(amount);
+    let low_key = get_fee_token_balance_base_storage_address(address);
+    let high_key = low_key + Felt::ONE;
+
+    for token in [fee_contracts.eth, fee_contracts.strk] {
+        let storage = updates.storage_updates.entry(token).or_default();
+        storage.insert(low_key, low);
+        storage.insert(high_key, high);
+    }
+}
+
+fn map_block_production_error(error: BlockProductionError) -> DevApiError {
+    match error {
+        BlockProductionError::BlockProductionInProgress => DevApiError::BlockProductionInProgress,
+        BlockProductionError::PendingTransactions => DevApiError::PendingTransactions,
+        // the state updates applied through the dev api are provided by the client
+        BlockProductionError::TransactionExecutionError(error) => {
+            DevApiError::InvalidState { reason: error.to_string() }
+        }
+        error => DevApiError::UnexpectedError { reason: error.to_string() },
+    }
 }
 
 #[async_trait]
@@ -81,18 +294,64 @@ impl<EF: ExecutorFactory> DevApiServer for DevApi<EF> {
 
     async fn set_storage_at(
         &self,
-        _contract_address: Felt,
-        _key: Felt,
-        _value: Felt,
+        contract_address: Felt,
+        key: Felt,
+        value: Felt,
     ) -> Result<(), Error> {
-        // self.sequencer
-        //     .set_storage_at(contract_address.into(), key, value)
-        //     .await
-        //     .map_err(|_| Error::from(KatanaApiError::FailedToUpdateStorage))
+        Ok(self.set_storage_at(contract_address.into(), key, value)?)
+    }
+
+    async fn set_balance(&self, address: Felt, amount: Felt) -> Result<(), Error> {
+        Ok(self.set_balance(address.into(), amount)?)
+    }
+
+    async fn set_nonce(&self, address: Felt, nonce: Felt) -> Result<(), Error> {
+        Ok(self.set_nonce(address.into(), nonce)?)
+    }
+
+    async fn set_class_hash_at(&self, address: Felt, class_hash: Felt) -> Result<(), Error> {
+        Ok(self.set_class_hash_at(address.into(), class_hash)?)
+    }
+
+    async fn impersonate_account(&self, address: Felt) -> Result<(), Error> {
+        self.impersonated_accounts().insert(address.into());
+        Ok(())
+    }
+
+    async fn stop_impersonating_account(&self, address: Felt) -> Result<(), Error> {
+        self.impersonated_accounts().remove(address.into());
         Ok(())
     }
 
+    async fn snapshot(&self) -> Result<u64, Error> {
+        Ok(self.snapshot()?)
+    }
+
+    async fn revert(&self, id: u64) -> Result<bool, Error> {
+        Ok(self.revert(id)?)
+    }
+
+    async fn reorg(&self, depth: u64) -> Result<u64, Error> {
+        Ok(self.reorg(depth)?)
+    }
+
+    async fn dump_state(&self) -> Result<GenesisJson, Error> {
+        Ok(self.dump_state()?)
+    }
+
+    async fn load_state(&self, state: GenesisJson) -> Result<(), Error> {
+        Ok(self.load_state(state)?)
+    }
+
     async fn predeployed_accounts(&self) -> Result<Vec<Account>, Error> {
         Ok(self.backend.chain_spec.genesis.accounts().map(|e| Account::new(*e.0, e.1)).collect())
     }
+
+    async fn profile_transaction(
+        &self,
+        transaction_hash: TxHash,
+        format: Option<ProfileFormat>,
+    ) -> Result<Profile<TxProfile>, Error> {
+        Ok(self.profile_transaction(transaction_hash, format.unwrap_or_default())?)
+    }
 }
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_core::backend::Backend;
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::BlockIdOrTag;
+use katana_provider::traits::block::{BlockIdReader, BlockProvider};
+use katana_provider::traits::da::StateDiffBlobProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::transaction::{TransactionTraceProvider, TransactionsProviderExt};
+use katana_rpc_api::katana::KatanaApiServer;
+use katana_rpc_types::da::BlockStateDiffBlob;
+use katana_rpc_types::error::katana::KatanaApiError;
+use katana_rpc_types::profile::{BlockProfile, Profile, ProfileFormat};
+use katana_tasks::TokioTaskSpawner;
+
+use crate::profile::{self, Profiler};
+
+#[allow(missing_debug_implementations)]
+pub struct KatanaApi<EF: ExecutorFactory> {
+    backend: Arc<Backend<EF>>,
+}
+
+impl<EF: ExecutorFactory> Clone for KatanaApi<EF> {
+    fn clone(&self) -> Self {
+        Self { backend: Arc::clone(&self.backend) }
+    }
+}
+
+impl<EF: ExecutorFactory> KatanaApi<EF> {
+    pub fn new(backend: Arc<Backend<EF>>) -> Self {
+        Self { backend }
+    }
+
+    async fn on_io_blocking_task<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(Self) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let this = self.clone();
+        TokioTaskSpawner::new().unwrap().spawn_blocking(move || func(this)).await.unwrap()
+    }
+
+    fn profile_block(&self, block_id: BlockIdOrTag) -> Result<BlockProfile, KatanaApiError> {
+        let provider = self.backend.blockchain.provider();
+        let profile_error = |_| KatanaApiError::FailedToBuildProfile;
+
+        let block_number = provider
+            .convert_block_id(block_id)
+            .map_err(profile_error)?
+            .ok_or(KatanaApiError::BlockNotFound)?;
+
+        let execs = provider
+            .transaction_executions_by_block(block_number.into())
+            .map_err(profile_error)?
+            .ok_or(KatanaApiError::BlockNotFound)?;
+
+        let indices = provider
+            .block_body_indices(block_number.into())
+            .map_err(profile_error)?
+            .ok_or(KatanaApiError::BlockNotFound)?;
+
+        let hashes = provider.transaction_hashes_in_range(indices.into()).map_err(profile_error)?;
+
+        let mut profiler = Profiler::new(provider.latest().map_err(profile_error)?);
+        let transactions = hashes
+            .into_iter()
+            .zip(execs)
+            .map(|(hash, exec)| profiler.profile(hash, &exec))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(profile_error)?;
+
+        Ok(BlockProfile { block_number, transactions })
+    }
+
+    fn state_diff_blob(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> Result<BlockStateDiffBlob, KatanaApiError> {
+        let provider = self.backend.blockchain.provider();
+        let blob_error = |_| KatanaApiError::FailedToGetStateDiffBlob;
+
+        let block_number = provider
+            .convert_block_id(block_id)
+            .map_err(blob_error)?
+            .ok_or(KatanaApiError::BlockNotFound)?;
+
+        let blobs = provider
+            .state_diff_blobs(block_number)
+            .map_err(blob_error)?
+            .ok_or(KatanaApiError::StateDiffBlobNotFound)?;
+
+        Ok(BlockStateDiffBlob { block_number, blobs })
+    }
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> KatanaApiServer for KatanaApi<EF> {
+    async fn profile_block(
+        &self,
+        block_id: BlockIdOrTag,
+        format: Option<ProfileFormat>,
+    ) -> RpcResult<Profile<BlockProfile>> {
+        self.on_io_blocking_task(move |this| {
+            let block = this.profile_block(block_id)?;
+            Ok(profile::encode(&block.transactions, format.unwrap_or_default(), || block.clone()))
+        })
+        .await
+    }
+
+    async fn get_block_state_diff_blob(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<BlockStateDiffBlob> {
+        self.on_io_blocking_task(move |this| Ok(this.state_diff_blob(block_id)?)).await
+    }
+}
@@ -3,10 +3,15 @@
 #![allow(clippy::blocks_in_conditions)]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+pub mod admin;
 pub mod dev;
+pub mod katana;
 pub mod metrics;
 pub mod saya;
 pub mod starknet;
 pub mod torii;
+pub mod txpool;
+pub mod versioning;
 
+mod profile;
 mod utils;
@@ -0,0 +1,310 @@
+//! Building the profiles of executed transactions and encoding them in the [`ProfileFormat`]s.
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use base64::Engine;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use katana_primitives::class::ClassHash;
+use katana_primitives::trace::{CallInfo, TxExecInfo};
+use katana_primitives::transaction::TxHash;
+use katana_primitives::Felt;
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::state::StateProvider;
+use katana_provider::ProviderResult;
+use katana_rpc_types::profile::{
+    CallProfile, Profile, ProfileFormat, TxProfile, TxProfileResources,
+};
+use starknet::core::types::contract::AbiEntry;
+use starknet::core::utils::get_selector_from_name;
+
+/// Builds the profiles of transactions, resolving the entry point names of their calls from the
+/// ABIs of the classes in `state`.
+#[derive(Debug)]
+pub struct Profiler {
+    state: Box<dyn StateProvider>,
+    /// The entry point names of the classes whose ABI was already parsed, by selector.
+    entry_points: HashMap<ClassHash, HashMap<Felt, String>>,
+}
+
+impl Profiler {
+    pub fn new(state: Box<dyn StateProvider>) -> Self {
+        Self { state, entry_points: HashMap::new() }
+    }
+
+    pub fn profile(&mut self, hash: TxHash, exec: &TxExecInfo) -> ProviderResult<TxProfile> {
+        let mut profile_call =
+            |call: &Option<CallInfo>| call.as_ref().map(|call| self.profile_call(call)).transpose();
+
+        let validate = profile_call(&exec.validate_call_info)?;
+        let execute = profile_call(&exec.execute_call_info)?;
+        let fee_transfer = profile_call(&exec.fee_transfer_call_info)?;
+
+        let resources = TxProfileResources {
+            steps: exec.actual_resources.vm_resources.n_steps as u64,
+            l1_gas: exec.actual_resources.total_gas_consumed.l1_gas,
+            l1_data_gas: exec.actual_resources.total_gas_consumed.l1_data_gas,
+        };
+
+        Ok(TxProfile { transaction_hash: hash, resources, validate, execute, fee_transfer })
+    }
+
+    fn profile_call(&mut self, call: &CallInfo) -> ProviderResult<CallProfile> {
+        // the entry points of all the classes in the call tree are loaded beforehand, as the
+        // resolver can't fail.
+        self.load_entry_points(call)?;
+
+        let mut resolve = |call: &CallInfo| {
+            let class_hash = self.class_hash(call);
+            let name = class_hash
+                .and_then(|hash| self.entry_points.get(&hash))
+                .and_then(|names| names.get(&call.entry_point_selector))
+                .cloned();
+            (class_hash, name)
+        };
+
+        Ok(CallProfile::new(call, &mut resolve))
+    }
+
+    fn load_entry_points(&mut self, call: &CallInfo) -> ProviderResult<()> {
+        let class_hash = match call.class_hash {
+            Some(hash) => Some(hash),
+            None => self.state.class_hash_of_contract(call.contract_address)?,
+        };
+
+        if let Some(hash) = class_hash {
+            if !self.entry_points.contains_key(&hash) {
+                let names = match self.state.sierra_class(hash)? {
+                    Some(class) => entry_point_names(&class.abi),
+                    // legacy classes don't have named entry points in their ABI
+                    None => HashMap::new(),
+                };
+                self.entry_points.insert(hash, names);
+            }
+        }
+
+        call.inner_calls.iter().try_for_each(|call| self.load_entry_points(call))
+    }
+
+    fn class_hash(&self, call: &CallInfo) -> Option<ClassHash> {
+        call.class_hash.or_else(|| self.state.class_hash_of_contract(call.contract_address).ok()?)
+    }
+}
+
+/// Returns the names of the functions of a Sierra class ABI, by selector.
+fn entry_point_names(abi: &str) -> HashMap<Felt, String> {
+    fn collect(entries: Vec<AbiEntry>, names: &mut HashMap<Felt, String>) {
+        for entry in entries {
+            let name = match entry {
+                AbiEntry::Function(function) => function.name,
+                AbiEntry::L1Handler(handler) => handler.name,
+                AbiEntry::Constructor(constructor) => constructor.name,
+                AbiEntry::Interface(interface) => {
+                    collect(interface.items, names);
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if let Ok(selector) = get_selector_from_name(&name) {
+                names.insert(selector, name);
+            }
+        }
+    }
+
+    let mut names = HashMap::new();
+    if let Ok(entries) = serde_json::from_str::<Vec<AbiEntry>>(abi) {
+        collect(entries, &mut names);
+    }
+    names
+}
+
+/// Encodes `profiles` in `format`. `call_tree` wraps the profiles for the call tree format.
+pub fn encode<T>(
+    profiles: &[TxProfile],
+    format: ProfileFormat,
+    call_tree: impl FnOnce() -> T,
+) -> Profile<T> {
+    match format {
+        ProfileFormat::CallTree => Profile::CallTree(call_tree()),
+        ProfileFormat::Folded => Profile::Folded { stacks: folded(profiles) },
+        ProfileFormat::Pprof => Profile::Pprof { profile: pprof(profiles) },
+    }
+}
+
+/// Visits every call of `profiles` with the stack of frames leading to it, rooted at the phase of
+/// the transaction it belongs to.
+fn visit_stacks(profiles: &[TxProfile], mut visit: impl FnMut(&[String], &CallProfile)) {
+    fn walk(
+        call: &CallProfile,
+        stack: &mut Vec<String>,
+        visit: &mut impl FnMut(&[String], &CallProfile),
+    ) {
+        stack.push(call.frame_name());
+        visit(stack, call);
+        for inner in &call.inner_calls {
+            walk(inner, stack, visit);
+        }
+        stack.pop();
+    }
+
+    for profile in profiles {
+        for (phase, call) in profile.phases() {
+            let mut stack = vec![phase.to_string()];
+            walk(call, &mut stack, &mut visit);
+        }
+    }
+}
+
+/// Returns the own steps of the calls in the folded stacks format, ie one `frame;frame;... steps`
+/// line per call.
+pub fn folded(profiles: &[TxProfile]) -> String {
+    let mut lines = String::new();
+    visit_stacks(profiles, |stack, call| {
+        if call.own.steps > 0 {
+            lines.push_str(&stack.join(";"));
+            lines.push_str(&format!(" {}\n", call.own.steps));
+        }
+    });
+    lines
+}
+
+/// Returns the own steps of the calls as a gzipped pprof profile, encoded in base64.
+pub fn pprof(profiles: &[TxProfile]) -> String {
+    let mut builder = pprof::Builder::default();
+    visit_stacks(profiles, |stack, call| {
+        if call.own.steps > 0 {
+            builder.add_sample(stack, call.own.steps as i64);
+        }
+    });
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&builder.encode()).expect("qed; writing to a vec can't fail");
+    let bytes = encoder.finish().expect("qed; writing to a vec can't fail");
+
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// The subset of the [pprof format](https://github.com/google/pprof/blob/main/proto/profile.proto)
+/// needed to encode the profiles.
+mod pprof {
+    use std::collections::HashMap;
+
+    use prost::Message;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Profile {
+        #[prost(message, repeated, tag = "1")]
+        sample_type: Vec<ValueType>,
+        #[prost(message, repeated, tag = "2")]
+        sample: Vec<Sample>,
+        #[prost(message, repeated, tag = "4")]
+        location: Vec<Location>,
+        #[prost(message, repeated, tag = "5")]
+        function: Vec<Function>,
+        #[prost(string, repeated, tag = "6")]
+        string_table: Vec<String>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct ValueType {
+        #[prost(int64, tag = "1")]
+        r#type: i64,
+        #[prost(int64, tag = "2")]
+        unit: i64,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Sample {
+        /// The locations of the stack, leaf first.
+        #[prost(uint64, repeated, tag = "1")]
+        location_id: Vec<u64>,
+        #[prost(int64, repeated, tag = "2")]
+        value: Vec<i64>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Location {
+        #[prost(uint64, tag = "1")]
+        id: u64,
+        #[prost(message, repeated, tag = "4")]
+        line: Vec<Line>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Line {
+        #[prost(uint64, tag = "1")]
+        function_id: u64,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Function {
+        #[prost(uint64, tag = "1")]
+        id: u64,
+        #[prost(int64, tag = "2")]
+        name: i64,
+    }
+
+    /// Builds a profile with a single `steps` sample type, with one location and function per
+    /// frame name.
+    #[derive(Debug)]
+    pub(super) struct Builder {
+        profile: Profile,
+        strings: HashMap<String, i64>,
+        locations: HashMap<String, u64>,
+    }
+
+    impl Default for Builder {
+        fn default() -> Self {
+            let mut builder = Self {
+                // the first string of the table must be empty
+                profile: Profile { string_table: vec![String::new()], ..Default::default() },
+                strings: HashMap::from([(String::new(), 0)]),
+                locations: HashMap::new(),
+            };
+
+            let sample_type =
+                ValueType { r#type: builder.string("steps"), unit: builder.string("count") };
+            builder.profile.sample_type.push(sample_type);
+            builder
+        }
+    }
+
+    impl Builder {
+        /// Adds a sample of `value` steps, for the stack of frames `stack`, root first.
+        pub(super) fn add_sample(&mut self, stack: &[String], value: i64) {
+            let location_id = stack.iter().rev().map(|frame| self.location(frame)).collect();
+            self.profile.sample.push(Sample { location_id, value: vec![value] });
+        }
+
+        pub(super) fn encode(self) -> Vec<u8> {
+            self.profile.encode_to_vec()
+        }
+
+        fn location(&mut self, frame: &str) -> u64 {
+            if let Some(id) = self.locations.get(frame) {
+                return *id;
+            }
+
+            // the ids are 1-based, as 0 is reserved
+            let id = self.locations.len() as u64 + 1;
+            let name = self.string(frame);
+            self.profile.function.push(Function { id, name });
+            self.profile.location.push(Location { id, line: vec![Line { function_id: id }] });
+            self.locations.insert(frame.to_string(), id);
+            id
+        }
+
+        fn string(&mut self, value: &str) -> i64 {
+            if let Some(index) = self.strings.get(value) {
+                return *index;
+            }
+
+            let index = self.profile.string_table.len() as i64;
+            self.profile.string_table.push(value.to_string());
+            self.strings.insert(value.to_string(), index);
+            index
+        }
+    }
+}
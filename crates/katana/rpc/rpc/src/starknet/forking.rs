@@ -1,3 +1,6 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
 use katana_primitives::block::{BlockHash, BlockIdOrTag, BlockNumber};
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::transaction::TxHash;
@@ -31,10 +34,12 @@ pub enum Error {
     UnexpectedPendingData,
 }
 
-#[derive(Debug)]
+/// Clones of a [`ForkedClient`] share the same fork block, so that updating it with
+/// [`ForkedClient::set_block`] is visible to all of them.
+#[derive(Debug, Clone)]
 pub struct ForkedClient<P: Provider = JsonRpcClient<HttpTransport>> {
     /// The block number where the node is forked from.
-    block: BlockNumber,
+    block: Arc<AtomicU64>,
     /// The Starknet Json RPC provider client for doing the request to the forked network.
     provider: P,
 }
@@ -42,19 +47,25 @@ pub struct ForkedClient<P: Provider = JsonRpcClient<HttpTransport>> {
 impl<P: Provider> ForkedClient<P> {
     /// Creates a new forked client from the given [`Provider`] and block number.
     pub fn new(provider: P, block: BlockNumber) -> Self {
-        Self { provider, block }
+        Self { provider, block: Arc::new(AtomicU64::new(block)) }
     }
 
     /// Returns the block number of the forked client.
-    pub fn block(&self) -> &BlockNumber {
-        &self.block
+    pub fn block(&self) -> BlockNumber {
+        self.block.load(Ordering::Relaxed)
+    }
+
+    /// Sets the block number where the node is forked from, eg after the node has been rebased
+    /// onto a newer block of the forked network.
+    pub fn set_block(&self, block: BlockNumber) {
+        self.block.store(block, Ordering::Relaxed)
     }
 }
 
 impl ForkedClient {
     /// Creates a new forked client from the given HTTP URL and block number.
     pub fn new_http(url: Url, block: BlockNumber) -> Self {
-        Self { provider: JsonRpcClient::new(HttpTransport::new(url)), block }
+        Self::new(JsonRpcClient::new(HttpTransport::new(url)), block)
     }
 }
 
@@ -68,7 +79,7 @@ impl<P: Provider> ForkedClient<P> {
             return Err(Error::UnexpectedPendingData);
         };
 
-        if block.block_number > self.block {
+        if block.block_number > self.block() {
             Err(Error::BlockOutOfRange)
         } else {
             Ok(block.block_number)
@@ -87,7 +98,7 @@ impl<P: Provider> ForkedClient<P> {
         let receipt = self.provider.get_transaction_receipt(hash).await?;
 
         if let starknet::core::types::ReceiptBlock::Block { block_number, .. } = receipt.block {
-            if block_number > self.block {
+            if block_number > self.block() {
                 return Err(Error::BlockOutOfRange);
             }
         }
@@ -114,7 +125,7 @@ impl<P: Provider> ForkedClient<P> {
     ) -> Result<Tx, Error> {
         match block_id {
             BlockIdOrTag::Number(num) => {
-                if num > self.block {
+                if num > self.block() {
                     return Err(Error::BlockOutOfRange);
                 }
 
@@ -137,7 +148,7 @@ impl<P: Provider> ForkedClient<P> {
                     }
                 };
 
-                if number > self.block {
+                if number > self.block() {
                     return Err(Error::BlockOutOfRange);
                 }
 
@@ -156,7 +167,7 @@ impl<P: Provider> ForkedClient<P> {
 
         match block {
             starknet::core::types::MaybePendingBlockWithTxs::Block(ref b) => {
-                if b.block_number > self.block {
+                if b.block_number > self.block() {
                     Err(Error::BlockOutOfRange)
                 } else {
                     Ok(block.into())
@@ -177,7 +188,7 @@ impl<P: Provider> ForkedClient<P> {
 
         match block {
             starknet::core::types::MaybePendingBlockWithReceipts::Block(ref b) => {
-                if b.block_number > self.block {
+                if b.block_number > self.block() {
                     return Err(Error::BlockOutOfRange);
                 }
             }
@@ -197,7 +208,7 @@ impl<P: Provider> ForkedClient<P> {
 
         match block {
             starknet::core::types::MaybePendingBlockWithTxHashes::Block(ref b) => {
-                if b.block_number > self.block {
+                if b.block_number > self.block() {
                     return Err(Error::BlockOutOfRange);
                 }
             }
@@ -211,14 +222,14 @@ impl<P: Provider> ForkedClient<P> {
 
     pub async fn get_block_transaction_count(&self, block_id: BlockIdOrTag) -> Result<u64, Error> {
         match block_id {
-            BlockIdOrTag::Number(num) if num > self.block => {
+            BlockIdOrTag::Number(num) if num > self.block() => {
                 return Err(Error::BlockOutOfRange);
             }
             BlockIdOrTag::Hash(hash) => {
                 let block =
                     self.provider.get_block_with_tx_hashes(BlockIdOrTag::Hash(hash)).await?;
                 if let starknet::core::types::MaybePendingBlockWithTxHashes::Block(b) = block {
-                    if b.block_number > self.block {
+                    if b.block_number > self.block() {
                         return Err(Error::BlockOutOfRange);
                     }
                 }
@@ -238,14 +249,14 @@ impl<P: Provider> ForkedClient<P> {
         block_id: BlockIdOrTag,
     ) -> Result<MaybePendingStateUpdate, Error> {
         match block_id {
-            BlockIdOrTag::Number(num) if num > self.block => {
+            BlockIdOrTag::Number(num) if num > self.block() => {
                 return Err(Error::BlockOutOfRange);
             }
             BlockIdOrTag::Hash(hash) => {
                 let block =
                     self.provider.get_block_with_tx_hashes(BlockIdOrTag::Hash(hash)).await?;
                 if let starknet::core::types::MaybePendingBlockWithTxHashes::Block(b) = block {
-                    if b.block_number > self.block {
+                    if b.block_number > self.block() {
                         return Err(Error::BlockOutOfRange);
                     }
                 }
@@ -272,7 +283,7 @@ impl<P: Provider> ForkedClient<P> {
         continuation_token: Option<String>,
         chunk_size: u64,
     ) -> Result<EventsPage, Error> {
-        if from > self.block || to > self.block {
+        if from > self.block() || to > self.block() {
             return Err(Error::BlockOutOfRange);
         }
 
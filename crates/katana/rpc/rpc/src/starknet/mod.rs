@@ -2,17 +2,21 @@
 
 pub mod forking;
 mod read;
+mod subscription;
 mod trace;
+mod v0_8;
 mod write;
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use forking::ForkedClient;
+use jsonrpsee::types::SubscriptionId;
 use katana_core::backend::Backend;
 use katana_core::service::block_producer::{BlockProducer, BlockProducerMode, PendingExecutor};
 use katana_executor::{ExecutionResult, ExecutorFactory};
 use katana_pool::validation::stateful::TxValidator;
-use katana_pool::TxPool;
+use katana_pool::{TransactionPool, TxPool};
 use katana_primitives::block::{
     BlockHash, BlockHashOrNumber, BlockIdOrTag, BlockNumber, BlockTag, FinalityStatus,
     PartialHeader,
@@ -23,36 +27,50 @@ use katana_primitives::conversion::rpc::legacy_inner_to_rpc_class;
 use katana_primitives::da::L1DataAvailabilityMode;
 use katana_primitives::env::BlockEnv;
 use katana_primitives::event::MaybeForkedContinuationToken;
+use katana_primitives::message::{L1ToL2Message, L1TxHash, MessageCancellation};
 use katana_primitives::transaction::{ExecutableTxWithHash, TxHash};
 use katana_primitives::Felt;
 use katana_provider::traits::block::{BlockHashProvider, BlockIdReader, BlockNumberProvider};
 use katana_provider::traits::contract::ContractClassProvider;
 use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::message::L1ToL2MessageProvider;
 use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
 use katana_provider::traits::transaction::{
     ReceiptProvider, TransactionProvider, TransactionStatusProvider,
 };
+use katana_provider::traits::trie::StateProofProvider;
 use katana_rpc_types::block::{
     MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
     PendingBlockWithReceipts, PendingBlockWithTxHashes, PendingBlockWithTxs,
 };
 use katana_rpc_types::error::starknet::StarknetApiError;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
+use katana_rpc_types::message::MessageStatus;
 use katana_rpc_types::receipt::{ReceiptBlock, TxReceiptWithBlockInfo};
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::Tx;
+use katana_rpc_types::trie::{
+    ContractLeafData, ContractStorageKeys, ContractsProof, GetStorageProofResponse, GlobalRoots,
+};
 use katana_rpc_types::FeeEstimate;
 use katana_rpc_types_builder::ReceiptBuilder;
 use katana_tasks::{BlockingTaskPool, TokioTaskSpawner};
+use parking_lot::Mutex;
 use starknet::core::types::{
-    ContractClass, PriceUnit, ResultPageRequest, TransactionExecutionStatus, TransactionStatus,
+    ContractClass, PriceUnit, ResultPageRequest, SequencerTransactionStatus,
+    TransactionExecutionStatus, TransactionStatus,
 };
+use tokio::task::AbortHandle;
 
 use crate::utils;
 use crate::utils::events::{Cursor, EventBlockId};
 
 pub type StarknetApiResult<T> = Result<T, StarknetApiError>;
 
+/// The maximum number of keys (class hashes, contract addresses and storage keys combined) that
+/// can be proven in a single `starknet_getStorageProof` request.
+const MAX_PROOF_KEYS: usize = 100;
+
 #[allow(missing_debug_implementations)]
 pub struct StarknetApi<EF: ExecutorFactory> {
     inner: Arc<Inner<EF>>,
@@ -71,6 +89,9 @@ struct Inner<EF: ExecutorFactory> {
     block_producer: BlockProducer<EF>,
     blocking_task_pool: BlockingTaskPool,
     forked_client: Option<ForkedClient>,
+    /// The tasks of the active subscriptions, so that they can be closed with
+    /// `starknet_unsubscribe`.
+    subscriptions: Mutex<HashMap<SubscriptionId<'static>, AbortHandle>>,
 }
 
 impl<EF: ExecutorFactory> StarknetApi<EF> {
@@ -102,8 +123,15 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
     ) -> Self {
         let blocking_task_pool =
             BlockingTaskPool::new().expect("failed to create blocking task pool");
-        let inner =
-            Inner { pool, backend, block_producer, blocking_task_pool, validator, forked_client };
+        let inner = Inner {
+            pool,
+            backend,
+            block_producer,
+            blocking_task_pool,
+            validator,
+            forked_client,
+            subscriptions: Mutex::default(),
+        };
         Self { inner: Arc::new(inner) }
     }
 
@@ -203,8 +231,13 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                 if let Some(exec) = self.pending_executor() {
                     Some(exec.read().block_env())
                 } else {
+                    // the next block will be priced by the gas oracle
                     let num = provider.latest_number()?;
-                    provider.block_env_at(num.into())?
+                    let mut env = provider.block_env_at(num.into())?;
+                    if let Some(env) = &mut env {
+                        self.inner.backend.gas_oracle.update_block_env(env);
+                    }
+                    env
                 }
             }
 
@@ -295,6 +328,80 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         Ok(value.unwrap_or_default())
     }
 
+    fn storage_proof(
+        &self,
+        block_id: BlockIdOrTag,
+        class_hashes: Vec<ClassHash>,
+        contract_addresses: Vec<ContractAddress>,
+        contracts_storage_keys: Vec<ContractStorageKeys>,
+    ) -> StarknetApiResult<GetStorageProofResponse> {
+        let provider = self.inner.backend.blockchain.provider();
+
+        let storage_keys_count =
+            contracts_storage_keys.iter().map(|c| c.storage_keys.len()).sum::<usize>();
+        let total_keys = class_hashes.len() + contract_addresses.len() + storage_keys_count;
+
+        if total_keys > MAX_PROOF_KEYS {
+            return Err(StarknetApiError::ProofLimitExceeded);
+        }
+
+        let block_number = match block_id {
+            BlockIdOrTag::Tag(BlockTag::Latest) => None,
+            BlockIdOrTag::Tag(BlockTag::Pending) => return Err(StarknetApiError::BlockNotFound),
+            BlockIdOrTag::Number(num) => Some(num),
+            BlockIdOrTag::Hash(hash) => {
+                Some(provider.block_number_by_hash(hash)?.ok_or(StarknetApiError::BlockNotFound)?)
+            }
+        };
+
+        let storage_keys = contracts_storage_keys
+            .into_iter()
+            .map(|keys| (keys.contract_address.into(), keys.storage_keys))
+            .collect::<Vec<_>>();
+
+        // the roots and the proofs are read together, so that they match even if a new block is
+        // committed in the meantime. the tries are not maintained in forked mode.
+        let Some(proof) =
+            provider.latest_state_proof(&class_hashes, &contract_addresses, &storage_keys)?
+        else {
+            return Err(StarknetApiError::StorageProofNotSupported);
+        };
+
+        match block_number {
+            Some(num) if num > proof.block_number => return Err(StarknetApiError::BlockNotFound),
+            // only the latest storage tries are kept, so proofs can only be generated for the
+            // latest block.
+            Some(num) if num != proof.block_number => {
+                return Err(StarknetApiError::StorageProofNotSupported);
+            }
+            _ => {}
+        }
+
+        let contract_leaves_data = proof
+            .contract_leaves
+            .into_iter()
+            .map(|leaf| ContractLeafData {
+                nonce: leaf.nonce,
+                class_hash: leaf.class_hash,
+                storage_root: leaf.storage_root,
+            })
+            .collect();
+
+        Ok(GetStorageProofResponse {
+            classes_proof: proof.classes_proof.into(),
+            contracts_proof: ContractsProof {
+                nodes: proof.contracts_proof.into(),
+                contract_leaves_data,
+            },
+            contracts_storage_proofs: proof.storage_proofs.into_iter().map(Into::into).collect(),
+            global_roots: GlobalRoots {
+                contracts_tree_root: proof.roots.contracts,
+                classes_tree_root: proof.roots.classes,
+                block_hash: proof.block_hash,
+            },
+        })
+    }
+
     async fn block_tx_count(&self, block_id: BlockIdOrTag) -> StarknetApiResult<u64> {
         let count = self
             .on_io_blocking_task(move |this| {
@@ -567,6 +674,73 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         }
     }
 
+    async fn messages_status(&self, l1_tx_hash: L1TxHash) -> StarknetApiResult<Vec<MessageStatus>> {
+        let messages = self
+            .on_io_blocking_task(move |this| -> StarknetApiResult<_> {
+                let provider = this.inner.backend.blockchain.provider();
+                Ok(provider.messages_by_l1_tx(l1_tx_hash)?)
+            })
+            .await?
+            .ok_or(StarknetApiError::TxnHashNotFound)?;
+
+        let mut statuses = Vec::with_capacity(messages.len());
+        for message in messages {
+            statuses.push(self.message_status(message).await?);
+        }
+
+        Ok(statuses)
+    }
+
+    async fn message_status(&self, message: L1ToL2Message) -> StarknetApiResult<MessageStatus> {
+        let hash = message.tx_hash;
+        let status = match self.transaction_status(hash).await {
+            Ok(status) => Some(status),
+            Err(StarknetApiError::TxnHashNotFound) => None,
+            Err(error) => return Err(error),
+        };
+
+        let (finality_status, execution_status) = match status {
+            Some(TransactionStatus::Received) => (SequencerTransactionStatus::Received, None),
+            Some(TransactionStatus::Rejected) => (SequencerTransactionStatus::Rejected, None),
+            Some(TransactionStatus::AcceptedOnL2(status)) => {
+                (SequencerTransactionStatus::AcceptedOnL2, Some(status))
+            }
+            Some(TransactionStatus::AcceptedOnL1(status)) => {
+                (SequencerTransactionStatus::AcceptedOnL1, Some(status))
+            }
+            // the transactions of the gathered messages are added to the pool right away
+            None if self.inner.pool.contains(hash) => (SequencerTransactionStatus::Received, None),
+            None => (SequencerTransactionStatus::Rejected, None),
+        };
+
+        let failure_reason = match (&finality_status, &execution_status) {
+            (_, Some(TransactionExecutionStatus::Reverted)) => {
+                self.on_io_blocking_task(move |this| -> StarknetApiResult<_> {
+                    let receipt = this.inner.backend.blockchain.provider().receipt_by_hash(hash)?;
+                    Ok(receipt.and_then(|r| r.revert_reason().map(ToString::to_string)))
+                })
+                .await?
+            }
+            (SequencerTransactionStatus::Rejected, _)
+                if message.cancellation == Some(MessageCancellation::Cancelled) =>
+            {
+                Some("Message was cancelled on L1".to_string())
+            }
+            (SequencerTransactionStatus::Rejected, _) if message.dropped => {
+                Some("Transaction was dropped without being included in a block".to_string())
+            }
+            _ => None,
+        };
+
+        Ok(MessageStatus {
+            transaction_hash: hash,
+            finality_status,
+            execution_status,
+            failure_reason,
+            cancellation: message.cancellation,
+        })
+    }
+
     async fn block_with_txs(
         &self,
         block_id: BlockIdOrTag,
@@ -869,7 +1043,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
 
                 // first determine whether the continuation token is from the forked client
                 let from_after_forked_if_any = if let Some(client) = &self.inner.forked_client {
-                    let forked_block = *client.block();
+                    let forked_block = client.block();
 
                     // if the from block is lower than the forked block, we fetch events from the
                     // forked client
@@ -914,7 +1088,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
 
                     // we start from block + 1 because we dont have the events locally and we may
                     // have fetched it from the forked network earlier
-                    *client.block() + 1
+                    client.block() + 1
                 } else {
                     from
                 };
@@ -943,7 +1117,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
 
                 // first determine whether the continuation token is from the forked client
                 let from_after_forked_if_any = if let Some(client) = &self.inner.forked_client {
-                    let forked_block = *client.block();
+                    let forked_block = client.block();
 
                     // if the from block is lower than the forked block, we fetch events from the
                     // forked client
@@ -988,7 +1162,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
 
                     // we start from block + 1 because we dont have the events locally and we may
                     // have fetched it from the forked network earlier
-                    *client.block() + 1
+                    client.block() + 1
                 } else {
                     from
                 };
@@ -1,6 +1,8 @@
 use jsonrpsee::core::{async_trait, Error, RpcResult};
 use katana_executor::{EntryPointCall, ExecutorFactory};
 use katana_primitives::block::BlockIdOrTag;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::message::L1TxHash;
 use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxHash};
 use katana_primitives::Felt;
 use katana_rpc_api::starknet::StarknetApiServer;
@@ -10,10 +12,11 @@ use katana_rpc_types::block::{
 };
 use katana_rpc_types::error::starknet::StarknetApiError;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
-use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::message::{MessageStatus, MsgFromL1};
 use katana_rpc_types::receipt::TxReceiptWithBlockInfo;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::{BroadcastedTx, Tx};
+use katana_rpc_types::trie::{ContractStorageKeys, GetStorageProofResponse};
 use katana_rpc_types::{
     ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlagForEstimateFee,
 };
@@ -163,6 +166,34 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
         .await
     }
 
+    async fn get_storage_proof(
+        &self,
+        block_id: BlockIdOrTag,
+        class_hashes: Option<Vec<Felt>>,
+        contract_addresses: Option<Vec<Felt>>,
+        contracts_storage_keys: Option<Vec<ContractStorageKeys>>,
+    ) -> RpcResult<GetStorageProofResponse> {
+        self.on_io_blocking_task(move |this| {
+            let class_hashes = class_hashes.unwrap_or_default();
+            let contract_addresses = contract_addresses
+                .unwrap_or_default()
+                .into_iter()
+                .map(ContractAddress::from)
+                .collect::<Vec<_>>();
+            let contracts_storage_keys = contracts_storage_keys.unwrap_or_default();
+
+            let proof = this.storage_proof(
+                block_id,
+                class_hashes,
+                contract_addresses,
+                contracts_storage_keys,
+            )?;
+
+            Ok(proof)
+        })
+        .await
+    }
+
     async fn estimate_fee(
         &self,
         request: Vec<BroadcastedTx>,
@@ -217,9 +248,12 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
             //
             // This doesn't completely disregard the nonce as nonce < account nonce will
             // return an error. It only 'relaxes' the check for nonce >= account nonce.
+            let impersonated =
+                this.inner.backend.executor_factory.execution_flags().impersonated_accounts();
             let flags = katana_executor::ExecutionFlags::new()
                 .with_account_validation(should_validate)
-                .with_nonce_check(false);
+                .with_nonce_check(false)
+                .with_impersonated_accounts(impersonated.clone());
 
             let results = this.estimate_fee_with(transactions, block_id, flags)?;
             Ok(results)
@@ -266,4 +300,11 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
     ) -> RpcResult<TransactionStatus> {
         Ok(self.transaction_status(transaction_hash).await?)
     }
+
+    async fn get_messages_status(
+        &self,
+        transaction_hash: L1TxHash,
+    ) -> RpcResult<Vec<MessageStatus>> {
+        Ok(self.messages_status(transaction_hash).await?)
+    }
 }
@@ -0,0 +1,360 @@
+use std::future::Future;
+use std::ops::RangeInclusive;
+
+use futures::{stream, StreamExt};
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::server::SubscriptionSink;
+use jsonrpsee::types::{SubscriptionId, SubscriptionResult};
+use katana_executor::ExecutorFactory;
+use katana_pool::TransactionPool;
+use katana_primitives::block::{BlockIdOrTag, BlockNumber};
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::transaction::{DeclareTx, InvokeTx, Tx, TxHash};
+use katana_primitives::Felt;
+use katana_provider::traits::block::{BlockHashProvider, BlockNumberProvider, HeaderProvider};
+use katana_rpc_api::starknet::StarknetWsApiServer;
+use katana_rpc_types::block::BlockHeader;
+use katana_rpc_types::error::starknet::StarknetApiError;
+use katana_rpc_types::event::EmittedEvent;
+use katana_rpc_types::transaction::{PendingTx, TxStatusUpdate};
+use starknet::core::types::{TransactionExecutionStatus, TransactionStatus};
+
+use super::{StarknetApi, StarknetApiResult};
+use crate::utils::events::{self, Filter};
+
+/// The maximum number of blocks in the past a subscription is allowed to start from.
+const MAX_BLOCKS_BACK: u64 = 1024;
+
+/// The maximum number of addresses allowed in the `sender_address` filter of the pending
+/// transactions subscription.
+const MAX_SENDER_ADDRESSES: usize = 128;
+
+impl<EF: ExecutorFactory> StarknetApi<EF> {
+    /// Accepts the subscription and spawns the task that feeds its sink. The task is tracked until
+    /// it ends so that the subscription can be closed with `starknet_unsubscribe`.
+    fn spawn_subscription<F, Fut>(&self, mut sink: SubscriptionSink, task: F) -> SubscriptionResult
+    where
+        F: FnOnce(SubscriptionSink) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        sink.accept()?;
+        let id = sink.subscription_id().expect("subscription is accepted");
+        let task = task(sink);
+
+        // the lock is held until the task is registered, otherwise a task that ends right away
+        // would try to unregister itself before being registered.
+        let mut subscriptions = self.inner.subscriptions.lock();
+
+        let this = self.clone();
+        let key = id.clone();
+        let handle = tokio::spawn(async move {
+            task.await;
+            this.inner.subscriptions.lock().remove(&key);
+        });
+
+        subscriptions.insert(id, handle.abort_handle());
+        Ok(())
+    }
+
+    /// Resolves the `block_id` from which a subscription should start sending historical data.
+    ///
+    /// Returns the number of the first historical block to send (if any) along with the latest
+    /// block number.
+    fn subscription_start(
+        &self,
+        block_id: Option<BlockIdOrTag>,
+    ) -> StarknetApiResult<(Option<BlockNumber>, BlockNumber)> {
+        let provider = self.inner.backend.blockchain.provider();
+        let latest = provider.latest_number()?;
+
+        let start = match block_id {
+            None | Some(BlockIdOrTag::Tag(_)) => return Ok((None, latest)),
+            Some(BlockIdOrTag::Number(num)) => num,
+            Some(BlockIdOrTag::Hash(hash)) => {
+                provider.block_number_by_hash(hash)?.ok_or(StarknetApiError::BlockNotFound)?
+            }
+        };
+
+        if start > latest {
+            return Err(StarknetApiError::BlockNotFound);
+        }
+
+        if latest - start > MAX_BLOCKS_BACK {
+            return Err(StarknetApiError::TooManyBlocksBack);
+        }
+
+        Ok((Some(start), latest))
+    }
+
+    fn block_headers(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> StarknetApiResult<Vec<BlockHeader>> {
+        let provider = self.inner.backend.blockchain.provider();
+
+        let mut headers = Vec::new();
+        for num in range {
+            let hash = provider.block_hash_by_num(num)?.ok_or(StarknetApiError::BlockNotFound)?;
+            let header = provider.header(num.into())?.ok_or(StarknetApiError::BlockNotFound)?;
+            headers.push(BlockHeader::new(hash, header));
+        }
+
+        Ok(headers)
+    }
+
+    fn block_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        filter: &Filter,
+    ) -> StarknetApiResult<Vec<EmittedEvent>> {
+        let provider = self.inner.backend.blockchain.provider();
+        let mut buffer = Vec::new();
+        events::fetch_events_at_blocks(provider, range, filter, u64::MAX, None, &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+impl<EF: ExecutorFactory> StarknetWsApiServer for StarknetApi<EF> {
+    fn subscribe_new_heads(
+        &self,
+        mut sink: SubscriptionSink,
+        block_id: Option<BlockIdOrTag>,
+    ) -> SubscriptionResult {
+        // the listener must be registered before resolving the latest block so that we don't miss
+        // any blocks that are mined in between.
+        let mut rx = self.inner.block_producer.add_block_listener();
+
+        let (start, latest) = match self.subscription_start(block_id) {
+            Ok(res) => res,
+            Err(err) => {
+                sink.reject(err)?;
+                return Ok(());
+            }
+        };
+
+        let this = self.clone();
+        self.spawn_subscription(sink, |mut sink| async move {
+            let mut last_sent = latest;
+
+            if let Some(start) = start {
+                let res = this.on_io_blocking_task(move |this| this.block_headers(start..=latest));
+                match res.await {
+                    Ok(headers) => {
+                        for header in headers {
+                            if !matches!(sink.send(&header), Ok(true)) {
+                                return;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        sink.close(err);
+                        return;
+                    }
+                }
+            }
+
+            while let Some(outcome) = rx.next().await {
+                if outcome.block_number <= last_sent {
+                    continue;
+                }
+
+                // fetch every block since the last one sent, in case some notifications were
+                // dropped because the channel was full.
+                let range = (last_sent + 1)..=outcome.block_number;
+                let res = this.on_io_blocking_task(move |this| this.block_headers(range));
+                let Ok(headers) = res.await else {
+                    break;
+                };
+
+                for header in headers {
+                    if !matches!(sink.send(&header), Ok(true)) {
+                        return;
+                    }
+                }
+
+                last_sent = outcome.block_number;
+            }
+        })
+    }
+
+    fn subscribe_events(
+        &self,
+        mut sink: SubscriptionSink,
+        from_address: Option<Felt>,
+        keys: Option<Vec<Vec<Felt>>>,
+        block_id: Option<BlockIdOrTag>,
+    ) -> SubscriptionResult {
+        let mut rx = self.inner.block_producer.add_block_listener();
+
+        let (start, latest) = match self.subscription_start(block_id) {
+            Ok(res) => res,
+            Err(err) => {
+                sink.reject(err)?;
+                return Ok(());
+            }
+        };
+
+        let filter = Filter { address: from_address.map(ContractAddress::from), keys };
+
+        let this = self.clone();
+        self.spawn_subscription(sink, |mut sink| async move {
+            let mut last_sent = latest;
+
+            if let Some(start) = start {
+                let filter = filter.clone();
+                let res = this
+                    .on_io_blocking_task(move |this| this.block_events(start..=latest, &filter));
+
+                match res.await {
+                    Ok(events) => {
+                        for event in events {
+                            if !matches!(sink.send(&event), Ok(true)) {
+                                return;
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        sink.close(err);
+                        return;
+                    }
+                }
+            }
+
+            while let Some(outcome) = rx.next().await {
+                if outcome.block_number <= last_sent {
+                    continue;
+                }
+
+                let range = (last_sent + 1)..=outcome.block_number;
+                let filter = filter.clone();
+                let res = this.on_io_blocking_task(move |this| this.block_events(range, &filter));
+                let Ok(events) = res.await else {
+                    break;
+                };
+
+                for event in events {
+                    if !matches!(sink.send(&event), Ok(true)) {
+                        return;
+                    }
+                }
+
+                last_sent = outcome.block_number;
+            }
+        })
+    }
+
+    fn subscribe_transaction_status(
+        &self,
+        sink: SubscriptionSink,
+        transaction_hash: TxHash,
+    ) -> SubscriptionResult {
+        // register the listener first so that we don't miss the transaction if it gets executed
+        // while we're checking its current status.
+        let mut rx = self.inner.block_producer.add_listener();
+
+        let this = self.clone();
+        self.spawn_subscription(sink, |mut sink| async move {
+            match this.transaction_status(transaction_hash).await {
+                Ok(status) => {
+                    let _ = sink.send(&TxStatusUpdate { transaction_hash, status });
+                    return;
+                }
+
+                Err(StarknetApiError::TxnHashNotFound) => {}
+
+                Err(err) => {
+                    sink.close(err);
+                    return;
+                }
+            }
+
+            if this.inner.pool.contains(transaction_hash) {
+                let status = TransactionStatus::Received;
+                if !matches!(sink.send(&TxStatusUpdate { transaction_hash, status }), Ok(true)) {
+                    return;
+                }
+            }
+
+            while let Some(txs) = rx.next().await {
+                let Some(tx) = txs.into_iter().find(|tx| tx.tx.hash == transaction_hash) else {
+                    continue;
+                };
+
+                let exec_status = if tx.receipt.is_reverted() {
+                    TransactionExecutionStatus::Reverted
+                } else {
+                    TransactionExecutionStatus::Succeeded
+                };
+
+                let status = TransactionStatus::AcceptedOnL2(exec_status);
+                let _ = sink.send(&TxStatusUpdate { transaction_hash, status });
+                break;
+            }
+        })
+    }
+
+    fn subscribe_pending_transactions(
+        &self,
+        mut sink: SubscriptionSink,
+        transaction_details: Option<bool>,
+        sender_address: Option<Vec<Felt>>,
+    ) -> SubscriptionResult {
+        if sender_address.as_ref().is_some_and(|addrs| addrs.len() > MAX_SENDER_ADDRESSES) {
+            sink.reject(StarknetApiError::TooManyAddressesInFilter)?;
+            return Ok(());
+        }
+
+        let details = transaction_details.unwrap_or(false);
+        let senders = sender_address
+            .map(|addrs| addrs.into_iter().map(ContractAddress::from).collect::<Vec<_>>());
+
+        let stream = self.inner.block_producer.add_listener().flat_map(move |txs| {
+            let txs = txs
+                .into_iter()
+                .filter(|tx| {
+                    senders.as_ref().map_or(true, |s| s.contains(&tx_sender(&tx.tx.transaction)))
+                })
+                .map(|tx| {
+                    if details {
+                        PendingTx::Full(tx.tx.into())
+                    } else {
+                        PendingTx::Hash(tx.tx.hash.into())
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            stream::iter(txs)
+        });
+
+        self.spawn_subscription(sink, |mut sink| async move {
+            sink.pipe_from_stream(stream).await;
+        })
+    }
+
+    fn unsubscribe(&self, subscription_id: u64) -> RpcResult<bool> {
+        // the server only hands out random numeric subscription ids. plain methods aren't given
+        // the calling connection, so the subscriptions are looked up by id only, and an id can't
+        // be guessed from another connection.
+        let id = SubscriptionId::Num(subscription_id);
+        let Some(task) = self.inner.subscriptions.lock().remove(&id) else {
+            return Err(StarknetApiError::InvalidSubscriptionId.into());
+        };
+
+        // dropping the subscription sink removes it from the server's subscriptions.
+        task.abort();
+        Ok(true)
+    }
+}
+
+/// Returns the address of the contract that initiated the transaction.
+fn tx_sender(tx: &Tx) -> ContractAddress {
+    match tx {
+        Tx::Invoke(InvokeTx::V1(tx)) => tx.sender_address,
+        Tx::Invoke(InvokeTx::V3(tx)) => tx.sender_address,
+        Tx::Declare(DeclareTx::V1(tx)) => tx.sender_address,
+        Tx::Declare(DeclareTx::V2(tx)) => tx.sender_address,
+        Tx::Declare(DeclareTx::V3(tx)) => tx.sender_address,
+        Tx::L1Handler(tx) => tx.contract_address,
+        Tx::DeployAccount(tx) => tx.contract_address(),
+    }
+}
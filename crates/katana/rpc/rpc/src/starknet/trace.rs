@@ -21,12 +21,13 @@ use starknet::core::types::{
 use super::StarknetApi;
 
 impl<EF: ExecutorFactory> StarknetApi<EF> {
-    fn simulate_txs(
+    /// Simulates `transactions`, returning their traces and fees.
+    pub(super) fn simulate_txs(
         &self,
         block_id: BlockIdOrTag,
         transactions: Vec<BroadcastedTx>,
         simulation_flags: Vec<SimulationFlag>,
-    ) -> Result<Vec<SimulatedTransaction>, StarknetApiError> {
+    ) -> Result<Vec<(TxExecInfo, TxFeeInfo)>, StarknetApiError> {
         let chain_id = self.inner.backend.chain_spec.id;
 
         let executables = transactions
@@ -72,9 +73,12 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         let should_skip_fee = !simulation_flags.contains(&SimulationFlag::SkipFeeCharge)
             && self.inner.backend.executor_factory.execution_flags().fee();
 
+        let impersonated =
+            self.inner.backend.executor_factory.execution_flags().impersonated_accounts();
         let flags = katana_executor::ExecutionFlags::new()
             .with_account_validation(should_validate)
-            .with_fee(!should_skip_fee);
+            .with_fee(!should_skip_fee)
+            .with_impersonated_accounts(impersonated.clone());
 
         // get the state and block env at the specified block for execution
         let state = self.state(&block_id)?;
@@ -88,10 +92,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         for (i, ResultAndStates { result, .. }) in results.into_iter().enumerate() {
             match result {
                 ExecutionResult::Success { trace, receipt } => {
-                    let transaction_trace = to_rpc_trace(trace);
-                    let fee_estimation = to_rpc_fee_estimate(receipt.fee().clone());
-                    let value = SimulatedTransaction { transaction_trace, fee_estimation };
-                    simulated.push(value)
+                    simulated.push((trace, receipt.fee().clone()))
                 }
 
                 ExecutionResult::Failed { error } => {
@@ -107,10 +108,11 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         Ok(simulated)
     }
 
-    fn block_traces(
+    /// Returns the hashes and traces of the transactions of a block.
+    pub(super) fn block_traces(
         &self,
         block_id: BlockIdOrTag,
-    ) -> Result<Vec<TransactionTraceWithHash>, StarknetApiError> {
+    ) -> Result<Vec<(TxHash, TxExecInfo)>, StarknetApiError> {
         use StarknetApiError::BlockNotFound;
 
         let provider = self.inner.backend.blockchain.provider();
@@ -121,17 +123,12 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                     let pending_block = state.read();
 
                     // extract the txs from the pending block
-                    let traces = pending_block.transactions().iter().filter_map(|(t, r)| {
-                        if let Some(trace) = r.trace() {
-                            let transaction_hash = t.hash;
-                            let trace_root = to_rpc_trace(trace.clone());
-                            Some(TransactionTraceWithHash { transaction_hash, trace_root })
-                        } else {
-                            None
-                        }
-                    });
-
-                    return Ok(traces.collect::<Vec<TransactionTraceWithHash>>());
+                    let traces = pending_block
+                        .transactions()
+                        .iter()
+                        .filter_map(|(t, r)| r.trace().map(|trace| (t.hash, trace.clone())));
+
+                    return Ok(traces.collect::<Vec<_>>());
                 }
 
                 // if there is no pending block, return the latest block
@@ -147,18 +144,11 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         let hashes = provider.transaction_hashes_in_range(indices.into())?;
         let traces = provider.transaction_executions_by_block(block_id)?.ok_or(BlockNotFound)?;
 
-        // convert to rpc types
-        let traces = traces.into_iter().map(to_rpc_trace);
-        let result = hashes
-            .into_iter()
-            .zip(traces)
-            .map(|(h, r)| TransactionTraceWithHash { transaction_hash: h, trace_root: r })
-            .collect::<Vec<_>>();
-
-        Ok(result)
+        Ok(hashes.into_iter().zip(traces).collect())
     }
 
-    fn trace(&self, tx_hash: TxHash) -> Result<TransactionTrace, StarknetApiError> {
+    /// Returns the trace of a transaction.
+    pub(super) fn trace(&self, tx_hash: TxHash) -> Result<TxExecInfo, StarknetApiError> {
         use StarknetApiError::TxnHashNotFound;
 
         // Check in the pending block first
@@ -167,22 +157,20 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
             let tx = pending_block.transactions().iter().find(|(t, _)| t.hash == tx_hash);
 
             if let Some(trace) = tx.and_then(|(_, res)| res.trace()) {
-                return Ok(to_rpc_trace(trace.clone()));
+                return Ok(trace.clone());
             }
         }
 
         // If not found in pending block, fallback to the provider
         let provider = self.inner.backend.blockchain.provider();
-        let trace = provider.transaction_execution(tx_hash)?.ok_or(TxnHashNotFound)?;
-
-        Ok(to_rpc_trace(trace))
+        Ok(provider.transaction_execution(tx_hash)?.ok_or(TxnHashNotFound)?)
     }
 }
 
 #[async_trait]
 impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
     async fn trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<TransactionTrace> {
-        self.on_io_blocking_task(move |this| Ok(this.trace(transaction_hash)?)).await
+        self.on_io_blocking_task(move |this| Ok(to_rpc_trace(this.trace(transaction_hash)?))).await
     }
 
     async fn simulate_transactions(
@@ -192,7 +180,14 @@ impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
         simulation_flags: Vec<SimulationFlag>,
     ) -> RpcResult<Vec<SimulatedTransaction>> {
         self.on_cpu_blocking_task(move |this| {
-            Ok(this.simulate_txs(block_id, transactions, simulation_flags)?)
+            let simulated = this.simulate_txs(block_id, transactions, simulation_flags)?;
+            Ok(simulated
+                .into_iter()
+                .map(|(trace, fee)| SimulatedTransaction {
+                    transaction_trace: to_rpc_trace(trace),
+                    fee_estimation: to_rpc_fee_estimate(fee),
+                })
+                .collect())
         })
         .await
     }
@@ -201,7 +196,17 @@ impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
         &self,
         block_id: BlockIdOrTag,
     ) -> RpcResult<Vec<TransactionTraceWithHash>> {
-        self.on_io_blocking_task(move |this| Ok(this.block_traces(block_id)?)).await
+        self.on_io_blocking_task(move |this| {
+            let traces = this.block_traces(block_id)?;
+            Ok(traces
+                .into_iter()
+                .map(|(transaction_hash, trace)| TransactionTraceWithHash {
+                    transaction_hash,
+                    trace_root: to_rpc_trace(trace),
+                })
+                .collect())
+        })
+        .await
     }
 }
 
@@ -290,7 +295,7 @@ fn to_rpc_resources(resources: katana_primitives::trace::ExecutionResources) ->
     ExecutionResources { data_resources, computation_resources }
 }
 
-fn to_rpc_fee_estimate(fee: TxFeeInfo) -> FeeEstimate {
+pub(super) fn to_rpc_fee_estimate(fee: TxFeeInfo) -> FeeEstimate {
     FeeEstimate {
         unit: match fee.unit {
             katana_primitives::fee::PriceUnit::Wei => PriceUnit::Wei,
@@ -0,0 +1,192 @@
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_executor::{ExecutionResult, ExecutorFactory};
+use katana_primitives::block::BlockIdOrTag;
+use katana_primitives::transaction::TxHash;
+use katana_provider::traits::transaction::ReceiptProvider;
+use katana_rpc_api::starknet::{
+    StarknetApiServer, StarknetApiV0_8Server, StarknetWriteApiServer, StarknetWriteApiV0_8Server,
+};
+use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::transaction::{DeclareTxResult, DeployAccountTxResult, InvokeTxResult};
+use katana_rpc_types::v0_8::{
+    BroadcastedDeclareTx, BroadcastedDeployAccountTx, BroadcastedInvokeTx, BroadcastedTx,
+    ExecutionResources, FeeEstimate, MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes,
+    MaybePendingBlockWithTxs, SimulatedTx, Tx, TxReceiptWithBlockInfo, TxTrace, TxTraceWithHash,
+};
+use katana_rpc_types::{SimulationFlag, SimulationFlagForEstimateFee};
+
+use super::trace::to_rpc_fee_estimate;
+use super::{StarknetApi, StarknetApiResult};
+
+impl<EF: ExecutorFactory> StarknetApi<EF> {
+    /// Returns the resources consumed by the transactions, in the same order.
+    ///
+    /// The receipts of the forked network don't include the total gas consumed, so the resources
+    /// of the transactions that weren't executed by this node are zero.
+    async fn execution_resources(
+        &self,
+        hashes: Vec<TxHash>,
+    ) -> StarknetApiResult<Vec<ExecutionResources>> {
+        self.on_io_blocking_task(move |this| {
+            let provider = this.inner.backend.blockchain.provider();
+            let pending = this.pending_executor();
+
+            hashes
+                .into_iter()
+                .map(|hash| {
+                    if let Some(receipt) = provider.receipt_by_hash(hash)? {
+                        return Ok(ExecutionResources::from(receipt.resources_used()));
+                    }
+
+                    let resources = pending.as_ref().and_then(|executor| {
+                        executor.read().transactions().iter().find_map(|(tx, res)| match res {
+                            ExecutionResult::Success { receipt, .. } if tx.hash == hash => {
+                                Some(ExecutionResources::from(receipt.resources_used()))
+                            }
+                            _ => None,
+                        })
+                    });
+
+                    Ok(resources.unwrap_or_default())
+                })
+                .collect::<StarknetApiResult<Vec<_>>>()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> StarknetApiV0_8Server for StarknetApi<EF> {
+    async fn get_block_with_tx_hashes(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithTxHashes> {
+        Ok(self.block_with_tx_hashes(block_id).await?.into())
+    }
+
+    async fn get_block_with_txs(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithTxs> {
+        Ok(self.block_with_txs(block_id).await?.into())
+    }
+
+    async fn get_block_with_receipts(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<MaybePendingBlockWithReceipts> {
+        let block = self.block_with_receipts(block_id).await?;
+        let hashes = block.transaction_hashes().collect();
+        let execution_resources = self.execution_resources(hashes).await?;
+        Ok(MaybePendingBlockWithReceipts::new(block, execution_resources))
+    }
+
+    async fn get_transaction_by_hash(&self, transaction_hash: TxHash) -> RpcResult<Tx> {
+        Ok(self.transaction(transaction_hash).await?.into())
+    }
+
+    async fn get_transaction_by_block_id_and_index(
+        &self,
+        block_id: BlockIdOrTag,
+        index: u64,
+    ) -> RpcResult<Tx> {
+        Ok(self.transaction_by_block_id_and_index(block_id, index).await?.into())
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<TxReceiptWithBlockInfo> {
+        let receipt = self.receipt(transaction_hash).await?;
+        let resources = self.execution_resources(vec![transaction_hash]).await?;
+        let execution_resources = resources.into_iter().next().unwrap_or_default();
+        Ok(TxReceiptWithBlockInfo::new(receipt, execution_resources))
+    }
+
+    async fn estimate_fee(
+        &self,
+        request: Vec<BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlagForEstimateFee>,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<FeeEstimate>> {
+        let request = request.into_iter().map(Into::into).collect();
+        let estimates =
+            StarknetApiServer::estimate_fee(self, request, simulation_flags, block_id).await?;
+        Ok(estimates.into_iter().map(FeeEstimate::from).collect())
+    }
+
+    async fn estimate_message_fee(
+        &self,
+        message: MsgFromL1,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<FeeEstimate> {
+        let estimate = StarknetApiServer::estimate_message_fee(self, message, block_id).await?;
+        Ok(FeeEstimate::from(estimate))
+    }
+
+    async fn trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<TxTrace> {
+        self.on_io_blocking_task(move |this| Ok(this.trace(transaction_hash)?.into())).await
+    }
+
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockIdOrTag,
+        transactions: Vec<BroadcastedTx>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTx>> {
+        let transactions = transactions.into_iter().map(Into::into).collect();
+        self.on_cpu_blocking_task(move |this| {
+            let simulated = this.simulate_txs(block_id, transactions, simulation_flags)?;
+            Ok(simulated
+                .into_iter()
+                .map(|(trace, fee)| SimulatedTx {
+                    transaction_trace: trace.into(),
+                    fee_estimation: to_rpc_fee_estimate(fee).into(),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    async fn trace_block_transactions(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Vec<TxTraceWithHash>> {
+        self.on_io_blocking_task(move |this| {
+            let traces = this.block_traces(block_id)?;
+            Ok(traces
+                .into_iter()
+                .map(|(transaction_hash, trace)| TxTraceWithHash {
+                    transaction_hash,
+                    trace_root: trace.into(),
+                })
+                .collect())
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> StarknetWriteApiV0_8Server for StarknetApi<EF> {
+    async fn add_invoke_transaction(
+        &self,
+        invoke_transaction: BroadcastedInvokeTx,
+    ) -> RpcResult<InvokeTxResult> {
+        StarknetWriteApiServer::add_invoke_transaction(self, invoke_transaction.into()).await
+    }
+
+    async fn add_declare_transaction(
+        &self,
+        declare_transaction: BroadcastedDeclareTx,
+    ) -> RpcResult<DeclareTxResult> {
+        StarknetWriteApiServer::add_declare_transaction(self, declare_transaction.into()).await
+    }
+
+    async fn add_deploy_account_transaction(
+        &self,
+        deploy_account_transaction: BroadcastedDeployAccountTx,
+    ) -> RpcResult<DeployAccountTxResult> {
+        let tx = deploy_account_transaction.into();
+        StarknetWriteApiServer::add_deploy_account_transaction(self, tx).await
+    }
+}
@@ -0,0 +1,31 @@
+use jsonrpsee::core::{async_trait, RpcResult};
+use katana_pool::{TransactionPool, TxPool};
+use katana_rpc_api::txpool::TxPoolApiServer;
+use katana_rpc_types::txpool::{TxPoolContent, TxPoolStatus, TxPoolTransaction};
+
+#[allow(missing_debug_implementations)]
+pub struct TxPoolApi {
+    pool: TxPool,
+}
+
+impl TxPoolApi {
+    pub fn new(pool: TxPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TxPoolApiServer for TxPoolApi {
+    async fn txpool_status(&self) -> RpcResult<TxPoolStatus> {
+        let pending = self.pool.pending_transactions().len() as u64;
+        let queued = self.pool.queued_transactions().len() as u64;
+        Ok(TxPoolStatus { pending, queued })
+    }
+
+    async fn txpool_content(&self) -> RpcResult<TxPoolContent> {
+        let to_rpc = |txs: Vec<_>| txs.iter().map(|tx| TxPoolTransaction::new(&**tx)).collect();
+        let pending = to_rpc(self.pool.pending_transactions());
+        let queued = to_rpc(self.pool.queued_transactions());
+        Ok(TxPoolContent { pending, queued })
+    }
+}
@@ -4,13 +4,13 @@ use std::ops::RangeInclusive;
 use anyhow::Context;
 use katana_core::service::block_producer::PendingExecutor;
 use katana_primitives::block::{BlockHash, BlockNumber};
-use katana_primitives::contract::ContractAddress;
 use katana_primitives::event::ContinuationToken;
+pub use katana_primitives::event::EventFilter as Filter;
 use katana_primitives::receipt::Event;
 use katana_primitives::transaction::TxHash;
-use katana_primitives::Felt;
 use katana_provider::error::ProviderError;
 use katana_provider::traits::block::BlockProvider;
+use katana_provider::traits::event::EventIndexProvider;
 use katana_provider::traits::transaction::ReceiptProvider;
 use katana_rpc_types::error::starknet::StarknetApiError;
 use starknet::core::types::EmittedEvent;
@@ -33,18 +33,6 @@ pub enum EventBlockId {
     Num(BlockNumber),
 }
 
-/// An object to specify how events should be filtered.
-#[derive(Debug, Default, Clone)]
-pub struct Filter {
-    /// The contract address to filter by.
-    ///
-    /// If `None`, all events are considered. If `Some`, only events emitted by the specified
-    /// contract are considered.
-    pub address: Option<ContractAddress>,
-    /// The keys to filter by.
-    pub keys: Option<Vec<Vec<Felt>>>,
-}
-
 /// Internal cursor
 #[derive(Debug, Clone, PartialEq)]
 pub struct Cursor {
@@ -140,7 +128,7 @@ pub fn fetch_pending_events(
 
 /// Returns `true` if reach the end of the block range.
 pub fn fetch_events_at_blocks(
-    provider: impl BlockProvider + ReceiptProvider,
+    provider: impl BlockProvider + ReceiptProvider + EventIndexProvider,
     block_range: RangeInclusive<BlockNumber>,
     filter: &Filter,
     chunk_size: u64,
@@ -152,7 +140,9 @@ pub fn fetch_events_at_blocks(
     // update the block range to start from the block pointed by the cursor.
     let block_range = cursor.block..=*block_range.end();
 
-    for block_num in block_range {
+    // only go through the blocks that may have emitted events matching the filter, instead of
+    // every block in the range.
+    for block_num in provider.blocks_with_events(block_range, filter)? {
         // collect all receipts at `block_num` block.
         let block_hash = provider.block_hash_by_num(block_num)?.context("Missing block hash")?;
         let receipts = provider.receipts_by_block(block_num.into())?.context("Missing receipts")?;
@@ -237,42 +227,8 @@ impl<'a, I: Iterator<Item = &'a Event>> Iterator for FilteredEvents<'a, I> {
     type Item = &'a Event;
 
     fn next(&mut self) -> Option<Self::Item> {
-        for event in self.iter.by_ref() {
-            // Check if the event matches the address filter
-            if !self.filter.address.map_or(true, |addr| addr == event.from_address) {
-                continue;
-            }
-
-            // Check if the event matches the keys filter
-            let is_matched = match &self.filter.keys {
-                None => true,
-                // From starknet-api spec:
-                // Per key (by position), designate the possible values to be matched for events to
-                // be returned. Empty array designates 'any' value"
-                Some(filters) => filters.iter().enumerate().all(|(i, keys)| {
-                    // Lets say we want to filter events which are either named `Event1` or `Event2`
-                    // and custom key `0x1` or `0x2` Filter:
-                    // [[sn_keccak("Event1"), sn_keccak("Event2")], ["0x1", "0x2"]]
-
-                    // This checks: number of keys in event >= number of keys in filter (we check >
-                    // i and not >= i because i is zero indexed) because
-                    // otherwise this event doesn't contain all the keys we
-                    // requested
-                    event.keys.len() > i &&
-                         // This checks: Empty array desginates 'any' value
-                         (keys.is_empty()
-                         ||
-                         // This checks: If this events i'th value is one of the requested value in filter_keys[i]
-                         keys.contains(&event.keys[i]))
-                }),
-            };
-
-            if is_matched {
-                return Some(event);
-            }
-        }
-
-        None
+        let filter = self.filter;
+        self.iter.by_ref().find(|event| filter.matches(event))
     }
 }
 
@@ -0,0 +1,251 @@
+//! Serving multiple versions of the Starknet JSON-RPC specification from a single server.
+//!
+//! Each version is served under its own route (eg `/rpc/v0_8`). The methods whose types differ
+//! between versions are registered once per version, under a versioned namespace, and the
+//! [`RpcVersionLayer`] maps the `starknet_` methods of the requests to the ones of the version
+//! in their path. The versioned methods can't be called by their own name on the versioned routes.
+//! Requests to any other path are served by the default, 0.7, methods, and are passed to the
+//! server as is.
+//!
+//! Only the HTTP requests are mapped, the WebSocket subscriptions are the same for all versions.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use hyper::body::{Bytes, HttpBody};
+use hyper::header::CONTENT_LENGTH;
+use hyper::{Body, Method, Request};
+use serde_json::Value;
+use tower::{Layer, Service};
+
+/// A version of the Starknet JSON-RPC specification served by Katana.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcVersion {
+    V0_7,
+    V0_8,
+}
+
+impl RpcVersion {
+    /// Returns the path of the route serving this version.
+    pub fn path(&self) -> &'static str {
+        match self {
+            RpcVersion::V0_7 => "/rpc/v0_7",
+            RpcVersion::V0_8 => "/rpc/v0_8",
+        }
+    }
+
+    /// Returns the version served under `path`, if any.
+    pub fn from_path(path: &str) -> Option<Self> {
+        match path.trim_end_matches('/') {
+            "/rpc/v0_7" => Some(RpcVersion::V0_7),
+            "/rpc/v0_8" => Some(RpcVersion::V0_8),
+            _ => None,
+        }
+    }
+
+    /// Returns the namespace of the methods overriding the `starknet_` ones in this version, or
+    /// `None` if this version is served by the default methods.
+    fn namespace(&self) -> Option<&'static str> {
+        match self {
+            RpcVersion::V0_7 => None,
+            RpcVersion::V0_8 => Some("starknetV0_8"),
+        }
+    }
+}
+
+/// A layer mapping the `starknet_` methods of the requests to the versioned methods of the
+/// version in their path.
+#[derive(Debug, Clone)]
+pub struct RpcVersionLayer {
+    /// The names of the registered versioned methods.
+    methods: Arc<HashSet<String>>,
+    /// The maximum size of the request bodies accepted by the server, in bytes.
+    max_body_size: usize,
+}
+
+impl RpcVersionLayer {
+    /// Creates a layer mapping the methods to the versioned `methods`. At most `max_body_size`
+    /// bytes of a request are read, the larger requests are left for the server to reject.
+    pub fn new<I>(methods: I, max_body_size: u32) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let methods = Arc::new(methods.into_iter().map(Into::into).collect());
+        Self { methods, max_body_size: max_body_size as usize }
+    }
+}
+
+impl<S> Layer<S> for RpcVersionLayer {
+    type Service = RpcVersionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let methods = self.methods.clone();
+        RpcVersionService { inner, methods, max_body_size: self.max_body_size }
+    }
+}
+
+/// The service of the [`RpcVersionLayer`].
+#[derive(Debug, Clone)]
+pub struct RpcVersionService<S> {
+    inner: S,
+    methods: Arc<HashSet<String>>,
+    max_body_size: usize,
+}
+
+impl<S> Service<Request<Body>> for RpcVersionService<S>
+where
+    S: Service<Request<Body>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let namespace = RpcVersion::from_path(req.uri().path()).and_then(|v| v.namespace());
+        let Some(namespace) = namespace.filter(|_| req.method() == Method::POST) else {
+            return self.inner.call(req);
+        };
+
+        // the body is rewritten as it's read by the server, so its length isn't known upfront
+        let (mut parts, body) = req.into_parts();
+        parts.headers.remove(CONTENT_LENGTH);
+
+        let (methods, max_body_size) = (self.methods.clone(), self.max_body_size);
+        let body = stream::once(map_body(body, namespace, methods, max_body_size)).try_flatten();
+
+        self.inner.call(Request::from_parts(parts, Body::wrap_stream(body)))
+    }
+}
+
+/// Reads `body` and maps its methods with [`map_methods`]. A body larger than `max_size` is
+/// returned as is once `max_size` bytes have been read, for the server to reject it.
+async fn map_body(
+    mut body: Body,
+    namespace: &'static str,
+    methods: Arc<HashSet<String>>,
+    max_size: usize,
+) -> Result<BoxStream<'static, Result<Bytes, hyper::Error>>, hyper::Error> {
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk?);
+
+        if bytes.len() > max_size {
+            let read = stream::once(async move { Ok(Bytes::from(bytes)) });
+            return Ok(read.chain(body).boxed());
+        }
+    }
+
+    let body = map_methods(Bytes::from(bytes), namespace, &methods);
+    Ok(stream::once(async move { Ok(body) }).boxed())
+}
+
+/// The method that the calls to the versioned methods by their own name are mapped to. It isn't
+/// registered, so these calls are rejected like the ones to any unknown method.
+const UNKNOWN_METHOD: &str = "rpc_unknownMethod";
+
+/// Maps the `starknet_` methods of the calls in `body` to their counterpart in `namespace`, if
+/// it's one of `methods`, and the calls to any of `methods` to [`UNKNOWN_METHOD`].
+fn map_methods(body: Bytes, namespace: &str, methods: &HashSet<String>) -> Bytes {
+    // malformed requests are left as is, for the server to report the error
+    let Ok(mut request) = serde_json::from_slice::<Value>(&body) else { return body };
+
+    let calls = match &mut request {
+        Value::Array(calls) => calls.iter_mut().collect(),
+        call => vec![call],
+    };
+
+    for call in calls {
+        if let Some(Value::String(method)) = call.get_mut("method") {
+            if methods.contains(method.as_str()) {
+                *method = UNKNOWN_METHOD.to_string();
+            } else if let Some(name) = method.strip_prefix("starknet_") {
+                let versioned = format!("{namespace}_{name}");
+                if methods.contains(&versioned) {
+                    *method = versioned;
+                }
+            }
+        }
+    }
+
+    serde_json::to_vec(&request).map(Bytes::from).unwrap_or(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn methods(body: Value, namespace: &str) -> Vec<Value> {
+        let versioned = HashSet::from(["starknetV0_8_specVersion".to_string()]);
+        let body = map_methods(Bytes::from(body.to_string()), namespace, &versioned);
+        let body: Value = serde_json::from_slice(&body).unwrap();
+
+        match body {
+            Value::Array(calls) => calls.into_iter().map(|c| c["method"].clone()).collect(),
+            call => vec![call["method"].clone()],
+        }
+    }
+
+    #[test]
+    fn versioned_routes() {
+        assert_eq!(RpcVersion::from_path("/rpc/v0_7"), Some(RpcVersion::V0_7));
+        assert_eq!(RpcVersion::from_path("/rpc/v0_8/"), Some(RpcVersion::V0_8));
+        assert_eq!(RpcVersion::from_path("/"), None);
+
+        for version in [RpcVersion::V0_7, RpcVersion::V0_8] {
+            assert_eq!(RpcVersion::from_path(version.path()), Some(version));
+        }
+    }
+
+    #[test]
+    fn map_versioned_methods() {
+        let call = |method: &str| json!({ "jsonrpc": "2.0", "id": 1, "method": method });
+        let v0_8 = "starknetV0_8";
+
+        assert_eq!(methods(call("starknet_specVersion"), v0_8), vec!["starknetV0_8_specVersion"]);
+        // methods shared by all versions are left as is
+        assert_eq!(methods(call("starknet_blockNumber"), v0_8), vec!["starknet_blockNumber"]);
+        assert_eq!(methods(call("dev_generateBlock"), v0_8), vec!["dev_generateBlock"]);
+
+        let batch = json!([call("starknet_specVersion"), call("starknet_chainId")]);
+        assert_eq!(methods(batch, v0_8), vec!["starknetV0_8_specVersion", "starknet_chainId"]);
+
+        // the versioned methods can't be called by their own name
+        assert_eq!(methods(call("starknetV0_8_specVersion"), v0_8), vec![UNKNOWN_METHOD]);
+
+        // malformed requests are left as is
+        let body = Bytes::from_static(b"{ not json");
+        assert_eq!(map_methods(body.clone(), v0_8, &HashSet::new()), body);
+    }
+
+    #[tokio::test]
+    async fn map_bodies_up_to_max_size() {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": "starknet_specVersion" });
+        let body = body.to_string();
+        let versioned = Arc::new(HashSet::from(["starknetV0_8_specVersion".to_string()]));
+
+        let read = |max_size| {
+            let body = Body::from(body.clone());
+            let versioned = versioned.clone();
+            async move {
+                let mapped = map_body(body, "starknetV0_8", versioned, max_size).await.unwrap();
+                let bytes = hyper::body::to_bytes(Body::wrap_stream(mapped)).await.unwrap();
+                String::from_utf8(bytes.to_vec()).unwrap()
+            }
+        };
+
+        assert!(read(body.len()).await.contains("starknetV0_8_specVersion"));
+        // larger bodies are left as is
+        assert_eq!(read(body.len() - 1).await, body);
+    }
+}
@@ -0,0 +1,68 @@
+use std::fs;
+
+use assert_matches::assert_matches;
+use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use dojo_utils::TransactionWaiter;
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_node::config::rpc::ApiKind;
+use katana_node::config::SequencingConfig;
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_rpc_api::admin::AdminApiClient;
+use starknet::accounts::{Account, AccountError, ConnectedAccount};
+use starknet::core::types::{Call, Felt, StarknetError};
+use starknet::macros::{felt, selector};
+use starknet::providers::ProviderError;
+
+mod common;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn policy_is_enforced_and_reloaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let policy_file = dir.path().join("policy.toml");
+
+    let config = get_default_test_config(SequencingConfig::default());
+    let (sender, _) = config.chain.genesis.accounts().next().unwrap();
+
+    // the fee token can't be called
+    let policy = format!(
+        "[senders]\nallow = [\"{:#x}\"]\n[contracts]\ndeny = [\"{:#x}\"]",
+        Felt::from(*sender),
+        Felt::from(DEFAULT_ETH_FEE_TOKEN_ADDRESS)
+    );
+    fs::write(&policy_file, policy).unwrap();
+
+    let mut config = config;
+    config.pool.policy_file = Some(policy_file.clone());
+    config.rpc.apis.insert(ApiKind::Admin);
+    let sequencer = TestSequencer::start(config).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+    assert_eq!(account.address(), Felt::from(*sender));
+
+    let policy = client.get_policy().await.unwrap().expect("policy must be set");
+    assert!(!policy.contracts.permits(&DEFAULT_ETH_FEE_TOKEN_ADDRESS));
+
+    let transfer = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+    };
+    let max_fee = felt!("0x100000000000000000");
+
+    let res = account.execute_v1(vec![transfer.clone()]).max_fee(max_fee).send().await;
+    assert_account_starknet_err!(res.unwrap_err(), StarknetError::ValidationFailure(_));
+
+    // lifting the restriction doesn't require restarting the node
+    fs::write(&policy_file, "").unwrap();
+    let policy = client.reload_policy().await.unwrap();
+    assert!(policy.contracts.is_unrestricted());
+
+    let res = account.execute_v1(vec![transfer]).max_fee(max_fee).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, account.provider()).await.unwrap();
+
+    // an invalid policy file keeps the current policy
+    fs::write(&policy_file, "admins = 1").unwrap();
+    assert!(client.reload_policy().await.is_err());
+    assert_eq!(client.get_policy().await.unwrap(), Some(policy));
+}
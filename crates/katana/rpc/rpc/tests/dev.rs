@@ -1,8 +1,29 @@
 use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use dojo_utils::TransactionWaiter;
 use katana_node::config::SequencingConfig;
+use katana_node::test_utils::TestNode;
+use katana_primitives::block::BlockIdOrTag;
+use katana_primitives::chain::ChainId;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::da::blob;
+use katana_primitives::genesis::constant::{
+    DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+};
 use katana_provider::traits::block::{BlockNumberProvider, BlockProvider};
 use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
 use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::katana::KatanaApiClient;
+use katana_rpc_api::starknet::StarknetApiClient;
+use katana_rpc_types::profile::{Profile, ProfileFormat};
+use katana_rpc_types::trie::ContractStorageKeys;
+use katana_trie::Pedersen;
+use starknet::accounts::{Account, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::{BlockId, BlockTag, Call, Felt, MaybePendingBlockWithTxHashes};
+use starknet::core::utils::get_storage_var_address;
+use starknet::macros::{felt, selector};
+use starknet::providers::Provider;
+use starknet::signers::{LocalWallet, SigningKey};
 
 async fn create_test_sequencer() -> TestSequencer {
     TestSequencer::start(get_default_test_config(SequencingConfig::default())).await
@@ -99,6 +120,399 @@ async fn test_increase_next_block_timestamp() {
     );
 }
 
+#[tokio::test]
+async fn snapshot_and_revert() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let block_number = provider.block_number().await.unwrap();
+    let nonce = account.get_nonce().await.unwrap();
+
+    let id = client.snapshot().await.unwrap();
+
+    // transfer some ETH to change the state of the account and the fee token
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = account.execute_v1(vec![call]).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let _: () = client.generate_block().await.unwrap();
+
+    assert_eq!(provider.block_number().await.unwrap(), block_number + 2);
+    assert_eq!(account.get_nonce().await.unwrap(), nonce + Felt::ONE);
+
+    // a snapshot taken after the first one is removed when reverting to the first one
+    let later_id = client.snapshot().await.unwrap();
+    assert!(later_id > id);
+
+    assert!(client.revert(id).await.unwrap());
+
+    assert_eq!(provider.block_number().await.unwrap(), block_number);
+    assert_eq!(account.get_nonce().await.unwrap(), nonce);
+    assert!(provider.get_transaction_by_hash(res.transaction_hash).await.is_err());
+
+    let key = get_storage_var_address("ERC20_balances", &[felt!("0x1")]).unwrap();
+    let fee_token = Felt::from(DEFAULT_ETH_FEE_TOKEN_ADDRESS);
+    let block_id = BlockId::Tag(BlockTag::Latest);
+    let balance = provider.get_storage_at(fee_token, key, block_id).await.unwrap();
+    assert_eq!(balance, Felt::ZERO);
+
+    assert!(!client.revert(id).await.unwrap());
+    assert!(!client.revert(later_id).await.unwrap());
+
+    // the reverted transaction can be sent again on top of the reverted chain
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = account.execute_v1(vec![call]).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+    assert_eq!(provider.block_number().await.unwrap(), block_number + 1);
+}
+
+#[tokio::test]
+async fn reorg() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let block_number = provider.block_number().await.unwrap();
+    let nonce = account.get_nonce().await.unwrap();
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = account.execute_v1(vec![call]).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let _: () = client.generate_block().await.unwrap();
+    assert_eq!(provider.block_number().await.unwrap(), block_number + 2);
+
+    // the chain can't be unwound past the genesis block
+    assert!(client.reorg(block_number + 3).await.is_err());
+
+    assert_eq!(client.reorg(2).await.unwrap(), block_number);
+    assert_eq!(provider.block_number().await.unwrap(), block_number);
+    assert_eq!(account.get_nonce().await.unwrap(), nonce);
+    assert!(provider.get_transaction_by_hash(res.transaction_hash).await.is_err());
+}
+
+#[tokio::test]
+async fn impersonate_account() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let address = sequencer.account().address();
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    // an account whose signer doesn't match the on-chain public key of the account
+    let mut account = SingleOwnerAccount::new(
+        sequencer.provider(),
+        LocalWallet::from(SigningKey::from_random()),
+        address,
+        provider.chain_id().await.unwrap(),
+        ExecutionEncoding::New,
+    );
+    account.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+    };
+
+    assert!(account.execute_v1(vec![call.clone()]).send().await.is_err());
+
+    client.impersonate_account(address).await.unwrap();
+    let res = account.execute_v1(vec![call.clone()]).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    client.stop_impersonating_account(address).await.unwrap();
+    assert!(account.execute_v1(vec![call]).send().await.is_err());
+}
+
+#[tokio::test]
+async fn set_contract_state() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let block_id = BlockId::Tag(BlockTag::Latest);
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let address = felt!("0x1337");
+    let key = felt!("0x20");
+    let value = felt!("0xabc");
+
+    client.set_storage_at(address, key, value).await.unwrap();
+    assert_eq!(provider.get_storage_at(address, key, block_id).await.unwrap(), value);
+
+    client.set_nonce(address, felt!("0x5")).await.unwrap();
+    assert_eq!(provider.get_nonce(block_id, address).await.unwrap(), felt!("0x5"));
+
+    let class_hash = provider.get_class_hash_at(block_id, sequencer.account().address()).await;
+    let class_hash = class_hash.unwrap();
+    client.set_class_hash_at(address, class_hash).await.unwrap();
+    assert_eq!(provider.get_class_hash_at(block_id, address).await.unwrap(), class_hash);
+
+    // the balance is set in both fee tokens
+    let amount = felt!("0xdeadbeef");
+    client.set_balance(address, amount).await.unwrap();
+
+    let key = get_storage_var_address("ERC20_balances", &[address]).unwrap();
+    for token in [DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_STRK_FEE_TOKEN_ADDRESS] {
+        let balance = provider.get_storage_at(Felt::from(token), key, block_id).await.unwrap();
+        assert_eq!(balance, amount);
+    }
+}
+
+#[tokio::test]
+async fn set_storage_at_out_of_range_key() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let block_id = BlockId::Tag(BlockTag::Latest);
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    // storage keys must be lower than 2^251
+    let address = felt!("0x1337");
+    let key = Felt::MAX;
+    let result = client.set_storage_at(address, key, felt!("0xabc")).await;
+    assert!(result.is_err(), "out of range storage key should be rejected");
+
+    // the node is still able to apply valid state updates
+    let key = felt!("0x20");
+    client.set_storage_at(address, key, felt!("0xabc")).await.unwrap();
+    assert_eq!(provider.get_storage_at(address, key, block_id).await.unwrap(), felt!("0xabc"));
+}
+
+#[tokio::test]
+async fn contract_state_is_committed_in_a_block() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let address = sequencer.account().address();
+    let key = felt!("0x20");
+    let value = felt!("0xabc");
+
+    let parent = provider.block_number().await.unwrap();
+    client.set_storage_at(address, key, value).await.unwrap();
+
+    // in instant mode, the update is committed in a block of its own
+    let block = provider.block_number().await.unwrap();
+    assert_eq!(block, parent + 1);
+
+    // the update is part of the history of the contract
+    let storage = provider.get_storage_at(address, key, BlockId::Number(parent)).await.unwrap();
+    assert_eq!(storage, Felt::ZERO);
+    let storage = provider.get_storage_at(address, key, BlockId::Number(block)).await.unwrap();
+    assert_eq!(storage, value);
+
+    // and the state root of the block commits to it
+    let block_id = BlockId::Number(block);
+    let keys = vec![ContractStorageKeys { contract_address: address, storage_keys: vec![key] }];
+    let proof = StarknetApiClient::get_storage_proof(
+        &client,
+        block_id,
+        None,
+        Some(vec![address]),
+        Some(keys),
+    );
+    let proof = proof.await.unwrap();
+
+    let MaybePendingBlockWithTxHashes::Block(header) =
+        provider.get_block_with_tx_hashes(block_id).await.unwrap()
+    else {
+        panic!("expected a mined block")
+    };
+
+    let roots = proof.global_roots;
+    let state_root = katana_trie::state_root(roots.contracts_tree_root, roots.classes_tree_root);
+    assert_eq!(state_root, header.new_root);
+
+    let leaf = &proof.contracts_proof.contract_leaves_data[0];
+    let nodes =
+        proof.contracts_storage_proofs[0].0.iter().map(|n| (n.node_hash, n.node.clone().into()));
+    let nodes = nodes.collect::<Vec<_>>();
+    assert_eq!(katana_trie::verify_proof::<Pedersen>(leaf.storage_root, key, &nodes), Some(value));
+
+    // without block production, the update is applied to the pending block and committed with it
+    let config =
+        get_default_test_config(SequencingConfig { no_mining: true, ..Default::default() });
+    let sequencer = TestSequencer::start(config).await;
+    let provider = sequencer.provider();
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let block = provider.block_number().await.unwrap();
+    client.set_storage_at(address, key, value).await.unwrap();
+
+    let pending = BlockId::Tag(BlockTag::Pending);
+    assert_eq!(provider.get_storage_at(address, key, pending).await.unwrap(), value);
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(provider.get_storage_at(address, key, latest).await.unwrap(), Felt::ZERO);
+
+    client.generate_block().await.unwrap();
+    assert_eq!(provider.block_number().await.unwrap(), block + 1);
+    assert_eq!(provider.get_storage_at(address, key, latest).await.unwrap(), value);
+
+    let update = sequencer.backend().blockchain.provider().state_update((block + 1).into());
+    let update = update.unwrap().unwrap();
+    assert_eq!(update.storage_updates[&ContractAddress::from(address)][&key], value);
+}
+
+#[tokio::test]
+async fn dump_and_load_state() {
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let account = sequencer.account().address();
+    let key = felt!("0x20");
+    let value = felt!("0xabc");
+    client.set_storage_at(account, key, value).await.unwrap();
+
+    let state = client.dump_state().await.unwrap();
+
+    // the genesis accounts are dumped as accounts, along with their current storage
+    let dumped = &state.accounts[&ContractAddress::from(account)];
+    assert_eq!(dumped.storage.as_ref().unwrap()[&key], value);
+    assert!(state.contracts.contains_key(&DEFAULT_ETH_FEE_TOKEN_ADDRESS));
+
+    let other = create_test_sequencer().await;
+    let provider = other.provider();
+    let block_id = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(provider.get_storage_at(account, key, block_id).await.unwrap(), Felt::ZERO);
+
+    let other_client = HttpClientBuilder::default().build(other.url()).unwrap();
+    let block_number = provider.block_number().await.unwrap();
+    other_client.load_state(state).await.unwrap();
+    assert_eq!(provider.get_storage_at(account, key, block_id).await.unwrap(), value);
+
+    // the loaded state is committed in a new block
+    assert_eq!(provider.block_number().await.unwrap(), block_number + 1);
+    let block_id = BlockId::Number(block_number);
+    assert_eq!(provider.get_storage_at(account, key, block_id).await.unwrap(), Felt::ZERO);
+}
+
+#[tokio::test]
+async fn profile_transaction() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+    };
+    let res = account.execute_v1(vec![call]).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let Profile::CallTree(profile) =
+        client.profile_transaction(res.transaction_hash, None).await.unwrap()
+    else {
+        panic!("expected a call tree profile")
+    };
+
+    assert_eq!(profile.transaction_hash, res.transaction_hash);
+    assert!(profile.resources.steps > 0);
+
+    // the account's `__execute__` calls the fee token's `transfer`
+    let execute = profile.execute.as_ref().unwrap();
+    assert_eq!(execute.contract_address, account.address().into());
+    assert_eq!(execute.entry_point_name.as_deref(), Some("__execute__"));
+    assert_eq!(execute.total.syscalls.call_contract, 1);
+
+    let transfer = &execute.inner_calls[0];
+    assert_eq!(transfer.contract_address, DEFAULT_ETH_FEE_TOKEN_ADDRESS);
+    assert_eq!(transfer.entry_point_selector, selector!("transfer"));
+    assert_eq!(execute.total.steps, execute.own.steps + transfer.total.steps);
+
+    let format = Some(ProfileFormat::Folded);
+    let Profile::Folded { stacks } =
+        client.profile_transaction(res.transaction_hash, format).await.unwrap()
+    else {
+        panic!("expected a folded stacks profile")
+    };
+
+    let frames = format!("execute;{};{}", execute.frame_name(), transfer.frame_name());
+    assert!(stacks.lines().any(|line| line.starts_with(&frames)));
+
+    let block = provider.block_number().await.unwrap();
+    let Profile::CallTree(block) =
+        client.profile_block(BlockIdOrTag::Number(block), None).await.unwrap()
+    else {
+        panic!("expected a call tree profile")
+    };
+    assert_eq!(block.transactions, vec![profile]);
+
+    let res = client.profile_transaction(felt!("0x1337"), None).await;
+    assert!(res.is_err());
+}
+
+#[tokio::test]
+async fn embedded_test_node() {
+    let node = TestNode::builder().no_mining().fee(true).start().await.unwrap();
+    let provider = node.provider();
+
+    assert_eq!(provider.chain_id().await.unwrap(), ChainId::SEPOLIA.into());
+    assert_eq!(node.account().address(), node.accounts()[0].0.into());
+
+    let block = provider.block_number().await.unwrap();
+    node.dev_client().generate_block().await.unwrap();
+    assert_eq!(provider.block_number().await.unwrap(), block + 1);
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn state_diff_blob() {
+    let config = SequencingConfig { state_diff_blobs: true, ..Default::default() };
+    let sequencer = TestSequencer::start(get_default_test_config(config)).await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+    };
+    let res = account.execute_v1(vec![call]).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let block = provider.block_number().await.unwrap();
+    let blob = client.get_block_state_diff_blob(BlockIdOrTag::Number(block)).await.unwrap();
+    assert_eq!(blob.block_number, block);
+    assert_eq!(blob.blobs.len(), 1);
+
+    // the blob is the encoded state diff of the block
+    let state_update =
+        sequencer.backend().blockchain.provider().state_update(block.into()).unwrap().unwrap();
+    let expected = blob::from_state_updates(state_update);
+    assert_eq!(blob.blobs[0].data.as_ref(), blob::to_bytes(&expected[0]).as_slice());
+
+    // the blobs aren't computed by default
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let res = client.get_block_state_diff_blob(BlockIdOrTag::Tag(BlockTag::Latest)).await;
+    assert!(res.is_err());
+}
+
 // #[tokio::test]
 // async fn test_set_storage_at_on_instant_mode() {
 //     let sequencer = create_test_sequencer().await;
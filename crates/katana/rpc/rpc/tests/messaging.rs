@@ -3,30 +3,39 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use alloy::primitives::{Uint, U256};
-use alloy::providers::{ProviderBuilder, WalletProvider};
+use alloy::providers::{Provider as _, ProviderBuilder, WalletProvider};
 use alloy::sol;
 use anyhow::Result;
 use cainome::cairo_serde::EthAddress;
 use cainome::rs::abigen;
 use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
 use dojo_utils::TransactionWaiter;
+use jsonrpsee::http_client::HttpClientBuilder;
 use katana_core::service::messaging::MessagingConfig;
+use katana_node::config::gas_oracle::GasOracleConfig;
 use katana_node::config::SequencingConfig;
+use katana_node::test_utils::TestNode;
 use katana_primitives::felt;
+use katana_primitives::message::{L1TxHash, MessageCancellation};
 use katana_primitives::utils::transaction::{
     compute_l1_handler_tx_hash, compute_l1_to_l2_message_hash, compute_l2_to_l1_message_hash,
 };
+use katana_provider::traits::stage::StageCheckpointProvider;
+use katana_provider::traits::transaction::TransactionStatusProvider;
+use katana_rpc_api::starknet::StarknetApiClient;
 use katana_rpc_types::receipt::ReceiptBlock;
 use rand::Rng;
-use starknet::accounts::{Account, ConnectedAccount};
+use starknet::accounts::{Account, ConnectedAccount, SingleOwnerAccount};
 use starknet::contract::ContractFactory;
 use starknet::core::types::{
-    BlockId, BlockTag, ContractClass, Felt, Hash256, MsgFromL1, Transaction,
-    TransactionFinalityStatus, TransactionReceipt,
+    BlockId, BlockTag, ContractClass, Felt, Hash256, MsgFromL1, SequencerTransactionStatus,
+    Transaction, TransactionExecutionStatus, TransactionFinalityStatus, TransactionReceipt,
 };
 use starknet::core::utils::get_contract_address;
 use starknet::macros::selector;
-use starknet::providers::Provider;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider};
+use starknet::signers::LocalWallet;
 
 mod common;
 
@@ -72,6 +81,7 @@ async fn test_messaging() {
         private_key: "".to_string(),
         interval: 2,
         from_block: 0,
+        confirmations: 0,
     };
 
     let mut config = get_default_test_config(SequencingConfig::default());
@@ -81,53 +91,7 @@ async fn test_messaging() {
     let katana_account = sequencer.account();
 
     // Deploy test L2 contract that can send/receive messages to/from L1
-    let l2_test_contract = {
-        // Prepare contract declaration params
-        let path = PathBuf::from("tests/test_data/cairo_l1_msg_contract.json");
-        let (contract, compiled_hash) = common::prepare_contract_declaration_params(&path).unwrap();
-
-        // Declare the contract
-        let class_hash = contract.class_hash();
-        let res = katana_account.declare_v2(contract.into(), compiled_hash).send().await.unwrap();
-
-        // The waiter already checks that the transaction is accepted and succeeded on L2.
-        TransactionWaiter::new(res.transaction_hash, katana_account.provider())
-            .await
-            .expect("declare tx failed");
-
-        // Checks that the class was indeed declared
-        let block_id = BlockId::Tag(BlockTag::Latest);
-        let actual_class = katana_account.provider().get_class(block_id, class_hash).await.unwrap();
-
-        let ContractClass::Sierra(class) = actual_class else { panic!("Invalid class type") };
-        assert_eq!(class.class_hash(), class_hash, "invalid declared class"); // just to make sure the rpc returns the correct class
-
-        // Compute the contract address
-        let address = get_contract_address(Felt::ZERO, class_hash, &[], Felt::ZERO);
-
-        // Deploy the contract using UDC
-        let res = ContractFactory::new(class_hash, &katana_account)
-            .deploy_v1(Vec::new(), Felt::ZERO, false)
-            .send()
-            .await
-            .expect("Unable to deploy contract");
-
-        // The waiter already checks that the transaction is accepted and succeeded on L2.
-        TransactionWaiter::new(res.transaction_hash, katana_account.provider())
-            .await
-            .expect("deploy tx failed");
-
-        // Checks that the class was indeed deployed with the correct class
-        let actual_class_hash = katana_account
-            .provider()
-            .get_class_hash_at(block_id, address)
-            .await
-            .expect("failed to get class hash at address");
-
-        assert_eq!(actual_class_hash, class_hash, "invalid deployed class");
-
-        address
-    };
+    let l2_test_contract = deploy_l2_test_contract(&katana_account).await;
 
     // Send message from L1 to L2
     {
@@ -231,6 +195,21 @@ async fn test_messaging() {
                 panic!("Error, No Receipt TransactionReceipt")
             }
         }
+
+        // the message status is looked up by the hash of the L1 transaction that sent it
+        let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+        let l1_tx_hash = L1TxHash::from(receipt.transaction_hash.0);
+        let statuses = client.get_messages_status(l1_tx_hash).await.unwrap();
+
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].transaction_hash, tx_hash);
+        assert_eq!(statuses[0].finality_status, SequencerTransactionStatus::AcceptedOnL2);
+        assert_eq!(statuses[0].execution_status, Some(TransactionExecutionStatus::Succeeded));
+        assert_eq!(statuses[0].failure_reason, None);
+        assert_eq!(statuses[0].cancellation, None);
+
+        // L1 transactions that didn't send any message are unknown
+        assert!(client.get_messages_status(L1TxHash::ZERO).await.is_err());
     }
 
     // Send message from L2 to L1
@@ -381,3 +360,301 @@ async fn estimate_message_fee() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn l1_gas_prices_sampling() {
+    let port: u16 = rand::thread_rng().gen_range(35000..65000);
+
+    let l1_provider = {
+        ProviderBuilder::new()
+            .with_recommended_fillers()
+            .on_anvil_with_wallet_and_config(|anvil| anvil.port(port))
+    };
+
+    let core_contract = StarknetContract::deploy(&l1_provider).await.unwrap();
+
+    let messaging_config = MessagingConfig {
+        chain: "ethereum".to_string(),
+        rpc_url: format!("http://localhost:{}", port),
+        contract_address: core_contract.address().to_string(),
+        sender_address: l1_provider.default_signer_address().to_string(),
+        private_key: "".to_string(),
+        interval: 2,
+        from_block: 0,
+        confirmations: 0,
+    };
+
+    let mut config = get_default_test_config(SequencingConfig::default());
+    config.messaging = Some(messaging_config);
+    config.gas_oracle =
+        GasOracleConfig { l1_sampling: Some(Duration::from_millis(100)), l2_target_gas: None };
+    let sequencer = TestSequencer::start(config).await;
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // no block is mined on L1 in the meantime, so the base fee doesn't change
+    let history = l1_provider.get_fee_history(1, Default::default(), &[]).await.unwrap();
+    let base_fee = history.next_block_base_fee().unwrap();
+
+    let prices = sequencer.backend().gas_oracle.current_prices();
+    assert_eq!(prices.gas.eth, base_fee);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn gathered_messages_are_executed_once_across_restarts() {
+    let port: u16 = rand::thread_rng().gen_range(35000..65000);
+    let l1_provider = {
+        ProviderBuilder::new()
+            .with_recommended_fillers()
+            .on_anvil_with_wallet_and_config(|anvil| anvil.port(port))
+    };
+
+    let core_contract = StarknetContract::deploy(&l1_provider).await.unwrap();
+    let l1_test_contract = Contract1::deploy(&l1_provider, *core_contract.address()).await.unwrap();
+
+    let messaging_config = MessagingConfig {
+        chain: "ethereum".to_string(),
+        rpc_url: format!("http://localhost:{}", port),
+        contract_address: core_contract.address().to_string(),
+        sender_address: l1_provider.default_signer_address().to_string(),
+        private_key: "".to_string(),
+        interval: 1,
+        from_block: 0,
+        confirmations: 0,
+    };
+
+    let db_dir = tempfile::tempdir().unwrap();
+
+    // deploy the L2 recipient of the message
+    let node = TestNode::builder().db_dir(db_dir.path()).start().await.unwrap();
+    let recipient = deploy_l2_test_contract(&node.account()).await;
+    let chain_id = node.provider().chain_id().await.unwrap();
+    node.stop().await.unwrap();
+    drop(node);
+
+    // gather the message without mining the block executing it
+    let node = TestNode::builder()
+        .db_dir(db_dir.path())
+        .no_mining()
+        .messaging(messaging_config.clone())
+        .start()
+        .await
+        .unwrap();
+
+    let selector = selector!("msg_handler_value");
+    let nonce = core_contract.l1ToL2MessageNonce().call().await.unwrap()._0;
+    let receipt = l1_test_contract
+        .sendMessage(
+            U256::from_str(&recipient.to_string()).unwrap(),
+            U256::from_str(&selector.to_string()).unwrap(),
+            vec![U256::from(123)],
+        )
+        .gas(12000000)
+        .value(Uint::from(1))
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    assert!(receipt.status(), "failed to send L1 -> L2 message");
+
+    let sender = Felt::from_bytes_be_slice(l1_test_contract.address().as_slice());
+    let tx_hash = compute_l1_handler_tx_hash(
+        Felt::ZERO,
+        recipient,
+        selector,
+        &[sender, Felt::from(123)],
+        chain_id,
+        nonce.to::<u64>().into(),
+    );
+
+    tokio::time::sleep(Duration::from_secs(3)).await;
+
+    // the message was gathered, but its transaction is only part of the pending block
+    let client = HttpClientBuilder::default().build(node.url()).unwrap();
+    let l1_tx_hash = L1TxHash::from(receipt.transaction_hash.0);
+    let statuses = client.get_messages_status(l1_tx_hash).await.unwrap();
+    assert_eq!(statuses[0].transaction_hash, tx_hash);
+
+    let provider = node.backend().blockchain.provider();
+    assert_eq!(provider.transaction_status(tx_hash).unwrap(), None);
+    let checkpoint = provider.checkpoint("messaging/gather").unwrap();
+    assert!(checkpoint.map_or(true, |block| block < receipt.block_number.unwrap()));
+
+    node.stop().await.unwrap();
+    drop(node);
+
+    // the message is gathered again after the restart, and executed once
+    let node = TestNode::builder()
+        .db_dir(db_dir.path())
+        .messaging(messaging_config)
+        .start()
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+
+    let provider = node.provider();
+    let receipt = provider.get_transaction_receipt(tx_hash).await.unwrap();
+    assert!(matches!(receipt.receipt, TransactionReceipt::L1Handler(_)));
+
+    let latest = provider.block_number().await.unwrap();
+    let mut executions = 0;
+    for number in 0..=latest {
+        let block = provider.get_block_with_tx_hashes(BlockId::Number(number)).await.unwrap();
+        executions += block.transactions().iter().filter(|hash| **hash == tx_hash).count();
+    }
+    assert_eq!(executions, 1);
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cancelled_message_status() {
+    let port: u16 = rand::thread_rng().gen_range(35000..65000);
+    let l1_provider = {
+        ProviderBuilder::new()
+            .with_recommended_fillers()
+            .on_anvil_with_wallet_and_config(|anvil| anvil.port(port))
+    };
+
+    let core_contract = StarknetContract::deploy(&l1_provider).await.unwrap();
+
+    // the message is sent and cancelled before Katana gathers it
+    let recipient = felt!("0x1234");
+    let selector = selector!("msg_handler_value");
+    let to_address = U256::from_str(&recipient.to_string()).unwrap();
+    let selector_u256 = U256::from_str(&selector.to_string()).unwrap();
+    let payload = vec![U256::from(123)];
+    let nonce = core_contract.l1ToL2MessageNonce().call().await.unwrap()._0;
+
+    let receipt = core_contract
+        .sendMessageToL2(to_address, selector_u256, payload.clone())
+        .value(Uint::from(1))
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    assert!(receipt.status(), "failed to send L1 -> L2 message");
+
+    let started = core_contract
+        .startL1ToL2MessageCancellation(to_address, selector_u256, payload.clone(), nonce)
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    assert!(started.status(), "failed to start the message cancellation");
+
+    let cancelled = core_contract
+        .cancelL1ToL2Message(to_address, selector_u256, payload, nonce)
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    assert!(cancelled.status(), "failed to cancel the message");
+
+    let messaging_config = MessagingConfig {
+        chain: "ethereum".to_string(),
+        rpc_url: format!("http://localhost:{}", port),
+        contract_address: core_contract.address().to_string(),
+        sender_address: l1_provider.default_signer_address().to_string(),
+        private_key: "".to_string(),
+        interval: 1,
+        from_block: 0,
+        confirmations: 0,
+    };
+
+    let node = TestNode::builder().messaging(messaging_config).start().await.unwrap();
+    let chain_id = node.provider().chain_id().await.unwrap();
+
+    let sender = Felt::from_bytes_be_slice(l1_provider.default_signer_address().as_slice());
+    let tx_hash = compute_l1_handler_tx_hash(
+        Felt::ZERO,
+        recipient,
+        selector,
+        &[sender, Felt::from(123)],
+        chain_id,
+        nonce.to::<u64>().into(),
+    );
+
+    tokio::time::sleep(Duration::from_secs(3)).await;
+
+    // the transaction of the cancelled message is never executed
+    let client = HttpClientBuilder::default().build(node.url()).unwrap();
+    let l1_tx_hash = L1TxHash::from(receipt.transaction_hash.0);
+    let statuses = client.get_messages_status(l1_tx_hash).await.unwrap();
+
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].transaction_hash, tx_hash);
+    assert_eq!(statuses[0].finality_status, SequencerTransactionStatus::Rejected);
+    assert_eq!(statuses[0].execution_status, None);
+    assert_eq!(statuses[0].failure_reason.as_deref(), Some("Message was cancelled on L1"));
+    assert_eq!(statuses[0].cancellation, Some(MessageCancellation::Cancelled));
+
+    // and the blocks of the message are settled
+    let provider = node.backend().blockchain.provider();
+    assert_eq!(provider.transaction_status(tx_hash).unwrap(), None);
+    let checkpoint = provider.checkpoint("messaging/gather").unwrap().unwrap();
+    assert!(checkpoint >= cancelled.block_number.unwrap());
+
+    node.stop().await.unwrap();
+}
+
+/// Declares and deploys the L2 contract that sends and receives messages to and from L1, returning
+/// its address.
+async fn deploy_l2_test_contract(
+    katana_account: &SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>,
+) -> Felt {
+    // Prepare contract declaration params
+    let path = PathBuf::from("tests/test_data/cairo_l1_msg_contract.json");
+    let (contract, compiled_hash) = common::prepare_contract_declaration_params(&path).unwrap();
+
+    // Declare the contract
+    let class_hash = contract.class_hash();
+    let res = katana_account.declare_v2(contract.into(), compiled_hash).send().await.unwrap();
+
+    // The waiter already checks that the transaction is accepted and succeeded on L2.
+    TransactionWaiter::new(res.transaction_hash, katana_account.provider())
+        .await
+        .expect("declare tx failed");
+
+    // Checks that the class was indeed declared
+    let block_id = BlockId::Tag(BlockTag::Latest);
+    let actual_class = katana_account.provider().get_class(block_id, class_hash).await.unwrap();
+
+    let ContractClass::Sierra(class) = actual_class else { panic!("Invalid class type") };
+    assert_eq!(class.class_hash(), class_hash, "invalid declared class"); // just to make sure the rpc returns the correct class
+
+    // Compute the contract address
+    let address = get_contract_address(Felt::ZERO, class_hash, &[], Felt::ZERO);
+
+    // Deploy the contract using UDC
+    let res = ContractFactory::new(class_hash, katana_account)
+        .deploy_v1(Vec::new(), Felt::ZERO, false)
+        .send()
+        .await
+        .expect("Unable to deploy contract");
+
+    // The waiter already checks that the transaction is accepted and succeeded on L2.
+    TransactionWaiter::new(res.transaction_hash, katana_account.provider())
+        .await
+        .expect("deploy tx failed");
+
+    // Checks that the class was indeed deployed with the correct class
+    let actual_class_hash = katana_account
+        .provider()
+        .get_class_hash_at(block_id, address)
+        .await
+        .expect("failed to get class hash at address");
+
+    assert_eq!(actual_class_hash, class_hash, "invalid deployed class");
+
+    address
+}
@@ -8,7 +8,10 @@ use cainome::rs::abigen_legacy;
 use common::split_felt;
 use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
 use indexmap::IndexSet;
+use jsonrpsee::core::client::ClientT;
 use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use katana_executor::BlockLimits;
 use katana_node::config::SequencingConfig;
 use katana_primitives::event::ContinuationToken;
 use katana_primitives::genesis::constant::{
@@ -16,14 +19,18 @@ use katana_primitives::genesis::constant::{
     DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS,
 };
 use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::starknet::StarknetApiClient;
+use katana_trie::Pedersen;
+use serde_json::{json, Value};
 use starknet::accounts::{
     Account, AccountError, AccountFactory, ConnectedAccount, ExecutionEncoding,
     OpenZeppelinAccountFactory, SingleOwnerAccount,
 };
 use starknet::core::types::contract::legacy::LegacyContractClass;
 use starknet::core::types::{
-    BlockId, BlockTag, Call, DeclareTransactionReceipt, DeployAccountTransactionReceipt,
-    EventFilter, EventsPage, ExecutionResult, Felt, StarknetError, TransactionExecutionStatus,
+    BlockId, BlockTag, BroadcastedInvokeTransaction, Call, DeclareTransactionReceipt,
+    DeployAccountTransactionReceipt, EventFilter, EventsPage, ExecutionResult, Felt,
+    MaybePendingBlockWithTxHashes, StarknetError, TransactionExecutionStatus,
     TransactionFinalityStatus, TransactionReceipt, TransactionTrace,
 };
 use starknet::core::utils::get_contract_address;
@@ -951,3 +958,213 @@ async fn v3_transactions() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn get_storage_proof() -> Result<()> {
+    let sequencer =
+        TestSequencer::start(get_default_test_config(SequencingConfig::default())).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    // send a tx to update the state of the sender and the fee token contract.
+    let contract = Erc20Contract::new(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), &account);
+    let res = contract.transfer(&Felt::ONE, &Uint256 { low: Felt::ONE, high: Felt::ZERO });
+    let res = res.send().await?;
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await?;
+
+    let block_id = BlockId::Tag(BlockTag::Latest);
+    let MaybePendingBlockWithTxHashes::Block(block) =
+        provider.get_block_with_tx_hashes(block_id).await?
+    else {
+        panic!("expected a mined block")
+    };
+
+    let address = account.address();
+    let proof =
+        StarknetApiClient::get_storage_proof(&client, block_id, None, Some(vec![address]), None)
+            .await?;
+
+    // the state root of the block must be derived from the returned trie roots.
+    let roots = proof.global_roots;
+    assert_eq!(roots.block_hash, block.block_hash);
+    assert_eq!(
+        katana_trie::state_root(roots.contracts_tree_root, roots.classes_tree_root),
+        block.new_root
+    );
+
+    // the proof must prove the account's leaf in the contracts trie.
+    let nodes = proof.contracts_proof.nodes.0.into_iter().map(|n| (n.node_hash, n.node.into()));
+    let nodes = nodes.collect::<Vec<_>>();
+    let leaf = &proof.contracts_proof.contract_leaves_data[0];
+    let expected = katana_trie::contract_state_hash(leaf.class_hash, leaf.storage_root, leaf.nonce);
+
+    assert_eq!(leaf.nonce, Felt::ONE);
+    assert_eq!(
+        katana_trie::verify_proof::<Pedersen>(roots.contracts_tree_root, address, &nodes),
+        Some(expected)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn instant_mining_block_limits() -> Result<()> {
+    let block_limits = BlockLimits { tx_count: 1, ..Default::default() };
+    let config = get_default_test_config(SequencingConfig { block_limits, ..Default::default() });
+    let sequencer = TestSequencer::start(config).await;
+
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    let contract = Erc20Contract::new(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), &account);
+    let amount = Uint256 { low: Felt::ONE, high: Felt::ZERO };
+    let nonce = account.get_nonce().await?;
+
+    // the transactions are sent at once, so that some of them are queued while a block is mined
+    let mut hashes = Vec::new();
+    for i in 0..3u64 {
+        let transfer = contract.transfer(&Felt::ONE, &amount).nonce(nonce + Felt::from(i));
+        let res = transfer.max_fee(felt!("0x1000000000000")).send().await?;
+        hashes.push(res.transaction_hash);
+    }
+
+    for hash in hashes {
+        dojo_utils::TransactionWaiter::new(hash, &provider).await?;
+    }
+
+    // each block only has a single transaction
+    assert_eq!(provider.block_number().await?, 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn block_closed_early_on_limits() -> Result<()> {
+    let block_limits = BlockLimits { tx_count: 2, ..Default::default() };
+    let config = get_default_test_config(SequencingConfig {
+        no_mining: true,
+        block_limits,
+        ..Default::default()
+    });
+    let sequencer = TestSequencer::start(config).await;
+
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    let contract = Erc20Contract::new(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), &account);
+    let amount = Uint256 { low: Felt::ONE, high: Felt::ZERO };
+
+    for _ in 0..5 {
+        let res = contract.transfer(&Felt::ONE, &amount).send().await?;
+        dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await?;
+    }
+
+    // the blocks are mined as soon as they are full, even without block production
+    assert_eq!(provider.block_number().await?, 2);
+
+    for num in 1..=2 {
+        let MaybePendingBlockWithTxHashes::Block(block) =
+            provider.get_block_with_tx_hashes(BlockId::Number(num)).await?
+        else {
+            panic!("expected a mined block")
+        };
+        assert_eq!(block.transactions.len(), 2);
+    }
+
+    let MaybePendingBlockWithTxHashes::PendingBlock(block) =
+        provider.get_block_with_tx_hashes(BlockId::Tag(BlockTag::Pending)).await?
+    else {
+        panic!("expected the pending block")
+    };
+    assert_eq!(block.transactions.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn versioned_routes() -> Result<()> {
+    let sequencer =
+        TestSequencer::start(get_default_test_config(SequencingConfig::default())).await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x1"), felt!("0x1"), Felt::ZERO],
+    };
+    let res = account.execute_v1(vec![call.clone()]).send().await?;
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await?;
+
+    let client =
+        |path: &str| HttpClientBuilder::default().build(sequencer.url().join(path).unwrap());
+    let (root, v0_7, v0_8) = (client("")?, client("rpc/v0_7")?, client("rpc/v0_8")?);
+
+    // the root keeps serving the 0.7 spec
+    assert_eq!(root.spec_version().await?, "0.7.1");
+    assert_eq!(v0_7.spec_version().await?, "0.7.1");
+    assert_eq!(v0_8.spec_version().await?, "0.8.0");
+
+    let params = rpc_params![res.transaction_hash];
+    let receipt: Value = v0_7.request("starknet_getTransactionReceipt", params.clone()).await?;
+    assert!(receipt["execution_resources"]["steps"].as_u64().unwrap() > 0);
+
+    let receipt: Value = v0_8.request("starknet_getTransactionReceipt", params).await?;
+    let resources = &receipt["execution_resources"];
+    assert!(resources["l1_gas"].as_u64().unwrap() > 0);
+    assert_eq!(resources["l2_gas"], 0);
+
+    let block: Value = v0_7.request("starknet_getBlockWithReceipts", rpc_params!["latest"]).await?;
+    assert!(block.get("l2_gas_price").is_none());
+
+    let block: Value = v0_8.request("starknet_getBlockWithReceipts", rpc_params!["latest"]).await?;
+    assert!(block.get("l2_gas_price").is_some());
+    assert_eq!(&block["transactions"][0]["receipt"]["execution_resources"], resources);
+
+    let params = rpc_params![res.transaction_hash];
+    let trace: Value = v0_7.request("starknet_traceTransaction", params.clone()).await?;
+    assert!(trace["execution_resources"]["steps"].as_u64().unwrap() > 0);
+
+    let trace: Value = v0_8.request("starknet_traceTransaction", params).await?;
+    assert_eq!(&trace["execution_resources"], resources);
+    assert_eq!(trace["execute_invocation"]["is_reverted"], false);
+
+    let traces: Value =
+        v0_8.request("starknet_traceBlockTransactions", rpc_params!["latest"]).await?;
+    assert_eq!(traces[0]["trace_root"], trace);
+
+    // the methods whose types are the same are shared by both versions
+    assert_eq!(v0_7.block_number().await?, v0_8.block_number().await?);
+
+    // the 0.8 V3 transactions include the L1 data gas bounds
+    let transfer = Call { to: DEFAULT_STRK_FEE_TOKEN_ADDRESS.into(), ..call };
+    let execution = account
+        .execute_v3(vec![transfer])
+        .nonce(account.get_nonce().await?)
+        .gas(100000000000)
+        .gas_price(0x1000000000000);
+    let tx = execution.prepared()?.get_invoke_request(false, false).await?;
+    let mut tx = serde_json::to_value(BroadcastedInvokeTransaction::V3(tx))?;
+    tx["resource_bounds"]["l1_data_gas"] =
+        json!({ "max_amount": "0x0", "max_price_per_unit": "0x0" });
+
+    let params = rpc_params![vec![tx.clone()], Vec::<Value>::new(), "pending"];
+    let estimates: Value = v0_8.request("starknet_estimateFee", params).await?;
+    assert_eq!(estimates[0]["unit"], "FRI");
+
+    let res: Value = v0_8.request("starknet_addInvokeTransaction", rpc_params![tx.clone()]).await?;
+    let hash = serde_json::from_value::<Felt>(res["transaction_hash"].clone())?;
+    dojo_utils::TransactionWaiter::new(hash, &provider).await?;
+
+    tx["resource_bounds"].as_object_mut().unwrap().remove("l1_data_gas");
+    let params = rpc_params![vec![tx], Vec::<Value>::new(), "pending"];
+    assert!(v0_8.request::<Value, _>("starknet_estimateFee", params).await.is_err());
+
+    // the versioned methods can't be called by their own name on the versioned routes
+    let res: Result<String, _> = v0_8.request("starknetV0_8_specVersion", rpc_params![]).await;
+    assert!(res.is_err());
+
+    Ok(())
+}
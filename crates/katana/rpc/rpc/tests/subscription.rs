@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use cainome::rs::abigen_legacy;
+use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use dojo_utils::TransactionWaiter;
+use jsonrpsee::core::client::SubscriptionKind;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::types::SubscriptionId;
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use katana_node::config::SequencingConfig;
+use katana_primitives::block::BlockIdOrTag;
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_primitives::Felt;
+use katana_provider::traits::block::BlockHashProvider;
+use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::starknet::StarknetWsApiClient;
+use katana_rpc_types::transaction::PendingTx;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{TransactionExecutionStatus, TransactionStatus};
+use starknet::macros::felt;
+
+abigen_legacy!(Erc20Contract, "crates/katana/rpc/rpc/tests/test_data/erc20.json");
+
+async fn create_test_sequencer() -> TestSequencer {
+    TestSequencer::start(get_default_test_config(SequencingConfig::default())).await
+}
+
+async fn ws_client(sequencer: &TestSequencer) -> WsClient {
+    let mut url = sequencer.url();
+    url.set_scheme("ws").unwrap();
+    WsClientBuilder::default().build(url).await.unwrap()
+}
+
+#[tokio::test]
+async fn subscribe_new_heads() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.backend().blockchain.provider();
+
+    let client = ws_client(&sequencer).await;
+    let dev_client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let mut subscription = client.subscribe_new_heads(None).await.unwrap();
+
+    for expected_num in 1..=3 {
+        dev_client.generate_block().await.unwrap();
+
+        let header = subscription.next().await.unwrap().unwrap();
+        let expected_hash = provider.block_hash_by_num(expected_num).unwrap().unwrap();
+
+        assert_eq!(header.block_number, expected_num);
+        assert_eq!(header.block_hash, expected_hash);
+    }
+}
+
+#[tokio::test]
+async fn subscribe_new_heads_from_past_block() {
+    let sequencer = create_test_sequencer().await;
+
+    let client = ws_client(&sequencer).await;
+    let dev_client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    dev_client.generate_block().await.unwrap();
+    dev_client.generate_block().await.unwrap();
+
+    // all the blocks from the genesis block up to the latest block should be sent first.
+    let block_id = BlockIdOrTag::Number(0);
+    let mut subscription = client.subscribe_new_heads(Some(block_id)).await.unwrap();
+
+    for expected_num in 0..=2 {
+        let header = subscription.next().await.unwrap().unwrap();
+        assert_eq!(header.block_number, expected_num);
+    }
+
+    // followed by the newly mined blocks.
+    dev_client.generate_block().await.unwrap();
+    let header = subscription.next().await.unwrap().unwrap();
+    assert_eq!(header.block_number, 3);
+}
+
+#[tokio::test]
+async fn subscribe_new_heads_from_unknown_block() {
+    let sequencer = create_test_sequencer().await;
+    let client = ws_client(&sequencer).await;
+
+    let block_id = BlockIdOrTag::Number(100);
+    let result = client.subscribe_new_heads(Some(block_id)).await;
+
+    assert!(result.is_err(), "subscribing from a non-existent block should fail");
+}
+
+#[tokio::test]
+async fn subscribe_events() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    let client = ws_client(&sequencer).await;
+
+    let fee_token: Felt = DEFAULT_ETH_FEE_TOKEN_ADDRESS.into();
+    let mut subscription = client.subscribe_events(Some(fee_token), None, None).await.unwrap();
+
+    // the transfer emits a `Transfer` event from the fee token contract.
+    let contract = Erc20Contract::new(fee_token, &account);
+    let amount = Uint256 { low: Felt::ONE, high: Felt::ZERO };
+    let res = contract.transfer(&Felt::ONE, &amount).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let event = subscription.next().await.unwrap().unwrap();
+    assert_eq!(event.from_address, fee_token);
+    assert_eq!(event.transaction_hash, res.transaction_hash);
+    assert_eq!(event.block_number, Some(1));
+}
+
+#[tokio::test]
+async fn subscribe_transaction_status() {
+    let sequencer = create_test_sequencer().await;
+    let account = sequencer.account();
+
+    let client = ws_client(&sequencer).await;
+
+    let contract = Erc20Contract::new(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), &account);
+    let amount = Uint256 { low: Felt::ONE, high: Felt::ZERO };
+    let nonce = account.get_nonce().await.unwrap();
+
+    // subscribe before the transaction is submitted, so that the update is sent once it's mined.
+    let tx = contract
+        .transfer(&Felt::ONE, &amount)
+        .nonce(nonce)
+        .max_fee(felt!("0x1000000000000"))
+        .prepared()
+        .unwrap();
+    let hash = tx.transaction_hash(false);
+
+    let mut subscription = client.subscribe_transaction_status(hash).await.unwrap();
+    tx.send().await.unwrap();
+
+    let update = subscription.next().await.unwrap().unwrap();
+    assert_eq!(update.transaction_hash, hash);
+    assert_eq!(
+        update.status,
+        TransactionStatus::AcceptedOnL2(TransactionExecutionStatus::Succeeded)
+    );
+
+    // the status of an already mined transaction is sent right away.
+    let mut subscription = client.subscribe_transaction_status(hash).await.unwrap();
+    let update = subscription.next().await.unwrap().unwrap();
+    assert_eq!(
+        update.status,
+        TransactionStatus::AcceptedOnL2(TransactionExecutionStatus::Succeeded)
+    );
+}
+
+#[tokio::test]
+async fn subscribe_pending_transactions() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+
+    let client = ws_client(&sequencer).await;
+
+    let mut hashes = client.subscribe_pending_transactions(None, None).await.unwrap();
+    let sender = Some(vec![account.address()]);
+    let mut details = client.subscribe_pending_transactions(Some(true), sender).await.unwrap();
+
+    let contract = Erc20Contract::new(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), &account);
+    let amount = Uint256 { low: Felt::ONE, high: Felt::ZERO };
+    let res = contract.transfer(&Felt::ONE, &amount).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let tx = hashes.next().await.unwrap().unwrap();
+    assert!(matches!(tx, PendingTx::Hash(hash) if Felt::from(hash) == res.transaction_hash));
+
+    let tx = details.next().await.unwrap().unwrap();
+    assert!(matches!(tx, PendingTx::Full(tx) if *tx.0.transaction_hash() == res.transaction_hash));
+}
+
+#[tokio::test]
+async fn subscribe_pending_transactions_with_too_many_senders() {
+    let sequencer = create_test_sequencer().await;
+    let client = ws_client(&sequencer).await;
+
+    let senders = (0..=128u64).map(Felt::from).collect::<Vec<_>>();
+    let result = client.subscribe_pending_transactions(None, Some(senders)).await;
+
+    assert!(result.is_err(), "subscribing with too many sender addresses should fail");
+}
+
+#[tokio::test]
+async fn unsubscribe() {
+    let sequencer = create_test_sequencer().await;
+
+    let client = ws_client(&sequencer).await;
+    let dev_client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let mut subscription = client.subscribe_new_heads(None).await.unwrap();
+    let SubscriptionKind::Subscription(SubscriptionId::Num(id)) = subscription.kind().clone()
+    else {
+        panic!("subscription ids should be numeric");
+    };
+
+    assert!(client.unsubscribe(id).await.unwrap());
+
+    // no more headers are sent once the subscription is closed.
+    dev_client.generate_block().await.unwrap();
+    let next = tokio::time::timeout(Duration::from_secs(1), subscription.next()).await;
+    assert!(next.is_err(), "closed subscription should not receive new headers");
+
+    // the subscription doesn't exist anymore.
+    assert!(client.unsubscribe(id).await.is_err());
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::Error;
+use jsonrpsee::rpc_params;
+use jsonrpsee::types::error::{CallError, ErrorCode};
+use katana_node::config::sync::SyncConfig;
+use katana_node::test_utils::TestNode;
+use katana_rpc_api::dev::DevApiClient;
+use serde_json::Value;
+use starknet::providers::Provider;
+
+#[tokio::test]
+async fn replica_is_read_only() {
+    let source = TestNode::start().await.unwrap();
+    source.dev_client().generate_block().await.unwrap();
+
+    let sync = SyncConfig { url: source.url(), interval: Duration::from_millis(100) };
+    let replica =
+        TestNode::builder().configure(|config| config.sync = Some(sync)).start().await.unwrap();
+
+    // the blocks after the genesis block are synced once the node is running
+    let provider = replica.provider();
+    let synced = async {
+        while provider.block_number().await.unwrap() < 1 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(10), synced).await.expect("replica should sync");
+
+    // the transactions sent to a replica would never be included in a block
+    let client = replica.dev_client();
+    let result = client.request::<Value, _>("starknet_addInvokeTransaction", rpc_params![]).await;
+    assert!(matches!(
+        result,
+        Err(Error::Call(CallError::Custom(err))) if err.code() == ErrorCode::MethodNotFound.code()
+    ));
+}
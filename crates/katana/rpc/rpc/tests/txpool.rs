@@ -0,0 +1,60 @@
+use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use dojo_utils::TransactionWaiter;
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_node::config::SequencingConfig;
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_rpc_api::txpool::TxPoolApiClient;
+use katana_rpc_types::txpool::TxPoolStatus;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{Call, Felt};
+use starknet::macros::{felt, selector};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn queued_txs_are_promoted() {
+    let sequencing_config = SequencingConfig { no_mining: true, ..Default::default() };
+    let sequencer = TestSequencer::start(get_default_test_config(sequencing_config)).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let account = sequencer.account();
+    let provider = account.provider();
+
+    let status = client.txpool_status().await.unwrap();
+    assert_eq!(status, TxPoolStatus { pending: 0, queued: 0 });
+
+    let transfer = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+    };
+
+    let nonce = account.get_nonce().await.unwrap();
+    let max_fee = felt!("0x100000000000000000");
+
+    // a tx with a nonce ahead of the account's nonce waits in the queue
+    let res = account
+        .execute_v1(vec![transfer.clone()])
+        .nonce(nonce + Felt::ONE)
+        .max_fee(max_fee)
+        .send()
+        .await
+        .unwrap();
+    let queued_tx = res.transaction_hash;
+
+    let status = client.txpool_status().await.unwrap();
+    assert_eq!(status, TxPoolStatus { pending: 0, queued: 1 });
+
+    let content = client.txpool_content().await.unwrap();
+    assert!(content.pending.is_empty());
+    assert_eq!(content.queued.len(), 1);
+    assert_eq!(Felt::from(content.queued[0].hash), queued_tx);
+    assert_eq!(Felt::from(content.queued[0].nonce), nonce + Felt::ONE);
+
+    // filling the nonce gap makes the queued tx executable
+    let res =
+        account.execute_v1(vec![transfer]).nonce(nonce).max_fee(max_fee).send().await.unwrap();
+    TransactionWaiter::new(res.transaction_hash, provider).await.unwrap();
+    TransactionWaiter::new(queued_tx, provider).await.unwrap();
+
+    let status = client.txpool_status().await.unwrap();
+    assert_eq!(status, TxPoolStatus { pending: 0, queued: 0 });
+}
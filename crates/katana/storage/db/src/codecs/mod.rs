@@ -1,6 +1,7 @@
 #[cfg(feature = "postcard")]
 pub mod postcard;
 
+use alloy_primitives::B256;
 use katana_primitives::block::FinalityStatus;
 use katana_primitives::class::FlattenedSierraClass;
 use katana_primitives::contract::ContractAddress;
@@ -72,6 +73,32 @@ macro_rules! impl_encode_and_decode_for_felts {
 impl_encode_and_decode_for_uints!(u64);
 impl_encode_and_decode_for_felts!(Felt, ContractAddress);
 
+impl Encode for B256 {
+    type Encoded = [u8; 32];
+    fn encode(self) -> Self::Encoded {
+        self.0
+    }
+}
+
+impl Decode for B256 {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        B256::try_from(bytes.as_ref()).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
+impl Encode for String {
+    type Encoded = Vec<u8>;
+    fn encode(self) -> Self::Encoded {
+        self.into_bytes()
+    }
+}
+
+impl Decode for String {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        String::from_utf8(bytes.as_ref().to_vec()).map_err(|e| CodecError::Decode(e.to_string()))
+    }
+}
+
 impl Compress for FlattenedSierraClass {
     type Compressed = Vec<u8>;
     fn compress(self) -> Self::Compressed {
@@ -101,3 +128,16 @@ impl Decompress for FinalityStatus {
         }
     }
 }
+
+impl Compress for B256 {
+    type Compressed = [u8; 32];
+    fn compress(self) -> Self::Compressed {
+        self.0
+    }
+}
+
+impl Decompress for B256 {
+    fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        B256::try_from(bytes.as_ref()).map_err(|e| CodecError::Decompress(e.to_string()))
+    }
+}
@@ -1,9 +1,12 @@
 use katana_primitives::block::Header;
 use katana_primitives::contract::{ContractAddress, GenericContractInfo};
+use katana_primitives::da::StateDiffBlob;
+use katana_primitives::message::L1ToL2Message;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::Tx;
 use katana_primitives::Felt;
+use katana_trie::Node;
 use postcard;
 
 use super::{Compress, Decompress};
@@ -11,6 +14,7 @@ use crate::error::CodecError;
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::ContractInfoChangeList;
 use crate::models::list::BlockList;
+use crate::models::trie::TrieRoots;
 
 macro_rules! impl_compress_and_decompress_for_table_values {
     ($($name:ty),*) => {
@@ -42,5 +46,9 @@ impl_compress_and_decompress_for_table_values!(
     BlockList,
     GenericContractInfo,
     StoredBlockBodyIndices,
-    ContractInfoChangeList
+    ContractInfoChangeList,
+    Node,
+    TrieRoots,
+    L1ToL2Message,
+    Vec<StateDiffBlob>
 );
@@ -0,0 +1,216 @@
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::event::EventFilter;
+use katana_primitives::receipt::Event;
+use katana_primitives::Felt;
+
+use crate::codecs::{Compress, Decode, Decompress, Encode};
+use crate::error::CodecError;
+
+/// The size of an [`EventBloom`] in bytes.
+pub const EVENT_BLOOM_BYTES: usize = 256;
+
+/// The number of bits set in an [`EventBloom`] for every inserted item.
+const EVENT_BLOOM_HASHES: usize = 3;
+
+/// A bloom filter of the emitters and keys of the events emitted in a block.
+///
+/// Used to cheaply rule out the blocks that can't contain events matching a filter. A positive
+/// answer may be a false positive, a negative one is always correct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventBloom([u8; EVENT_BLOOM_BYTES]);
+
+impl EventBloom {
+    /// Adds the emitter and keys of `event` to the bloom.
+    pub fn accrue(&mut self, event: &Event) {
+        self.insert(&address_item(event.from_address));
+        for (index, key) in event.keys.iter().enumerate() {
+            self.insert(&key_item(index as u32, *key));
+        }
+    }
+
+    /// Returns `false` if no event emitted by `address` has been added to the bloom.
+    pub fn contains_address(&self, address: ContractAddress) -> bool {
+        self.contains(&address_item(address))
+    }
+
+    /// Returns `false` if no event with `key` at position `index` has been added to the bloom.
+    pub fn contains_key(&self, index: u32, key: Felt) -> bool {
+        self.contains(&key_item(index, key))
+    }
+
+    /// Returns `false` if none of the events added to the bloom can match `filter`.
+    pub fn may_match(&self, filter: &EventFilter) -> bool {
+        if let Some(address) = filter.address {
+            if !self.contains_address(address) {
+                return false;
+            }
+        }
+
+        let Some(keys) = &filter.keys else { return true };
+
+        keys.iter()
+            .enumerate()
+            .filter(|(_, keys)| !keys.is_empty())
+            .all(|(index, keys)| keys.iter().any(|key| self.contains_key(index as u32, *key)))
+    }
+
+    fn insert(&mut self, item: &[u8]) {
+        for bit in bloom_bits(item) {
+            self.0[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    fn contains(&self, item: &[u8]) -> bool {
+        bloom_bits(item).into_iter().all(|bit| self.0[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+}
+
+impl Default for EventBloom {
+    fn default() -> Self {
+        Self([0; EVENT_BLOOM_BYTES])
+    }
+}
+
+impl Compress for EventBloom {
+    type Compressed = [u8; EVENT_BLOOM_BYTES];
+    fn compress(self) -> Self::Compressed {
+        self.0
+    }
+}
+
+impl Decompress for EventBloom {
+    fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        let bytes = <[u8; EVENT_BLOOM_BYTES]>::try_from(bytes.as_ref())
+            .map_err(|e| CodecError::Decompress(e.to_string()))?;
+        Ok(Self(bytes))
+    }
+}
+
+fn address_item(address: ContractAddress) -> Vec<u8> {
+    let mut item = vec![0];
+    item.extend_from_slice(&address.encode());
+    item
+}
+
+fn key_item(index: u32, key: Felt) -> Vec<u8> {
+    let mut item = vec![1];
+    item.extend_from_slice(&index.to_be_bytes());
+    item.extend_from_slice(&key.encode());
+    item
+}
+
+/// Returns the positions of the bits of `item` in the bloom.
+///
+/// The positions are derived from a FNV-1a hash of the item, as the blooms are persisted and must
+/// be computed the same way by every version of the node.
+fn bloom_bits(item: &[u8]) -> [usize; EVENT_BLOOM_HASHES] {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let hash = item
+        .iter()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME));
+
+    let mask = (EVENT_BLOOM_BYTES * 8 - 1) as u64;
+    std::array::from_fn(|i| ((hash >> (i * 16)) & mask) as usize)
+}
+
+/// An event key along with its position in the keys of the event.
+///
+/// Key of the table indexing the blocks by the keys of the events they emitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(test, derive(::arbitrary::Arbitrary))]
+pub struct EventKey {
+    /// The position of the key in the event keys.
+    pub index: u32,
+    /// The key value.
+    pub key: Felt,
+}
+
+impl Encode for EventKey {
+    type Encoded = [u8; 36];
+    fn encode(self) -> Self::Encoded {
+        let mut buf = [0u8; 36];
+        buf[0..4].copy_from_slice(&self.index.to_be_bytes());
+        buf[4..36].copy_from_slice(&self.key.encode());
+        buf
+    }
+}
+
+impl Decode for EventKey {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        let bytes = bytes.as_ref();
+        if bytes.len() != 36 {
+            return Err(CodecError::Decode(format!("invalid event key length {}", bytes.len())));
+        }
+
+        let mut index = [0u8; 4];
+        index.copy_from_slice(&bytes[0..4]);
+        let key = Felt::decode(&bytes[4..36])?;
+        Ok(Self { index: u32::from_be_bytes(index), key })
+    }
+}
+
+/// An entry of the event index tables: a block that emitted events with the entry's key.
+///
+/// The block number is the subkey of the dupsort tables, so it's stored as big-endian for the
+/// entries of a key to be sorted by block number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(test, derive(::arbitrary::Arbitrary))]
+pub struct EventBlock(pub BlockNumber);
+
+impl Compress for EventBlock {
+    type Compressed = [u8; 8];
+    fn compress(self) -> Self::Compressed {
+        self.0.encode()
+    }
+}
+
+impl Decompress for EventBlock {
+    fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        Ok(Self(BlockNumber::decode(bytes)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::address;
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::event::EventFilter;
+    use katana_primitives::receipt::Event;
+    use starknet::macros::felt;
+
+    use super::EventBloom;
+
+    #[test]
+    fn bloom_matches_added_events() {
+        let mut bloom = EventBloom::default();
+        bloom.accrue(&Event {
+            from_address: address!("0x1337"),
+            keys: vec![felt!("0x1"), felt!("0x2")],
+            data: Vec::new(),
+        });
+
+        let filter = |address: Option<ContractAddress>, keys| EventFilter { address, keys };
+
+        assert!(bloom.may_match(&filter(None, None)));
+        assert!(bloom.may_match(&filter(Some(address!("0x1337")), None)));
+        assert!(bloom.may_match(&filter(None, Some(vec![vec![felt!("0x1")], vec![]]))));
+        assert!(
+            bloom.may_match(&filter(None, Some(vec![vec![], vec![felt!("0x3"), felt!("0x2")]])))
+        );
+        assert!(bloom.may_match(&filter(
+            Some(address!("0x1337")),
+            Some(vec![vec![felt!("0x1")], vec![felt!("0x2")]])
+        )));
+
+        assert!(!bloom.may_match(&filter(Some(address!("0x1338")), None)));
+        // the key has been added, but at a different position
+        assert!(!bloom.may_match(&filter(None, Some(vec![vec![felt!("0x2")]]))));
+        assert!(!bloom.may_match(&filter(
+            Some(address!("0x1337")),
+            Some(vec![vec![felt!("0x1")], vec![felt!("0x3")]])
+        )));
+    }
+}
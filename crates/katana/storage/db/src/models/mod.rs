@@ -1,5 +1,7 @@
 pub mod block;
 pub mod class;
 pub mod contract;
+pub mod event;
 pub mod list;
 pub mod storage;
+pub mod trie;
@@ -0,0 +1,18 @@
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+
+/// The roots of the global state tries at a particular block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrieRoots {
+    /// The root of the classes trie.
+    pub classes: Felt,
+    /// The root of the contracts trie.
+    pub contracts: Felt,
+}
+
+impl TrieRoots {
+    /// Returns the global state root derived from the trie roots.
+    pub fn state_root(&self) -> Felt {
+        katana_trie::state_root(self.contracts, self.classes)
+    }
+}
@@ -1,15 +1,21 @@
 use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use katana_primitives::contract::{ContractAddress, GenericContractInfo, StorageKey};
+use katana_primitives::da::StateDiffBlob;
+use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash, L1TxHash};
 use katana_primitives::receipt::Receipt;
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{Tx, TxHash, TxNumber};
+use katana_primitives::Felt;
+use katana_trie::Node as TrieNode;
 
 use crate::codecs::{Compress, Decode, Decompress, Encode};
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::{ContractClassChange, ContractInfoChangeList, ContractNonceChange};
+use crate::models::event::{EventBlock, EventBloom, EventKey};
 use crate::models::list::BlockList;
 use crate::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
+use crate::models::trie::TrieRoots;
 
 pub trait Key: Encode + Decode + Clone + std::fmt::Debug {}
 pub trait Value: Compress + Decompress + std::fmt::Debug {}
@@ -35,6 +41,16 @@ pub trait DupSort: Table {
     type SubKey: Key;
 }
 
+/// A visitor for running generic code against a table that is only known at runtime.
+///
+/// See [`Tables::visit`].
+pub trait TableVisitor {
+    /// The value returned by the visitor.
+    type Output;
+    /// Visits the table `T`.
+    fn visit<T: Table>(&mut self) -> Self::Output;
+}
+
 /// Enum for the types of tables present in libmdbx.
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TableType {
@@ -44,7 +60,7 @@ pub enum TableType {
     DupSort,
 }
 
-pub const NUM_TABLES: usize = 23;
+pub const NUM_TABLES: usize = 35;
 
 /// Macro to declare `libmdbx` tables.
 #[macro_export]
@@ -79,6 +95,15 @@ macro_rules! define_tables_enum {
                     },)*
                 }
             }
+
+            /// Calls [`TableVisitor::visit`] with the concrete type of the given table.
+            pub fn visit<V: TableVisitor>(&self, visitor: &mut V) -> V::Output {
+                match self {
+                    $(Tables::$table => {
+                        visitor.visit::<$table>()
+                    },)*
+                }
+            }
         }
 
         impl std::fmt::Display for Tables {
@@ -167,7 +192,19 @@ define_tables_enum! {[
     (NonceChangeHistory, TableType::DupSort),
     (ClassChangeHistory, TableType::DupSort),
     (StorageChangeHistory, TableType::DupSort),
-    (StorageChangeSet, TableType::Table)
+    (StorageChangeSet, TableType::Table),
+    (ClassesTrie, TableType::Table),
+    (ContractsTrie, TableType::Table),
+    (StoragesTrie, TableType::Table),
+    (ContractStorageRoots, TableType::Table),
+    (BlockTrieRoots, TableType::Table),
+    (StageCheckpoints, TableType::Table),
+    (EventBlooms, TableType::Table),
+    (ContractEventBlocks, TableType::DupSort),
+    (KeyEventBlocks, TableType::DupSort),
+    (L1ToL2Messages, TableType::Table),
+    (L1TxMessages, TableType::DupSort),
+    (StateDiffBlobs, TableType::Table)
 ]}
 
 tables! {
@@ -223,7 +260,37 @@ tables! {
     /// storage change set
     StorageChangeSet: (ContractStorageKey) => BlockList,
     /// Account storage change set
-    StorageChangeHistory: (BlockNumber, ContractStorageKey) => ContractStorageEntry
+    StorageChangeHistory: (BlockNumber, ContractStorageKey) => ContractStorageEntry,
+
+    /// Nodes of the classes trie according to their hashes.
+    ClassesTrie: (Felt) => TrieNode,
+    /// Nodes of the contracts trie according to their hashes.
+    ContractsTrie: (Felt) => TrieNode,
+    /// Nodes of the contracts storage tries according to their hashes.
+    StoragesTrie: (Felt) => TrieNode,
+    /// Stores the latest root of a contract's storage trie.
+    ContractStorageRoots: (ContractAddress) => Felt,
+    /// Stores the roots of the classes and contracts tries at every block.
+    BlockTrieRoots: (BlockNumber) => TrieRoots,
+
+    /// Stores the last block processed by each stage of the sync pipeline, and by the messaging
+    /// service, according to their id.
+    StageCheckpoints: (String) => BlockNumber,
+
+    /// Stores the bloom filter of the emitters and keys of the events emitted in a block.
+    EventBlooms: (BlockNumber) => EventBloom,
+    /// Stores the list of blocks that emitted events according to the address of the emitter.
+    ContractEventBlocks: (ContractAddress, BlockNumber) => EventBlock,
+    /// Stores the list of blocks that emitted events according to the keys of the events.
+    KeyEventBlocks: (EventKey, BlockNumber) => EventBlock,
+
+    /// Stores the L1 to L2 messages gathered from the settlement chain according to their hashes.
+    L1ToL2Messages: (L1ToL2MessageHash) => L1ToL2Message,
+    /// Stores the hashes of the L1 to L2 messages sent by a settlement chain transaction.
+    L1TxMessages: (L1TxHash, L1ToL2MessageHash) => L1ToL2MessageHash,
+
+    /// Stores the EIP-4844 blobs of the state diff of a block, if they have been computed.
+    StateDiffBlobs: (BlockNumber) => Vec<StateDiffBlob>
 
 }
 
@@ -258,6 +325,18 @@ mod tests {
         assert_eq!(Tables::ALL[20].name(), ClassChangeHistory::NAME);
         assert_eq!(Tables::ALL[21].name(), StorageChangeHistory::NAME);
         assert_eq!(Tables::ALL[22].name(), StorageChangeSet::NAME);
+        assert_eq!(Tables::ALL[23].name(), ClassesTrie::NAME);
+        assert_eq!(Tables::ALL[24].name(), ContractsTrie::NAME);
+        assert_eq!(Tables::ALL[25].name(), StoragesTrie::NAME);
+        assert_eq!(Tables::ALL[26].name(), ContractStorageRoots::NAME);
+        assert_eq!(Tables::ALL[27].name(), BlockTrieRoots::NAME);
+        assert_eq!(Tables::ALL[28].name(), StageCheckpoints::NAME);
+        assert_eq!(Tables::ALL[29].name(), EventBlooms::NAME);
+        assert_eq!(Tables::ALL[30].name(), ContractEventBlocks::NAME);
+        assert_eq!(Tables::ALL[31].name(), KeyEventBlocks::NAME);
+        assert_eq!(Tables::ALL[32].name(), L1ToL2Messages::NAME);
+        assert_eq!(Tables::ALL[33].name(), L1TxMessages::NAME);
+        assert_eq!(Tables::ALL[34].name(), StateDiffBlobs::NAME);
 
         assert_eq!(Tables::Headers.table_type(), TableType::Table);
         assert_eq!(Tables::BlockHashes.table_type(), TableType::Table);
@@ -282,16 +361,32 @@ mod tests {
         assert_eq!(Tables::ClassChangeHistory.table_type(), TableType::DupSort);
         assert_eq!(Tables::StorageChangeHistory.table_type(), TableType::DupSort);
         assert_eq!(Tables::StorageChangeSet.table_type(), TableType::Table);
+        assert_eq!(Tables::ClassesTrie.table_type(), TableType::Table);
+        assert_eq!(Tables::ContractsTrie.table_type(), TableType::Table);
+        assert_eq!(Tables::StoragesTrie.table_type(), TableType::Table);
+        assert_eq!(Tables::ContractStorageRoots.table_type(), TableType::Table);
+        assert_eq!(Tables::BlockTrieRoots.table_type(), TableType::Table);
+        assert_eq!(Tables::StageCheckpoints.table_type(), TableType::Table);
+        assert_eq!(Tables::EventBlooms.table_type(), TableType::Table);
+        assert_eq!(Tables::ContractEventBlocks.table_type(), TableType::DupSort);
+        assert_eq!(Tables::KeyEventBlocks.table_type(), TableType::DupSort);
+        assert_eq!(Tables::L1ToL2Messages.table_type(), TableType::Table);
+        assert_eq!(Tables::L1TxMessages.table_type(), TableType::DupSort);
+        assert_eq!(Tables::StateDiffBlobs.table_type(), TableType::Table);
     }
 
+    use alloy_primitives::B256;
     use katana_primitives::address;
     use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
     use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash};
     use katana_primitives::contract::{ContractAddress, GenericContractInfo};
+    use katana_primitives::da::{KzgBytes, StateDiffBlob};
     use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash, MessageCancellation};
     use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
     use katana_primitives::trace::TxExecInfo;
     use katana_primitives::transaction::{InvokeTx, Tx, TxHash, TxNumber};
+    use katana_trie::Node as TrieNode;
     use starknet::macros::felt;
 
     use crate::codecs::{Compress, Decode, Decompress, Encode};
@@ -299,8 +394,10 @@ mod tests {
     use crate::models::contract::{
         ContractClassChange, ContractInfoChangeList, ContractNonceChange,
     };
+    use crate::models::event::{EventBlock, EventBloom, EventKey};
     use crate::models::list::BlockList;
     use crate::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
+    use crate::models::trie::TrieRoots;
 
     macro_rules! assert_key_encode_decode {
 	    { $( ($name:ty, $key:expr) ),* } => {
@@ -339,7 +436,10 @@ mod tests {
             (TxNumber, 100),
             (ClassHash, felt!("0x123456789")),
             (ContractAddress, address!("0x123456789")),
-            (ContractStorageKey, ContractStorageKey { contract_address : address!("0x123456789"), key : felt!("0x123456789")})
+            (ContractStorageKey, ContractStorageKey { contract_address : address!("0x123456789"), key : felt!("0x123456789")}),
+            (String, String::from("Headers")),
+            (EventKey, EventKey { index: 1, key: felt!("0x123456789") }),
+            (L1ToL2MessageHash, L1ToL2MessageHash::repeat_byte(0x12))
         }
     }
 
@@ -367,6 +467,25 @@ mod tests {
             (ContractClassChange, ContractClassChange::default()),
             (BlockList, BlockList::default()),
             (ContractStorageEntry, ContractStorageEntry::default()),
+            (TrieNode, TrieNode::Binary { left: felt!("0x1"), right: felt!("0x2") }),
+            (TrieNode, TrieNode::Edge { child: felt!("0x1"), path: Default::default() }),
+            (TrieRoots, TrieRoots { classes: felt!("0x1"), contracts: felt!("0x2") }),
+            (EventBloom, EventBloom::default()),
+            (EventBlock, EventBlock(100)),
+            (L1ToL2MessageHash, L1ToL2MessageHash::repeat_byte(0x12)),
+            (L1ToL2Message, L1ToL2Message {
+                        l1_tx_hash: L1ToL2MessageHash::repeat_byte(0x1),
+                        message_hash: L1ToL2MessageHash::repeat_byte(0x2),
+                        tx_hash: felt!("0x123456789"),
+                        cancellation: Some(MessageCancellation::Started),
+                        dropped: false,
+                    }),
+            (Vec<StateDiffBlob>, vec![StateDiffBlob {
+                        data: vec![0x1; 64].into(),
+                        commitment: KzgBytes::repeat_byte(0x2),
+                        proof: KzgBytes::repeat_byte(0x3),
+                        versioned_hash: B256::repeat_byte(0x4),
+                    }]),
             (Receipt, Receipt::Invoke(InvokeTxReceipt {
                         revert_error: None,
                         events: Vec::new(),
@@ -0,0 +1,46 @@
+//! Adapters for storing the nodes of the state tries in the database.
+
+use std::marker::PhantomData;
+
+use katana_primitives::Felt;
+use katana_trie::{Node, TrieStorage, TrieStorageMut};
+
+use crate::abstraction::{DbTx, DbTxMut};
+use crate::tables::Table;
+
+/// A [`TrieStorage`] backed by a database table.
+#[derive(Debug)]
+pub struct TrieDb<'a, Tb, Tx> {
+    tx: &'a Tx,
+    _table: PhantomData<Tb>,
+}
+
+impl<'a, Tb, Tx> TrieDb<'a, Tb, Tx>
+where
+    Tb: Table<Key = Felt, Value = Node>,
+    Tx: DbTx,
+{
+    pub fn new(tx: &'a Tx) -> Self {
+        Self { tx, _table: PhantomData }
+    }
+}
+
+impl<Tb, Tx> TrieStorage for TrieDb<'_, Tb, Tx>
+where
+    Tb: Table<Key = Felt, Value = Node>,
+    Tx: DbTx,
+{
+    fn get(&self, hash: &Felt) -> katana_trie::Result<Option<Node>> {
+        self.tx.get::<Tb>(*hash).map_err(|e| katana_trie::Error::Storage(Box::new(e)))
+    }
+}
+
+impl<Tb, Tx> TrieStorageMut for TrieDb<'_, Tb, Tx>
+where
+    Tb: Table<Key = Felt, Value = Node>,
+    Tx: DbTxMut,
+{
+    fn insert(&mut self, hash: Felt, node: Node) -> katana_trie::Result<()> {
+        self.tx.put::<Tb>(hash, node).map_err(|e| katana_trie::Error::Storage(Box::new(e)))
+    }
+}
@@ -5,7 +5,7 @@ use std::mem;
 use std::path::{Path, PathBuf};
 
 /// Current version of the database.
-pub const CURRENT_DB_VERSION: u32 = 3;
+pub const CURRENT_DB_VERSION: u32 = 9;
 
 /// Name of the version file.
 const DB_VERSION_FILE_NAME: &str = "db.version";
@@ -59,7 +59,7 @@ pub(super) fn check_db_version(path: impl AsRef<Path>) -> Result<(), DatabaseVer
 }
 
 /// Get the version of the database at the given `path`.
-pub(super) fn get_db_version(path: impl AsRef<Path>) -> Result<u32, DatabaseVersionError> {
+pub fn get_db_version(path: impl AsRef<Path>) -> Result<u32, DatabaseVersionError> {
     let path = path.as_ref();
     let path = if path.is_dir() { default_version_file_path(path) } else { path.to_path_buf() };
 
@@ -81,6 +81,6 @@ mod tests {
     #[test]
     fn test_current_version() {
         use super::CURRENT_DB_VERSION;
-        assert_eq!(CURRENT_DB_VERSION, 3, "Invalid current database version")
+        assert_eq!(CURRENT_DB_VERSION, 9, "Invalid current database version")
     }
 }
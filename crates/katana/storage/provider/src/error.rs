@@ -61,6 +61,10 @@ pub enum ProviderError {
     #[error("Missing compiled class hash for class hash {0:#x}")]
     MissingCompiledClassHash(ClassHash),
 
+    /// Error when a compiled class is not found but the class has been declared.
+    #[error("Missing compiled class for class hash {0:#x}")]
+    MissingCompiledClass(ClassHash),
+
     /// Error when a contract class change entry is not found but the block number of when the
     /// change happen exists in the class change list.
     #[error("Missing contract class change entry")]
@@ -96,10 +100,25 @@ pub enum ProviderError {
         storage_key: StorageKey,
     },
 
+    /// Error when the roots of the state tries are not found but the block exists.
+    #[error("Missing trie roots for block number {0}")]
+    MissingTrieRoots(BlockNumber),
+
+    /// Error when the state of a block is needed but its history has been pruned.
+    #[error(
+        "The history of block {block} has been pruned, only the blocks from {horizon} are \
+         available"
+    )]
+    PrunedBlock { block: BlockNumber, horizon: BlockNumber },
+
     /// Error returned by the database implementation.
     #[error(transparent)]
     Database(#[from] DatabaseError),
 
+    /// Error returned when reading or updating the state tries.
+    #[error(transparent)]
+    Trie(#[from] katana_trie::Error),
+
     /// Error returned by a [ForkedBackend](crate::providers::fork::backend::ForkedBackend) used by
     /// [ForkedProvider](crate::providers::fork::ForkedProvider).
     #[cfg(feature = "fork")]
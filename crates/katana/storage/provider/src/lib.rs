@@ -1,23 +1,35 @@
 use std::ops::{Range, RangeInclusive};
 
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::trie::TrieRoots;
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
     SealedBlockWithStatus,
 };
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
+use katana_primitives::da::StateDiffBlob;
 use katana_primitives::env::BlockEnv;
+use katana_primitives::event::EventFilter;
+use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash, L1TxHash, MessageCancellation};
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
 use katana_primitives::Felt;
-use traits::block::{BlockIdReader, BlockStatusProvider, BlockWriter};
+use katana_trie::MultiProof;
+use traits::block::{
+    BlockIdReader, BlockPartsWriter, BlockStatusProvider, BlockUnwinder, BlockWriter,
+};
 use traits::contract::{ContractClassProvider, ContractClassWriter};
+use traits::da::{StateDiffBlobProvider, StateDiffBlobWriter};
 use traits::env::BlockEnvProvider;
-use traits::state::{StateRootProvider, StateWriter};
+use traits::event::EventIndexProvider;
+use traits::message::{L1ToL2MessageProvider, L1ToL2MessageWriter};
+use traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use traits::state::{StateDumpProvider, StateRootProvider, StateWriter};
 use traits::transaction::{TransactionStatusProvider, TransactionTraceProvider};
+use traits::trie::{StateProof, StateProofProvider, TrieWriter};
 
 pub mod error;
 pub mod providers;
@@ -135,6 +147,149 @@ where
     ) -> ProviderResult<()> {
         self.provider.insert_block_with_states_and_receipts(block, states, receipts, executions)
     }
+
+    fn insert_block_with_trie_updates(
+        &self,
+        block: Block,
+        status: FinalityStatus,
+        states: StateUpdatesWithDeclaredClasses,
+        receipts: Vec<Receipt>,
+        executions: Vec<TxExecInfo>,
+        blobs: Option<Vec<StateDiffBlob>>,
+    ) -> ProviderResult<BlockHash> {
+        self.provider
+            .insert_block_with_trie_updates(block, status, states, receipts, executions, blobs)
+    }
+}
+
+impl<Db> BlockPartsWriter for BlockchainProvider<Db>
+where
+    Db: BlockPartsWriter,
+{
+    fn insert_header(
+        &self,
+        hash: BlockHash,
+        header: Header,
+        status: FinalityStatus,
+    ) -> ProviderResult<()> {
+        self.provider.insert_header(hash, header, status)
+    }
+
+    fn insert_body(
+        &self,
+        block_number: BlockNumber,
+        transactions: Vec<TxWithHash>,
+        receipts: Vec<Receipt>,
+    ) -> ProviderResult<()> {
+        self.provider.insert_body(block_number, transactions, receipts)
+    }
+
+    fn insert_state_updates(
+        &self,
+        block_number: BlockNumber,
+        state_updates: StateUpdates,
+    ) -> ProviderResult<()> {
+        self.provider.insert_state_updates(block_number, state_updates)
+    }
+}
+
+impl<Db> StageCheckpointProvider for BlockchainProvider<Db>
+where
+    Db: StageCheckpointProvider,
+{
+    fn checkpoint(&self, id: &str) -> ProviderResult<Option<BlockNumber>> {
+        self.provider.checkpoint(id)
+    }
+}
+
+impl<Db> StageCheckpointWriter for BlockchainProvider<Db>
+where
+    Db: StageCheckpointWriter,
+{
+    fn set_checkpoint(&self, id: &str, block_number: BlockNumber) -> ProviderResult<()> {
+        self.provider.set_checkpoint(id, block_number)
+    }
+}
+
+impl<Db> L1ToL2MessageProvider for BlockchainProvider<Db>
+where
+    Db: L1ToL2MessageProvider,
+{
+    fn message(&self, hash: L1ToL2MessageHash) -> ProviderResult<Option<L1ToL2Message>> {
+        self.provider.message(hash)
+    }
+
+    fn messages_by_l1_tx(&self, hash: L1TxHash) -> ProviderResult<Option<Vec<L1ToL2Message>>> {
+        self.provider.messages_by_l1_tx(hash)
+    }
+}
+
+impl<Db> L1ToL2MessageWriter for BlockchainProvider<Db>
+where
+    Db: L1ToL2MessageWriter,
+{
+    fn insert_message(&self, message: L1ToL2Message) -> ProviderResult<()> {
+        self.provider.insert_message(message)
+    }
+
+    fn set_message_cancellation(
+        &self,
+        hash: L1ToL2MessageHash,
+        cancellation: MessageCancellation,
+    ) -> ProviderResult<bool> {
+        self.provider.set_message_cancellation(hash, cancellation)
+    }
+
+    fn set_message_dropped(&self, hash: L1ToL2MessageHash) -> ProviderResult<bool> {
+        self.provider.set_message_dropped(hash)
+    }
+}
+
+impl<Db> StateDiffBlobProvider for BlockchainProvider<Db>
+where
+    Db: StateDiffBlobProvider,
+{
+    fn state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<Vec<StateDiffBlob>>> {
+        self.provider.state_diff_blobs(block_number)
+    }
+}
+
+impl<Db> StateDiffBlobWriter for BlockchainProvider<Db>
+where
+    Db: StateDiffBlobWriter,
+{
+    fn insert_state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+        blobs: Vec<StateDiffBlob>,
+    ) -> ProviderResult<()> {
+        self.provider.insert_state_diff_blobs(block_number, blobs)
+    }
+}
+
+impl<Db> EventIndexProvider for BlockchainProvider<Db>
+where
+    Db: EventIndexProvider,
+{
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        filter: &EventFilter,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider.blocks_with_events(range, filter)
+    }
+}
+
+impl<Db> BlockUnwinder for BlockchainProvider<Db>
+where
+    Db: BlockUnwinder,
+{
+    fn unwind_to(&self, block_number: BlockNumber) -> ProviderResult<()> {
+        self.provider.unwind_to(block_number)
+    }
 }
 
 impl<Db> TransactionProvider for BlockchainProvider<Db>
@@ -372,6 +527,15 @@ where
     }
 }
 
+impl<Db> StateDumpProvider for BlockchainProvider<Db>
+where
+    Db: StateDumpProvider,
+{
+    fn dump_state(&self) -> ProviderResult<StateUpdatesWithDeclaredClasses> {
+        self.provider.dump_state()
+    }
+}
+
 impl<Db> BlockEnvProvider for BlockchainProvider<Db>
 where
     Db: BlockEnvProvider,
@@ -380,3 +544,62 @@ where
         self.provider.block_env_at(id)
     }
 }
+
+impl<Db> TrieWriter for BlockchainProvider<Db>
+where
+    Db: TrieWriter,
+{
+    fn trie_insert_state_updates(
+        &self,
+        block_number: BlockNumber,
+        state_updates: &StateUpdates,
+    ) -> ProviderResult<Felt> {
+        self.provider.trie_insert_state_updates(block_number, state_updates)
+    }
+}
+
+impl<Db> StateProofProvider for BlockchainProvider<Db>
+where
+    Db: StateProofProvider,
+{
+    fn trie_roots(&self, block_number: BlockNumber) -> ProviderResult<Option<TrieRoots>> {
+        self.provider.trie_roots(block_number)
+    }
+
+    fn classes_proof(
+        &self,
+        block_number: BlockNumber,
+        class_hashes: &[ClassHash],
+    ) -> ProviderResult<MultiProof> {
+        self.provider.classes_proof(block_number, class_hashes)
+    }
+
+    fn contracts_proof(
+        &self,
+        block_number: BlockNumber,
+        addresses: &[ContractAddress],
+    ) -> ProviderResult<MultiProof> {
+        self.provider.contracts_proof(block_number, addresses)
+    }
+
+    fn storage_root(&self, address: ContractAddress) -> ProviderResult<Felt> {
+        self.provider.storage_root(address)
+    }
+
+    fn storage_proof(
+        &self,
+        address: ContractAddress,
+        keys: &[StorageKey],
+    ) -> ProviderResult<MultiProof> {
+        self.provider.storage_proof(address, keys)
+    }
+
+    fn latest_state_proof(
+        &self,
+        class_hashes: &[ClassHash],
+        addresses: &[ContractAddress],
+        storage_keys: &[(ContractAddress, Vec<StorageKey>)],
+    ) -> ProviderResult<Option<StateProof>> {
+        self.provider.latest_state_proof(class_hashes, addresses, storage_keys)
+    }
+}
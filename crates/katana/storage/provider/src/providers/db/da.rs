@@ -0,0 +1,83 @@
+use katana_db::abstraction::{Database, DbTx, DbTxMut};
+use katana_db::tables;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::da::StateDiffBlob;
+
+use super::DbProvider;
+use crate::traits::da::{StateDiffBlobProvider, StateDiffBlobWriter};
+use crate::ProviderResult;
+
+impl<Db: Database> StateDiffBlobProvider for DbProvider<Db> {
+    fn state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<Vec<StateDiffBlob>>> {
+        let db_tx = self.0.tx()?;
+        let blobs = db_tx.get::<tables::StateDiffBlobs>(block_number)?;
+        db_tx.commit()?;
+        Ok(blobs)
+    }
+}
+
+impl<Db: Database> StateDiffBlobWriter for DbProvider<Db> {
+    fn insert_state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+        blobs: Vec<StateDiffBlob>,
+    ) -> ProviderResult<()> {
+        self.0.update(move |db_tx| -> ProviderResult<()> {
+            db_tx.put::<tables::StateDiffBlobs>(block_number, blobs)?;
+            Ok(())
+        })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use katana_primitives::block::{Block, FinalityStatus};
+    use katana_primitives::da::{KzgBytes, StateDiffBlob};
+
+    use super::DbProvider;
+    use crate::traits::block::BlockWriter;
+    use crate::traits::da::{StateDiffBlobProvider, StateDiffBlobWriter};
+
+    fn blob() -> StateDiffBlob {
+        StateDiffBlob {
+            data: vec![0x1; 64].into(),
+            commitment: KzgBytes::repeat_byte(0x2),
+            proof: KzgBytes::repeat_byte(0x3),
+            versioned_hash: B256::repeat_byte(0x4),
+        }
+    }
+
+    #[test]
+    fn insert_and_get_state_diff_blobs() {
+        let provider = DbProvider::new_ephemeral();
+
+        provider.insert_state_diff_blobs(1, vec![blob()]).unwrap();
+
+        assert_eq!(provider.state_diff_blobs(1).unwrap(), Some(vec![blob()]));
+        assert_eq!(provider.state_diff_blobs(2).unwrap(), None);
+    }
+
+    #[test]
+    fn insert_block_with_state_diff_blobs() {
+        let provider = DbProvider::new_ephemeral();
+
+        let status = FinalityStatus::AcceptedOnL2;
+        let blobs = Some(vec![blob()]);
+        provider
+            .insert_block_with_trie_updates(
+                Block::default(),
+                status,
+                Default::default(),
+                Vec::new(),
+                Vec::new(),
+                blobs,
+            )
+            .unwrap();
+
+        assert_eq!(provider.state_diff_blobs(0).unwrap(), Some(vec![blob()]));
+    }
+}
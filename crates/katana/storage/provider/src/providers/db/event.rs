@@ -0,0 +1,236 @@
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+use katana_db::abstraction::{Database, DbDupSortCursor, DbTx, DbTxMut};
+use katana_db::models::event::{EventBlock, EventBloom, EventKey};
+use katana_db::tables::{self, DupSort};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::event::EventFilter;
+use katana_primitives::receipt::Event;
+
+use super::DbProvider;
+use crate::traits::event::EventIndexProvider;
+use crate::ProviderResult;
+
+impl<Db: Database> EventIndexProvider for DbProvider<Db> {
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        filter: &EventFilter,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let db_tx = self.0.tx()?;
+
+        // the blocks matching each of the criteria of the filter, according to the index tables
+        let mut candidates = Vec::new();
+
+        if let Some(address) = filter.address {
+            candidates
+                .push(indexed_blocks::<_, tables::ContractEventBlocks>(&db_tx, address, &range)?);
+        }
+
+        for (index, keys) in filter.keys.iter().flatten().enumerate() {
+            let mut blocks = BTreeSet::new();
+            for key in keys {
+                let key = EventKey { index: index as u32, key: *key };
+                blocks.extend(indexed_blocks::<_, tables::KeyEventBlocks>(&db_tx, key, &range)?);
+            }
+
+            // an empty list of keys matches any value
+            if !keys.is_empty() {
+                candidates.push(blocks.into_iter().collect());
+            }
+        }
+
+        // Start from the most selective criterion, and rule out the blocks that don't match the
+        // other ones using their blooms, instead of intersecting every list of blocks.
+        let Some(blocks) = candidates.into_iter().min_by_key(Vec::len) else {
+            db_tx.commit()?;
+            return Ok(range.collect());
+        };
+
+        let mut matching = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let bloom = db_tx.get::<tables::EventBlooms>(block)?;
+            if bloom.map_or(true, |bloom| bloom.may_match(filter)) {
+                matching.push(block);
+            }
+        }
+
+        db_tx.commit()?;
+        Ok(matching)
+    }
+}
+
+/// Indexes the events emitted in a block by their emitters and keys.
+pub(super) fn insert_event_index<'a, Tx: DbTxMut>(
+    db_tx: &Tx,
+    block_number: BlockNumber,
+    events: impl Iterator<Item = &'a Event>,
+) -> ProviderResult<()> {
+    let mut bloom = EventBloom::default();
+    let mut addresses = BTreeSet::new();
+    let mut keys = BTreeSet::new();
+
+    for event in events {
+        bloom.accrue(event);
+        addresses.insert(event.from_address);
+        for (index, key) in event.keys.iter().enumerate() {
+            keys.insert(EventKey { index: index as u32, key: *key });
+        }
+    }
+
+    db_tx.put::<tables::EventBlooms>(block_number, bloom)?;
+
+    for address in addresses {
+        db_tx.put::<tables::ContractEventBlocks>(address, EventBlock(block_number))?;
+    }
+
+    for key in keys {
+        db_tx.put::<tables::KeyEventBlocks>(key, EventBlock(block_number))?;
+    }
+
+    Ok(())
+}
+
+/// Removes the events emitted in a block from the index tables.
+pub(super) fn remove_event_index<'a, Tx: DbTxMut>(
+    db_tx: &Tx,
+    block_number: BlockNumber,
+    events: impl Iterator<Item = &'a Event>,
+) -> ProviderResult<()> {
+    for event in events {
+        db_tx.delete::<tables::ContractEventBlocks>(
+            event.from_address,
+            Some(EventBlock(block_number)),
+        )?;
+
+        for (index, key) in event.keys.iter().enumerate() {
+            let key = EventKey { index: index as u32, key: *key };
+            db_tx.delete::<tables::KeyEventBlocks>(key, Some(EventBlock(block_number)))?;
+        }
+    }
+
+    db_tx.delete::<tables::EventBlooms>(block_number, None)?;
+
+    Ok(())
+}
+
+/// Returns the blocks in `range` that are stored under `key` in the event index table `Tb`.
+fn indexed_blocks<Tx, Tb>(
+    db_tx: &Tx,
+    key: Tb::Key,
+    range: &RangeInclusive<BlockNumber>,
+) -> ProviderResult<Vec<BlockNumber>>
+where
+    Tx: DbTx,
+    Tb: DupSort<SubKey = BlockNumber, Value = EventBlock> + Debug,
+{
+    let mut cursor = db_tx.cursor_dup::<Tb>()?;
+
+    // check that there is an entry in the range first, as the walker isn't positioned otherwise
+    if cursor.seek_by_key_subkey(key.clone(), *range.start())?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let Some(walker) = cursor.walk_dup(Some(key), Some(*range.start()))? else {
+        return Ok(Vec::new());
+    };
+
+    let mut blocks = Vec::new();
+    for entry in walker {
+        let (_, EventBlock(block)) = entry?;
+        if block > *range.end() {
+            break;
+        }
+        blocks.push(block);
+    }
+
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::block::{Block, FinalityStatus, Header, SealedBlockWithStatus};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::event::EventFilter;
+    use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::receipt::{Event, InvokeTxReceipt, Receipt};
+    use katana_primitives::trace::TxExecInfo;
+    use katana_primitives::transaction::{InvokeTx, Tx, TxWithHash};
+    use katana_primitives::{address, Felt};
+    use starknet::macros::felt;
+
+    use super::DbProvider;
+    use crate::traits::block::{BlockUnwinder, BlockWriter};
+    use crate::traits::event::EventIndexProvider;
+
+    fn insert_block(provider: &DbProvider, number: u64, events: Vec<Event>) {
+        let header = Header { number, ..Default::default() };
+        let tx = TxWithHash {
+            hash: Felt::from(number + 100),
+            transaction: Tx::Invoke(InvokeTx::V1(Default::default())),
+        };
+        let block = Block { header, body: vec![tx] }.seal();
+        let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
+
+        let receipt = Receipt::Invoke(InvokeTxReceipt {
+            events,
+            revert_error: None,
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+            fee: TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: 0, unit: PriceUnit::Wei },
+        });
+
+        provider
+            .insert_block_with_states_and_receipts(
+                block,
+                Default::default(),
+                vec![receipt],
+                vec![TxExecInfo::default()],
+            )
+            .unwrap();
+    }
+
+    fn event(from_address: ContractAddress, keys: Vec<Felt>) -> Event {
+        Event { from_address, keys, data: Vec::new() }
+    }
+
+    #[test]
+    fn blocks_with_events() {
+        let provider = DbProvider::new_ephemeral();
+
+        let a = address!("0x1");
+        let b = address!("0x2");
+
+        insert_block(&provider, 0, vec![]);
+        insert_block(&provider, 1, vec![event(a, vec![felt!("0xa"), felt!("0x1")])]);
+        insert_block(&provider, 2, vec![event(b, vec![felt!("0xa")])]);
+        insert_block(&provider, 3, vec![event(a, vec![felt!("0xb")]), event(b, vec![])]);
+        insert_block(&provider, 4, vec![event(b, vec![felt!("0xb"), felt!("0xa")])]);
+
+        let blocks = |range, address, keys| {
+            provider.blocks_with_events(range, &EventFilter { address, keys }).unwrap()
+        };
+
+        // without any criteria, every block is returned
+        assert_eq!(blocks(0..=4, None, None), vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(blocks(0..=4, Some(a), None), vec![1, 3]);
+        assert_eq!(blocks(2..=4, Some(b), None), vec![2, 3, 4]);
+        assert_eq!(blocks(0..=4, None, Some(vec![vec![felt!("0xa")]])), vec![1, 2]);
+        assert_eq!(blocks(0..=4, None, Some(vec![vec![], vec![felt!("0xa")]])), vec![4]);
+        assert_eq!(
+            blocks(0..=4, None, Some(vec![vec![felt!("0xa"), felt!("0xb")]])),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(blocks(0..=4, Some(b), Some(vec![vec![felt!("0xa")]])), vec![2]);
+        assert_eq!(blocks(0..=1, Some(b), Some(vec![vec![felt!("0xa")]])), Vec::<u64>::new());
+        assert_eq!(blocks(0..=4, Some(address!("0x3")), None), Vec::<u64>::new());
+
+        // the events of the unwound blocks are removed from the index
+        provider.unwind_to(2).unwrap();
+        assert_eq!(blocks(0..=4, Some(b), None), vec![2]);
+        assert_eq!(blocks(0..=4, None, Some(vec![vec![felt!("0xb")]])), Vec::<u64>::new());
+    }
+}
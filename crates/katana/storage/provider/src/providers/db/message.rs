@@ -0,0 +1,119 @@
+use katana_db::abstraction::{Database, DbDupSortCursor, DbTx, DbTxMut};
+use katana_db::tables;
+use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash, L1TxHash, MessageCancellation};
+
+use super::DbProvider;
+use crate::traits::message::{L1ToL2MessageProvider, L1ToL2MessageWriter};
+use crate::ProviderResult;
+
+impl<Db: Database> L1ToL2MessageProvider for DbProvider<Db> {
+    fn message(&self, hash: L1ToL2MessageHash) -> ProviderResult<Option<L1ToL2Message>> {
+        let db_tx = self.0.tx()?;
+        let message = db_tx.get::<tables::L1ToL2Messages>(hash)?;
+        db_tx.commit()?;
+        Ok(message)
+    }
+
+    fn messages_by_l1_tx(&self, hash: L1TxHash) -> ProviderResult<Option<Vec<L1ToL2Message>>> {
+        let db_tx = self.0.tx()?;
+
+        let mut cursor = db_tx.cursor_dup::<tables::L1TxMessages>()?;
+        let Some(walker) = cursor.walk_dup(Some(hash), None)? else {
+            db_tx.commit()?;
+            return Ok(None);
+        };
+
+        let mut messages = Vec::new();
+        for entry in walker {
+            let (_, message_hash) = entry?;
+            if let Some(message) = db_tx.get::<tables::L1ToL2Messages>(message_hash)? {
+                messages.push(message);
+            }
+        }
+
+        db_tx.commit()?;
+        Ok(if messages.is_empty() { None } else { Some(messages) })
+    }
+}
+
+impl<Db: Database> L1ToL2MessageWriter for DbProvider<Db> {
+    fn insert_message(&self, message: L1ToL2Message) -> ProviderResult<()> {
+        self.0.update(move |db_tx| -> ProviderResult<()> {
+            db_tx.put::<tables::L1TxMessages>(message.l1_tx_hash, message.message_hash)?;
+            db_tx.put::<tables::L1ToL2Messages>(message.message_hash, message)?;
+            Ok(())
+        })?
+    }
+
+    fn set_message_cancellation(
+        &self,
+        hash: L1ToL2MessageHash,
+        cancellation: MessageCancellation,
+    ) -> ProviderResult<bool> {
+        self.0.update(move |db_tx| -> ProviderResult<bool> {
+            let Some(mut message) = db_tx.get::<tables::L1ToL2Messages>(hash)? else {
+                return Ok(false);
+            };
+
+            message.cancellation = Some(cancellation);
+            db_tx.put::<tables::L1ToL2Messages>(hash, message)?;
+            Ok(true)
+        })?
+    }
+
+    fn set_message_dropped(&self, hash: L1ToL2MessageHash) -> ProviderResult<bool> {
+        self.0.update(move |db_tx| -> ProviderResult<bool> {
+            let Some(mut message) = db_tx.get::<tables::L1ToL2Messages>(hash)? else {
+                return Ok(false);
+            };
+
+            message.dropped = true;
+            db_tx.put::<tables::L1ToL2Messages>(hash, message)?;
+            Ok(true)
+        })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::message::{L1ToL2Message, L1TxHash, MessageCancellation};
+    use starknet::macros::felt;
+
+    use super::DbProvider;
+    use crate::traits::message::{L1ToL2MessageProvider, L1ToL2MessageWriter};
+
+    #[test]
+    fn messages_of_l1_tx() {
+        let provider = DbProvider::new_ephemeral();
+
+        let l1_tx_hash = L1TxHash::repeat_byte(0x1);
+        let message = |byte, tx_hash| L1ToL2Message {
+            l1_tx_hash,
+            message_hash: L1TxHash::repeat_byte(byte),
+            tx_hash,
+            cancellation: None,
+            dropped: false,
+        };
+
+        provider.insert_message(message(0x2, felt!("0x2"))).unwrap();
+        provider.insert_message(message(0x3, felt!("0x3"))).unwrap();
+
+        let messages = provider.messages_by_l1_tx(l1_tx_hash).unwrap().unwrap();
+        assert_eq!(messages, vec![message(0x2, felt!("0x2")), message(0x3, felt!("0x3"))]);
+        assert_eq!(provider.messages_by_l1_tx(L1TxHash::repeat_byte(0x4)).unwrap(), None);
+
+        let hash = L1TxHash::repeat_byte(0x2);
+        assert!(provider.set_message_cancellation(hash, MessageCancellation::Cancelled).unwrap());
+        let cancelled = provider.message(hash).unwrap().unwrap();
+        assert_eq!(cancelled.cancellation, Some(MessageCancellation::Cancelled));
+
+        let hash = L1TxHash::repeat_byte(0x3);
+        assert!(provider.set_message_dropped(hash).unwrap());
+        assert!(provider.message(hash).unwrap().unwrap().dropped);
+
+        // unknown messages can't be cancelled
+        let unknown = L1TxHash::repeat_byte(0x5);
+        assert!(!provider.set_message_cancellation(unknown, MessageCancellation::Started).unwrap());
+        assert!(!provider.set_message_dropped(unknown).unwrap());
+    }
+}
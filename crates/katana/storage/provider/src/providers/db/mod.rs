@@ -1,4 +1,9 @@
+mod da;
+mod event;
+mod message;
 pub mod state;
+mod trie;
+mod unwind;
 
 use std::collections::BTreeMap;
 use std::fmt::Debug;
@@ -24,6 +29,7 @@ use katana_primitives::class::{ClassHash, CompiledClassHash};
 use katana_primitives::contract::{
     ContractAddress, GenericContractInfo, Nonce, StorageKey, StorageValue,
 };
+use katana_primitives::da::StateDiffBlob;
 use katana_primitives::env::BlockEnv;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
@@ -33,10 +39,11 @@ use katana_primitives::Felt;
 
 use crate::error::ProviderError;
 use crate::traits::block::{
-    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
-    HeaderProvider,
+    BlockHashProvider, BlockNumberProvider, BlockPartsWriter, BlockProvider, BlockStatusProvider,
+    BlockWriter, HeaderProvider,
 };
 use crate::traits::env::BlockEnvProvider;
+use crate::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
 use crate::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider};
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
@@ -45,6 +52,10 @@ use crate::traits::transaction::{
 };
 use crate::ProviderResult;
 
+/// The id of the checkpoint storing the prune horizon, under which the history of the blocks has
+/// been removed by `katana db prune`.
+pub const PRUNE_CHECKPOINT: &str = "prune";
+
 /// A provider implementation that uses a persistent database as the backend.
 // TODO: remove the default generic type
 #[derive(Debug)]
@@ -90,7 +101,10 @@ impl<Db: Database> StateFactoryProvider for DbProvider<Db> {
 
         let Some(num) = block_number else { return Ok(None) };
 
-        Ok(Some(Box::new(self::state::HistoricalStateProvider::new(self.0.tx()?, num))))
+        let db_tx = self.0.tx()?;
+        check_prune_horizon(&db_tx, num)?;
+
+        Ok(Some(Box::new(self::state::HistoricalStateProvider::new(db_tx, num))))
     }
 }
 
@@ -614,164 +628,304 @@ impl<Db: Database> BlockWriter for DbProvider<Db> {
         receipts: Vec<Receipt>,
         executions: Vec<TxExecInfo>,
     ) -> ProviderResult<()> {
-        self.0.update(move |db_tx| -> ProviderResult<()> {
+        self.0.update(move |db_tx| insert_block(db_tx, block, states, receipts, executions))?
+    }
+
+    fn insert_block_with_trie_updates(
+        &self,
+        mut block: Block,
+        status: FinalityStatus,
+        states: StateUpdatesWithDeclaredClasses,
+        receipts: Vec<Receipt>,
+        executions: Vec<TxExecInfo>,
+        blobs: Option<Vec<StateDiffBlob>>,
+    ) -> ProviderResult<BlockHash> {
+        self.0.update(move |db_tx| -> ProviderResult<BlockHash> {
+            let block_number = block.header.number;
+
+            // legacy classes don't have a Sierra class and are not part of the classes trie
+            let classes = states
+                .state_updates
+                .declared_classes
+                .iter()
+                .filter(|(hash, _)| states.declared_sierra_classes.contains_key(*hash))
+                .map(|(hash, compiled_hash)| (*hash, *compiled_hash));
+
+            block.header.state_root =
+                trie::insert_trie_updates(db_tx, block_number, &states.state_updates, classes)?;
+
+            let block = SealedBlockWithStatus { block: block.seal(), status };
             let block_hash = block.block.hash;
-            let block_number = block.block.header.number;
-
-            let block_header = block.block.header;
-            let transactions = block.block.body;
-
-            let tx_count = transactions.len() as u64;
-            let tx_offset = db_tx.entries::<tables::Transactions>()? as u64;
-            let block_body_indices = StoredBlockBodyIndices { tx_offset, tx_count };
-
-            db_tx.put::<tables::BlockHashes>(block_number, block_hash)?;
-            db_tx.put::<tables::BlockNumbers>(block_hash, block_number)?;
-            db_tx.put::<tables::BlockStatusses>(block_number, block.status)?;
-
-            db_tx.put::<tables::Headers>(block_number, block_header)?;
-            db_tx.put::<tables::BlockBodyIndices>(block_number, block_body_indices)?;
-
-            for (i, (transaction, receipt, execution)) in transactions
-                .into_iter()
-                .zip(receipts.into_iter())
-                .zip(executions.into_iter())
-                .map(|((transaction, receipt), execution)| (transaction, receipt, execution))
-                .enumerate()
-            {
-                let tx_number = tx_offset + i as u64;
-                let tx_hash = transaction.hash;
-
-                db_tx.put::<tables::TxHashes>(tx_number, tx_hash)?;
-                db_tx.put::<tables::TxNumbers>(tx_hash, tx_number)?;
-                db_tx.put::<tables::TxBlocks>(tx_number, block_number)?;
-                db_tx.put::<tables::Transactions>(tx_number, transaction.transaction)?;
-                db_tx.put::<tables::Receipts>(tx_number, receipt)?;
-                db_tx.put::<tables::TxTraces>(tx_number, execution)?;
+
+            insert_block(db_tx, block, states, receipts, executions)?;
+
+            if let Some(blobs) = blobs {
+                db_tx.put::<tables::StateDiffBlobs>(block_number, blobs)?;
             }
 
-            // insert classes
+            Ok(block_hash)
+        })?
+    }
+}
 
-            for (class_hash, compiled_hash) in states.state_updates.declared_classes {
-                db_tx.put::<tables::CompiledClassHashes>(class_hash, compiled_hash)?;
+impl<Db: Database> BlockPartsWriter for DbProvider<Db> {
+    fn insert_header(
+        &self,
+        hash: BlockHash,
+        header: Header,
+        status: FinalityStatus,
+    ) -> ProviderResult<()> {
+        self.0.update(move |db_tx| insert_header(db_tx, hash, header, status))?
+    }
 
-                db_tx.put::<tables::ClassDeclarationBlock>(class_hash, block_number)?;
-                db_tx.put::<tables::ClassDeclarations>(block_number, class_hash)?
-            }
+    fn insert_body(
+        &self,
+        block_number: BlockNumber,
+        transactions: Vec<TxWithHash>,
+        receipts: Vec<Receipt>,
+    ) -> ProviderResult<()> {
+        self.0.update(move |db_tx| -> ProviderResult<()> {
+            insert_body(db_tx, block_number, transactions, receipts)?;
+            Ok(())
+        })?
+    }
 
-            for (hash, compiled_class) in states.declared_compiled_classes {
-                db_tx.put::<tables::CompiledClasses>(hash, compiled_class)?;
-            }
+    fn insert_state_updates(
+        &self,
+        block_number: BlockNumber,
+        state_updates: StateUpdates,
+    ) -> ProviderResult<()> {
+        self.0.update(move |db_tx| insert_state_updates(db_tx, block_number, state_updates))?
+    }
+}
 
-            for (class_hash, sierra_class) in states.declared_sierra_classes {
-                db_tx.put::<tables::SierraClasses>(class_hash, sierra_class)?;
-            }
+impl<Db: Database> StageCheckpointProvider for DbProvider<Db> {
+    fn checkpoint(&self, id: &str) -> ProviderResult<Option<BlockNumber>> {
+        let db_tx = self.0.tx()?;
+        let checkpoint = db_tx.get::<tables::StageCheckpoints>(id.to_string())?;
+        db_tx.commit()?;
+        Ok(checkpoint)
+    }
+}
 
-            // insert storage changes
-            {
-                let mut storage_cursor = db_tx.cursor_dup_mut::<tables::ContractStorage>()?;
-                for (addr, entries) in states.state_updates.storage_updates {
-                    let entries =
-                        entries.into_iter().map(|(key, value)| StorageEntry { key, value });
-
-                    for entry in entries {
-                        match storage_cursor.seek_by_key_subkey(addr, entry.key)? {
-                            Some(current) if current.key == entry.key => {
-                                storage_cursor.delete_current()?;
-                            }
-
-                            _ => {}
-                        }
-
-                        // update block list in the change set
-                        let changeset_key =
-                            ContractStorageKey { contract_address: addr, key: entry.key };
-                        let list = db_tx.get::<tables::StorageChangeSet>(changeset_key.clone())?;
-
-                        let updated_list = match list {
-                            Some(mut list) => {
-                                list.insert(block_number);
-                                list
-                            }
-                            // create a new block list if it doesn't yet exist, and insert the block
-                            // number
-                            None => BlockList::from([block_number]),
-                        };
-
-                        db_tx.put::<tables::StorageChangeSet>(changeset_key, updated_list)?;
-                        storage_cursor.upsert(addr, entry)?;
-
-                        let storage_change_sharded_key =
-                            ContractStorageKey { contract_address: addr, key: entry.key };
-
-                        db_tx.put::<tables::StorageChangeHistory>(
-                            block_number,
-                            ContractStorageEntry {
-                                key: storage_change_sharded_key,
-                                value: entry.value,
-                            },
-                        )?;
+impl<Db: Database> StageCheckpointWriter for DbProvider<Db> {
+    fn set_checkpoint(&self, id: &str, block_number: BlockNumber) -> ProviderResult<()> {
+        self.0.update(move |db_tx| -> ProviderResult<()> {
+            db_tx.put::<tables::StageCheckpoints>(id.to_string(), block_number)?;
+            Ok(())
+        })?
+    }
+}
+
+/// Stores an executed block along with its execution output.
+fn insert_block<Tx: DbTxMut>(
+    db_tx: &Tx,
+    block: SealedBlockWithStatus,
+    states: StateUpdatesWithDeclaredClasses,
+    receipts: Vec<Receipt>,
+    executions: Vec<TxExecInfo>,
+) -> ProviderResult<()> {
+    let block_hash = block.block.hash;
+    let block_number = block.block.header.number;
+
+    insert_header(db_tx, block_hash, block.block.header, block.status)?;
+    let tx_offset = insert_body(db_tx, block_number, block.block.body, receipts)?;
+
+    for (i, execution) in executions.into_iter().enumerate() {
+        db_tx.put::<tables::TxTraces>(tx_offset + i as u64, execution)?;
+    }
+
+    insert_state_updates(db_tx, block_number, states.state_updates)?;
+
+    for (hash, compiled_class) in states.declared_compiled_classes {
+        db_tx.put::<tables::CompiledClasses>(hash, compiled_class)?;
+    }
+
+    for (class_hash, sierra_class) in states.declared_sierra_classes {
+        db_tx.put::<tables::SierraClasses>(class_hash, sierra_class)?;
+    }
+
+    Ok(())
+}
+
+/// Stores the header of a block along with the mappings between its number and hash.
+fn insert_header<Tx: DbTxMut>(
+    db_tx: &Tx,
+    block_hash: BlockHash,
+    header: Header,
+    status: FinalityStatus,
+) -> ProviderResult<()> {
+    let block_number = header.number;
+
+    db_tx.put::<tables::BlockHashes>(block_number, block_hash)?;
+    db_tx.put::<tables::BlockNumbers>(block_hash, block_number)?;
+    db_tx.put::<tables::BlockStatusses>(block_number, status)?;
+    db_tx.put::<tables::Headers>(block_number, header)?;
+
+    Ok(())
+}
+
+/// Stores the transactions and receipts of a block, returning the number of its first transaction.
+fn insert_body<Tx: DbTxMut>(
+    db_tx: &Tx,
+    block_number: BlockNumber,
+    transactions: Vec<TxWithHash>,
+    receipts: Vec<Receipt>,
+) -> ProviderResult<TxNumber> {
+    let tx_count = transactions.len() as u64;
+    let tx_offset = db_tx.entries::<tables::Transactions>()? as u64;
+    let block_body_indices = StoredBlockBodyIndices { tx_offset, tx_count };
+
+    db_tx.put::<tables::BlockBodyIndices>(block_number, block_body_indices)?;
+
+    let events = receipts.iter().flat_map(|receipt| receipt.events());
+    event::insert_event_index(db_tx, block_number, events)?;
+
+    for (i, (transaction, receipt)) in transactions.into_iter().zip(receipts).enumerate() {
+        let tx_number = tx_offset + i as u64;
+        let tx_hash = transaction.hash;
+
+        db_tx.put::<tables::TxHashes>(tx_number, tx_hash)?;
+        db_tx.put::<tables::TxNumbers>(tx_hash, tx_number)?;
+        db_tx.put::<tables::TxBlocks>(tx_number, block_number)?;
+        db_tx.put::<tables::Transactions>(tx_number, transaction.transaction)?;
+        db_tx.put::<tables::Receipts>(tx_number, receipt)?;
+    }
+
+    Ok(tx_offset)
+}
+
+/// Fails if the history of `block_number` has been pruned, as its state can't be rebuilt anymore.
+pub(crate) fn check_prune_horizon<Tx: DbTx>(
+    db_tx: &Tx,
+    block_number: BlockNumber,
+) -> ProviderResult<()> {
+    match db_tx.get::<tables::StageCheckpoints>(PRUNE_CHECKPOINT.to_string())? {
+        Some(horizon) if block_number < horizon => {
+            Err(ProviderError::PrunedBlock { block: block_number, horizon })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Applies the state updates of a block to the latest state and records them in the history
+/// tables.
+fn insert_state_updates<Tx: DbTxMut>(
+    db_tx: &Tx,
+    block_number: BlockNumber,
+    state_updates: StateUpdates,
+) -> ProviderResult<()> {
+    // insert classes
+
+    // legacy classes are stored with their class hash as their compiled class hash
+    let legacy_classes = state_updates.deprecated_declared_classes.into_iter().map(|h| (h, h));
+
+    for (class_hash, compiled_hash) in
+        state_updates.declared_classes.into_iter().chain(legacy_classes)
+    {
+        db_tx.put::<tables::CompiledClassHashes>(class_hash, compiled_hash)?;
+
+        db_tx.put::<tables::ClassDeclarationBlock>(class_hash, block_number)?;
+        db_tx.put::<tables::ClassDeclarations>(block_number, class_hash)?
+    }
+
+    // insert storage changes
+    {
+        let mut storage_cursor = db_tx.cursor_dup_mut::<tables::ContractStorage>()?;
+        for (addr, entries) in state_updates.storage_updates {
+            let entries = entries.into_iter().map(|(key, value)| StorageEntry { key, value });
+
+            for entry in entries {
+                match storage_cursor.seek_by_key_subkey(addr, entry.key)? {
+                    Some(current) if current.key == entry.key => {
+                        storage_cursor.delete_current()?;
                     }
+
+                    _ => {}
                 }
-            }
 
-            // update contract info
+                // update block list in the change set
+                let changeset_key = ContractStorageKey { contract_address: addr, key: entry.key };
+                let list = db_tx.get::<tables::StorageChangeSet>(changeset_key.clone())?;
 
-            for (addr, class_hash) in states.state_updates.deployed_contracts {
-                let value = if let Some(info) = db_tx.get::<tables::ContractInfo>(addr)? {
-                    GenericContractInfo { class_hash, ..info }
-                } else {
-                    GenericContractInfo { class_hash, ..Default::default() }
-                };
-
-                let new_change_set = if let Some(mut change_set) =
-                    db_tx.get::<tables::ContractInfoChangeSet>(addr)?
-                {
-                    change_set.class_change_list.insert(block_number);
-                    change_set
-                } else {
-                    ContractInfoChangeList {
-                        class_change_list: BlockList::from([block_number]),
-                        ..Default::default()
+                let updated_list = match list {
+                    Some(mut list) => {
+                        list.insert(block_number);
+                        list
                     }
+                    // create a new block list if it doesn't yet exist, and insert the block
+                    // number
+                    None => BlockList::from([block_number]),
                 };
 
-                db_tx.put::<tables::ContractInfo>(addr, value)?;
+                db_tx.put::<tables::StorageChangeSet>(changeset_key, updated_list)?;
+                storage_cursor.upsert(addr, entry)?;
+
+                let storage_change_sharded_key =
+                    ContractStorageKey { contract_address: addr, key: entry.key };
 
-                let class_change_key = ContractClassChange { contract_address: addr, class_hash };
-                db_tx.put::<tables::ClassChangeHistory>(block_number, class_change_key)?;
-                db_tx.put::<tables::ContractInfoChangeSet>(addr, new_change_set)?;
+                db_tx.put::<tables::StorageChangeHistory>(
+                    block_number,
+                    ContractStorageEntry { key: storage_change_sharded_key, value: entry.value },
+                )?;
             }
+        }
+    }
 
-            for (addr, nonce) in states.state_updates.nonce_updates {
-                let value = if let Some(info) = db_tx.get::<tables::ContractInfo>(addr)? {
-                    GenericContractInfo { nonce, ..info }
-                } else {
-                    GenericContractInfo { nonce, ..Default::default() }
-                };
+    // update contract info
 
-                let new_change_set = if let Some(mut change_set) =
-                    db_tx.get::<tables::ContractInfoChangeSet>(addr)?
-                {
-                    change_set.nonce_change_list.insert(block_number);
-                    change_set
-                } else {
-                    ContractInfoChangeList {
-                        nonce_change_list: BlockList::from([block_number]),
-                        ..Default::default()
-                    }
-                };
+    let contracts = state_updates.deployed_contracts.into_iter();
+    for (addr, class_hash) in contracts.chain(state_updates.replaced_classes) {
+        let value = if let Some(info) = db_tx.get::<tables::ContractInfo>(addr)? {
+            GenericContractInfo { class_hash, ..info }
+        } else {
+            GenericContractInfo { class_hash, ..Default::default() }
+        };
 
-                db_tx.put::<tables::ContractInfo>(addr, value)?;
+        let new_change_set =
+            if let Some(mut change_set) = db_tx.get::<tables::ContractInfoChangeSet>(addr)? {
+                change_set.class_change_list.insert(block_number);
+                change_set
+            } else {
+                ContractInfoChangeList {
+                    class_change_list: BlockList::from([block_number]),
+                    ..Default::default()
+                }
+            };
 
-                let nonce_change_key = ContractNonceChange { contract_address: addr, nonce };
-                db_tx.put::<tables::NonceChangeHistory>(block_number, nonce_change_key)?;
-                db_tx.put::<tables::ContractInfoChangeSet>(addr, new_change_set)?;
-            }
+        db_tx.put::<tables::ContractInfo>(addr, value)?;
 
-            Ok(())
-        })?
+        let class_change_key = ContractClassChange { contract_address: addr, class_hash };
+        db_tx.put::<tables::ClassChangeHistory>(block_number, class_change_key)?;
+        db_tx.put::<tables::ContractInfoChangeSet>(addr, new_change_set)?;
     }
+
+    for (addr, nonce) in state_updates.nonce_updates {
+        let value = if let Some(info) = db_tx.get::<tables::ContractInfo>(addr)? {
+            GenericContractInfo { nonce, ..info }
+        } else {
+            GenericContractInfo { nonce, ..Default::default() }
+        };
+
+        let new_change_set =
+            if let Some(mut change_set) = db_tx.get::<tables::ContractInfoChangeSet>(addr)? {
+                change_set.nonce_change_list.insert(block_number);
+                change_set
+            } else {
+                ContractInfoChangeList {
+                    nonce_change_list: BlockList::from([block_number]),
+                    ..Default::default()
+                }
+            };
+
+        db_tx.put::<tables::ContractInfo>(addr, value)?;
+
+        let nonce_change_key = ContractNonceChange { contract_address: addr, nonce };
+        db_tx.put::<tables::NonceChangeHistory>(block_number, nonce_change_key)?;
+        db_tx.put::<tables::ContractInfoChangeSet>(addr, new_change_set)?;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use core::fmt;
 
-use katana_db::abstraction::{Database, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
+use katana_db::abstraction::{Database, DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
 use katana_db::models::contract::ContractInfoChangeList;
 use katana_db::models::list::BlockList;
 use katana_db::models::storage::{ContractStorageKey, StorageEntry};
@@ -10,11 +10,12 @@ use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, Flat
 use katana_primitives::contract::{
     ContractAddress, GenericContractInfo, Nonce, StorageKey, StorageValue,
 };
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 
 use super::DbProvider;
 use crate::error::ProviderError;
 use crate::traits::contract::{ContractClassProvider, ContractClassWriter};
-use crate::traits::state::{StateProvider, StateWriter};
+use crate::traits::state::{StateDumpProvider, StateProvider, StateWriter};
 use crate::ProviderResult;
 
 impl<Db: Database> StateWriter for DbProvider<Db> {
@@ -100,6 +101,44 @@ impl ContractClassWriter for DbProvider {
     }
 }
 
+impl<Db: Database> StateDumpProvider for DbProvider<Db> {
+    fn dump_state(&self) -> ProviderResult<StateUpdatesWithDeclaredClasses> {
+        let db_tx = self.0.tx()?;
+        let mut states = StateUpdatesWithDeclaredClasses::default();
+
+        for entry in db_tx.cursor::<tables::ContractInfo>()?.walk(None)? {
+            let (address, info) = entry?;
+            states.state_updates.deployed_contracts.insert(address, info.class_hash);
+            states.state_updates.nonce_updates.insert(address, info.nonce);
+        }
+
+        // walking a dupsort table visits every duplicate value of a key
+        for entry in db_tx.cursor::<tables::ContractStorage>()?.walk(None)? {
+            let (address, entry) = entry?;
+            let storage = states.state_updates.storage_updates.entry(address).or_default();
+            storage.insert(entry.key, entry.value);
+        }
+
+        for entry in db_tx.cursor::<tables::CompiledClassHashes>()?.walk(None)? {
+            let (hash, compiled_hash) = entry?;
+            states.state_updates.declared_classes.insert(hash, compiled_hash);
+        }
+
+        for entry in db_tx.cursor::<tables::CompiledClasses>()?.walk(None)? {
+            let (hash, class) = entry?;
+            states.declared_compiled_classes.insert(hash, class);
+        }
+
+        for entry in db_tx.cursor::<tables::SierraClasses>()?.walk(None)? {
+            let (hash, class) = entry?;
+            states.declared_sierra_classes.insert(hash, class);
+        }
+
+        db_tx.commit()?;
+        Ok(states)
+    }
+}
+
 /// A state provider that provides the latest states from the database.
 #[derive(Debug)]
 pub(super) struct LatestStateProvider<Tx: DbTx>(Tx);
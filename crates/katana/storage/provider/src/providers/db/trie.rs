@@ -0,0 +1,395 @@
+use std::collections::BTreeSet;
+
+use katana_db::abstraction::{Database, DbCursor, DbTx, DbTxMut};
+use katana_db::models::trie::TrieRoots;
+use katana_db::tables;
+use katana_db::trie::TrieDb;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::class::{ClassHash, CompiledClassHash};
+use katana_primitives::contract::{ContractAddress, StorageKey};
+use katana_primitives::state::StateUpdates;
+use katana_primitives::Felt;
+use katana_trie::{BinaryTrie, MultiProof, Pedersen, Poseidon};
+
+use super::DbProvider;
+use crate::error::ProviderError;
+use crate::traits::trie::{ContractLeaf, StateProof, StateProofProvider, TrieWriter};
+use crate::ProviderResult;
+
+impl<Db: Database> TrieWriter for DbProvider<Db> {
+    fn trie_insert_state_updates(
+        &self,
+        block_number: BlockNumber,
+        state_updates: &StateUpdates,
+    ) -> ProviderResult<Felt> {
+        self.0.update(|db_tx| {
+            let classes = state_updates.declared_classes.iter().map(|(h, c)| (*h, *c));
+            insert_trie_updates(db_tx, block_number, state_updates, classes)
+        })?
+    }
+}
+
+/// Applies the state updates of a block to the tries and returns the new global state root.
+///
+/// Only `classes` are inserted in the classes trie, as the legacy classes are not part of it.
+pub(super) fn insert_trie_updates<Tx: DbTxMut>(
+    db_tx: &Tx,
+    block_number: BlockNumber,
+    state_updates: &StateUpdates,
+    classes: impl Iterator<Item = (ClassHash, CompiledClassHash)>,
+) -> ProviderResult<Felt> {
+    let prev_roots = db_tx
+        .cursor::<tables::BlockTrieRoots>()?
+        .last()?
+        .map(|(_, roots)| roots)
+        .unwrap_or_default();
+
+    // classes trie
+
+    let mut trie = BinaryTrie::<_, Poseidon>::new(TrieDb::<tables::ClassesTrie, _>::new(db_tx));
+
+    let leaves =
+        classes.map(|(hash, compiled_hash)| (hash, katana_trie::class_leaf_hash(compiled_hash)));
+
+    let classes = trie.insert_batch(prev_roots.classes, leaves)?;
+    trie.commit(classes)?;
+
+    // contracts storage tries
+
+    let mut trie = BinaryTrie::<_, Pedersen>::new(TrieDb::<tables::StoragesTrie, _>::new(db_tx));
+
+    let mut addresses = BTreeSet::new();
+    addresses.extend(state_updates.nonce_updates.keys());
+    addresses.extend(state_updates.storage_updates.keys());
+    addresses.extend(state_updates.deployed_contracts.keys());
+    addresses.extend(state_updates.replaced_classes.keys());
+
+    let mut leaves = Vec::with_capacity(addresses.len());
+
+    for address in addresses {
+        let info = db_tx.get::<tables::ContractInfo>(*address)?.unwrap_or_default();
+        let mut storage_root =
+            db_tx.get::<tables::ContractStorageRoots>(*address)?.unwrap_or_default();
+
+        if let Some(entries) = state_updates.storage_updates.get(address) {
+            let entries = entries.iter().map(|(k, v)| (*k, *v));
+            storage_root = trie.insert_batch(storage_root, entries)?;
+            trie.commit(storage_root)?;
+            db_tx.put::<tables::ContractStorageRoots>(*address, storage_root)?;
+        }
+
+        let class_hash = state_updates
+            .replaced_classes
+            .get(address)
+            .or_else(|| state_updates.deployed_contracts.get(address))
+            .copied()
+            .unwrap_or(info.class_hash);
+
+        let nonce = state_updates.nonce_updates.get(address).copied().unwrap_or(info.nonce);
+
+        let leaf = katana_trie::contract_state_hash(class_hash, storage_root, nonce);
+        leaves.push((address.0, leaf));
+    }
+
+    // contracts trie
+
+    let mut trie = BinaryTrie::<_, Pedersen>::new(TrieDb::<tables::ContractsTrie, _>::new(db_tx));
+
+    let contracts = trie.insert_batch(prev_roots.contracts, leaves)?;
+    trie.commit(contracts)?;
+
+    let roots = TrieRoots { classes, contracts };
+    db_tx.put::<tables::BlockTrieRoots>(block_number, roots)?;
+
+    Ok(roots.state_root())
+}
+
+impl<Db: Database> StateProofProvider for DbProvider<Db> {
+    fn trie_roots(&self, block_number: BlockNumber) -> ProviderResult<Option<TrieRoots>> {
+        let db_tx = self.0.tx()?;
+        let roots = db_tx.get::<tables::BlockTrieRoots>(block_number)?;
+        db_tx.commit()?;
+        Ok(roots)
+    }
+
+    fn classes_proof(
+        &self,
+        block_number: BlockNumber,
+        class_hashes: &[ClassHash],
+    ) -> ProviderResult<MultiProof> {
+        self.0.view(|db_tx| -> ProviderResult<MultiProof> {
+            let roots = db_tx
+                .get::<tables::BlockTrieRoots>(block_number)?
+                .ok_or(ProviderError::MissingTrieRoots(block_number))?;
+
+            let trie = BinaryTrie::<_, Poseidon>::new(TrieDb::<tables::ClassesTrie, _>::new(db_tx));
+            Ok(trie.multiproof(roots.classes, class_hashes)?)
+        })?
+    }
+
+    fn contracts_proof(
+        &self,
+        block_number: BlockNumber,
+        addresses: &[ContractAddress],
+    ) -> ProviderResult<MultiProof> {
+        self.0.view(|db_tx| -> ProviderResult<MultiProof> {
+            let roots = db_tx
+                .get::<tables::BlockTrieRoots>(block_number)?
+                .ok_or(ProviderError::MissingTrieRoots(block_number))?;
+
+            let keys = addresses.iter().map(|address| address.0).collect::<Vec<_>>();
+            let trie =
+                BinaryTrie::<_, Pedersen>::new(TrieDb::<tables::ContractsTrie, _>::new(db_tx));
+            Ok(trie.multiproof(roots.contracts, &keys)?)
+        })?
+    }
+
+    fn storage_root(&self, address: ContractAddress) -> ProviderResult<Felt> {
+        let db_tx = self.0.tx()?;
+        let root = db_tx.get::<tables::ContractStorageRoots>(address)?.unwrap_or_default();
+        db_tx.commit()?;
+        Ok(root)
+    }
+
+    fn storage_proof(
+        &self,
+        address: ContractAddress,
+        keys: &[StorageKey],
+    ) -> ProviderResult<MultiProof> {
+        self.0.view(|db_tx| -> ProviderResult<MultiProof> {
+            let root = db_tx.get::<tables::ContractStorageRoots>(address)?.unwrap_or_default();
+            let trie =
+                BinaryTrie::<_, Pedersen>::new(TrieDb::<tables::StoragesTrie, _>::new(db_tx));
+            Ok(trie.multiproof(root, keys)?)
+        })?
+    }
+
+    fn latest_state_proof(
+        &self,
+        class_hashes: &[ClassHash],
+        addresses: &[ContractAddress],
+        storage_keys: &[(ContractAddress, Vec<StorageKey>)],
+    ) -> ProviderResult<Option<StateProof>> {
+        self.0.view(|db_tx| -> ProviderResult<Option<StateProof>> {
+            let (block_number, block_hash) = db_tx
+                .cursor::<tables::BlockHashes>()?
+                .last()?
+                .ok_or(ProviderError::MissingLatestBlockNumber)?;
+
+            let Some(roots) = db_tx.get::<tables::BlockTrieRoots>(block_number)? else {
+                return Ok(None);
+            };
+
+            let trie = BinaryTrie::<_, Poseidon>::new(TrieDb::<tables::ClassesTrie, _>::new(db_tx));
+            let classes_proof = trie.multiproof(roots.classes, class_hashes)?;
+
+            let keys = addresses.iter().map(|address| address.0).collect::<Vec<_>>();
+            let trie =
+                BinaryTrie::<_, Pedersen>::new(TrieDb::<tables::ContractsTrie, _>::new(db_tx));
+            let contracts_proof = trie.multiproof(roots.contracts, &keys)?;
+
+            let mut contract_leaves = Vec::with_capacity(addresses.len());
+            for address in addresses {
+                let info = db_tx.get::<tables::ContractInfo>(*address)?.unwrap_or_default();
+                let storage_root =
+                    db_tx.get::<tables::ContractStorageRoots>(*address)?.unwrap_or_default();
+                let (nonce, class_hash) = (info.nonce, info.class_hash);
+                contract_leaves.push(ContractLeaf { nonce, class_hash, storage_root });
+            }
+
+            let trie =
+                BinaryTrie::<_, Pedersen>::new(TrieDb::<tables::StoragesTrie, _>::new(db_tx));
+            let mut storage_proofs = Vec::with_capacity(storage_keys.len());
+            for (address, keys) in storage_keys {
+                let root = db_tx.get::<tables::ContractStorageRoots>(*address)?.unwrap_or_default();
+                storage_proofs.push(trie.multiproof(root, keys)?);
+            }
+
+            Ok(Some(StateProof {
+                block_number,
+                block_hash,
+                roots,
+                classes_proof,
+                contracts_proof,
+                contract_leaves,
+                storage_proofs,
+            }))
+        })?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_primitives::block::{Block, FinalityStatus};
+    use katana_primitives::genesis::constant::{
+        DEFAULT_ACCOUNT_CLASS, DEFAULT_ACCOUNT_CLASS_HASH, DEFAULT_ACCOUNT_COMPILED_CLASS_HASH,
+        DEFAULT_LEGACY_UDC_CLASS_HASH,
+    };
+    use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
+    use katana_primitives::{address, Felt};
+    use katana_trie::{verify_proof, Pedersen, Poseidon};
+    use starknet::macros::felt;
+
+    use super::DbProvider;
+    use crate::traits::block::{BlockHashProvider, BlockWriter, HeaderProvider};
+    use crate::traits::trie::{ContractLeaf, StateProofProvider, TrieWriter};
+
+    #[test]
+    fn state_root_and_proofs() {
+        let provider = DbProvider::new_ephemeral();
+
+        let address = address!("0x1337");
+        let class_hash = felt!("0x111");
+        let compiled_class_hash = felt!("0x222");
+        let storage_key = felt!("0x1");
+        let storage_value = felt!("0x99");
+
+        let state_updates = StateUpdates {
+            deployed_contracts: BTreeMap::from([(address, class_hash)]),
+            declared_classes: BTreeMap::from([(class_hash, compiled_class_hash)]),
+            nonce_updates: BTreeMap::from([(address, Felt::ONE)]),
+            storage_updates: BTreeMap::from([(
+                address,
+                BTreeMap::from([(storage_key, storage_value)]),
+            )]),
+            ..Default::default()
+        };
+
+        let state_root = provider.trie_insert_state_updates(0, &state_updates).unwrap();
+
+        let roots = provider.trie_roots(0).unwrap().unwrap();
+        assert_eq!(roots.state_root(), state_root);
+
+        let storage_root = provider.storage_root(address).unwrap();
+        let proof = provider.storage_proof(address, &[storage_key]).unwrap();
+        assert_eq!(
+            verify_proof::<Pedersen>(storage_root, storage_key, &proof),
+            Some(storage_value)
+        );
+
+        let proof = provider.contracts_proof(0, &[address]).unwrap();
+        let leaf = katana_trie::contract_state_hash(class_hash, storage_root, Felt::ONE);
+        assert_eq!(verify_proof::<Pedersen>(roots.contracts, address.0, &proof), Some(leaf));
+
+        let proof = provider.classes_proof(0, &[class_hash]).unwrap();
+        let leaf = katana_trie::class_leaf_hash(compiled_class_hash);
+        assert_eq!(verify_proof::<Poseidon>(roots.classes, class_hash, &proof), Some(leaf));
+
+        // updating the nonce only changes the contract's leaf, the storage root stays the same.
+        let state_updates = StateUpdates {
+            nonce_updates: BTreeMap::from([(address, Felt::TWO)]),
+            ..Default::default()
+        };
+
+        let new_state_root = provider.trie_insert_state_updates(1, &state_updates).unwrap();
+        assert_ne!(new_state_root, state_root);
+        assert_eq!(provider.storage_root(address).unwrap(), storage_root);
+        assert_eq!(provider.trie_roots(1).unwrap().unwrap().classes, roots.classes);
+
+        // proofs at the previous block are still available.
+        let proof = provider.contracts_proof(0, &[address]).unwrap();
+        let leaf = katana_trie::contract_state_hash(class_hash, storage_root, Felt::ONE);
+        assert_eq!(verify_proof::<Pedersen>(roots.contracts, address.0, &proof), Some(leaf));
+    }
+
+    #[test]
+    fn latest_state_proof() {
+        let provider = DbProvider::new_ephemeral();
+
+        let mut states = StateUpdatesWithDeclaredClasses::default();
+        let address = address!("0x1337");
+        let (storage_key, storage_value) = (felt!("0x1"), felt!("0x99"));
+        states.state_updates.deployed_contracts.insert(address, DEFAULT_ACCOUNT_CLASS_HASH);
+        states.state_updates.nonce_updates.insert(address, Felt::ONE);
+        states
+            .state_updates
+            .storage_updates
+            .insert(address, BTreeMap::from([(storage_key, storage_value)]));
+
+        let status = FinalityStatus::AcceptedOnL2;
+        let hash = provider
+            .insert_block_with_trie_updates(
+                Block::default(),
+                status,
+                states,
+                Vec::new(),
+                Vec::new(),
+                None,
+            )
+            .unwrap();
+
+        let storage_keys = [(address, vec![storage_key])];
+        let proof = provider.latest_state_proof(&[], &[address], &storage_keys).unwrap().unwrap();
+
+        assert_eq!(proof.block_number, 0);
+        assert_eq!(proof.block_hash, hash);
+        assert_eq!(Some(proof.roots), provider.trie_roots(0).unwrap());
+
+        let leaf = ContractLeaf {
+            nonce: Felt::ONE,
+            class_hash: DEFAULT_ACCOUNT_CLASS_HASH,
+            storage_root: provider.storage_root(address).unwrap(),
+        };
+        assert_eq!(proof.contract_leaves, vec![leaf]);
+
+        let leaf_hash =
+            katana_trie::contract_state_hash(leaf.class_hash, leaf.storage_root, leaf.nonce);
+        let value =
+            verify_proof::<Pedersen>(proof.roots.contracts, address.0, &proof.contracts_proof);
+        assert_eq!(value, Some(leaf_hash));
+
+        let value =
+            verify_proof::<Pedersen>(leaf.storage_root, storage_key, &proof.storage_proofs[0]);
+        assert_eq!(value, Some(storage_value));
+    }
+
+    #[test]
+    fn block_with_trie_updates() {
+        let provider = DbProvider::new_ephemeral();
+
+        let mut states = StateUpdatesWithDeclaredClasses::default();
+        let sierra = DEFAULT_ACCOUNT_CLASS.clone().flatten().unwrap();
+        states.declared_sierra_classes.insert(DEFAULT_ACCOUNT_CLASS_HASH, sierra);
+        states
+            .state_updates
+            .declared_classes
+            .insert(DEFAULT_ACCOUNT_CLASS_HASH, DEFAULT_ACCOUNT_COMPILED_CLASS_HASH);
+        // legacy classes are declared with their class hash as their compiled class hash
+        states
+            .state_updates
+            .declared_classes
+            .insert(DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_LEGACY_UDC_CLASS_HASH);
+
+        let block = Block::default();
+        let status = FinalityStatus::AcceptedOnL2;
+        let hash = provider
+            .insert_block_with_trie_updates(block, status, states, Vec::new(), Vec::new(), None)
+            .unwrap();
+
+        // the block is stored with the state root of the tries
+        let roots = provider.trie_roots(0).unwrap().unwrap();
+        let header = provider.header_by_number(0).unwrap().unwrap();
+        assert_eq!(header.state_root, roots.state_root());
+        assert_eq!(provider.block_hash_by_num(0).unwrap(), Some(hash));
+        assert_eq!(header.compute_hash(), hash);
+
+        // only the Sierra class is part of the classes trie
+        let proof = provider.classes_proof(0, &[DEFAULT_ACCOUNT_CLASS_HASH]).unwrap();
+        let leaf = katana_trie::class_leaf_hash(DEFAULT_ACCOUNT_COMPILED_CLASS_HASH);
+        let value = verify_proof::<Poseidon>(roots.classes, DEFAULT_ACCOUNT_CLASS_HASH, &proof);
+        assert_eq!(value, Some(leaf));
+
+        let reference = DbProvider::new_ephemeral();
+        let state_updates = StateUpdates {
+            declared_classes: BTreeMap::from([(
+                DEFAULT_ACCOUNT_CLASS_HASH,
+                DEFAULT_ACCOUNT_COMPILED_CLASS_HASH,
+            )]),
+            ..Default::default()
+        };
+        reference.trie_insert_state_updates(0, &state_updates).unwrap();
+        assert_eq!(reference.trie_roots(0).unwrap().unwrap().classes, roots.classes);
+    }
+}
@@ -0,0 +1,414 @@
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+use katana_db::abstraction::{Database, DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
+use katana_db::models::contract::{ContractClassChange, ContractNonceChange};
+use katana_db::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
+use katana_db::tables::{self, DupSort};
+use katana_db::trie::TrieDb;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::{ContractAddress, GenericContractInfo};
+use katana_primitives::Felt;
+use katana_trie::{BinaryTrie, Pedersen};
+
+use super::{check_prune_horizon, DbProvider};
+use crate::error::ProviderError;
+use crate::traits::block::BlockUnwinder;
+use crate::ProviderResult;
+
+impl<Db: Database> BlockUnwinder for DbProvider<Db> {
+    fn unwind_to(&self, block_number: BlockNumber) -> ProviderResult<()> {
+        self.0.update(|db_tx| -> ProviderResult<()> {
+            let latest = db_tx
+                .cursor::<tables::BlockHashes>()?
+                .last()?
+                .map(|(num, _)| num)
+                .ok_or(ProviderError::MissingLatestBlockNumber)?;
+
+            // the changes needed to revert the state to `block_number` may have been pruned
+            check_prune_horizon(db_tx, block_number)?;
+
+            // contracts whose storage trie needs to be rebuilt
+            let mut storage_changed = BTreeSet::new();
+
+            for num in (block_number + 1..=latest).rev() {
+                unwind_block(db_tx, num)?;
+                storage_changed.extend(unwind_state(db_tx, num)?);
+            }
+
+            // The storage roots are only stored for the latest state, so the storage tries of the
+            // affected contracts are rebuilt from their reverted storage.
+            for address in storage_changed {
+                let entries = dup_values::<_, tables::ContractStorage>(db_tx, address)?
+                    .into_iter()
+                    .map(|StorageEntry { key, value }| (key, value));
+
+                let mut trie =
+                    BinaryTrie::<_, Pedersen>::new(TrieDb::<tables::StoragesTrie, _>::new(db_tx));
+
+                let root = trie.insert_batch(Felt::ZERO, entries)?;
+                trie.commit(root)?;
+
+                if root == Felt::ZERO {
+                    db_tx.delete::<tables::ContractStorageRoots>(address, None)?;
+                } else {
+                    db_tx.put::<tables::ContractStorageRoots>(address, root)?;
+                }
+            }
+
+            Ok(())
+        })?
+    }
+}
+
+/// Removes the block, its transactions, the events it emitted and the classes declared in it.
+fn unwind_block<Tx: DbTxMut>(db_tx: &Tx, block_number: BlockNumber) -> ProviderResult<()> {
+    let indices = db_tx
+        .get::<tables::BlockBodyIndices>(block_number)?
+        .ok_or(ProviderError::MissingBlockBodyIndices(block_number))?;
+
+    let hash = db_tx
+        .get::<tables::BlockHashes>(block_number)?
+        .ok_or(ProviderError::MissingBlockHash(block_number))?;
+
+    let mut receipts = Vec::with_capacity(indices.tx_count as usize);
+
+    for tx_number in indices.tx_offset..indices.tx_offset + indices.tx_count {
+        if let Some(receipt) = db_tx.get::<tables::Receipts>(tx_number)? {
+            receipts.push(receipt);
+        }
+
+        if let Some(tx_hash) = db_tx.get::<tables::TxHashes>(tx_number)? {
+            db_tx.delete::<tables::TxNumbers>(tx_hash, None)?;
+        }
+
+        db_tx.delete::<tables::TxHashes>(tx_number, None)?;
+        db_tx.delete::<tables::TxBlocks>(tx_number, None)?;
+        db_tx.delete::<tables::Transactions>(tx_number, None)?;
+        db_tx.delete::<tables::Receipts>(tx_number, None)?;
+        db_tx.delete::<tables::TxTraces>(tx_number, None)?;
+    }
+
+    let events = receipts.iter().flat_map(|receipt| receipt.events());
+    super::event::remove_event_index(db_tx, block_number, events)?;
+
+    for class_hash in dup_values::<_, tables::ClassDeclarations>(db_tx, block_number)? {
+        db_tx.delete::<tables::CompiledClassHashes>(class_hash, None)?;
+        db_tx.delete::<tables::ClassDeclarationBlock>(class_hash, None)?;
+        db_tx.delete::<tables::CompiledClasses>(class_hash, None)?;
+        db_tx.delete::<tables::SierraClasses>(class_hash, None)?;
+    }
+
+    db_tx.delete::<tables::ClassDeclarations>(block_number, None)?;
+
+    db_tx.delete::<tables::BlockHashes>(block_number, None)?;
+    db_tx.delete::<tables::BlockNumbers>(hash, None)?;
+    db_tx.delete::<tables::BlockStatusses>(block_number, None)?;
+    db_tx.delete::<tables::Headers>(block_number, None)?;
+    db_tx.delete::<tables::BlockBodyIndices>(block_number, None)?;
+    db_tx.delete::<tables::BlockTrieRoots>(block_number, None)?;
+    db_tx.delete::<tables::StateDiffBlobs>(block_number, None)?;
+
+    Ok(())
+}
+
+/// Reverts the state changes made in the block, restoring every changed value to the one it had
+/// before the block. Returns the addresses of the contracts whose storage has been reverted.
+fn unwind_state<Tx: DbTxMut>(
+    db_tx: &Tx,
+    block_number: BlockNumber,
+) -> ProviderResult<BTreeSet<ContractAddress>> {
+    // storage
+
+    let mut storage_changed = BTreeSet::new();
+
+    for entry in dup_values::<_, tables::StorageChangeHistory>(db_tx, block_number)? {
+        let ContractStorageEntry { key, .. } = entry;
+        let ContractStorageKey { contract_address, key: storage_key } = key.clone();
+
+        let prev_block = match db_tx.get::<tables::StorageChangeSet>(key.clone())? {
+            Some(mut list) => {
+                list.remove(block_number);
+                let prev = list.rank(block_number).checked_sub(1).and_then(|n| list.select(n));
+
+                if list.is_empty() {
+                    db_tx.delete::<tables::StorageChangeSet>(key.clone(), None)?;
+                } else {
+                    db_tx.put::<tables::StorageChangeSet>(key.clone(), list)?;
+                }
+
+                prev
+            }
+            None => None,
+        };
+
+        let mut cursor = db_tx.cursor_dup_mut::<tables::ContractStorage>()?;
+        if let Some(current) = cursor.seek_by_key_subkey(contract_address, storage_key)? {
+            if current.key == storage_key {
+                cursor.delete_current()?;
+            }
+        }
+
+        if let Some(prev_block) = prev_block {
+            let prev = db_tx
+                .cursor_dup::<tables::StorageChangeHistory>()?
+                .seek_by_key_subkey(prev_block, key.clone())?
+                .filter(|entry| entry.key == key);
+
+            if let Some(ContractStorageEntry { value, .. }) = prev {
+                cursor.upsert(contract_address, StorageEntry { key: storage_key, value })?;
+            }
+        }
+
+        storage_changed.insert(contract_address);
+    }
+
+    db_tx.delete::<tables::StorageChangeHistory>(block_number, None)?;
+
+    // contract info
+
+    let class_changes = dup_values::<_, tables::ClassChangeHistory>(db_tx, block_number)?;
+    let nonce_changes = dup_values::<_, tables::NonceChangeHistory>(db_tx, block_number)?;
+
+    let addresses = class_changes
+        .iter()
+        .map(|c| c.contract_address)
+        .chain(nonce_changes.iter().map(|c| c.contract_address))
+        .collect::<BTreeSet<_>>();
+
+    for address in addresses {
+        let Some(mut change_set) = db_tx.get::<tables::ContractInfoChangeSet>(address)? else {
+            continue;
+        };
+
+        let mut info = db_tx.get::<tables::ContractInfo>(address)?.unwrap_or_default();
+
+        if change_set.class_change_list.remove(block_number) {
+            let list = &change_set.class_change_list;
+            let prev = list.rank(block_number).checked_sub(1).and_then(|n| list.select(n));
+
+            info.class_hash = match prev {
+                Some(prev_block) => db_tx
+                    .cursor_dup::<tables::ClassChangeHistory>()?
+                    .seek_by_key_subkey(prev_block, address)?
+                    .filter(|c| c.contract_address == address)
+                    .map(|ContractClassChange { class_hash, .. }| class_hash)
+                    .unwrap_or_default(),
+                None => Default::default(),
+            };
+        }
+
+        if change_set.nonce_change_list.remove(block_number) {
+            let list = &change_set.nonce_change_list;
+            let prev = list.rank(block_number).checked_sub(1).and_then(|n| list.select(n));
+
+            info.nonce = match prev {
+                Some(prev_block) => db_tx
+                    .cursor_dup::<tables::NonceChangeHistory>()?
+                    .seek_by_key_subkey(prev_block, address)?
+                    .filter(|c| c.contract_address == address)
+                    .map(|ContractNonceChange { nonce, .. }| nonce)
+                    .unwrap_or_default(),
+                None => Default::default(),
+            };
+        }
+
+        if info == GenericContractInfo::default() {
+            db_tx.delete::<tables::ContractInfo>(address, None)?;
+        } else {
+            db_tx.put::<tables::ContractInfo>(address, info)?;
+        }
+
+        if change_set.class_change_list.is_empty() && change_set.nonce_change_list.is_empty() {
+            db_tx.delete::<tables::ContractInfoChangeSet>(address, None)?;
+        } else {
+            db_tx.put::<tables::ContractInfoChangeSet>(address, change_set)?;
+        }
+    }
+
+    db_tx.delete::<tables::ClassChangeHistory>(block_number, None)?;
+    db_tx.delete::<tables::NonceChangeHistory>(block_number, None)?;
+
+    Ok(storage_changed)
+}
+
+/// Returns all the values of `key` in a dupsort table.
+fn dup_values<Tx, Tb>(db_tx: &Tx, key: Tb::Key) -> ProviderResult<Vec<Tb::Value>>
+where
+    Tx: DbTx,
+    Tb: DupSort + Debug,
+{
+    let mut cursor = db_tx.cursor_dup::<Tb>()?;
+    let Some(walker) = cursor.walk_dup(Some(key), None)? else { return Ok(Vec::new()) };
+    Ok(walker.map(|entry| entry.map(|(_, value)| value)).collect::<Result<_, _>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use katana_primitives::block::{
+        Block, BlockHashOrNumber, FinalityStatus, Header, SealedBlockWithStatus,
+    };
+    use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
+    use katana_primitives::trace::TxExecInfo;
+    use katana_primitives::transaction::{InvokeTx, Tx, TxWithHash};
+    use katana_primitives::{address, Felt};
+    use starknet::macros::felt;
+
+    use super::DbProvider;
+    use crate::error::ProviderError;
+    use crate::providers::db::PRUNE_CHECKPOINT;
+    use crate::traits::block::{
+        BlockHashProvider, BlockNumberProvider, BlockUnwinder, BlockWriter,
+    };
+    use crate::traits::stage::StageCheckpointWriter;
+    use crate::traits::state::StateFactoryProvider;
+    use crate::traits::state_update::StateUpdateProvider;
+    use crate::traits::transaction::TransactionProvider;
+    use crate::traits::trie::{StateProofProvider, TrieWriter};
+
+    fn insert_block(provider: &DbProvider, number: u64, state_updates: StateUpdates) -> Felt {
+        let state_root = provider.trie_insert_state_updates(number, &state_updates).unwrap();
+
+        let header = Header { number, state_root, ..Default::default() };
+        let tx = TxWithHash {
+            hash: Felt::from(number + 100),
+            transaction: Tx::Invoke(InvokeTx::V1(Default::default())),
+        };
+        let block = Block { header, body: vec![tx] }.seal();
+        let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
+
+        let states = StateUpdatesWithDeclaredClasses { state_updates, ..Default::default() };
+        let receipt = Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+            fee: TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: 0, unit: PriceUnit::Wei },
+        });
+
+        provider
+            .insert_block_with_states_and_receipts(
+                block,
+                states,
+                vec![receipt],
+                vec![TxExecInfo::default()],
+            )
+            .unwrap();
+
+        state_root
+    }
+
+    #[test]
+    fn unwind_reverts_blocks_and_state() {
+        let provider = DbProvider::new_ephemeral();
+
+        let address = address!("0x1337");
+        let address2 = address!("0x1338");
+
+        let genesis_state_root = insert_block(
+            &provider,
+            0,
+            StateUpdates {
+                nonce_updates: BTreeMap::from([(address, felt!("0x1"))]),
+                deployed_contracts: BTreeMap::from([(address, felt!("0x111"))]),
+                declared_classes: BTreeMap::from([(felt!("0x111"), felt!("0x999"))]),
+                storage_updates: BTreeMap::from([(
+                    address,
+                    BTreeMap::from([(felt!("0x1"), felt!("0x10"))]),
+                )]),
+                ..Default::default()
+            },
+        );
+
+        let genesis_hash = provider.latest_hash().unwrap();
+        let storage_root = provider.storage_root(address).unwrap();
+
+        insert_block(
+            &provider,
+            1,
+            StateUpdates {
+                nonce_updates: BTreeMap::from([(address, felt!("0x2"))]),
+                deployed_contracts: BTreeMap::from([(address2, felt!("0x222"))]),
+                declared_classes: BTreeMap::from([(felt!("0x222"), felt!("0x888"))]),
+                storage_updates: BTreeMap::from([
+                    (address, BTreeMap::from([(felt!("0x1"), felt!("0x20"))])),
+                    (address2, BTreeMap::from([(felt!("0x5"), felt!("0x50"))])),
+                ]),
+                ..Default::default()
+            },
+        );
+
+        insert_block(
+            &provider,
+            2,
+            StateUpdates {
+                nonce_updates: BTreeMap::from([(address, felt!("0x3"))]),
+                storage_updates: BTreeMap::from([(
+                    address,
+                    BTreeMap::from([(felt!("0x1"), felt!("0x30")), (felt!("0x2"), felt!("0x1"))]),
+                )]),
+                ..Default::default()
+            },
+        );
+
+        provider.unwind_to(0).unwrap();
+
+        assert_eq!(provider.latest_number().unwrap(), 0);
+        assert_eq!(provider.latest_hash().unwrap(), genesis_hash);
+        assert!(provider.transaction_by_hash(felt!("0x65")).unwrap().is_none());
+        assert!(provider.transaction_by_hash(felt!("0x64")).unwrap().is_some());
+        assert!(provider.state_update(BlockHashOrNumber::Num(1)).unwrap().is_none());
+
+        let state = provider.latest().unwrap();
+        assert_eq!(state.nonce(address).unwrap(), Some(felt!("0x1")));
+        assert_eq!(state.storage(address, felt!("0x1")).unwrap(), Some(felt!("0x10")));
+        assert_eq!(state.storage(address, felt!("0x2")).unwrap(), None);
+        assert_eq!(state.class_hash_of_contract(address2).unwrap(), None);
+        assert_eq!(state.storage(address2, felt!("0x5")).unwrap(), None);
+        assert_eq!(state.compiled_class_hash_of_class_hash(felt!("0x222")).unwrap(), None);
+
+        assert_eq!(provider.storage_root(address).unwrap(), storage_root);
+        assert_eq!(provider.storage_root(address2).unwrap(), Felt::ZERO);
+        assert!(provider.trie_roots(1).unwrap().is_none());
+
+        // the chain can be extended again on top of the unwound block, and the resulting state root
+        // must be the same as if the unwound blocks never existed.
+        let state_root = insert_block(
+            &provider,
+            1,
+            StateUpdates {
+                nonce_updates: BTreeMap::from([(address, felt!("0x1"))]),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(provider.latest_number().unwrap(), 1);
+        assert_eq!(state_root, genesis_state_root);
+        assert_eq!(provider.latest().unwrap().nonce(address).unwrap(), Some(felt!("0x1")));
+    }
+
+    #[test]
+    fn unwind_below_prune_horizon() {
+        let provider = DbProvider::new_ephemeral();
+        for number in 0..=3 {
+            insert_block(&provider, number, StateUpdates::default());
+        }
+
+        provider.set_checkpoint(PRUNE_CHECKPOINT, 2).unwrap();
+
+        // the history below the horizon can't be read nor reverted to
+        let result = provider.historical(1.into());
+        assert!(matches!(result, Err(ProviderError::PrunedBlock { block: 1, horizon: 2 })));
+        let result = provider.unwind_to(1);
+        assert!(matches!(result, Err(ProviderError::PrunedBlock { block: 1, horizon: 2 })));
+        assert_eq!(provider.latest_number().unwrap(), 3);
+
+        assert!(provider.historical(2.into()).unwrap().is_some());
+        provider.unwind_to(2).unwrap();
+        assert_eq!(provider.latest_number().unwrap(), 2);
+    }
+}
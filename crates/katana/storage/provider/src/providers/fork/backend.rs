@@ -11,7 +11,7 @@ use futures::channel::mpsc::{channel as async_channel, Receiver, SendError, Send
 use futures::future::BoxFuture;
 use futures::stream::Stream;
 use futures::{Future, FutureExt};
-use katana_primitives::block::BlockHashOrNumber;
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
 use katana_primitives::conversion::rpc::{
@@ -24,6 +24,8 @@ use starknet::core::types::{BlockId, ContractClass as RpcContractClass, Starknet
 use starknet::providers::{Provider, ProviderError as StarknetProviderError};
 use tracing::{error, trace};
 
+use super::cache::ForkCache;
+use super::metrics::ForkCacheMetrics;
 use crate::error::ProviderError;
 use crate::providers::in_memory::cache::CacheStateDb;
 use crate::traits::contract::ContractClassProvider;
@@ -67,6 +69,8 @@ enum BackendRequest {
     Class(Request<ClassHash, RpcContractClass>),
     ClassHash(Request<ContractAddress, ClassHash>),
     Storage(Request<(ContractAddress, StorageKey), StorageValue>),
+    /// Pins all subsequent requests to a different block.
+    SetBlock(BlockId),
     // Test-only request kind for requesting the backend stats
     #[cfg(test)]
     Stats(OneshotSender<usize>),
@@ -235,6 +239,10 @@ where
                 self.pending_requests.push(fut);
             }
 
+            BackendRequest::SetBlock(block) => {
+                self.block = block;
+            }
+
             #[cfg(test)]
             BackendRequest::Stats(sender) => {
                 let total_ongoing_request = self.pending_requests.len();
@@ -354,6 +362,17 @@ impl BackendHandle {
         }
     }
 
+    /// Pins all subsequent requests to the block `block_id`. Requests that are already being
+    /// processed are not affected.
+    pub fn set_block(&self, block_id: BlockHashOrNumber) -> Result<(), BackendError> {
+        trace!(target: LOG_TARGET, block = %block_id, "Changing pinned block.");
+        let block = match block_id {
+            BlockHashOrNumber::Num(number) => BlockId::Number(number),
+            BlockHashOrNumber::Hash(hash) => BlockId::Hash(hash),
+        };
+        self.request(BackendRequest::SetBlock(block))
+    }
+
     /// Send a request to the backend thread.
     fn request(&self, req: BackendRequest) -> Result<(), BackendError> {
         self.0.lock().try_send(req).map_err(|e| e.into_send_error())?;
@@ -373,12 +392,36 @@ impl BackendHandle {
 /// Check in cache first, if not found, then fetch from the forked provider and store it in the
 /// cache to avoid fetching it again. This is shared across multiple instances of
 /// [`ForkedStateDb`](super::state::ForkedStateDb).
+///
+/// If a persistent [`ForkCache`] is set, it is checked after the in-memory cache and the values
+/// fetched from the forked provider are also stored in it, so that they can be reused by later
+/// runs.
 #[derive(Clone, Debug)]
-pub struct SharedStateProvider(pub(crate) Arc<CacheStateDb<BackendHandle>>);
+pub struct SharedStateProvider(
+    pub(crate) Arc<CacheStateDb<BackendHandle>>,
+    pub(crate) Option<Arc<ForkCache>>,
+    pub(crate) Arc<ForkCacheMetrics>,
+);
 
 impl SharedStateProvider {
-    pub(crate) fn new_with_backend(backend: BackendHandle) -> Self {
-        Self(Arc::new(CacheStateDb::new(backend)))
+    pub(crate) fn new_with_backend(backend: BackendHandle, cache: Option<ForkCache>) -> Self {
+        Self(Arc::new(CacheStateDb::new(backend)), cache.map(Arc::new), Default::default())
+    }
+
+    /// Makes all subsequent reads fetch the state of the forked network at block `block`.
+    ///
+    /// The fetched contract states and storage values are discarded as they may be outdated at the
+    /// new block, but the classes are kept as they are immutable.
+    pub(crate) fn rebase(&self, block: BlockNumber) -> ProviderResult<()> {
+        self.0.db.set_block(BlockHashOrNumber::Num(block))?;
+        self.0.contract_state.write().clear();
+        self.0.storage.write().clear();
+
+        if let Some(cache) = &self.1 {
+            cache.rebase(block).map_err(|e| ProviderError::Other(e.to_string()))?;
+        }
+
+        Ok(())
     }
 }
 
@@ -408,14 +451,26 @@ impl StateProvider for SharedStateProvider {
             .map(|i| i.nonce)
             .filter(|n| n != &Nonce::ZERO)
         {
+            self.2.nonce.memory_hits_total.increment(1);
             return Ok(nonce);
         }
 
+        if let Some(nonce) = self.1.as_ref().map(|c| c.nonce(address)).transpose()?.flatten() {
+            self.2.nonce.persistent_hits_total.increment(1);
+            self.0.contract_state.write().entry(address).or_default().nonce = nonce;
+            return Ok(Some(nonce));
+        }
+
+        self.2.nonce.misses_total.increment(1);
+
         if let Some(nonce) = handle_not_found_err(self.0.get_nonce(address)).map_err(|error| {
             error!(target: LOG_TARGET, %address, %error, "Fetching nonce.");
             error
         })? {
             self.0.contract_state.write().entry(address).or_default().nonce = nonce;
+            if let Some(cache) = &self.1 {
+                cache.set_nonce(address, nonce)?;
+            }
             Ok(Some(nonce))
         } else {
             Ok(None)
@@ -430,9 +485,20 @@ impl StateProvider for SharedStateProvider {
         if let value @ Some(_) =
             self.0.storage.read().get(&address).and_then(|s| s.get(&storage_key))
         {
+            self.2.storage.memory_hits_total.increment(1);
             return Ok(value.copied());
         }
 
+        if let Some(value) =
+            self.1.as_ref().map(|c| c.storage(address, storage_key)).transpose()?.flatten()
+        {
+            self.2.storage.persistent_hits_total.increment(1);
+            self.0.storage.write().entry(address).or_default().insert(storage_key, value);
+            return Ok(Some(value));
+        }
+
+        self.2.storage.misses_total.increment(1);
+
         let value =
             handle_not_found_err(self.0.get_storage(address, storage_key)).map_err(|error| {
                 error!(target: LOG_TARGET, %address, storage_key = %format!("{storage_key:#x}"), %error, "Fetching storage value.");
@@ -446,6 +512,10 @@ impl StateProvider for SharedStateProvider {
             .or_default()
             .insert(storage_key, value.unwrap_or_default());
 
+        if let Some(cache) = &self.1 {
+            cache.set_storage(address, storage_key, value.unwrap_or_default())?;
+        }
+
         Ok(value)
     }
 
@@ -462,9 +532,20 @@ impl StateProvider for SharedStateProvider {
             .map(|i| i.class_hash)
             .filter(|h| h != &ClassHash::ZERO)
         {
+            self.2.class_hash.memory_hits_total.increment(1);
             return Ok(hash);
         }
 
+        if let Some(hash) =
+            self.1.as_ref().map(|c| c.class_hash_of_contract(address)).transpose()?.flatten()
+        {
+            self.2.class_hash.persistent_hits_total.increment(1);
+            self.0.contract_state.write().entry(address).or_default().class_hash = hash;
+            return Ok(Some(hash));
+        }
+
+        self.2.class_hash.misses_total.increment(1);
+
         if let Some(hash) =
             handle_not_found_err(self.0.get_class_hash_at(address)).map_err(|error| {
                 error!(target: LOG_TARGET, %address, %error, "Fetching class hash.");
@@ -472,6 +553,9 @@ impl StateProvider for SharedStateProvider {
             })?
         {
             self.0.contract_state.write().entry(address).or_default().class_hash = hash;
+            if let Some(cache) = &self.1 {
+                cache.set_class_hash_of_contract(address, hash)?;
+            }
             Ok(Some(hash))
         } else {
             Ok(None)
@@ -482,9 +566,18 @@ impl StateProvider for SharedStateProvider {
 impl ContractClassProvider for SharedStateProvider {
     fn sierra_class(&self, hash: ClassHash) -> ProviderResult<Option<FlattenedSierraClass>> {
         if let class @ Some(_) = self.0.shared_contract_classes.sierra_classes.read().get(&hash) {
+            self.2.sierra_class.memory_hits_total.increment(1);
             return Ok(class.cloned());
         }
 
+        if let Some(class) = self.1.as_ref().map(|c| c.sierra_class(hash)).transpose()?.flatten() {
+            self.2.sierra_class.persistent_hits_total.increment(1);
+            self.0.shared_contract_classes.sierra_classes.write().insert(hash, class.clone());
+            return Ok(Some(class));
+        }
+
+        self.2.sierra_class.misses_total.increment(1);
+
         let Some(class) = handle_not_found_err(self.0.get_class_at(hash)).map_err(|error| {
             error!(target: LOG_TARGET, hash = %format!("{hash:#x}"), %error, "Fetching sierra class.");
             error
@@ -501,6 +594,11 @@ impl ContractClassProvider for SharedStateProvider {
                     .sierra_classes
                     .write()
                     .insert(hash, sierra_class.clone());
+
+                if let Some(cache) = &self.1 {
+                    cache.set_sierra_class(hash, sierra_class.clone())?;
+                }
+
                 Ok(Some(sierra_class))
             }
         }
@@ -511,17 +609,35 @@ impl ContractClassProvider for SharedStateProvider {
         hash: ClassHash,
     ) -> ProviderResult<Option<CompiledClassHash>> {
         if let hash @ Some(_) = self.0.compiled_class_hashes.read().get(&hash) {
+            self.2.compiled_class_hash.memory_hits_total.increment(1);
             return Ok(hash.cloned());
         }
 
-        if let Some(hash) =
+        if let Some(compiled_hash) = self
+            .1
+            .as_ref()
+            .map(|c| c.compiled_class_hash_of_class_hash(hash))
+            .transpose()?
+            .flatten()
+        {
+            self.2.compiled_class_hash.persistent_hits_total.increment(1);
+            self.0.compiled_class_hashes.write().insert(hash, compiled_hash);
+            return Ok(Some(compiled_hash));
+        }
+
+        self.2.compiled_class_hash.misses_total.increment(1);
+
+        if let Some(compiled_hash) =
             handle_not_found_err(self.0.get_compiled_class_hash(hash)).map_err(|error| {
                 error!(target: LOG_TARGET, hash = %format!("{hash:#x}"), %error, "Fetching compiled class hash.");
                 error
             })?
         {
-            self.0.compiled_class_hashes.write().insert(hash, hash);
-            Ok(Some(hash))
+            self.0.compiled_class_hashes.write().insert(hash, compiled_hash);
+            if let Some(cache) = &self.1 {
+                cache.set_compiled_class_hash_of_class_hash(hash, compiled_hash)?;
+            }
+            Ok(Some(compiled_hash))
         } else {
             Ok(None)
         }
@@ -529,9 +645,23 @@ impl ContractClassProvider for SharedStateProvider {
 
     fn class(&self, hash: ClassHash) -> ProviderResult<Option<CompiledClass>> {
         if let Some(class) = self.0.shared_contract_classes.compiled_classes.read().get(&hash) {
+            self.2.class.memory_hits_total.increment(1);
             return Ok(Some(class.clone()));
         }
 
+        if let Some(class) = self.1.as_ref().map(|c| c.class(hash)).transpose()?.flatten() {
+            self.2.class.persistent_hits_total.increment(1);
+            self.0
+                .shared_contract_classes
+                .compiled_classes
+                .write()
+                .entry(hash)
+                .or_insert(class.clone());
+            return Ok(Some(class));
+        }
+
+        self.2.class.misses_total.increment(1);
+
         let Some(class) = handle_not_found_err(self.0.get_class_at(hash)).map_err(|error| {
             error!(target: LOG_TARGET, hash = %format!("{hash:#x}"), %error, "Fetching class.");
             error
@@ -561,6 +691,10 @@ impl ContractClassProvider for SharedStateProvider {
             }
         };
 
+        if let Some(cache) = &self.1 {
+            cache.set_class(class_hash, compiled_class_hash, casm.clone(), sierra.clone())?;
+        }
+
         self.0.compiled_class_hashes.write().insert(class_hash, compiled_class_hash);
 
         self.0
@@ -647,6 +781,7 @@ mod tests {
 
     use std::time::Duration;
 
+    use katana_primitives::chain::ChainId;
     use katana_primitives::contract::GenericContractInfo;
     use starknet::macros::felt;
 
@@ -723,7 +858,7 @@ mod tests {
             GenericContractInfo { nonce: ADDR_1_NONCE, class_hash: ADDR_1_CLASS_HASH },
         );
 
-        let provider = SharedStateProvider(Arc::new(state_db));
+        let provider = SharedStateProvider(Arc::new(state_db), None, Default::default());
 
         assert_eq!(StateProvider::nonce(&provider, ADDR_1).unwrap(), Some(ADDR_1_NONCE));
         assert_eq!(
@@ -736,13 +871,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_from_persistent_cache_if_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ForkCache::open(dir.path(), ChainId::SEPOLIA, 1).unwrap();
+        cache.set_nonce(ADDR_1, ADDR_1_NONCE).unwrap();
+        cache.set_class_hash_of_contract(ADDR_1, ADDR_1_CLASS_HASH).unwrap();
+        cache.set_storage(ADDR_1, STORAGE_KEY, ADDR_1_STORAGE_VALUE).unwrap();
+
+        let backend = create_forked_backend(LOCAL_RPC_URL, 1);
+        let provider = SharedStateProvider::new_with_backend(backend, Some(cache));
+
+        assert_eq!(StateProvider::nonce(&provider, ADDR_1).unwrap(), Some(ADDR_1_NONCE));
+        assert_eq!(
+            StateProvider::storage(&provider, ADDR_1, STORAGE_KEY).unwrap(),
+            Some(ADDR_1_STORAGE_VALUE)
+        );
+        assert_eq!(
+            StateProvider::class_hash_of_contract(&provider, ADDR_1).unwrap(),
+            Some(ADDR_1_CLASS_HASH)
+        );
+
+        // the values read from the persistent cache are kept in memory
+        let info = provider.0.contract_state.read().get(&ADDR_1).copied();
+        assert_eq!(
+            info,
+            Some(GenericContractInfo { nonce: ADDR_1_NONCE, class_hash: ADDR_1_CLASS_HASH })
+        );
+    }
+
+    #[test]
+    fn rebase_discards_fetched_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ForkCache::open(dir.path(), ChainId::SEPOLIA, 1).unwrap();
+        cache.set_nonce(ADDR_1, ADDR_1_NONCE).unwrap();
+
+        let backend = create_forked_backend(LOCAL_RPC_URL, 1);
+        let provider = SharedStateProvider::new_with_backend(backend, Some(cache));
+
+        assert_eq!(StateProvider::nonce(&provider, ADDR_1).unwrap(), Some(ADDR_1_NONCE));
+        provider
+            .0
+            .storage
+            .write()
+            .entry(ADDR_1)
+            .or_default()
+            .insert(STORAGE_KEY, ADDR_1_STORAGE_VALUE);
+
+        provider.rebase(2).unwrap();
+
+        assert!(provider.0.contract_state.read().is_empty());
+        assert!(provider.0.storage.read().is_empty());
+
+        // the persistent cache now points to the cache of the new fork block
+        let cache = provider.1.as_ref().unwrap();
+        assert_eq!(cache.block(), 2);
+        assert_eq!(cache.nonce(ADDR_1).unwrap(), None);
+    }
+
     // TODO: unignore this once we have separate the spawning of the backend thread from the backend
     // creation
     #[test]
     #[ignore]
     fn fetch_from_fork_will_err_if_backend_thread_not_running() {
         let backend = create_forked_backend(LOCAL_RPC_URL, 1);
-        let provider = SharedStateProvider(Arc::new(CacheStateDb::new(backend)));
+        let provider =
+            SharedStateProvider(Arc::new(CacheStateDb::new(backend)), None, Default::default());
         assert!(StateProvider::nonce(&provider, ADDR_1).is_err())
     }
 
@@ -759,7 +953,8 @@ mod tests {
     #[ignore]
     fn fetch_from_fork_if_not_in_cache() {
         let backend = create_forked_backend(FORKED_URL, 908622);
-        let provider = SharedStateProvider(Arc::new(CacheStateDb::new(backend)));
+        let provider =
+            SharedStateProvider(Arc::new(CacheStateDb::new(backend)), None, Default::default());
 
         // fetch from remote
 
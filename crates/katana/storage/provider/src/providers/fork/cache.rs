@@ -0,0 +1,270 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use katana_db::abstraction::{Database, DbTx};
+use katana_db::mdbx::DbEnv;
+use katana_db::tables;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::chain::ChainId;
+use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
+use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
+use parking_lot::RwLock;
+
+use crate::providers::db::DbProvider;
+use crate::traits::contract::{ContractClassProvider, ContractClassWriter};
+use crate::traits::state::{StateFactoryProvider, StateProvider, StateWriter};
+use crate::ProviderResult;
+
+/// A persistent cache of the data fetched from a forked network.
+///
+/// The fetched nonces, storage values and classes are stored in a database located at
+/// `<dir>/<chain id>/<block number>`, so that they can be reused by every node forking the same
+/// network at the same block instead of being fetched again.
+#[derive(Debug)]
+pub struct ForkCache {
+    dir: PathBuf,
+    chain_id: ChainId,
+    /// The forked block, the database of its cache and whether it was created by a rebase.
+    db: RwLock<(BlockNumber, DbEnv, bool)>,
+}
+
+/// The number of entries stored in a [`ForkCache`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ForkCacheStats {
+    pub contracts: usize,
+    pub storage: usize,
+    pub classes: usize,
+}
+
+impl ForkCache {
+    /// Opens the cache of the network `chain_id` forked at block `block` located in `dir`, creating
+    /// it if it doesn't exist yet.
+    pub fn open(
+        dir: impl AsRef<Path>,
+        chain_id: ChainId,
+        block: BlockNumber,
+    ) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let db = Self::open_db(&dir, chain_id, block)?;
+        Ok(Self { dir, chain_id, db: RwLock::new((block, db, false)) })
+    }
+
+    /// Returns the forked block whose data is cached.
+    pub fn block(&self) -> BlockNumber {
+        self.db.read().0
+    }
+
+    /// Switches to the cache of the same network forked at block `block`.
+    ///
+    /// The cache of the previous block is removed if it was created by an earlier rebase, so that
+    /// following a network doesn't leave a database behind for every block it has been rebased on.
+    pub fn rebase(&self, block: BlockNumber) -> anyhow::Result<()> {
+        let created = !Self::path(&self.dir, self.chain_id, block).exists();
+        let db = Self::open_db(&self.dir, self.chain_id, block)?;
+        let (previous, previous_db, previous_created) =
+            std::mem::replace(&mut *self.db.write(), (block, db, created));
+
+        if previous_created && previous != block {
+            drop(previous_db);
+            let path = Self::path(&self.dir, self.chain_id, previous);
+            std::fs::remove_dir_all(&path)
+                .with_context(|| format!("Removing fork cache at path {}", path.display()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the path of the cache of the network `chain_id` forked at block `block` in `dir`.
+    pub fn path(dir: impl AsRef<Path>, chain_id: ChainId, block: BlockNumber) -> PathBuf {
+        dir.as_ref().join(format!("{:#x}", chain_id.id())).join(block.to_string())
+    }
+
+    /// Returns the number of entries stored in the cache.
+    pub fn stats(&self) -> ProviderResult<ForkCacheStats> {
+        let tx = self.db.read().1.tx()?;
+        let stats = ForkCacheStats {
+            contracts: tx.entries::<tables::ContractInfo>()?,
+            storage: tx.entries::<tables::ContractStorage>()?,
+            classes: tx.entries::<tables::CompiledClasses>()?,
+        };
+        tx.commit()?;
+        Ok(stats)
+    }
+
+    fn open_db(dir: &Path, chain_id: ChainId, block: BlockNumber) -> anyhow::Result<DbEnv> {
+        let path = Self::path(dir, chain_id, block);
+        katana_db::init_db(&path)
+            .with_context(|| format!("Opening fork cache at path {}", path.display()))
+    }
+
+    fn provider(&self) -> DbProvider {
+        DbProvider::new(self.db.read().1.clone())
+    }
+
+    // A zero nonce or class hash can't be distinguished from a value that hasn't been fetched yet,
+    // as both are stored in the same entry. See `SharedStateProvider::nonce`.
+
+    pub(crate) fn nonce(&self, address: ContractAddress) -> ProviderResult<Option<Nonce>> {
+        Ok(self.provider().latest()?.nonce(address)?.filter(|n| n != &Nonce::ZERO))
+    }
+
+    pub(crate) fn class_hash_of_contract(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<Option<ClassHash>> {
+        let hash = self.provider().latest()?.class_hash_of_contract(address)?;
+        Ok(hash.filter(|h| h != &ClassHash::ZERO))
+    }
+
+    pub(crate) fn storage(
+        &self,
+        address: ContractAddress,
+        key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        self.provider().latest()?.storage(address, key)
+    }
+
+    pub(crate) fn class(&self, hash: ClassHash) -> ProviderResult<Option<CompiledClass>> {
+        self.provider().latest()?.class(hash)
+    }
+
+    pub(crate) fn sierra_class(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<FlattenedSierraClass>> {
+        self.provider().latest()?.sierra_class(hash)
+    }
+
+    pub(crate) fn compiled_class_hash_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClassHash>> {
+        self.provider().latest()?.compiled_class_hash_of_class_hash(hash)
+    }
+
+    pub(crate) fn set_nonce(&self, address: ContractAddress, nonce: Nonce) -> ProviderResult<()> {
+        self.provider().set_nonce(address, nonce)
+    }
+
+    pub(crate) fn set_class_hash_of_contract(
+        &self,
+        address: ContractAddress,
+        hash: ClassHash,
+    ) -> ProviderResult<()> {
+        self.provider().set_class_hash_of_contract(address, hash)
+    }
+
+    pub(crate) fn set_storage(
+        &self,
+        address: ContractAddress,
+        key: StorageKey,
+        value: StorageValue,
+    ) -> ProviderResult<()> {
+        self.provider().set_storage(address, key, value)
+    }
+
+    pub(crate) fn set_compiled_class_hash_of_class_hash(
+        &self,
+        hash: ClassHash,
+        compiled_hash: CompiledClassHash,
+    ) -> ProviderResult<()> {
+        self.provider().set_compiled_class_hash_of_class_hash(hash, compiled_hash)
+    }
+
+    pub(crate) fn set_class(
+        &self,
+        hash: ClassHash,
+        compiled_hash: CompiledClassHash,
+        class: CompiledClass,
+        sierra: Option<FlattenedSierraClass>,
+    ) -> ProviderResult<()> {
+        self.provider().set_compiled_class_hash_of_class_hash(hash, compiled_hash)?;
+        self.provider().set_class(hash, class)?;
+        if let Some(sierra) = sierra {
+            self.provider().set_sierra_class(hash, sierra)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_sierra_class(
+        &self,
+        hash: ClassHash,
+        sierra: FlattenedSierraClass,
+    ) -> ProviderResult<()> {
+        self.provider().set_sierra_class(hash, sierra)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::chain::ChainId;
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::genesis::constant::{
+        DEFAULT_LEGACY_UDC_CASM, DEFAULT_LEGACY_UDC_CLASS_HASH,
+    };
+    use katana_primitives::{address, felt};
+
+    use super::{ForkCache, ForkCacheStats};
+
+    #[test]
+    fn persist_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let address = address!("0x1337");
+
+        {
+            let cache = ForkCache::open(dir.path(), ChainId::SEPOLIA, 100).unwrap();
+            cache.set_nonce(address, felt!("0x1")).unwrap();
+            cache.set_storage(address, felt!("0x2"), felt!("0x3")).unwrap();
+            cache
+                .set_class(
+                    DEFAULT_LEGACY_UDC_CLASS_HASH,
+                    DEFAULT_LEGACY_UDC_CLASS_HASH,
+                    DEFAULT_LEGACY_UDC_CASM.clone(),
+                    None,
+                )
+                .unwrap();
+        }
+
+        let cache = ForkCache::open(dir.path(), ChainId::SEPOLIA, 100).unwrap();
+        assert_eq!(cache.nonce(address).unwrap(), Some(felt!("0x1")));
+        assert_eq!(cache.storage(address, felt!("0x2")).unwrap(), Some(felt!("0x3")));
+        assert_eq!(
+            cache.class(DEFAULT_LEGACY_UDC_CLASS_HASH).unwrap(),
+            Some(DEFAULT_LEGACY_UDC_CASM.clone())
+        );
+        // the class hash of the contract was never fetched
+        assert_eq!(cache.class_hash_of_contract(address).unwrap(), None);
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats, ForkCacheStats { contracts: 1, storage: 1, classes: 1 });
+
+        // a different fork block uses a separate cache
+        let cache = ForkCache::open(dir.path(), ChainId::SEPOLIA, 101).unwrap();
+        assert_eq!(cache.nonce(address).unwrap(), None);
+
+        cache.rebase(100).unwrap();
+        assert_eq!(cache.block(), 100);
+        assert_eq!(cache.nonce(address).unwrap(), Some(felt!("0x1")));
+    }
+
+    #[test]
+    fn rebase_removes_previous_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |block| ForkCache::path(dir.path(), ChainId::SEPOLIA, block);
+
+        let cache = ForkCache::open(dir.path(), ChainId::SEPOLIA, 100).unwrap();
+
+        cache.rebase(101).unwrap();
+        cache.set_nonce(address!("0x1337"), felt!("0x1")).unwrap();
+        assert!(path(101).exists());
+
+        cache.rebase(102).unwrap();
+        assert_eq!(cache.block(), 102);
+        assert_eq!(cache.nonce(address!("0x1337")).unwrap(), None);
+
+        // only the cache created by the rebase is removed, the opened one is kept
+        assert!(!path(101).exists());
+        assert!(path(100).exists());
+        assert!(path(102).exists());
+    }
+}
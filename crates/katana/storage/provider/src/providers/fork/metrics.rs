@@ -0,0 +1,38 @@
+use dojo_metrics::Metrics;
+use metrics::Counter;
+
+/// Metrics of the lookups of the state fetched from the forked network, per kind of value.
+#[derive(Debug)]
+pub(crate) struct ForkCacheMetrics {
+    pub(crate) nonce: ForkCacheLookupMetrics,
+    pub(crate) storage: ForkCacheLookupMetrics,
+    pub(crate) class_hash: ForkCacheLookupMetrics,
+    pub(crate) sierra_class: ForkCacheLookupMetrics,
+    pub(crate) compiled_class_hash: ForkCacheLookupMetrics,
+    pub(crate) class: ForkCacheLookupMetrics,
+}
+
+impl Default for ForkCacheMetrics {
+    fn default() -> Self {
+        let new = |kind: &'static str| ForkCacheLookupMetrics::new_with_labels(&[("kind", kind)]);
+        Self {
+            nonce: new("nonce"),
+            storage: new("storage"),
+            class_hash: new("class_hash"),
+            sierra_class: new("sierra_class"),
+            compiled_class_hash: new("compiled_class_hash"),
+            class: new("class"),
+        }
+    }
+}
+
+#[derive(Metrics)]
+#[metrics(scope = "fork.cache")]
+pub(crate) struct ForkCacheLookupMetrics {
+    /// The number of values found in the in-memory cache.
+    pub(crate) memory_hits_total: Counter,
+    /// The number of values found in the persistent cache.
+    pub(crate) persistent_hits_total: Counter,
+    /// The number of values fetched from the forked network.
+    pub(crate) misses_total: Counter,
+}
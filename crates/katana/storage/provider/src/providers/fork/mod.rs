@@ -1,41 +1,57 @@
 pub mod backend;
+pub mod cache;
+mod metrics;
 pub mod state;
 
 use std::ops::{Range, RangeInclusive};
 use std::sync::Arc;
 
 use katana_db::models::block::StoredBlockBodyIndices;
+use katana_db::models::trie::TrieRoots;
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
     SealedBlockWithStatus,
 };
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
-use katana_primitives::contract::ContractAddress;
+use katana_primitives::contract::{ContractAddress, StorageKey};
+use katana_primitives::da::StateDiffBlob;
 use katana_primitives::env::BlockEnv;
+use katana_primitives::event::EventFilter;
+use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash, L1TxHash, MessageCancellation};
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{Tx, TxHash, TxNumber, TxWithHash};
+use katana_trie::MultiProof;
 use parking_lot::RwLock;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 
 use self::backend::{Backend, BackendError, SharedStateProvider};
+use self::cache::ForkCache;
 use self::state::ForkedStateDb;
 use super::in_memory::cache::{CacheDb, CacheStateDb};
 use super::in_memory::state::HistoricalStates;
+use crate::error::ProviderError;
 use crate::traits::block::{
-    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
-    HeaderProvider,
+    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockUnwinder,
+    BlockWriter, HeaderProvider,
 };
 use crate::traits::contract::ContractClassWriter;
+use crate::traits::da::{StateDiffBlobProvider, StateDiffBlobWriter};
 use crate::traits::env::BlockEnvProvider;
-use crate::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider, StateWriter};
+use crate::traits::event::EventIndexProvider;
+use crate::traits::message::{L1ToL2MessageProvider, L1ToL2MessageWriter};
+use crate::traits::stage::{StageCheckpointProvider, StageCheckpointWriter};
+use crate::traits::state::{
+    StateDumpProvider, StateFactoryProvider, StateProvider, StateRootProvider, StateWriter,
+};
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
     ReceiptProvider, TransactionProvider, TransactionStatusProvider, TransactionTraceProvider,
     TransactionsProviderExt,
 };
+use crate::traits::trie::{StateProof, StateProofProvider, TrieWriter};
 use crate::ProviderResult;
 
 #[derive(Debug)]
@@ -50,9 +66,27 @@ impl ForkedProvider {
     pub fn new(
         provider: Arc<JsonRpcClient<HttpTransport>>,
         block_id: BlockHashOrNumber,
+    ) -> Result<Self, BackendError> {
+        Self::new_inner(provider, block_id, None)
+    }
+
+    /// Creates a new [`ForkedProvider`] that stores the data fetched from the forked network in
+    /// `cache`, and reads it from there when it has already been fetched by a previous run.
+    pub fn new_with_cache(
+        provider: Arc<JsonRpcClient<HttpTransport>>,
+        block_id: BlockHashOrNumber,
+        cache: ForkCache,
+    ) -> Result<Self, BackendError> {
+        Self::new_inner(provider, block_id, Some(cache))
+    }
+
+    fn new_inner(
+        provider: Arc<JsonRpcClient<HttpTransport>>,
+        block_id: BlockHashOrNumber,
+        cache: Option<ForkCache>,
     ) -> Result<Self, BackendError> {
         let backend = Backend::new(provider, block_id)?;
-        let shared_provider = SharedStateProvider::new_with_backend(backend);
+        let shared_provider = SharedStateProvider::new_with_backend(backend, cache);
 
         let storage = RwLock::new(CacheDb::new(()));
         let state = Arc::new(CacheStateDb::new(shared_provider));
@@ -60,6 +94,30 @@ impl ForkedProvider {
 
         Ok(Self { storage, state, historical_states })
     }
+
+    /// Rebases the provider onto block `block` of the forked network.
+    ///
+    /// All the locally produced blocks and state changes are discarded, and `genesis` - which must
+    /// be derived from the forked block - becomes the new local genesis block. Subsequent reads
+    /// of data that doesn't exist locally are fetched from the forked network at block `block`.
+    pub fn rebase(
+        &self,
+        block: BlockNumber,
+        genesis: SealedBlockWithStatus,
+        states: StateUpdatesWithDeclaredClasses,
+    ) -> ProviderResult<()> {
+        *self.storage.write() = CacheDb::new(());
+        *self.historical_states.write() = HistoricalStates::default();
+
+        // Classes are kept as they are immutable, but those declared locally must be declared
+        // again on top of the new fork block.
+        self.state.storage.write().clear();
+        self.state.contract_state.write().clear();
+        self.state.compiled_class_hashes.write().clear();
+        self.state.db.rebase(block)?;
+
+        self.insert_block_with_states_and_receipts(genesis, states, Vec::new(), Vec::new())
+    }
 }
 
 impl BlockHashProvider for ForkedProvider {
@@ -406,6 +464,95 @@ impl ReceiptProvider for ForkedProvider {
     }
 }
 
+// The local chain of a fork is usually short, so its events aren't indexed and every block in the
+// range is considered.
+impl EventIndexProvider for ForkedProvider {
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        _filter: &EventFilter,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(range.collect())
+    }
+}
+
+impl L1ToL2MessageProvider for ForkedProvider {
+    fn message(&self, hash: L1ToL2MessageHash) -> ProviderResult<Option<L1ToL2Message>> {
+        Ok(self.storage.read().l1_to_l2_messages.get(&hash).cloned())
+    }
+
+    fn messages_by_l1_tx(&self, hash: L1TxHash) -> ProviderResult<Option<Vec<L1ToL2Message>>> {
+        let storage = self.storage.read();
+        let mut messages = storage
+            .l1_to_l2_messages
+            .values()
+            .filter(|message| message.l1_tx_hash == hash)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        messages.sort_by_key(|message| message.message_hash);
+        Ok(if messages.is_empty() { None } else { Some(messages) })
+    }
+}
+
+impl L1ToL2MessageWriter for ForkedProvider {
+    fn insert_message(&self, message: L1ToL2Message) -> ProviderResult<()> {
+        self.storage.write().l1_to_l2_messages.insert(message.message_hash, message);
+        Ok(())
+    }
+
+    fn set_message_cancellation(
+        &self,
+        hash: L1ToL2MessageHash,
+        cancellation: MessageCancellation,
+    ) -> ProviderResult<bool> {
+        let mut storage = self.storage.write();
+        let Some(message) = storage.l1_to_l2_messages.get_mut(&hash) else { return Ok(false) };
+        message.cancellation = Some(cancellation);
+        Ok(true)
+    }
+
+    fn set_message_dropped(&self, hash: L1ToL2MessageHash) -> ProviderResult<bool> {
+        let mut storage = self.storage.write();
+        let Some(message) = storage.l1_to_l2_messages.get_mut(&hash) else { return Ok(false) };
+        message.dropped = true;
+        Ok(true)
+    }
+}
+
+impl StageCheckpointProvider for ForkedProvider {
+    fn checkpoint(&self, id: &str) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self.storage.read().stage_checkpoints.get(id).copied())
+    }
+}
+
+impl StageCheckpointWriter for ForkedProvider {
+    fn set_checkpoint(&self, id: &str, block_number: BlockNumber) -> ProviderResult<()> {
+        self.storage.write().stage_checkpoints.insert(id.to_string(), block_number);
+        Ok(())
+    }
+}
+
+impl StateDiffBlobProvider for ForkedProvider {
+    fn state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<Vec<StateDiffBlob>>> {
+        Ok(self.storage.read().state_diff_blobs.get(&block_number).cloned())
+    }
+}
+
+impl StateDiffBlobWriter for ForkedProvider {
+    fn insert_state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+        blobs: Vec<StateDiffBlob>,
+    ) -> ProviderResult<()> {
+        self.storage.write().state_diff_blobs.insert(block_number, blobs);
+        Ok(())
+    }
+}
+
 impl StateRootProvider for ForkedProvider {
     fn state_root(
         &self,
@@ -418,6 +565,62 @@ impl StateRootProvider for ForkedProvider {
     }
 }
 
+// Computing the state tries requires the full state of the forked chain, which is not available
+// locally. Hence, the state root of the blocks produced on top of a fork is left as zero and state
+// proofs are not supported.
+impl TrieWriter for ForkedProvider {
+    fn trie_insert_state_updates(
+        &self,
+        _block_number: BlockNumber,
+        _state_updates: &StateUpdates,
+    ) -> ProviderResult<katana_primitives::Felt> {
+        Ok(katana_primitives::Felt::ZERO)
+    }
+}
+
+impl StateProofProvider for ForkedProvider {
+    fn trie_roots(&self, _block_number: BlockNumber) -> ProviderResult<Option<TrieRoots>> {
+        Ok(None)
+    }
+
+    fn classes_proof(
+        &self,
+        _block_number: BlockNumber,
+        _class_hashes: &[ClassHash],
+    ) -> ProviderResult<MultiProof> {
+        Err(ProviderError::Other("state proofs are not supported in forked mode".to_string()))
+    }
+
+    fn contracts_proof(
+        &self,
+        _block_number: BlockNumber,
+        _addresses: &[ContractAddress],
+    ) -> ProviderResult<MultiProof> {
+        Err(ProviderError::Other("state proofs are not supported in forked mode".to_string()))
+    }
+
+    fn storage_root(&self, _address: ContractAddress) -> ProviderResult<katana_primitives::Felt> {
+        Err(ProviderError::Other("state proofs are not supported in forked mode".to_string()))
+    }
+
+    fn storage_proof(
+        &self,
+        _address: ContractAddress,
+        _keys: &[StorageKey],
+    ) -> ProviderResult<MultiProof> {
+        Err(ProviderError::Other("state proofs are not supported in forked mode".to_string()))
+    }
+
+    fn latest_state_proof(
+        &self,
+        _class_hashes: &[ClassHash],
+        _addresses: &[ContractAddress],
+        _storage_keys: &[(ContractAddress, Vec<StorageKey>)],
+    ) -> ProviderResult<Option<StateProof>> {
+        Ok(None)
+    }
+}
+
 impl StateUpdateProvider for ForkedProvider {
     fn state_update(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<StateUpdates>> {
         let block_num = match block_id {
@@ -514,6 +717,91 @@ impl BlockWriter for ForkedProvider {
 
         Ok(())
     }
+
+    // the state tries aren't maintained in forked mode, see the `TrieWriter` implementation
+    fn insert_block_with_trie_updates(
+        &self,
+        mut block: Block,
+        status: FinalityStatus,
+        states: StateUpdatesWithDeclaredClasses,
+        receipts: Vec<Receipt>,
+        executions: Vec<TxExecInfo>,
+        blobs: Option<Vec<StateDiffBlob>>,
+    ) -> ProviderResult<BlockHash> {
+        block.header.state_root = katana_primitives::Felt::ZERO;
+        let block_number = block.header.number;
+        let block = SealedBlockWithStatus { block: block.seal(), status };
+        let block_hash = block.block.hash;
+
+        self.insert_block_with_states_and_receipts(block, states, receipts, executions)?;
+
+        if let Some(blobs) = blobs {
+            self.storage.write().state_diff_blobs.insert(block_number, blobs);
+        }
+
+        Ok(block_hash)
+    }
+}
+
+impl BlockUnwinder for ForkedProvider {
+    fn unwind_to(&self, block_number: BlockNumber) -> ProviderResult<()> {
+        let mut storage = self.storage.write();
+
+        let latest = storage.latest_block_number;
+        if block_number >= latest {
+            return Ok(());
+        }
+
+        let hash = *storage
+            .block_hashes
+            .get(&block_number)
+            .ok_or(ProviderError::MissingBlockHash(block_number))?;
+
+        let tx_end = storage
+            .block_body_indices
+            .get(&block_number)
+            .map(|indices| indices.tx_offset + indices.tx_count)
+            .ok_or(ProviderError::MissingBlockBodyIndices(block_number))?;
+
+        let mut reverted = Vec::new();
+
+        for num in block_number + 1..=latest {
+            if let Some(hash) = storage.block_hashes.remove(&num) {
+                storage.block_numbers.remove(&hash);
+            }
+
+            storage.block_headers.remove(&num);
+            storage.block_statusses.remove(&num);
+            storage.block_body_indices.remove(&num);
+            storage.state_diff_blobs.remove(&num);
+
+            if let Some(state_update) = storage.state_update.remove(&num) {
+                reverted.push(state_update);
+            }
+        }
+
+        for tx_number in tx_end..storage.transactions.len() as u64 {
+            if let Some(hash) = storage.transaction_hashes.remove(&tx_number) {
+                storage.transaction_numbers.remove(&hash);
+            }
+            storage.transaction_block.remove(&tx_number);
+        }
+
+        storage.transactions.truncate(tx_end as usize);
+        storage.receipts.truncate(tx_end as usize);
+        storage.transactions_executions.truncate(tx_end as usize);
+
+        storage.latest_block_hash = hash;
+        storage.latest_block_number = block_number;
+
+        let mut remaining = storage.state_update.iter().collect::<Vec<_>>();
+        remaining.sort_by_key(|(num, _)| **num);
+
+        self.state.revert_updates(&reverted, remaining.into_iter().map(|(_, updates)| updates));
+        self.historical_states.write().truncate(block_number);
+
+        Ok(())
+    }
 }
 
 impl ContractClassWriter for ForkedProvider {
@@ -571,6 +859,41 @@ impl StateWriter for ForkedProvider {
     }
 }
 
+impl StateDumpProvider for ForkedProvider {
+    /// The state of the forked network can't be enumerated, so only the state that exists locally
+    /// is dumped, ie the state changes made on top of the fork block.
+    fn dump_state(&self) -> ProviderResult<StateUpdatesWithDeclaredClasses> {
+        let latest = self.latest()?;
+        let mut states = StateUpdatesWithDeclaredClasses::default();
+
+        for (address, info) in self.state.contract_state.read().iter() {
+            // contracts whose nonce was updated locally may not have their class hash cached
+            let class_hash = if info.class_hash == ClassHash::ZERO {
+                latest.class_hash_of_contract(*address)?.unwrap_or_default()
+            } else {
+                info.class_hash
+            };
+
+            states.state_updates.deployed_contracts.insert(*address, class_hash);
+            states.state_updates.nonce_updates.insert(*address, info.nonce);
+        }
+
+        for (address, storage) in self.state.storage.read().iter() {
+            let entries = storage.iter().map(|(key, value)| (*key, *value));
+            states.state_updates.storage_updates.entry(*address).or_default().extend(entries);
+        }
+
+        let compiled_class_hashes = self.state.compiled_class_hashes.read().clone();
+        states.state_updates.declared_classes.extend(compiled_class_hashes);
+
+        let classes = &self.state.shared_contract_classes;
+        states.declared_sierra_classes.extend(classes.sierra_classes.read().clone());
+        states.declared_compiled_classes.extend(classes.compiled_classes.read().clone());
+
+        Ok(states)
+    }
+}
+
 impl BlockEnvProvider for ForkedProvider {
     fn block_env_at(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<BlockEnv>> {
         Ok(self.header(block_id)?.map(|header| BlockEnv {
@@ -237,7 +237,7 @@ mod tests {
 
         // Case: contract doesn't exist at all
         {
-            let remote = SharedStateProvider::new_with_backend(backend.clone());
+            let remote = SharedStateProvider::new_with_backend(backend.clone(), None);
             let local = ForkedStateDb::new(remote.clone());
 
             // asserts that its error for now
@@ -251,7 +251,7 @@ mod tests {
 
         // Case: contract exist remotely
         {
-            let remote = SharedStateProvider::new_with_backend(backend.clone());
+            let remote = SharedStateProvider::new_with_backend(backend.clone(), None);
             let local = ForkedStateDb::new(remote.clone());
 
             let nonce_updates = BTreeMap::from([(address, remote_nonce)]);
@@ -271,7 +271,7 @@ mod tests {
 
         // Case: contract exist remotely but nonce was updated locally
         {
-            let remote = SharedStateProvider::new_with_backend(backend.clone());
+            let remote = SharedStateProvider::new_with_backend(backend.clone(), None);
             let local = ForkedStateDb::new(remote.clone());
 
             let nonce_updates = BTreeMap::from([(address, remote_nonce)]);
@@ -303,7 +303,7 @@ mod tests {
 
         // Case: contract was deployed locally only and has non-zero nonce
         {
-            let remote = SharedStateProvider::new_with_backend(backend.clone());
+            let remote = SharedStateProvider::new_with_backend(backend.clone(), None);
             let local = ForkedStateDb::new(remote.clone());
 
             let deployed_contracts = BTreeMap::from([(address, class_hash)]);
@@ -328,7 +328,7 @@ mod tests {
 
         // Case: contract was deployed locally only and has zero nonce
         {
-            let remote = SharedStateProvider::new_with_backend(backend.clone());
+            let remote = SharedStateProvider::new_with_backend(backend.clone(), None);
             let local = ForkedStateDb::new(remote.clone());
 
             let deployed_contracts = BTreeMap::from([(address, class_hash)]);
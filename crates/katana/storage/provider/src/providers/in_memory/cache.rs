@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use katana_db::models::block::StoredBlockBodyIndices;
 use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use katana_primitives::contract::{ContractAddress, GenericContractInfo, StorageKey, StorageValue};
+use katana_primitives::da::StateDiffBlob;
+use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash};
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
@@ -69,6 +71,88 @@ impl<Db> CacheStateDb<Db> {
         sierra_classes.extend(updates.declared_sierra_classes);
         compiled_classes.extend(updates.declared_compiled_classes);
     }
+
+    /// Reverts the changes made by the `reverted` state updates.
+    ///
+    /// Every entry changed by `reverted` is removed from the cache and then restored from
+    /// `remaining`, the state updates of the blocks that are kept, in ascending block order.
+    /// Entries that are not updated by any of the `remaining` blocks will be read from the
+    /// underlying database again.
+    pub fn revert_updates<'a>(
+        &self,
+        reverted: &[StateUpdates],
+        remaining: impl IntoIterator<Item = &'a StateUpdates>,
+    ) {
+        let mut storage = self.storage.write();
+        let mut contract_state = self.contract_state.write();
+        let mut compiled_class_hashes = self.compiled_class_hashes.write();
+        let mut sierra_classes = self.shared_contract_classes.sierra_classes.write();
+        let mut compiled_classes = self.shared_contract_classes.compiled_classes.write();
+
+        let mut storage_keys = HashSet::new();
+        let mut nonces = HashSet::new();
+        let mut class_hashes = HashSet::new();
+        let mut declared = HashSet::new();
+
+        for updates in reverted {
+            for (address, entries) in &updates.storage_updates {
+                storage_keys.extend(entries.keys().map(|key| (*address, *key)));
+            }
+
+            nonces.extend(updates.nonce_updates.keys().copied());
+            class_hashes.extend(updates.deployed_contracts.keys().copied());
+            declared.extend(updates.declared_classes.keys().copied());
+        }
+
+        for (address, key) in &storage_keys {
+            if let Some(entries) = storage.get_mut(address) {
+                entries.remove(key);
+            }
+        }
+
+        for address in &nonces {
+            if let Some(info) = contract_state.get_mut(address) {
+                info.nonce = Default::default();
+            }
+        }
+
+        for address in &class_hashes {
+            if let Some(info) = contract_state.get_mut(address) {
+                info.class_hash = Default::default();
+            }
+        }
+
+        for hash in &declared {
+            compiled_class_hashes.remove(hash);
+            sierra_classes.remove(hash);
+            compiled_classes.remove(hash);
+        }
+
+        for updates in remaining {
+            for (address, entries) in &updates.storage_updates {
+                for (key, value) in entries {
+                    if storage_keys.contains(&(*address, *key)) {
+                        storage.entry(*address).or_default().insert(*key, *value);
+                    }
+                }
+            }
+
+            for (address, nonce) in &updates.nonce_updates {
+                if nonces.contains(address) {
+                    contract_state.entry(*address).or_default().nonce = *nonce;
+                }
+            }
+
+            for (address, class_hash) in &updates.deployed_contracts {
+                if class_hashes.contains(address) {
+                    contract_state.entry(*address).or_default().class_hash = *class_hash;
+                }
+            }
+        }
+
+        storage.retain(|_, entries| !entries.is_empty());
+        contract_state.retain(|_, info| *info != GenericContractInfo::default());
+    }
 }
 
 #[derive(Debug)]
@@ -88,6 +172,9 @@ pub struct CacheDb<Db> {
     pub(crate) transaction_hashes: HashMap<TxNumber, TxHash>,
     pub(crate) transaction_numbers: HashMap<TxHash, TxNumber>,
     pub(crate) transaction_block: HashMap<TxNumber, BlockNumber>,
+    pub(crate) l1_to_l2_messages: HashMap<L1ToL2MessageHash, L1ToL2Message>,
+    pub(crate) stage_checkpoints: HashMap<String, BlockNumber>,
+    pub(crate) state_diff_blobs: HashMap<BlockNumber, Vec<StateDiffBlob>>,
 }
 
 impl<Db> CacheStateDb<Db> {
@@ -118,6 +205,9 @@ impl<Db> CacheDb<Db> {
             block_body_indices: HashMap::new(),
             transaction_numbers: HashMap::new(),
             transactions_executions: Vec::new(),
+            l1_to_l2_messages: HashMap::new(),
+            stage_checkpoints: HashMap::new(),
+            state_diff_blobs: HashMap::new(),
             latest_block_hash: Default::default(),
             latest_block_number: Default::default(),
         }
@@ -67,6 +67,12 @@ impl HistoricalStates {
         self.present.push_back(block_num);
     }
 
+    /// Removes the states of all the blocks after `block_num`.
+    pub fn truncate(&mut self, block_num: BlockNumber) {
+        self.present.retain(|num| *num <= block_num);
+        self.states.retain(|num, _| *num <= block_num);
+    }
+
     /// Enforces configured limits
     fn enforce_limits(&mut self) {
         // enforce memory limits
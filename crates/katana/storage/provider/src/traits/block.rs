@@ -5,9 +5,11 @@ use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockIdOrTag, BlockNumber, BlockTag, BlockWithTxHashes,
     FinalityStatus, Header, SealedBlockWithStatus,
 };
+use katana_primitives::da::StateDiffBlob;
 use katana_primitives::receipt::Receipt;
-use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
+use katana_primitives::transaction::TxWithHash;
 
 use super::transaction::{TransactionProvider, TransactionsProviderExt};
 use crate::ProviderResult;
@@ -150,4 +152,63 @@ pub trait BlockWriter: Send + Sync {
         receipts: Vec<Receipt>,
         executions: Vec<TxExecInfo>,
     ) -> ProviderResult<()>;
+
+    /// Applies the state updates of an executed block to the state tries, and stores the block
+    /// with the resulting state root along with its execution output and the blobs of its state
+    /// diff, if any. Everything is written atomically, so that the tries can't get out of sync
+    /// with the stored blocks.
+    ///
+    /// The state root of the block header is overwritten. Returns the hash of the sealed block.
+    fn insert_block_with_trie_updates(
+        &self,
+        block: Block,
+        status: FinalityStatus,
+        states: StateUpdatesWithDeclaredClasses,
+        receipts: Vec<Receipt>,
+        executions: Vec<TxExecInfo>,
+        blobs: Option<Vec<StateDiffBlob>>,
+    ) -> ProviderResult<BlockHash>;
+}
+
+/// Writes the different parts of a block separately.
+///
+/// Unlike [`BlockWriter`], this is meant for blocks whose parts are not all available at once, eg.
+/// when they are downloaded by the different stages of the sync pipeline.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait BlockPartsWriter: Send + Sync {
+    /// Stores the header of a block along with its hash and finality status.
+    fn insert_header(
+        &self,
+        hash: BlockHash,
+        header: Header,
+        status: FinalityStatus,
+    ) -> ProviderResult<()>;
+
+    /// Stores the transactions of a block along with their receipts.
+    ///
+    /// Bodies must be inserted in increasing block order as the transaction numbers are assigned
+    /// sequentially.
+    fn insert_body(
+        &self,
+        block_number: BlockNumber,
+        transactions: Vec<TxWithHash>,
+        receipts: Vec<Receipt>,
+    ) -> ProviderResult<()>;
+
+    /// Applies the state updates of a block. The definitions of the declared classes have to be
+    /// stored separately.
+    fn insert_state_updates(
+        &self,
+        block_number: BlockNumber,
+        state_updates: StateUpdates,
+    ) -> ProviderResult<()>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait BlockUnwinder: Send + Sync {
+    /// Unwinds the chain to `block_number`, removing every block after it along with their
+    /// transactions, receipts and traces, and reverting the state to the one at `block_number`.
+    ///
+    /// Does nothing if `block_number` is greater than or equal to the latest block number.
+    fn unwind_to(&self, block_number: BlockNumber) -> ProviderResult<()>;
 }
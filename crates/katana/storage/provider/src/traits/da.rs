@@ -0,0 +1,25 @@
+use katana_primitives::block::BlockNumber;
+use katana_primitives::da::StateDiffBlob;
+
+use crate::ProviderResult;
+
+/// A provider for the EIP-4844 blobs of the state diffs of the blocks.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait StateDiffBlobProvider: Send + Sync {
+    /// Returns the blobs of the state diff of the given block, or `None` if they haven't been
+    /// computed.
+    fn state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<Vec<StateDiffBlob>>>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait StateDiffBlobWriter: Send + Sync {
+    /// Stores the blobs of the state diff of the given block.
+    fn insert_state_diff_blobs(
+        &self,
+        block_number: BlockNumber,
+        blobs: Vec<StateDiffBlob>,
+    ) -> ProviderResult<()>;
+}
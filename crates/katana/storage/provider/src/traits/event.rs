@@ -0,0 +1,22 @@
+use std::ops::RangeInclusive;
+
+use katana_primitives::block::BlockNumber;
+use katana_primitives::event::EventFilter;
+
+use crate::ProviderResult;
+
+/// A provider for finding the blocks that emitted the events matching a filter, without going
+/// through the receipts of every block.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait EventIndexProvider: Send + Sync {
+    /// Returns the blocks in `range` that may have emitted events matching `filter`, in ascending
+    /// order.
+    ///
+    /// The returned blocks may include blocks without any matching events, so their events must
+    /// still be filtered, but a block with matching events is never left out.
+    fn blocks_with_events(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        filter: &EventFilter,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+}
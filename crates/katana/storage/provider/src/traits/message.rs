@@ -0,0 +1,33 @@
+use katana_primitives::message::{L1ToL2Message, L1ToL2MessageHash, L1TxHash, MessageCancellation};
+
+use crate::ProviderResult;
+
+/// A provider for the L1 to L2 messages gathered from the settlement chain.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait L1ToL2MessageProvider: Send + Sync {
+    /// Returns the message with the given hash, or `None` if it hasn't been gathered.
+    fn message(&self, hash: L1ToL2MessageHash) -> ProviderResult<Option<L1ToL2Message>>;
+
+    /// Returns the messages sent by the settlement chain transaction with the given hash, sorted by
+    /// their hashes, or `None` if none of its messages have been gathered.
+    fn messages_by_l1_tx(&self, hash: L1TxHash) -> ProviderResult<Option<Vec<L1ToL2Message>>>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait L1ToL2MessageWriter: Send + Sync {
+    /// Stores a message gathered from the settlement chain, replacing the existing one with the
+    /// same hash, if any.
+    fn insert_message(&self, message: L1ToL2Message) -> ProviderResult<()>;
+
+    /// Updates the cancellation progress of the message with the given hash. Returns `false` if
+    /// the message hasn't been gathered.
+    fn set_message_cancellation(
+        &self,
+        hash: L1ToL2MessageHash,
+        cancellation: MessageCancellation,
+    ) -> ProviderResult<bool>;
+
+    /// Records that the transaction of the message with the given hash was dropped without being
+    /// committed in a block. Returns `false` if the message hasn't been gathered.
+    fn set_message_dropped(&self, hash: L1ToL2MessageHash) -> ProviderResult<bool>;
+}
@@ -1,6 +1,11 @@
 pub mod block;
 pub mod contract;
+pub mod da;
 pub mod env;
+pub mod event;
+pub mod message;
+pub mod stage;
 pub mod state;
 pub mod state_update;
 pub mod transaction;
+pub mod trie;
@@ -0,0 +1,17 @@
+use katana_primitives::block::BlockNumber;
+
+use crate::ProviderResult;
+
+/// A provider for the progress of the sync pipeline stages.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait StageCheckpointProvider: Send + Sync {
+    /// Returns the last block processed by the stage with the given id, or `None` if the stage
+    /// hasn't processed any block yet.
+    fn checkpoint(&self, id: &str) -> ProviderResult<Option<BlockNumber>>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait StageCheckpointWriter: Send + Sync {
+    /// Sets the last block processed by the stage with the given id.
+    fn set_checkpoint(&self, id: &str, block_number: BlockNumber) -> ProviderResult<()>;
+}
@@ -1,6 +1,7 @@
 use katana_primitives::block::BlockHashOrNumber;
 use katana_primitives::class::ClassHash;
 use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::Felt;
 
 use super::contract::ContractClassProvider;
@@ -63,3 +64,11 @@ pub trait StateWriter: Send + Sync {
         class_hash: ClassHash,
     ) -> ProviderResult<()>;
 }
+
+/// A type which can export the whole latest state at once.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait StateDumpProvider: Send + Sync {
+    /// Returns the latest state as a single state update: every deployed contract along with its
+    /// nonce and storage, and every declared class along with its definitions.
+    fn dump_state(&self) -> ProviderResult<StateUpdatesWithDeclaredClasses>;
+}
@@ -0,0 +1,92 @@
+use katana_db::models::trie::TrieRoots;
+use katana_primitives::block::{BlockHash, BlockNumber};
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{ContractAddress, Nonce, StorageKey};
+use katana_primitives::state::StateUpdates;
+use katana_primitives::Felt;
+use katana_trie::MultiProof;
+
+use crate::ProviderResult;
+
+/// A type that maintains the tries from which the global state root is derived.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait TrieWriter: Send + Sync {
+    /// Applies the state updates of a block to the classes, contracts and storage tries and
+    /// returns the new global state root.
+    ///
+    /// Values that are not part of the state updates (eg. the class hash of a contract whose only
+    /// its storage is updated) are read from the latest state. All the declared classes are
+    /// inserted in the classes trie, so they must not include the legacy (Cairo 0) classes, which
+    /// are not part of it.
+    fn trie_insert_state_updates(
+        &self,
+        block_number: BlockNumber,
+        state_updates: &StateUpdates,
+    ) -> ProviderResult<Felt>;
+}
+
+/// The data of a contract's leaf in the contracts trie.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContractLeaf {
+    pub nonce: Nonce,
+    pub class_hash: ClassHash,
+    pub storage_root: Felt,
+}
+
+/// The proofs of a set of classes, contracts and storage keys at a block, along with the roots of
+/// the tries they are proven against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateProof {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    pub roots: TrieRoots,
+    pub classes_proof: MultiProof,
+    pub contracts_proof: MultiProof,
+    /// The leaves of the contracts, in the order of the proven addresses.
+    pub contract_leaves: Vec<ContractLeaf>,
+    /// The proofs of the storage keys, in the order of the proven contracts.
+    pub storage_proofs: Vec<MultiProof>,
+}
+
+/// A provider of Merkle proofs for the global state.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait StateProofProvider: Send + Sync {
+    /// Returns the roots of the classes and contracts tries at the given block.
+    fn trie_roots(&self, block_number: BlockNumber) -> ProviderResult<Option<TrieRoots>>;
+
+    /// Returns the proof of the given classes in the classes trie at the given block.
+    fn classes_proof(
+        &self,
+        block_number: BlockNumber,
+        class_hashes: &[ClassHash],
+    ) -> ProviderResult<MultiProof>;
+
+    /// Returns the proof of the given contracts in the contracts trie at the given block.
+    fn contracts_proof(
+        &self,
+        block_number: BlockNumber,
+        addresses: &[ContractAddress],
+    ) -> ProviderResult<MultiProof>;
+
+    /// Returns the latest root of a contract's storage trie.
+    fn storage_root(&self, address: ContractAddress) -> ProviderResult<Felt>;
+
+    /// Returns the proof of the given storage keys in the latest storage trie of a contract.
+    fn storage_proof(
+        &self,
+        address: ContractAddress,
+        keys: &[StorageKey],
+    ) -> ProviderResult<MultiProof>;
+
+    /// Returns the proofs of the given classes, contracts and contracts' storage keys at the
+    /// latest block. Unlike the individual proofs, the roots and all the proofs are read from the
+    /// same state, even if a block is committed in the meantime.
+    ///
+    /// Returns `None` if the tries aren't maintained.
+    fn latest_state_proof(
+        &self,
+        class_hashes: &[ClassHash],
+        addresses: &[ContractAddress],
+        storage_keys: &[(ContractAddress, Vec<StorageKey>)],
+    ) -> ProviderResult<Option<StateProof>>;
+}
@@ -8,7 +8,8 @@ use katana_primitives::transaction::TxWithHash;
 use katana_provider::providers::db::DbProvider;
 use katana_provider::providers::fork::ForkedProvider;
 use katana_provider::traits::block::{
-    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
+    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockUnwinder,
+    BlockWriter,
 };
 use katana_provider::traits::env::BlockEnvProvider;
 use katana_provider::traits::state::StateRootProvider;
@@ -272,6 +273,90 @@ where
     Ok(())
 }
 
+#[apply(unwind_block_cases)]
+fn unwind_block_with_fork_provider(
+    #[from(fork_provider)] provider: BlockchainProvider<ForkedProvider>,
+    #[case] block_count: u64,
+    #[case] unwind_to: BlockNumber,
+) -> Result<()> {
+    unwind_block_test_impl(provider, block_count, unwind_to)
+}
+
+#[apply(unwind_block_cases)]
+fn unwind_block_with_db_provider(
+    #[from(db_provider)] provider: BlockchainProvider<DbProvider>,
+    #[case] block_count: u64,
+    #[case] unwind_to: BlockNumber,
+) -> Result<()> {
+    unwind_block_test_impl(provider, block_count, unwind_to)
+}
+
+fn unwind_block_test_impl<Db>(
+    provider: BlockchainProvider<Db>,
+    count: u64,
+    unwind_to: BlockNumber,
+) -> Result<()>
+where
+    Db: BlockProvider
+        + BlockWriter
+        + BlockUnwinder
+        + ReceiptProvider
+        + TransactionStatusProvider
+        + TransactionTraceProvider,
+{
+    let blocks = utils::generate_dummy_blocks_and_receipts(count);
+
+    for (block, receipts, executions) in &blocks {
+        provider.insert_block_with_states_and_receipts(
+            block.clone(),
+            Default::default(),
+            receipts.clone(),
+            executions.clone(),
+        )?;
+    }
+
+    provider.unwind_to(unwind_to)?;
+
+    let latest = unwind_to.min(count - 1);
+    assert_eq!(provider.latest_number()?, latest);
+    assert_eq!(provider.latest_hash()?, blocks[latest as usize].0.block.hash);
+
+    for (block, receipts, _) in &blocks {
+        let block_id = BlockHashOrNumber::Num(block.block.header.number);
+        let kept = block.block.header.number <= latest;
+
+        assert_eq!(provider.block(block_id)?.is_some(), kept);
+        assert_eq!(provider.block_hash_by_num(block.block.header.number)?.is_some(), kept);
+        assert_eq!(provider.block_status(block_id)?.is_some(), kept);
+
+        for (idx, tx) in block.block.body.iter().enumerate() {
+            let actual_receipt = provider.receipt_by_hash(tx.hash)?;
+            let expected_receipt = kept.then(|| receipts[idx].clone());
+
+            assert_eq!(actual_receipt, expected_receipt);
+            assert_eq!(provider.transaction_by_hash(tx.hash)?.is_some(), kept);
+            assert_eq!(provider.transaction_execution(tx.hash)?.is_some(), kept);
+            assert_eq!(provider.transaction_status(tx.hash)?.is_some(), kept);
+        }
+    }
+
+    // blocks can be appended again on top of the unwound chain
+    let next = utils::generate_dummy_blocks_and_receipts(latest + 2).pop().unwrap();
+    let (mut block, receipts, executions) = next;
+    block.block.header.parent_hash = blocks[latest as usize].0.block.hash;
+    provider.insert_block_with_states_and_receipts(
+        block.clone(),
+        Default::default(),
+        receipts,
+        executions,
+    )?;
+
+    assert_eq!(provider.latest_number()?, latest + 1);
+    assert_eq!(provider.latest_hash()?, block.block.hash);
+
+    Ok(())
+}
+
 #[apply(test_read_state_update)]
 fn test_read_state_update_with_fork_provider(
     #[with(fork_provider_with_spawned_fork_network::default())] provider: BlockchainProvider<
@@ -313,6 +398,14 @@ where
 #[case::insert_10_block(10)]
 fn insert_block_cases(#[case] block_count: u64) {}
 
+#[template]
+#[rstest::rstest]
+#[case::unwind_to_genesis(5, 0)]
+#[case::unwind_to_middle(10, 4)]
+#[case::unwind_to_latest(5, 4)]
+#[case::unwind_past_latest(5, 10)]
+fn unwind_block_cases(#[case] block_count: u64, #[case] unwind_to: BlockNumber) {}
+
 #[template]
 #[rstest::rstest]
 #[case::state_update_at_block_1(1, mock_state_updates()[0].clone())]
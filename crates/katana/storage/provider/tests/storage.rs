@@ -5,6 +5,7 @@ use fixtures::{fork_provider_with_spawned_fork_network, provider_with_states};
 use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
 use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
 use katana_provider::providers::fork::ForkedProvider;
+use katana_provider::traits::block::{BlockNumberProvider, BlockUnwinder};
 use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
 use katana_provider::BlockchainProvider;
 use rstest_reuse::{self, *};
@@ -88,6 +89,21 @@ mod historical {
         assert_state_provider_storage(state_provider, expected_storage_entry)
     }
 
+    /// Unwinding the chain to `block_num` must restore the latest state to the state at that
+    /// block.
+    fn assert_unwound_storage_value<Db>(
+        provider: BlockchainProvider<Db>,
+        block_num: BlockNumber,
+        expected_storage_entry: Vec<(ContractAddress, StorageKey, Option<StorageValue>)>,
+    ) -> Result<()>
+    where
+        Db: BlockNumberProvider + BlockUnwinder + StateFactoryProvider,
+    {
+        provider.unwind_to(block_num)?;
+        assert_eq!(provider.latest_number()?, block_num);
+        assert_state_provider_storage(provider.latest()?, expected_storage_entry)
+    }
+
     const ERROR_CREATE_HISTORICAL_PROVIDER: &str = "Failed to create historical state provider.";
 
     #[template]
@@ -155,4 +171,24 @@ mod historical {
     ) -> Result<()> {
         assert_historical_storage_value(provider, block_num, expected_storage_entry)
     }
+
+    #[apply(test_historical_storage_read)]
+    fn unwind_storage_of_fork_provider_with_spawned_fork_network(
+        #[with(fork_provider_with_spawned_fork_network::default())] provider: BlockchainProvider<
+            ForkedProvider,
+        >,
+        #[case] block_num: BlockNumber,
+        #[case] expected_storage_entry: Vec<(ContractAddress, StorageKey, Option<StorageValue>)>,
+    ) -> Result<()> {
+        assert_unwound_storage_value(provider, block_num, expected_storage_entry)
+    }
+
+    #[apply(test_historical_storage_read)]
+    fn unwind_storage_of_db_provider(
+        #[with(db_provider())] provider: BlockchainProvider<DbProvider>,
+        #[case] block_num: BlockNumber,
+        #[case] expected_storage_entry: Vec<(ContractAddress, StorageKey, Option<StorageValue>)>,
+    ) -> Result<()> {
+        assert_unwound_storage_value(provider, block_num, expected_storage_entry)
+    }
 }
@@ -0,0 +1,36 @@
+use katana_primitives::class::{ClassHash, CompiledClassHash};
+use katana_primitives::contract::Nonce;
+use katana_primitives::Felt;
+use starknet::macros::short_string;
+use starknet_crypto::{pedersen_hash, poseidon_hash, poseidon_hash_many};
+
+/// Computes the value of a contract's leaf in the contracts trie.
+///
+/// `H(H(H(class_hash, storage_root), nonce), 0)` where `H` is the Pedersen hash function.
+pub fn contract_state_hash(class_hash: ClassHash, storage_root: Felt, nonce: Nonce) -> Felt {
+    const CONTRACT_STATE_HASH_VERSION: Felt = Felt::ZERO;
+
+    let hash = pedersen_hash(&class_hash, &storage_root);
+    let hash = pedersen_hash(&hash, &nonce);
+    pedersen_hash(&hash, &CONTRACT_STATE_HASH_VERSION)
+}
+
+/// Computes the value of a class' leaf in the classes trie.
+///
+/// `H("CONTRACT_CLASS_LEAF_V0", compiled_class_hash)` where `H` is the Poseidon hash function.
+pub fn class_leaf_hash(compiled_class_hash: CompiledClassHash) -> Felt {
+    poseidon_hash(short_string!("CONTRACT_CLASS_LEAF_V0"), compiled_class_hash)
+}
+
+/// Computes the global state root from the roots of the contracts and classes tries.
+///
+/// If the classes trie is empty, the state root is the root of the contracts trie. Otherwise it
+/// is `H("STARKNET_STATE_V0", contracts_root, classes_root)` where `H` is the Poseidon hash
+/// function.
+pub fn state_root(contracts_root: Felt, classes_root: Felt) -> Felt {
+    if classes_root == Felt::ZERO {
+        contracts_root
+    } else {
+        poseidon_hash_many(&[short_string!("STARKNET_STATE_V0"), contracts_root, classes_root])
+    }
+}
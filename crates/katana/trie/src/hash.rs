@@ -0,0 +1,26 @@
+use katana_primitives::Felt;
+
+/// The hash function used to compute the hashes of a trie's nodes.
+pub trait TrieHash {
+    fn hash(a: &Felt, b: &Felt) -> Felt;
+}
+
+/// The Pedersen hash function. Used by the contracts and storage tries.
+#[derive(Debug)]
+pub struct Pedersen;
+
+impl TrieHash for Pedersen {
+    fn hash(a: &Felt, b: &Felt) -> Felt {
+        starknet_crypto::pedersen_hash(a, b)
+    }
+}
+
+/// The Poseidon hash function. Used by the classes trie.
+#[derive(Debug)]
+pub struct Poseidon;
+
+impl TrieHash for Poseidon {
+    fn hash(a: &Felt, b: &Felt) -> Felt {
+        starknet_crypto::poseidon_hash(*a, *b)
+    }
+}
@@ -0,0 +1,434 @@
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+//! Binary Merkle-Patricia tries as used by Starknet for its state commitment.
+//!
+//! See <https://docs.starknet.io/architecture-and-concepts/network-architecture/starknet-state/>
+//! for the specification of the tries and how the state root is derived from them.
+
+mod commitment;
+mod hash;
+mod node;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::marker::PhantomData;
+
+pub use commitment::*;
+pub use hash::{Pedersen, Poseidon, TrieHash};
+use katana_primitives::Felt;
+use node::key_bit;
+pub use node::{Node, Path};
+
+/// The height of the tries. Keys are 251-bit integers.
+pub const HEIGHT: usize = 251;
+
+/// The nodes needed to prove the membership (or non-membership) of a set of keys, paired with
+/// their hashes, ordered from the root to the leaves.
+pub type MultiProof = Vec<(Felt, Node)>;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("trie node {0:#x} not found")]
+    MissingNode(Felt),
+
+    #[error(transparent)]
+    Storage(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// The storage of the trie nodes, indexed by their hashes.
+pub trait TrieStorage {
+    /// Returns the node with the given hash.
+    fn get(&self, hash: &Felt) -> Result<Option<Node>>;
+}
+
+/// A [`TrieStorage`] to which nodes can be written.
+pub trait TrieStorageMut: TrieStorage {
+    /// Inserts a node with the given hash.
+    fn insert(&mut self, hash: Felt, node: Node) -> Result<()>;
+}
+
+impl TrieStorage for HashMap<Felt, Node> {
+    fn get(&self, hash: &Felt) -> Result<Option<Node>> {
+        Ok(HashMap::get(self, hash).copied())
+    }
+}
+
+impl TrieStorageMut for HashMap<Felt, Node> {
+    fn insert(&mut self, hash: Felt, node: Node) -> Result<()> {
+        HashMap::insert(self, hash, node);
+        Ok(())
+    }
+}
+
+/// A binary Merkle-Patricia trie of height [`HEIGHT`].
+///
+/// Nodes are addressed by their hashes, which means a single storage can hold many versions of
+/// the same trie, each one identified by its root hash. The empty trie has a root of zero.
+///
+/// New nodes are kept in memory until [`BinaryTrie::commit`] is called.
+#[derive(Debug)]
+pub struct BinaryTrie<S, H> {
+    storage: S,
+    /// Nodes created by the updates that haven't been committed yet.
+    nodes: HashMap<Felt, Node>,
+    _hash: PhantomData<H>,
+}
+
+impl<S: TrieStorage, H: TrieHash> BinaryTrie<S, H> {
+    pub fn new(storage: S) -> Self {
+        Self { storage, nodes: HashMap::new(), _hash: PhantomData }
+    }
+
+    /// Returns the underlying storage. Uncommitted nodes are discarded.
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
+    /// Applies the updates to the trie with the given root and returns the new root.
+    ///
+    /// Updating a key to zero removes it from the trie. If a key is updated more than once, only
+    /// the last value is kept.
+    pub fn insert_batch<I>(&mut self, root: Felt, updates: I) -> Result<Felt>
+    where
+        I: IntoIterator<Item = (Felt, Felt)>,
+    {
+        let updates = updates.into_iter().collect::<BTreeMap<_, _>>();
+        let updates = updates.into_iter().collect::<Vec<_>>();
+        self.update(root, 0, &updates)
+    }
+
+    /// Returns the value of `key` in the trie with the given root, if any.
+    pub fn get(&self, root: Felt, key: Felt) -> Result<Option<Felt>> {
+        let mut hash = root;
+        let mut depth = 0;
+
+        while depth < HEIGHT {
+            if hash == Felt::ZERO {
+                return Ok(None);
+            }
+
+            match self.node(hash)? {
+                Node::Binary { left, right } => {
+                    hash = if key_bit(&key, depth) { right } else { left };
+                    depth += 1;
+                }
+                Node::Edge { child, path } => {
+                    if Path::from_key(&key, depth, path.len as usize) != path {
+                        return Ok(None);
+                    }
+                    hash = child;
+                    depth += path.len as usize;
+                }
+            }
+        }
+
+        Ok(if hash == Felt::ZERO { None } else { Some(hash) })
+    }
+
+    /// Returns the nodes proving the values (or the absence) of `keys` in the trie with the
+    /// given root. Nodes shared by the paths of multiple keys are only included once.
+    pub fn multiproof(&self, root: Felt, keys: &[Felt]) -> Result<MultiProof> {
+        let mut proof = Vec::new();
+        let mut seen = HashSet::new();
+
+        for key in keys {
+            let mut hash = root;
+            let mut depth = 0;
+
+            while depth < HEIGHT && hash != Felt::ZERO {
+                let node = self.node(hash)?;
+                if seen.insert(hash) {
+                    proof.push((hash, node));
+                }
+
+                match node {
+                    Node::Binary { left, right } => {
+                        hash = if key_bit(key, depth) { right } else { left };
+                        depth += 1;
+                    }
+                    Node::Edge { child, path } => {
+                        if Path::from_key(key, depth, path.len as usize) != path {
+                            break;
+                        }
+                        hash = child;
+                        depth += path.len as usize;
+                    }
+                }
+            }
+        }
+
+        Ok(proof)
+    }
+
+    fn update(&mut self, hash: Felt, depth: usize, updates: &[(Felt, Felt)]) -> Result<Felt> {
+        if updates.is_empty() {
+            return Ok(hash);
+        }
+
+        if depth == HEIGHT {
+            return Ok(updates[updates.len() - 1].1);
+        }
+
+        let (left, right) = self.children(hash, depth)?;
+        let (left_updates, right_updates): (Vec<_>, Vec<_>) =
+            updates.iter().copied().partition(|(key, _)| !key_bit(key, depth));
+
+        let left = self.update(left, depth + 1, &left_updates)?;
+        let right = self.update(right, depth + 1, &right_updates)?;
+
+        self.make_node(left, right, depth)
+    }
+
+    /// Returns the hashes of the left and right children of the node at `depth`, expanding edge
+    /// nodes by one level if necessary.
+    fn children(&mut self, hash: Felt, depth: usize) -> Result<(Felt, Felt)> {
+        if hash == Felt::ZERO {
+            return Ok((Felt::ZERO, Felt::ZERO));
+        }
+
+        match self.node(hash)? {
+            Node::Binary { left, right } => Ok((left, right)),
+            Node::Edge { child, path } => {
+                let rest = if path.len == 1 {
+                    child
+                } else {
+                    debug_assert!(depth + (path.len as usize) <= HEIGHT);
+                    self.insert_node(Node::Edge { child, path: path.pop_first() })
+                };
+
+                if path.first() { Ok((Felt::ZERO, rest)) } else { Ok((rest, Felt::ZERO)) }
+            }
+        }
+    }
+
+    /// Creates the node at `depth` with the given children, keeping the trie in its canonical
+    /// form ie. consecutive edges are merged into a single one.
+    fn make_node(&mut self, left: Felt, right: Felt, depth: usize) -> Result<Felt> {
+        let (child, bit) = match (left == Felt::ZERO, right == Felt::ZERO) {
+            (true, true) => return Ok(Felt::ZERO),
+            (false, false) => return Ok(self.insert_node(Node::Binary { left, right })),
+            (false, true) => (left, false),
+            (true, false) => (right, true),
+        };
+
+        // the child is a leaf
+        if depth + 1 == HEIGHT {
+            return Ok(self.insert_node(Node::Edge { child, path: Path::single(bit) }));
+        }
+
+        let node = match self.node(child)? {
+            Node::Edge { child, path } => Node::Edge { child, path: path.push_first(bit) },
+            Node::Binary { .. } => Node::Edge { child, path: Path::single(bit) },
+        };
+
+        Ok(self.insert_node(node))
+    }
+
+    fn insert_node(&mut self, node: Node) -> Felt {
+        let hash = node.hash::<H>();
+        self.nodes.insert(hash, node);
+        hash
+    }
+
+    fn node(&self, hash: Felt) -> Result<Node> {
+        if let Some(node) = self.nodes.get(&hash) {
+            return Ok(*node);
+        }
+
+        self.storage.get(&hash)?.ok_or(Error::MissingNode(hash))
+    }
+}
+
+impl<S: TrieStorageMut, H: TrieHash> BinaryTrie<S, H> {
+    /// Persists all the nodes reachable from `root` that are not yet in the storage.
+    pub fn commit(&mut self, root: Felt) -> Result<()> {
+        let mut stack = vec![(root, 0)];
+
+        while let Some((hash, depth)) = stack.pop() {
+            if depth >= HEIGHT {
+                continue;
+            }
+
+            // nodes that are not in memory are either already persisted or the empty node.
+            let Some(node) = self.nodes.remove(&hash) else { continue };
+
+            match node {
+                Node::Binary { left, right } => {
+                    stack.push((left, depth + 1));
+                    stack.push((right, depth + 1));
+                }
+                Node::Edge { child, path } => stack.push((child, depth + path.len as usize)),
+            }
+
+            self.storage.insert(hash, node)?;
+        }
+
+        self.nodes.clear();
+        Ok(())
+    }
+}
+
+/// Verifies a proof for `key` against the given root.
+///
+/// Returns the proven value of the key, where zero means the key is not in the trie, or `None`
+/// if the proof is invalid.
+pub fn verify_proof<H: TrieHash>(root: Felt, key: Felt, proof: &[(Felt, Node)]) -> Option<Felt> {
+    let nodes = proof.iter().copied().collect::<HashMap<_, _>>();
+
+    let mut hash = root;
+    let mut depth = 0;
+
+    while depth < HEIGHT && hash != Felt::ZERO {
+        let node = nodes.get(&hash)?;
+        if node.hash::<H>() != hash {
+            return None;
+        }
+
+        match *node {
+            Node::Binary { left, right } => {
+                hash = if key_bit(&key, depth) { right } else { left };
+                depth += 1;
+            }
+            Node::Edge { child, path } => {
+                if Path::from_key(&key, depth, path.len as usize) != path {
+                    return Some(Felt::ZERO);
+                }
+                hash = child;
+                depth += path.len as usize;
+            }
+        }
+    }
+
+    Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+
+    type Trie = BinaryTrie<HashMap<Felt, Node>, Pedersen>;
+
+    fn trie() -> Trie {
+        BinaryTrie::new(HashMap::new())
+    }
+
+    #[test]
+    fn empty_trie() {
+        let mut trie = trie();
+        let root = trie.insert_batch(Felt::ZERO, []).unwrap();
+        assert_eq!(root, Felt::ZERO);
+
+        let root = trie.insert_batch(Felt::ZERO, [(felt!("0x1"), Felt::ZERO)]).unwrap();
+        assert_eq!(root, Felt::ZERO);
+    }
+
+    #[test]
+    fn single_leaf() {
+        let key = felt!("0x1");
+        let value = felt!("0xabc");
+
+        let mut trie = trie();
+        let root = trie.insert_batch(Felt::ZERO, [(key, value)]).unwrap();
+
+        // a single edge node from the root to the leaf
+        let path = Path { value: key, len: HEIGHT as u8 };
+        let expected = Pedersen::hash(&value, &path.value) + Felt::from(HEIGHT as u8);
+        assert_eq!(root, expected);
+        assert_eq!(trie.get(root, key).unwrap(), Some(value));
+        assert_eq!(trie.get(root, felt!("0x2")).unwrap(), None);
+    }
+
+    #[test]
+    fn sibling_leaves() {
+        let (k0, v0) = (felt!("0x0"), felt!("0x11"));
+        let (k1, v1) = (felt!("0x1"), felt!("0x22"));
+
+        let mut trie = trie();
+        let root = trie.insert_batch(Felt::ZERO, [(k0, v0), (k1, v1)]).unwrap();
+
+        // an edge of 250 zero bits leading to a binary node whose children are the leaves
+        let binary = Pedersen::hash(&v0, &v1);
+        let expected = Pedersen::hash(&binary, &Felt::ZERO) + Felt::from(250u8);
+        assert_eq!(root, expected);
+    }
+
+    #[test]
+    fn root_is_independent_of_insertion_order() {
+        let entries = (1u64..=20)
+            .map(|i| (Felt::from(i * 0x1234567) * Felt::from(i), Felt::from(i)))
+            .collect::<Vec<_>>();
+
+        let mut trie = trie();
+        let all_at_once = trie.insert_batch(Felt::ZERO, entries.clone()).unwrap();
+
+        let mut one_by_one = Felt::ZERO;
+        for entry in entries.iter().rev() {
+            one_by_one = trie.insert_batch(one_by_one, [*entry]).unwrap();
+        }
+
+        assert_eq!(all_at_once, one_by_one);
+    }
+
+    #[test]
+    fn removing_leaves() {
+        let a = (felt!("0x10"), felt!("0x1"));
+        let b = (felt!("0x11"), felt!("0x2"));
+        let c = (felt!("0x7ff"), felt!("0x3"));
+
+        let mut trie = trie();
+        let root_ab = trie.insert_batch(Felt::ZERO, [a, b]).unwrap();
+        let root_abc = trie.insert_batch(root_ab, [c]).unwrap();
+
+        let root = trie.insert_batch(root_abc, [(c.0, Felt::ZERO)]).unwrap();
+        assert_eq!(root, root_ab);
+
+        let root = trie.insert_batch(root, [(a.0, Felt::ZERO), (b.0, Felt::ZERO)]).unwrap();
+        assert_eq!(root, Felt::ZERO);
+    }
+
+    #[test]
+    fn commit_persists_reachable_nodes() {
+        let mut trie = trie();
+        let root1 = trie.insert_batch(Felt::ZERO, [(felt!("0x1"), felt!("0x1"))]).unwrap();
+        trie.commit(root1).unwrap();
+
+        let root2 = trie.insert_batch(root1, [(felt!("0x2"), felt!("0x2"))]).unwrap();
+        trie.commit(root2).unwrap();
+
+        // both versions of the trie can be read from the storage.
+        let storage = trie.into_storage();
+        let trie = Trie::new(storage);
+        assert_eq!(trie.get(root1, felt!("0x1")).unwrap(), Some(felt!("0x1")));
+        assert_eq!(trie.get(root1, felt!("0x2")).unwrap(), None);
+        assert_eq!(trie.get(root2, felt!("0x1")).unwrap(), Some(felt!("0x1")));
+        assert_eq!(trie.get(root2, felt!("0x2")).unwrap(), Some(felt!("0x2")));
+    }
+
+    #[test]
+    fn proofs() {
+        let entries = [
+            (felt!("0x1"), felt!("0x100")),
+            (felt!("0x2"), felt!("0x200")),
+            (felt!("0x400"), felt!("0x300")),
+        ];
+
+        let mut trie = trie();
+        let root = trie.insert_batch(Felt::ZERO, entries).unwrap();
+
+        let keys = entries.iter().map(|(k, _)| *k).chain([felt!("0x3")]).collect::<Vec<_>>();
+        let proof = trie.multiproof(root, &keys).unwrap();
+
+        for (key, value) in entries {
+            assert_eq!(verify_proof::<Pedersen>(root, key, &proof), Some(value));
+        }
+
+        // non-membership
+        assert_eq!(verify_proof::<Pedersen>(root, felt!("0x3"), &proof), Some(Felt::ZERO));
+
+        // a proof against a different root is invalid
+        assert_eq!(verify_proof::<Pedersen>(felt!("0x1234"), felt!("0x1"), &proof), None);
+    }
+}
@@ -0,0 +1,135 @@
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+
+use crate::hash::TrieHash;
+
+/// A node of a binary Merkle-Patricia trie.
+///
+/// Leaves are not stored as nodes. The value of a leaf is used directly as the hash of its
+/// parent's child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Node {
+    /// A node with two non-empty children.
+    Binary { left: Felt, right: Felt },
+    /// A node with a single non-empty child, located at the end of `path`.
+    Edge { child: Felt, path: Path },
+}
+
+impl Node {
+    /// Computes the hash of the node.
+    ///
+    /// - Binary node: `H(left, right)`
+    /// - Edge node: `H(child, path) + length`
+    pub fn hash<H: TrieHash>(&self) -> Felt {
+        match self {
+            Node::Binary { left, right } => H::hash(left, right),
+            Node::Edge { child, path } => H::hash(child, &path.value) + Felt::from(path.len),
+        }
+    }
+}
+
+/// The path of an edge node.
+///
+/// The path is stored as the integer represented by its bits, the first bit of the path being
+/// the most significant one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Path {
+    /// The bits of the path.
+    pub value: Felt,
+    /// The number of bits in the path.
+    pub len: u8,
+}
+
+impl Path {
+    /// Creates a path from the `len` bits of `key` starting at `depth`, where depth 0 refers to
+    /// the most significant bit of the key.
+    pub(crate) fn from_key(key: &Felt, depth: usize, len: usize) -> Self {
+        let mut bytes = [0u8; 32];
+        for i in 0..len {
+            if key_bit(key, depth + i) {
+                set_bit(&mut bytes, len - 1 - i);
+            }
+        }
+        Self { value: Felt::from_bytes_be(&bytes), len: len as u8 }
+    }
+
+    /// Creates a path made of a single bit.
+    pub(crate) fn single(bit: bool) -> Self {
+        Self { value: if bit { Felt::ONE } else { Felt::ZERO }, len: 1 }
+    }
+
+    /// Returns the first bit of the path.
+    pub(crate) fn first(&self) -> bool {
+        get_bit(&self.value.to_bytes_be(), self.len as usize - 1)
+    }
+
+    /// Returns the path without its first bit.
+    pub(crate) fn pop_first(&self) -> Self {
+        let mut bytes = self.value.to_bytes_be();
+        clear_bit(&mut bytes, self.len as usize - 1);
+        Self { value: Felt::from_bytes_be(&bytes), len: self.len - 1 }
+    }
+
+    /// Returns the path with `bit` prepended to it.
+    pub(crate) fn push_first(&self, bit: bool) -> Self {
+        let mut bytes = self.value.to_bytes_be();
+        if bit {
+            set_bit(&mut bytes, self.len as usize);
+        }
+        Self { value: Felt::from_bytes_be(&bytes), len: self.len + 1 }
+    }
+}
+
+/// Returns the bit of `key` at `depth`, where depth 0 refers to the most significant bit of a
+/// [`HEIGHT`](crate::HEIGHT)-bit key.
+pub(crate) fn key_bit(key: &Felt, depth: usize) -> bool {
+    get_bit(&key.to_bytes_be(), crate::HEIGHT - 1 - depth)
+}
+
+/// Returns the `index`-th least significant bit of a big endian integer.
+fn get_bit(bytes: &[u8; 32], index: usize) -> bool {
+    (bytes[31 - index / 8] >> (index % 8)) & 1 == 1
+}
+
+fn set_bit(bytes: &mut [u8; 32], index: usize) {
+    bytes[31 - index / 8] |= 1 << (index % 8);
+}
+
+fn clear_bit(bytes: &mut [u8; 32], index: usize) {
+    bytes[31 - index / 8] &= !(1 << (index % 8));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_manipulation() {
+        // 0b101
+        let path = Path { value: Felt::from(5u8), len: 3 };
+
+        assert!(path.first());
+        assert_eq!(path.pop_first(), Path { value: Felt::ONE, len: 2 });
+        assert_eq!(path.pop_first().push_first(true), path);
+        assert_eq!(path.push_first(false), Path { value: Felt::from(5u8), len: 4 });
+        assert!(!path.push_first(false).first());
+    }
+
+    #[test]
+    fn path_from_key() {
+        // the two most significant bits of a 251-bit key are 1 and 0
+        let key = Felt::from_bytes_be(&{
+            let mut bytes = [0u8; 32];
+            bytes[0] = 0b0000_0100;
+            bytes[31] = 1;
+            bytes
+        });
+
+        assert!(key_bit(&key, 0));
+        assert!(!key_bit(&key, 1));
+        assert!(key_bit(&key, crate::HEIGHT - 1));
+
+        assert_eq!(Path::from_key(&key, 0, 2), Path { value: Felt::TWO, len: 2 });
+        assert_eq!(Path::from_key(&key, 249, 2), Path { value: Felt::ONE, len: 2 });
+    }
+}